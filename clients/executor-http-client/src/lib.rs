@@ -1,69 +1,46 @@
 // std
+use std::ops::Deref;
 // crates
-use reqwest::{Client, ClientBuilder, StatusCode, Url};
+use reqwest::{Client, Url};
 use serde::Serialize;
 // internal
+pub use node_http_client::{Error, NodeHttpClient};
+use nomos_core::da::BlobId;
 use nomos_executor::api::{handlers::DispersalRequest, paths};
 
-#[derive(thiserror::Error, Debug)]
-pub enum Error {
-    #[error("Internal server error: {0}")]
-    Server(String),
-    #[error(transparent)]
-    Request(reqwest::Error),
-}
-
-#[derive(Clone)]
+/// Client for the http api served by executor nodes.
+/// Routes shared with validators are available through [`NodeHttpClient`].
+#[derive(Clone, Default)]
 pub struct ExecutorHttpClient {
-    client: Client,
-    executor_address: Url,
-}
-
-impl Default for ExecutorHttpClient {
-    fn default() -> Self {
-        let client = ClientBuilder::new()
-            .build()
-            .expect("Client from default settings should be able to build");
-        let executor_address = Url::parse("https://127.0.0.1:3333").unwrap();
-        Self {
-            client,
-            executor_address,
-        }
-    }
+    node: NodeHttpClient,
 }
 
 impl ExecutorHttpClient {
     pub fn new(client: Client, executor_address: Url) -> Self {
         Self {
-            client,
-            executor_address,
+            node: NodeHttpClient::new(client, executor_address),
         }
     }
 
-    /// Send a `Blob` to be dispersed
+    /// Send a `Blob` to be dispersed, returns the id of the dispersed blob.
+    /// The executor doesn't chunk `data`, it has to fit in a single blob.
     pub async fn publish_blob<Metadata: Serialize>(
         &self,
         data: Vec<u8>,
         metadata: Metadata,
-    ) -> Result<(), Error> {
+    ) -> Result<BlobId, Error> {
         let req = DispersalRequest { data, metadata };
-        let url = self
-            .executor_address
-            .join(paths::DISPERSE_DATA)
-            .expect("Url should build properly");
-        let response = self
-            .client
-            .post(url)
-            .json(&req)
-            .send()
+        let url = self.node.url(paths::DISPERSE_DATA);
+        self.node
+            .json_response(self.node.client().post(url).json(&req))
             .await
-            .map_err(Error::Request)?;
-        match response.status() {
-            StatusCode::OK => Ok(()),
-            StatusCode::INTERNAL_SERVER_ERROR => Err(Error::Server(
-                response.text().await.map_err(Error::Request)?,
-            )),
-            _ => unreachable!("As per the documentation it can only return 200 or 500 responses"),
-        }
+    }
+}
+
+impl Deref for ExecutorHttpClient {
+    type Target = NodeHttpClient;
+
+    fn deref(&self) -> &Self::Target {
+        &self.node
     }
 }
@@ -0,0 +1,447 @@
+// std
+use std::hash::Hash;
+use std::ops::Range;
+// crates
+use futures::{stream, Stream, StreamExt};
+use reqwest::header::ACCEPT;
+use reqwest::{Client, ClientBuilder, RequestBuilder, Response, StatusCode, Url};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+// internal
+use cryptarchia_consensus::{history::InclusionLocation, CryptarchiaInfo, TipChange};
+use kzgrs_backend::dispersal::BlobInfo;
+use nomos_core::block::Block;
+use nomos_core::da::{blob::metadata::Metadata, BlobId};
+use nomos_core::header::HeaderId;
+use nomos_da_sampling::SamplingOutcome;
+use nomos_mempool::{backend::Status, MempoolMetrics};
+use nomos_network::backends::libp2p::Libp2pInfo;
+use nomos_node::api::{handlers::GetRangeReq, paths};
+use nomos_service_status::HealthReport;
+
+pub use nomos_da_indexer::IndexView;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Internal server error: {0}")]
+    Server(String),
+    #[error("Requested data was pruned: {0}")]
+    Pruned(String),
+    #[error("Malformed event: {0}")]
+    Event(String),
+    #[error("Unexpected response status {status}: {body}")]
+    Unexpected { status: StatusCode, body: String },
+    #[error(transparent)]
+    Request(reqwest::Error),
+}
+
+/// Event received from one of the node event streams
+#[derive(Debug, Clone, PartialEq)]
+pub enum NodeEvent<T> {
+    Item(T),
+    /// The client was too slow and `skipped` events were dropped by the node.
+    /// Streams that can be resumed should be subscribed again from the last height processed.
+    Lagged {
+        skipped: u64,
+    },
+}
+
+/// Client for the http api served by validator nodes.
+/// Executors serve the same routes, see `executor-http-client` for the executor-only ones.
+#[derive(Clone)]
+pub struct NodeHttpClient {
+    client: Client,
+    base_url: Url,
+}
+
+impl Default for NodeHttpClient {
+    fn default() -> Self {
+        let client = ClientBuilder::new()
+            .build()
+            .expect("Client from default settings should be able to build");
+        let base_url = Url::parse("https://127.0.0.1:3333").unwrap();
+        Self { client, base_url }
+    }
+}
+
+impl NodeHttpClient {
+    pub fn new(client: Client, base_url: Url) -> Self {
+        Self { client, base_url }
+    }
+
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
+    pub fn base_url(&self) -> &Url {
+        &self.base_url
+    }
+
+    /// Get the status of the node services, whether the node is alive or not
+    pub async fn health(&self) -> Result<HealthReport, Error> {
+        self.health_report(paths::HEALTH).await
+    }
+
+    /// Get the status of the node services, whether the node is ready or not
+    pub async fn ready(&self) -> Result<HealthReport, Error> {
+        self.health_report(paths::READY).await
+    }
+
+    /// Get the node metrics in the Prometheus text format
+    pub async fn metrics(&self) -> Result<String, Error> {
+        let response = self.send(self.client.get(self.url(paths::METRICS))).await?;
+        response.text().await.map_err(Error::Request)
+    }
+
+    /// Get the metrics of the cl mempool
+    pub async fn cl_metrics(&self) -> Result<MempoolMetrics, Error> {
+        self.json_response(self.client.get(self.url(paths::CL_METRICS)))
+            .await
+    }
+
+    /// Get the metrics of the da mempool
+    pub async fn da_metrics(&self) -> Result<MempoolMetrics, Error> {
+        self.json_response(self.client.get(self.url(paths::DA_METRICS)))
+            .await
+    }
+
+    /// Get the status in the cl mempool of the transactions with the given hashes
+    pub async fn cl_status<TxHash: Serialize>(
+        &self,
+        hashes: &[TxHash],
+    ) -> Result<Vec<Status<HeaderId>>, Error> {
+        self.json_response(self.client.post(self.url(paths::CL_STATUS)).json(hashes))
+            .await
+    }
+
+    /// Get information about the local chain
+    pub async fn cryptarchia_info(&self) -> Result<CryptarchiaInfo, Error> {
+        self.json_response(self.client.get(self.url(paths::CRYPTARCHIA_INFO)))
+            .await
+    }
+
+    /// Get the ids of the headers from `from` (or the tip) back to `to` (or genesis)
+    pub async fn cryptarchia_headers(
+        &self,
+        from: Option<HeaderId>,
+        to: Option<HeaderId>,
+    ) -> Result<Vec<HeaderId>, Error> {
+        let mut request = self.client.get(self.url(paths::CRYPTARCHIA_HEADERS));
+        if let Some(from) = from {
+            request = request.query(&[("from", from)]);
+        }
+        if let Some(to) = to {
+            request = request.query(&[("to", to)]);
+        }
+        self.json_response(request).await
+    }
+
+    /// Send a DA blob to be verified and stored by the node
+    pub async fn add_blob<Blob: Serialize>(&self, blob: &Blob) -> Result<(), Error> {
+        self.send(self.client.post(self.url(paths::DA_ADD_BLOB)).json(blob))
+            .await
+            .map(|_| ())
+    }
+
+    /// Get the blobs of application `app_id` indexed in `range`, as seen in `view`
+    pub async fn get_range<M>(
+        &self,
+        app_id: M::AppId,
+        range: Range<M::Index>,
+        view: IndexView,
+    ) -> Result<Vec<(M::Index, Vec<Vec<u8>>)>, Error>
+    where
+        M: Metadata + Serialize,
+        M::AppId: Serialize + DeserializeOwned,
+        M::Index: Serialize + DeserializeOwned,
+    {
+        let request = GetRangeReq::<M> {
+            app_id,
+            range,
+            view,
+        };
+        self.json_response(
+            self.client
+                .post(self.url(paths::DA_GET_RANGE))
+                .json(&request),
+        )
+        .await
+    }
+
+    /// Get information about the node peer to peer network
+    pub async fn network_info(&self) -> Result<Libp2pInfo, Error> {
+        self.json_response(self.client.get(self.url(paths::NETWORK_INFO)))
+            .await
+    }
+
+    /// Get the block with the given id
+    pub async fn block<Tx>(&self, id: HeaderId) -> Result<Option<Block<Tx, BlobInfo>>, Error>
+    where
+        Tx: Clone + Eq + Hash + DeserializeOwned,
+    {
+        self.json_response(self.client.post(self.url(paths::STORAGE_BLOCK)).json(&id))
+            .await
+    }
+
+    /// Get the canonical block at `height`
+    pub async fn block_by_height<Tx>(
+        &self,
+        height: u64,
+    ) -> Result<Option<Block<Tx, BlobInfo>>, Error>
+    where
+        Tx: Clone + Eq + Hash + DeserializeOwned,
+    {
+        let url = self.url(paths::STORAGE_BLOCK_BY_HEIGHT);
+        self.json_response(self.client.get(url).query(&[("height", height)]))
+            .await
+    }
+
+    /// Get up to `limit` canonical blocks starting at height `from`
+    pub async fn blocks<Tx>(
+        &self,
+        from: u64,
+        limit: usize,
+    ) -> Result<Vec<Block<Tx, BlobInfo>>, Error>
+    where
+        Tx: Clone + Eq + Hash + DeserializeOwned,
+    {
+        let url = self.url(paths::STORAGE_BLOCKS);
+        self.json_response(
+            self.client
+                .get(url)
+                .query(&[("from", from), ("limit", limit as u64)]),
+        )
+        .await
+    }
+
+    /// Get the location in the canonical chain of the transaction with the given hash
+    pub async fn tx_location<TxHash: Serialize>(
+        &self,
+        hash: TxHash,
+    ) -> Result<Option<InclusionLocation>, Error> {
+        let url = self.url(paths::STORAGE_TX_LOCATION);
+        self.json_response(self.client.post(url).json(&hash)).await
+    }
+
+    /// Get the location in the canonical chain of the blob with the given id
+    pub async fn blob_location(&self, blob_id: BlobId) -> Result<Option<InclusionLocation>, Error> {
+        let url = self.url(paths::STORAGE_BLOB_LOCATION);
+        self.json_response(self.client.post(url).json(&blob_id))
+            .await
+    }
+
+    /// Add a transaction to the cl mempool
+    pub async fn add_tx<Tx: Serialize>(&self, tx: &Tx) -> Result<(), Error> {
+        self.send(self.client.post(self.url(paths::MEMPOOL_ADD_TX)).json(tx))
+            .await
+            .map(|_| ())
+    }
+
+    /// Add a blob info to the DA mempool
+    pub async fn add_blob_info<Info: Serialize>(&self, blob_info: &Info) -> Result<(), Error> {
+        let url = self.url(paths::MEMPOOL_ADD_BLOB_INFO);
+        self.send(self.client.post(url).json(blob_info))
+            .await
+            .map(|_| ())
+    }
+
+    /// Stream the canonical blocks with their height, starting at `from_height` if given or at
+    /// the next block otherwise. Blocks of a fork adopted in a reorg are sent again.
+    pub async fn events_blocks<Tx>(
+        &self,
+        from_height: Option<u64>,
+    ) -> Result<impl Stream<Item = Result<NodeEvent<(u64, Block<Tx, BlobInfo>)>, Error>>, Error>
+    where
+        Tx: Clone + Eq + Hash + DeserializeOwned + Send + 'static,
+    {
+        let mut request = self.client.get(self.url(paths::EVENTS_BLOCKS));
+        if let Some(from_height) = from_height {
+            request = request.query(&[("from_height", from_height)]);
+        }
+        self.events(request, "block").await
+    }
+
+    /// Stream the changes of the canonical tip, replaying the chain from `from_height` if given
+    pub async fn events_tips(
+        &self,
+        from_height: Option<u64>,
+    ) -> Result<impl Stream<Item = Result<NodeEvent<TipChange>, Error>>, Error> {
+        let mut request = self.client.get(self.url(paths::EVENTS_TIPS));
+        if let Some(from_height) = from_height {
+            request = request.query(&[("from_height", from_height)]);
+        }
+        self.events(request, "tip").await
+    }
+
+    /// Stream the transactions admitted into the cl mempool
+    pub async fn events_mempool_tx<Tx>(
+        &self,
+    ) -> Result<impl Stream<Item = Result<NodeEvent<Tx>, Error>>, Error>
+    where
+        Tx: DeserializeOwned + Send + 'static,
+    {
+        let request = self.client.get(self.url(paths::EVENTS_MEMPOOL_TX));
+        self.events(request, "tx").await
+    }
+
+    /// Stream the blob info admitted into the da mempool
+    pub async fn events_mempool_blob_info<Info>(
+        &self,
+    ) -> Result<impl Stream<Item = Result<NodeEvent<Info>, Error>>, Error>
+    where
+        Info: DeserializeOwned + Send + 'static,
+    {
+        let request = self.client.get(self.url(paths::EVENTS_MEMPOOL_BLOB_INFO));
+        self.events(request, "blob_info").await
+    }
+
+    /// Stream the results of the DA sampling of the node
+    pub async fn events_da_sampling(
+        &self,
+    ) -> Result<impl Stream<Item = Result<NodeEvent<SamplingOutcome<BlobId>>, Error>>, Error> {
+        let request = self.client.get(self.url(paths::EVENTS_DA_SAMPLING));
+        self.events(request, "sampling").await
+    }
+
+    /// Open the event stream of `request`, decoding the events called `name`
+    async fn events<T>(
+        &self,
+        request: RequestBuilder,
+        name: &'static str,
+    ) -> Result<impl Stream<Item = Result<NodeEvent<T>, Error>>, Error>
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        #[derive(Deserialize)]
+        struct Lagged {
+            skipped: u64,
+        }
+
+        let response = self
+            .send(request.header(ACCEPT, "text/event-stream"))
+            .await?;
+        let reader = EventReader {
+            bytes: Box::pin(response.bytes_stream()),
+            buffer: Vec::new(),
+        };
+        Ok(stream::unfold(Some(reader), move |reader| async move {
+            let mut reader = reader?;
+            loop {
+                let (event, data) = match reader.next_event().await {
+                    Ok(Some(event)) => event,
+                    Ok(None) => return None,
+                    Err(e) => return Some((Err(e), None)),
+                };
+                let event = match event.as_deref() {
+                    Some("lagged") => serde_json::from_str::<Lagged>(&data)
+                        .map(|Lagged { skipped }| NodeEvent::Lagged { skipped }),
+                    Some(event) if event == name => {
+                        serde_json::from_str(&data).map(NodeEvent::Item)
+                    }
+                    // not meant for this stream
+                    _ => continue,
+                };
+                return Some((event.map_err(|e| Error::Event(e.to_string())), Some(reader)));
+            }
+        }))
+    }
+
+    /// Build the url of `path` on this node
+    pub fn url(&self, path: &str) -> Url {
+        self.base_url.join(path).expect("Url should build properly")
+    }
+
+    /// Send `request`, failing on any non 200 response
+    pub async fn send(&self, request: RequestBuilder) -> Result<Response, Error> {
+        let response = request.send().await.map_err(Error::Request)?;
+        match response.status() {
+            StatusCode::OK => Ok(response),
+            StatusCode::INTERNAL_SERVER_ERROR => Err(Error::Server(
+                response.text().await.map_err(Error::Request)?,
+            )),
+            StatusCode::GONE => Err(Error::Pruned(
+                response.text().await.map_err(Error::Request)?,
+            )),
+            status => Err(Error::Unexpected {
+                status,
+                body: response.text().await.map_err(Error::Request)?,
+            }),
+        }
+    }
+
+    /// Send `request` and decode its json response
+    pub async fn json_response<T: DeserializeOwned>(
+        &self,
+        request: RequestBuilder,
+    ) -> Result<T, Error> {
+        self.send(request)
+            .await?
+            .json()
+            .await
+            .map_err(Error::Request)
+    }
+
+    async fn health_report(&self, path: &str) -> Result<HealthReport, Error> {
+        let response = self
+            .client
+            .get(self.url(path))
+            .send()
+            .await
+            .map_err(Error::Request)?;
+        match response.status() {
+            // unavailable nodes still describe the status of their services
+            StatusCode::OK | StatusCode::SERVICE_UNAVAILABLE => {
+                response.json().await.map_err(Error::Request)
+            }
+            status => Err(Error::Unexpected {
+                status,
+                body: response.text().await.map_err(Error::Request)?,
+            }),
+        }
+    }
+}
+
+/// Splits a server sent events response into events
+struct EventReader<S> {
+    bytes: S,
+    buffer: Vec<u8>,
+}
+
+impl<S, B> EventReader<S>
+where
+    S: Stream<Item = reqwest::Result<B>> + Unpin,
+    B: AsRef<[u8]>,
+{
+    /// Next event name and data, skipping comments such as keep alives.
+    /// Returns `None` once the node closes the stream.
+    async fn next_event(&mut self) -> Result<Option<(Option<String>, String)>, Error> {
+        loop {
+            if let Some(end) = self.buffer.windows(2).position(|bytes| bytes == b"\n\n") {
+                let block: Vec<u8> = self.buffer.drain(..end + 2).collect();
+                let block = String::from_utf8(block).map_err(|e| Error::Event(e.to_string()))?;
+                let mut event = None;
+                let mut data = Vec::new();
+                for line in block.lines() {
+                    if let Some(value) = line.strip_prefix("event:") {
+                        event = Some(value.trim_start().to_string());
+                    } else if let Some(value) = line.strip_prefix("data:") {
+                        data.push(value.strip_prefix(' ').unwrap_or(value));
+                    }
+                }
+                if !data.is_empty() {
+                    return Ok(Some((event, data.join("\n"))));
+                }
+                continue;
+            }
+            match self.bytes.next().await {
+                Some(chunk) => self.buffer.extend(
+                    chunk
+                        .map_err(Error::Request)?
+                        .as_ref()
+                        .iter()
+                        .filter(|byte| **byte != b'\r'),
+                ),
+                None => return Ok(None),
+            }
+        }
+    }
+}
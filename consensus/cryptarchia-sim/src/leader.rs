@@ -0,0 +1,144 @@
+// std
+use std::collections::HashMap;
+// crates
+use cl::{note::NoteWitness, InputWitness, Nullifier, NullifierSecret};
+use cryptarchia_engine::Slot;
+use nomos_ledger::{leader_proof::LeaderProof, EpochState, NoteTree};
+use nomos_proof_statements::leadership::{LeaderPrivate, LeaderPublic};
+// internal
+use crate::HeaderId;
+
+const EVOLVE_DOMAIN: &[u8] = b"NOMOS_POL";
+
+/// Produces the proofs of the slots won by the simulated nodes
+pub trait Prover {
+    type Proof: LeaderProof + Clone;
+
+    fn prove(&self, public: LeaderPublic, private: LeaderPrivate) -> Option<Self::Proof>;
+}
+
+/// Proof made of its public inputs only. Leaders still check their lottery tickets honestly
+/// before proposing, but nothing is proven to the other nodes.
+#[derive(Clone, Debug)]
+pub struct StubProof(LeaderPublic);
+
+impl LeaderProof for StubProof {
+    fn verify(&self, public_inputs: &LeaderPublic) -> bool {
+        &self.0 == public_inputs
+    }
+
+    fn nullifier(&self) -> Nullifier {
+        self.0.nullifier
+    }
+
+    fn evolved_commitment(&self) -> cl::NoteCommitment {
+        self.0.evolved_commitment
+    }
+
+    fn merke_root(&self) -> [u8; 32] {
+        self.0.cm_root
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StubProver;
+
+impl Prover for StubProver {
+    type Proof = StubProof;
+
+    fn prove(&self, public: LeaderPublic, _private: LeaderPrivate) -> Option<StubProof> {
+        Some(StubProof(public))
+    }
+}
+
+#[cfg(feature = "risc0")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Risc0Prover;
+
+#[cfg(feature = "risc0")]
+impl Prover for Risc0Prover {
+    type Proof = nomos_core::proofs::leader_proof::Risc0LeaderProof;
+
+    fn prove(&self, public: LeaderPublic, private: LeaderPrivate) -> Option<Self::Proof> {
+        Self::Proof::prove(public, private, risc0_zkvm::default_prover().as_ref()).ok()
+    }
+}
+
+/// Notes of a simulated node, following the same evolution rules as the consensus service
+pub struct Leader {
+    // for each block, the notes we control as of that block
+    notes: HashMap<HeaderId, Vec<NoteWitness>>,
+    nf_sk: NullifierSecret,
+    active_slot_coeff: f64,
+}
+
+impl Leader {
+    pub fn new(
+        genesis: HeaderId,
+        notes: Vec<NoteWitness>,
+        nf_sk: NullifierSecret,
+        active_slot_coeff: f64,
+    ) -> Self {
+        Self {
+            notes: HashMap::from([(genesis, notes)]),
+            nf_sk,
+            active_slot_coeff,
+        }
+    }
+
+    /// Signal that the chain extended with a new header, evolving the note that won it if it's ours
+    pub fn follow_chain(&mut self, parent: HeaderId, id: HeaderId, to_evolve: Nullifier) {
+        if let Some(notes) = self.notes.get(&parent) {
+            let notes = notes
+                .iter()
+                .map(|note| {
+                    let note_cm = note.commit(self.nf_sk.commit());
+                    if Nullifier::new(self.nf_sk, note_cm) == to_evolve {
+                        NoteWitness {
+                            nonce: note.evolved_nonce(self.nf_sk, EVOLVE_DOMAIN),
+                            ..*note
+                        }
+                    } else {
+                        *note
+                    }
+                })
+                .collect();
+            self.notes.insert(id, notes);
+        }
+    }
+
+    /// Inputs of the proof for the slot if one of our notes wins it on top of `parent`
+    pub fn try_win(
+        &self,
+        note_tree: &NoteTree,
+        epoch_state: &EpochState,
+        slot: Slot,
+        parent: HeaderId,
+    ) -> Option<(LeaderPublic, LeaderPrivate)> {
+        for note in self.notes.get(&parent)? {
+            let note_cm = note.commit(self.nf_sk.commit());
+            let Some(index) = note_tree.commitments().iter().position(|cm| cm == &note_cm) else {
+                continue;
+            };
+            // the lottery doesn't depend on the merkle path, which is only computed by winners
+            let mut input = InputWitness::new(*note, self.nf_sk, Vec::new());
+            let mut public = LeaderPublic::new(
+                [0; 32],
+                *epoch_state.nonce(),
+                slot.into(),
+                self.active_slot_coeff,
+                epoch_state.total_stake(),
+                Nullifier::new(self.nf_sk, note_cm),
+                input.evolve_output(EVOLVE_DOMAIN).commit_note(),
+            );
+            if public.check_winning(&input) {
+                public.cm_root = note_tree.root();
+                input.cm_path = note_tree
+                    .witness(index)
+                    .expect("note was found in the tree");
+                return Some((public, LeaderPrivate { input }));
+            }
+        }
+        None
+    }
+}
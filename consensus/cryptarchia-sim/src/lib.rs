@@ -0,0 +1,248 @@
+//! Deterministic simulation of Cryptarchia.
+//!
+//! Many nodes run the consensus engine and the ledger in a single process, driven by a virtual
+//! slot clock and connected by a mock gossip layer whose delays can be set per link. Every
+//! random choice is drawn from the seed of the simulation, so a run can be replayed exactly.
+
+pub mod leader;
+pub mod network;
+pub mod node;
+pub mod properties;
+
+// std
+// crates
+use cl::{note::NoteWitness, NullifierSecret};
+use cryptarchia_engine::Slot;
+use nomos_ledger::LedgerState;
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+// internal
+use leader::{Leader, Prover, StubProver};
+use network::{Delay, MockNetwork};
+use node::{Block, SimNode};
+
+pub type HeaderId = [u8; 32];
+/// Index of a node in the simulation
+pub type NodeId = usize;
+
+const GENESIS: HeaderId = [0; 32];
+
+#[derive(Clone, Debug)]
+pub struct SimulationConfig {
+    pub seed: u64,
+    /// Stake of each node, held in a single note
+    pub stakes: Vec<u64>,
+    pub ledger: nomos_ledger::Config,
+    /// Delay of the messages between nodes, unless set otherwise for a link
+    pub delay: Delay,
+}
+
+pub struct Simulation<P: Prover = StubProver> {
+    nodes: Vec<SimNode<P::Proof>>,
+    network: MockNetwork<Block<P::Proof>>,
+    prover: P,
+    rng: ChaCha20Rng,
+    /// Next slot to run
+    slot: Slot,
+    blocks: Vec<Block<P::Proof>>,
+}
+
+impl Simulation<StubProver> {
+    pub fn new(config: SimulationConfig) -> Self {
+        Self::with_prover(config, StubProver)
+    }
+}
+
+impl<P: Prover> Simulation<P> {
+    pub fn with_prover(config: SimulationConfig, prover: P) -> Self {
+        let mut rng = ChaCha20Rng::seed_from_u64(config.seed);
+        let keys: Vec<_> = config
+            .stakes
+            .iter()
+            .map(|stake| {
+                (
+                    NullifierSecret::random(&mut rng),
+                    NoteWitness::basic(*stake, [0; 32], &mut rng),
+                )
+            })
+            .collect();
+        let genesis_state = LedgerState::from_commitments(
+            keys.iter().map(|(nf_sk, note)| note.commit(nf_sk.commit())),
+            config.stakes.iter().sum(),
+        );
+        let active_slot_coeff = config.ledger.consensus_config.active_slot_coeff;
+        let nodes = keys
+            .into_iter()
+            .enumerate()
+            .map(|(id, (nf_sk, note))| {
+                SimNode::new(
+                    id,
+                    GENESIS,
+                    genesis_state.clone(),
+                    config.ledger.clone(),
+                    Leader::new(GENESIS, vec![note], nf_sk, active_slot_coeff),
+                )
+            })
+            .collect();
+        Self {
+            nodes,
+            network: MockNetwork::new(config.delay),
+            prover,
+            rng,
+            slot: Slot::genesis() + 1,
+            blocks: Vec::new(),
+        }
+    }
+
+    pub fn nodes(&self) -> &[SimNode<P::Proof>] {
+        &self.nodes
+    }
+
+    pub fn network_mut(&mut self) -> &mut MockNetwork<Block<P::Proof>> {
+        &mut self.network
+    }
+
+    /// Every block proposed so far, in proposal order
+    pub fn blocks(&self) -> &[Block<P::Proof>] {
+        &self.blocks
+    }
+
+    /// Last slot that was run
+    pub fn current_slot(&self) -> Slot {
+        (u64::from(self.slot) - 1).into()
+    }
+
+    /// Elect the leaders of the next slot, then deliver the messages due by its end
+    pub fn step(&mut self) {
+        let slot = self.slot;
+        let n_nodes = self.nodes.len();
+        for node in &mut self.nodes {
+            if let Some(block) = node.try_propose(slot, &self.prover) {
+                self.network.broadcast(
+                    node.id(),
+                    n_nodes,
+                    slot.into(),
+                    block.clone(),
+                    &mut self.rng,
+                );
+                self.blocks.push(block);
+            }
+        }
+        self.deliver();
+    }
+
+    pub fn run(&mut self, slots: u64) {
+        for _ in 0..slots {
+            self.step();
+        }
+    }
+
+    /// Run without electing leaders until every message in flight is delivered
+    pub fn settle(&mut self) {
+        while self.network.in_flight() > 0 {
+            self.deliver();
+        }
+    }
+
+    /// Deliver the messages due by the end of the current slot and move to the next one
+    fn deliver(&mut self) {
+        for (to, block) in self.network.deliver(self.slot.into()) {
+            let id = block.id;
+            // blocks are only proposed by honest nodes, so they must all be valid
+            if let Err(e) = self.nodes[to].receive_block(block) {
+                panic!("node {to} rejected block {id:?}: {e}");
+            }
+        }
+        self.slot = self.slot + 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::properties::{chain_growth, chain_quality, common_prefix_violations, distinct_tips};
+
+    pub fn ledger_config(security_param: u32, active_slot_coeff: f64) -> nomos_ledger::Config {
+        nomos_ledger::Config {
+            epoch_stake_distribution_stabilization: 3,
+            epoch_period_nonce_buffer: 3,
+            epoch_period_nonce_stabilization: 4,
+            consensus_config: cryptarchia_engine::Config {
+                security_param,
+                active_slot_coeff,
+            },
+        }
+    }
+
+    fn config(seed: u64, delay: Delay) -> SimulationConfig {
+        SimulationConfig {
+            seed,
+            stakes: vec![10, 20, 30, 40],
+            ledger: ledger_config(5, 0.1),
+            delay,
+        }
+    }
+
+    #[test]
+    fn runs_are_reproducible() {
+        let run = |seed| {
+            let mut sim = Simulation::new(config(seed, Delay::Uniform { min: 0, max: 3 }));
+            sim.run(200);
+            sim.blocks()
+                .iter()
+                .map(|block| block.id)
+                .collect::<Vec<_>>()
+        };
+        let blocks = run(1);
+        assert!(!blocks.is_empty());
+        assert_eq!(blocks, run(1));
+        assert_ne!(blocks, run(2));
+    }
+
+    #[test]
+    fn common_prefix_and_chain_growth() {
+        let mut sim = Simulation::new(config(0, Delay::Uniform { min: 0, max: 2 }));
+        let k = 5;
+        for _ in 0..10 {
+            sim.run(50);
+            assert!(common_prefix_violations(sim.nodes(), k).is_empty());
+        }
+        // with f = 0.1, about 50 blocks are produced in 500 slots, minus the ones lost in forks
+        let growth = chain_growth(sim.nodes());
+        assert!(growth >= 25, "chain only grew by {growth} blocks");
+        sim.settle();
+        assert_eq!(distinct_tips(sim.nodes()), 1);
+    }
+
+    #[test]
+    fn forks_resolve_after_adversarial_delay() {
+        let mut sim = Simulation::new(SimulationConfig {
+            stakes: vec![35, 35, 15, 15],
+            ..config(3, Delay::Fixed(0))
+        });
+        sim.run(20);
+        let fork_height = chain_growth(sim.nodes());
+
+        // the adversary holds back the blocks between the two sides of the network for longer
+        // than it takes to fork deeper than k, so that the density rule has to settle it
+        let (majority, minority) = ([0, 1], [2, 3]);
+        for a in majority {
+            for b in minority {
+                sim.network_mut().set_delay(a, b, Delay::Fixed(100));
+                sim.network_mut().set_delay(b, a, Delay::Fixed(100));
+            }
+        }
+        sim.run(100);
+        assert!(distinct_tips(sim.nodes()) >= 2);
+        assert!(!common_prefix_violations(sim.nodes(), 5).is_empty());
+
+        // once the held back blocks are delivered, every node follows the denser chain
+        sim.network_mut().clear_delays();
+        sim.run(100);
+        sim.settle();
+        assert_eq!(distinct_tips(sim.nodes()), 1);
+        let chain = sim.nodes()[2].chain();
+        let quality = chain_quality(&chain[fork_height..], sim.blocks(), &majority);
+        assert!(quality > 0.5, "minority chain was selected: {quality}");
+    }
+}
@@ -0,0 +1,143 @@
+// std
+use std::collections::{BTreeMap, HashMap};
+// crates
+use rand::Rng;
+// internal
+use crate::NodeId;
+
+/// Number of slots a message takes to reach a node
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Delay {
+    /// Delivered after exactly this many slots. Messages delayed by 0 slots are delivered at the
+    /// end of the slot they were sent in, before the next leaders are elected.
+    Fixed(u64),
+    /// Delivered after a number of slots drawn uniformly between `min` and `max` included
+    Uniform { min: u64, max: u64 },
+}
+
+impl Default for Delay {
+    fn default() -> Self {
+        Self::Fixed(0)
+    }
+}
+
+impl Delay {
+    fn sample<R: Rng>(&self, rng: &mut R) -> u64 {
+        match *self {
+            Self::Fixed(delay) => delay,
+            Self::Uniform { min, max } => rng.gen_range(min..=max),
+        }
+    }
+}
+
+/// Gossip layer broadcasting messages to every node, with a delay for each of them.
+///
+/// Messages due in the same slot are delivered one at a time in the order they were sent, so that a
+/// run only depends on the seed of the simulation. Each block then brings a single new chain to the
+/// fork choice of its receiver, which only moves to a strictly better chain, so chains of equal
+/// length are settled in favour of the one received first, whatever order the engine visits them in.
+pub struct MockNetwork<M> {
+    delay: Delay,
+    /// Delays overriding the default one, by sender and receiver
+    links: HashMap<(NodeId, NodeId), Delay>,
+    /// Messages by delivery slot and sequence number
+    queue: BTreeMap<(u64, u64), (NodeId, M)>,
+    sent: u64,
+}
+
+impl<M: Clone> MockNetwork<M> {
+    pub fn new(delay: Delay) -> Self {
+        Self {
+            delay,
+            links: HashMap::new(),
+            queue: BTreeMap::new(),
+            sent: 0,
+        }
+    }
+
+    /// Override the delay of the messages sent by `from` to `to`
+    pub fn set_delay(&mut self, from: NodeId, to: NodeId, delay: Delay) {
+        self.links.insert((from, to), delay);
+    }
+
+    pub fn clear_delay(&mut self, from: NodeId, to: NodeId) {
+        self.links.remove(&(from, to));
+    }
+
+    pub fn clear_delays(&mut self) {
+        self.links.clear();
+    }
+
+    /// Send a message to every node but its sender
+    pub fn broadcast<R: Rng>(
+        &mut self,
+        from: NodeId,
+        n_nodes: usize,
+        slot: u64,
+        message: M,
+        rng: &mut R,
+    ) {
+        for to in (0..n_nodes).filter(|to| *to != from) {
+            let delay = self.links.get(&(from, to)).unwrap_or(&self.delay);
+            let delivery_slot = slot + delay.sample(rng);
+            self.queue
+                .insert((delivery_slot, self.sent), (to, message.clone()));
+            self.sent += 1;
+        }
+    }
+
+    /// Remove the messages due by the end of `slot` from the queue, in delivery order
+    pub fn deliver(&mut self, slot: u64) -> Vec<(NodeId, M)> {
+        let pending = self.queue.split_off(&(slot + 1, 0));
+        std::mem::replace(&mut self.queue, pending)
+            .into_values()
+            .collect()
+    }
+
+    /// Number of messages not delivered yet
+    pub fn in_flight(&self) -> usize {
+        self.queue.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    use super::*;
+
+    #[test]
+    fn messages_are_delivered_after_their_delay() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let mut network = MockNetwork::new(Delay::Fixed(1));
+        network.set_delay(0, 2, Delay::Fixed(3));
+
+        network.broadcast(0, 3, 10, "a", &mut rng);
+        network.broadcast(1, 3, 10, "b", &mut rng);
+        assert_eq!(network.in_flight(), 4);
+
+        assert!(network.deliver(10).is_empty());
+        assert_eq!(network.deliver(11), vec![(1, "a"), (0, "b"), (2, "b")]);
+        assert!(network.deliver(12).is_empty());
+        assert_eq!(network.deliver(13), vec![(2, "a")]);
+        assert_eq!(network.in_flight(), 0);
+    }
+
+    #[test]
+    fn uniform_delays_stay_in_range() {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let mut network = MockNetwork::new(Delay::Uniform { min: 2, max: 4 });
+        for slot in 0..100 {
+            network.broadcast(0, 2, slot, slot, &mut rng);
+        }
+        let mut delivered = 0;
+        for slot in 0..110 {
+            for (_, sent) in network.deliver(slot) {
+                assert!((sent + 2..=sent + 4).contains(&slot));
+                delivered += 1;
+            }
+        }
+        assert_eq!(delivered, 100);
+    }
+}
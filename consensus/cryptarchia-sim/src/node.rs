@@ -0,0 +1,167 @@
+// std
+use std::collections::{HashMap, VecDeque};
+// crates
+use blake2::{digest::typenum::U32, Digest};
+use cryptarchia_engine::{Cryptarchia, Slot};
+use nomos_ledger::{leader_proof::LeaderProof, EpochState, Ledger, LedgerError, LedgerState};
+use thiserror::Error;
+// internal
+use crate::leader::{Leader, Prover};
+use crate::{HeaderId, NodeId};
+
+type Blake2b = blake2::Blake2b<U32>;
+
+#[derive(Clone, Debug)]
+pub struct Block<P> {
+    pub id: HeaderId,
+    pub parent: HeaderId,
+    pub slot: Slot,
+    pub proposer: NodeId,
+    pub proof: P,
+}
+
+impl<P: LeaderProof> Block<P> {
+    fn new(parent: HeaderId, slot: Slot, proposer: NodeId, proof: P) -> Self {
+        let id = Blake2b::new()
+            .chain_update(parent)
+            .chain_update(slot.to_be_bytes())
+            .chain_update(proof.nullifier().as_bytes())
+            .chain_update(proof.evolved_commitment().as_bytes())
+            .finalize()
+            .into();
+        Self {
+            id,
+            parent,
+            slot,
+            proposer,
+            proof,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Error)]
+pub enum Error {
+    #[error("Ledger error: {0}")]
+    Ledger(#[from] LedgerError<HeaderId>),
+    #[error("Consensus error: {0}")]
+    Consensus(#[from] cryptarchia_engine::Error<HeaderId>),
+}
+
+/// A node running the consensus and ledger rules in memory
+pub struct SimNode<P> {
+    id: NodeId,
+    consensus: Cryptarchia<HeaderId>,
+    ledger: Ledger<HeaderId>,
+    leader: Leader,
+    /// Blocks received before their parent, by parent
+    pending: HashMap<HeaderId, Vec<Block<P>>>,
+}
+
+impl<P: LeaderProof + Clone> SimNode<P> {
+    pub fn new(
+        id: NodeId,
+        genesis: HeaderId,
+        genesis_state: LedgerState,
+        config: nomos_ledger::Config,
+        leader: Leader,
+    ) -> Self {
+        Self {
+            id,
+            consensus: Cryptarchia::from_genesis(genesis, config.consensus_config.clone()),
+            ledger: Ledger::from_genesis(genesis, genesis_state, config),
+            leader,
+            pending: HashMap::new(),
+        }
+    }
+
+    pub fn id(&self) -> NodeId {
+        self.id
+    }
+
+    pub fn tip(&self) -> HeaderId {
+        self.consensus.tip()
+    }
+
+    pub fn consensus(&self) -> &Cryptarchia<HeaderId> {
+        &self.consensus
+    }
+
+    pub fn ledger(&self) -> &Ledger<HeaderId> {
+        &self.ledger
+    }
+
+    /// Ids of the blocks of the local chain, from the first block after genesis to the tip
+    pub fn chain(&self) -> Vec<HeaderId> {
+        let branches = self.consensus.branches();
+        let mut chain = Vec::new();
+        let mut id = self.tip();
+        while id != self.consensus.genesis() {
+            chain.push(id);
+            id = branches.get(&id).expect("local chain is known").parent();
+        }
+        chain.reverse();
+        chain
+    }
+
+    fn epoch_state_for_slot(&self, slot: Slot) -> Option<&EpochState> {
+        let state = self.ledger.state(&self.tip()).expect("no state for tip");
+        let requested_epoch = self.ledger.config().epoch(slot);
+        if state.epoch_state().epoch() == requested_epoch {
+            Some(state.epoch_state())
+        } else if requested_epoch == state.next_epoch_state().epoch() {
+            Some(state.next_epoch_state())
+        } else {
+            None
+        }
+    }
+
+    /// Propose a block on top of the local tip if one of our notes wins the slot
+    pub fn try_propose<Pr>(&mut self, slot: Slot, prover: &Pr) -> Option<Block<P>>
+    where
+        Pr: Prover<Proof = P>,
+    {
+        let parent = self.tip();
+        let note_tree = self.ledger.state(&parent)?.lead_commitments();
+        let epoch_state = self.epoch_state_for_slot(slot)?;
+        let (public, private) = self.leader.try_win(note_tree, epoch_state, slot, parent)?;
+        let block = Block::new(parent, slot, self.id, prover.prove(public, private)?);
+        self.receive_block(block.clone())
+            .expect("a block proposed on the local tip should be valid");
+        Some(block)
+    }
+
+    /// Apply a block, or keep it until its parent is received
+    pub fn receive_block(&mut self, block: Block<P>) -> Result<(), Error> {
+        if self.ledger.state(&block.id).is_some() {
+            return Ok(());
+        }
+        if self.ledger.state(&block.parent).is_none() {
+            self.pending.entry(block.parent).or_default().push(block);
+            return Ok(());
+        }
+        // children are applied in the order they were received
+        let mut ready = VecDeque::from([block]);
+        while let Some(block) = ready.pop_front() {
+            self.apply_block(&block)?;
+            ready.extend(self.pending.remove(&block.id).unwrap_or_default());
+        }
+        Ok(())
+    }
+
+    fn apply_block(&mut self, block: &Block<P>) -> Result<(), Error> {
+        self.ledger =
+            self.ledger
+                .try_update(block.id, block.parent, block.slot, &block.proof, [])?;
+        self.consensus = self
+            .consensus
+            .receive_block(block.id, block.parent, block.slot)?;
+        self.leader
+            .follow_chain(block.parent, block.id, block.proof.nullifier());
+        Ok(())
+    }
+
+    /// Number of blocks received whose parent is still unknown
+    pub fn pending(&self) -> usize {
+        self.pending.values().map(Vec::len).sum()
+    }
+}
@@ -0,0 +1,64 @@
+//! Properties of the chains followed by the simulated nodes, as defined in the Ouroboros
+//! papers.
+
+// std
+use std::collections::{HashMap, HashSet};
+// crates
+// internal
+use crate::node::{Block, SimNode};
+use crate::{HeaderId, NodeId};
+
+/// Pairs of nodes `(a, b)` for which the chain of `a`, without its last `k` blocks, is not a
+/// prefix of the chain of `b`
+pub fn common_prefix_violations<P>(nodes: &[SimNode<P>], k: usize) -> Vec<(NodeId, NodeId)>
+where
+    P: nomos_ledger::leader_proof::LeaderProof + Clone,
+{
+    let chains: Vec<_> = nodes.iter().map(SimNode::chain).collect();
+    let mut violations = Vec::new();
+    for (a, chain_a) in chains.iter().enumerate() {
+        let prefix = &chain_a[..chain_a.len().saturating_sub(k)];
+        for (b, chain_b) in chains.iter().enumerate() {
+            if a != b && !chain_b.starts_with(prefix) {
+                violations.push((a, b));
+            }
+        }
+    }
+    violations
+}
+
+/// Length of the shortest chain followed by a node
+pub fn chain_growth<P>(nodes: &[SimNode<P>]) -> usize
+where
+    P: nomos_ledger::leader_proof::LeaderProof + Clone,
+{
+    nodes
+        .iter()
+        .map(|node| node.chain().len())
+        .min()
+        .unwrap_or_default()
+}
+
+/// Number of different tips followed by the nodes
+pub fn distinct_tips<P>(nodes: &[SimNode<P>]) -> usize
+where
+    P: nomos_ledger::leader_proof::LeaderProof + Clone,
+{
+    nodes.iter().map(SimNode::tip).collect::<HashSet<_>>().len()
+}
+
+/// Fraction of the blocks of `chain` proposed by nodes of `group`
+pub fn chain_quality<P>(chain: &[HeaderId], blocks: &[Block<P>], group: &[NodeId]) -> f64 {
+    if chain.is_empty() {
+        return 0.0;
+    }
+    let proposers: HashMap<_, _> = blocks
+        .iter()
+        .map(|block| (block.id, block.proposer))
+        .collect();
+    let from_group = chain
+        .iter()
+        .filter(|id| group.contains(&proposers[*id]))
+        .count();
+    from_group as f64 / chain.len() as f64
+}
@@ -80,6 +80,11 @@ impl EpochState {
     pub fn total_stake(&self) -> Value {
         self.total_stake
     }
+
+    /// Commitments of the notes holding stake in this epoch
+    pub fn stake_distribution(&self) -> &NoteTree {
+        &self.commitments
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
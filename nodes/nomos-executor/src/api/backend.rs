@@ -9,6 +9,7 @@ use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use tower_http::{
     cors::{Any, CorsLayer},
     trace::TraceLayer,
+    validate_request::ValidateRequestHeaderLayer,
 };
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
@@ -27,12 +28,15 @@ use nomos_da_verifier::backend::VerifierBackend;
 use nomos_libp2p::PeerId;
 use nomos_mempool::{tx::service::openapi::Status, MempoolMetrics};
 use nomos_node::api::handlers::{
-    add_blob, add_blob_info, add_tx, block, cl_metrics, cl_status, cryptarchia_headers,
-    cryptarchia_info, get_range, libp2p_info,
+    add_blob, add_blob_info, add_tx, blob_location, block, block_by_height, blocks, cl_metrics,
+    cl_status, cryptarchia_headers, cryptarchia_info, da_metrics, events_blocks,
+    events_da_sampling, events_mempool_blob_info, events_mempool_tx, events_tips, get_range,
+    health, libp2p_info, metrics, ready, reset_tracing_filter, set_tracing_filter, tracing_filter,
+    tx_location,
 };
 use nomos_storage::backends::StorageSerde;
 use overwatch_rs::overwatch::handle::OverwatchHandle;
-use subnetworks_assignations::MembershipHandler;
+use subnetworks_assignations::{MembershipCreator, MembershipHandler};
 
 /// Configuration for the Http Server
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
@@ -41,6 +45,9 @@ pub struct AxumBackendSettings {
     pub address: std::net::SocketAddr,
     /// Allowed origins for this server deployment requests.
     pub cors_origins: Vec<String>,
+    /// Bearer token required by the administrative endpoints, which are not served if unset.
+    #[serde(default)]
+    pub admin_token: Option<String>,
 }
 
 pub struct AxumBackend<
@@ -150,7 +157,8 @@ where
         + Sync
         + 'static,
     <DaBlobInfo as DispersedBlobInfo>::BlobId: Clone + Send + Sync,
-    Membership: MembershipHandler<NetworkId = SubnetworkId, Id = PeerId>
+    Membership: MembershipCreator
+        + MembershipHandler<NetworkId = SubnetworkId, Id = PeerId>
         + Clone
         + Debug
         + Send
@@ -243,7 +251,7 @@ where
             );
         }
 
-        let app = Router::new()
+        let mut app = Router::new()
             .layer(
                 builder
                     .allow_headers([CONTENT_TYPE, USER_AGENT])
@@ -251,8 +259,23 @@ where
             )
             .layer(TraceLayer::new_for_http())
             .merge(SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", ApiDoc::openapi()))
+            .route(paths::HEALTH, routing::get(health))
+            .route(paths::READY, routing::get(ready))
+            .route(paths::METRICS, routing::get(metrics))
             .route(paths::CL_METRICS, routing::get(cl_metrics::<Tx>))
             .route(paths::CL_STATUS, routing::post(cl_status::<Tx>))
+            .route(
+                paths::DA_METRICS,
+                routing::get(
+                    da_metrics::<
+                        DaVerifiedBlobInfo,
+                        SamplingBackend,
+                        SamplingNetworkAdapter,
+                        SamplingRng,
+                        SamplingStorage,
+                    >,
+                ),
+            )
             .route(
                 paths::CRYPTARCHIA_INFO,
                 routing::get(
@@ -314,6 +337,22 @@ where
                 paths::STORAGE_BLOCK,
                 routing::post(block::<DaStorageSerializer, Tx>),
             )
+            .route(
+                paths::STORAGE_BLOCK_BY_HEIGHT,
+                routing::get(block_by_height::<DaStorageSerializer, Tx>),
+            )
+            .route(
+                paths::STORAGE_BLOCKS,
+                routing::get(blocks::<DaStorageSerializer, Tx>),
+            )
+            .route(
+                paths::STORAGE_TX_LOCATION,
+                routing::post(tx_location::<DaStorageSerializer, Tx>),
+            )
+            .route(
+                paths::STORAGE_BLOB_LOCATION,
+                routing::post(blob_location::<DaStorageSerializer>),
+            )
             .route(paths::MEMPOOL_ADD_TX, routing::post(add_tx::<Tx>))
             .route(
                 paths::MEMPOOL_ADD_BLOB_INFO,
@@ -327,6 +366,61 @@ where
                     >,
                 ),
             )
+            .route(
+                paths::EVENTS_BLOCKS,
+                routing::get(
+                    events_blocks::<
+                        Tx,
+                        DaStorageSerializer,
+                        SamplingBackend,
+                        SamplingNetworkAdapter,
+                        SamplingRng,
+                        SamplingStorage,
+                        SIZE,
+                    >,
+                ),
+            )
+            .route(
+                paths::EVENTS_TIPS,
+                routing::get(
+                    events_tips::<
+                        Tx,
+                        DaStorageSerializer,
+                        SamplingBackend,
+                        SamplingNetworkAdapter,
+                        SamplingRng,
+                        SamplingStorage,
+                        SIZE,
+                    >,
+                ),
+            )
+            .route(
+                paths::EVENTS_MEMPOOL_TX,
+                routing::get(events_mempool_tx::<Tx>),
+            )
+            .route(
+                paths::EVENTS_MEMPOOL_BLOB_INFO,
+                routing::get(
+                    events_mempool_blob_info::<
+                        DaVerifiedBlobInfo,
+                        SamplingBackend,
+                        SamplingNetworkAdapter,
+                        SamplingRng,
+                        SamplingStorage,
+                    >,
+                ),
+            )
+            .route(
+                paths::EVENTS_DA_SAMPLING,
+                routing::get(
+                    events_da_sampling::<
+                        SamplingBackend,
+                        SamplingNetworkAdapter,
+                        SamplingRng,
+                        SamplingStorage,
+                    >,
+                ),
+            )
             .route(
                 paths::DISPERSE_DATA,
                 routing::post(
@@ -338,8 +432,21 @@ where
                         Metadata,
                     >,
                 ),
-            )
-            .with_state(handle);
+            );
+
+        if let Some(token) = &self.settings.admin_token {
+            app = app.merge(
+                Router::new()
+                    .route(
+                        paths::TRACING_FILTER,
+                        routing::get(tracing_filter)
+                            .post(set_tracing_filter)
+                            .delete(reset_tracing_filter),
+                    )
+                    .route_layer(ValidateRequestHeaderLayer::bearer(token)),
+            );
+        }
+        let app = app.with_state(handle);
 
         Server::bind(&self.settings.address)
             .serve(app.into_make_service())
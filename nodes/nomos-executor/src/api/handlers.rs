@@ -27,7 +27,7 @@ pub struct DispersalRequest<Metadata> {
     post,
     path = paths::DISPERSE_DATA,
     responses(
-        (status = 200, description = "Disperse data in DA network"),
+        (status = 200, description = "Disperse data in DA network as a single blob, returns the id of the dispersed blob. Data is not chunked, it has to fit in a blob with the encoder settings of the executor; `nomos-cli disseminate` splits larger files in chunks described by a manifest", body = [u8;32]),
         (status = 500, description = "Internal server error", body = String),
     )
 )]
@@ -8,7 +8,11 @@ use nomos_mix_service::network::libp2p::Libp2pAdapter as MixNetworkAdapter;
 use nomos_mix_service::MixService;
 use nomos_network::backends::libp2p::Libp2p as NetworkBackend;
 use nomos_node::{
-    config::{update_cryptarchia_consensus, update_mix, update_network, update_tracing, MixArgs},
+    config::{
+        check_secret_keys, update_cryptarchia_consensus, update_cryptarchia_from_keystore,
+        update_mix, update_mix_from_keystore, update_network, update_tracing, KeystoreConfig,
+        MixArgs,
+    },
     CryptarchiaArgs, HttpArgs, LogArgs, NetworkArgs, NetworkService, Tracing, Wire,
 };
 use nomos_storage::backends::rocksdb::RocksBackend;
@@ -29,12 +33,41 @@ pub struct Config {
     pub da_indexer: <crate::ExecutorDaIndexer as ServiceData>::Settings,
     pub da_verifier: <crate::ExecutorDaVerifier as ServiceData>::Settings,
     pub da_sampling: <crate::ExecutorDaSampling as ServiceData>::Settings,
+    #[serde(default)]
+    pub da_membership: <crate::ExecutorDaMembership as ServiceData>::Settings,
     pub http: <ExecutorApiService as ServiceData>::Settings,
     pub cryptarchia: <crate::ExecutorCryptarchia as ServiceData>::Settings,
     pub storage: <crate::StorageService<RocksBackend<Wire>> as ServiceData>::Settings,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keystore: Option<KeystoreConfig>,
 }
 
 impl Config {
+    /// Replaces the keys of the services with the ones named in the keystore config, if any,
+    /// and fails if a secret key was neither in the settings nor in the keystore
+    pub fn update_from_keystore(mut self) -> Result<Self> {
+        if let Some(keystore) = &self.keystore {
+            let keys = keystore.load()?;
+            if let Some(node_key) = keys.network {
+                self.network.backend.inner.node_key = node_key;
+            }
+            if let Some(node_key) = keys.da_network {
+                self.da_network.backend.validator_settings.node_key = node_key;
+            }
+            if let Some(sk) = keys.da_verifier {
+                self.da_verifier.verifier_settings.sk = Some(sk);
+            }
+            update_mix_from_keystore(&mut self.mix, &keys);
+            update_cryptarchia_from_keystore(&mut self.cryptarchia, &keys);
+        }
+        check_secret_keys(
+            self.da_verifier.verifier_settings.sk.as_deref(),
+            &self.mix,
+            &self.cryptarchia,
+        )?;
+        Ok(self)
+    }
+
     pub fn update_from_args(
         mut self,
         log_args: LogArgs,
@@ -26,9 +26,9 @@ use nomos_node::HeaderId;
 use nomos_node::MempoolNetworkAdapter;
 use nomos_node::NetworkBackend;
 use nomos_node::{
-    BlobInfo, Cryptarchia, DaIndexer, DaMempool, DaNetworkService, DaSampling, DaVerifier,
-    NetworkService, NomosDaMembership, RocksBackend, StorageService, SystemSig, Tracing, Tx,
-    TxMempool, Wire, MB16,
+    BlobInfo, Cryptarchia, DaIndexer, DaMembership, DaMempool, DaNetworkService, DaSampling,
+    DaVerifier, NetworkService, NomosDaMembership, RocksBackend, StatusService, StorageService,
+    SystemSig, Tracing, Tx, TxMempool, Wire, MB16,
 };
 use overwatch_derive::Services;
 use overwatch_rs::services::handle::ServiceHandle;
@@ -83,6 +83,9 @@ pub type ExecutorDaSampling =
 
 pub type ExecutorDaVerifier = DaVerifier<VerifierNetworkAdapter<NomosDaMembership>>;
 
+pub type ExecutorDaMembership =
+    DaMembership<DaNetworkExecutorBackend<NomosDaMembership>, ExecutorCryptarchia>;
+
 #[derive(Services)]
 pub struct NomosExecutor {
     #[cfg(feature = "tracing")]
@@ -94,10 +97,12 @@ pub struct NomosExecutor {
     da_verifier: ServiceHandle<ExecutorDaVerifier>,
     da_sampling: ServiceHandle<ExecutorDaSampling>,
     da_network: ServiceHandle<DaNetworkService<DaNetworkExecutorBackend<NomosDaMembership>>>,
+    da_membership: ServiceHandle<ExecutorDaMembership>,
     cl_mempool: ServiceHandle<TxMempool>,
     da_mempool: ServiceHandle<DaMempool>,
     cryptarchia: ServiceHandle<ExecutorCryptarchia>,
     http: ServiceHandle<ExecutorApiService>,
     storage: ServiceHandle<StorageService<RocksBackend<Wire>>>,
+    status: ServiceHandle<StatusService>,
     system_sig: ServiceHandle<SystemSig>,
 }
@@ -45,6 +45,7 @@ fn main() -> Result<()> {
         cryptarchia_args,
     } = Args::parse();
     let config = serde_yaml::from_reader::<_, ExecutorConfig>(std::fs::File::open(config)?)?
+        .update_from_keystore()?
         .update_from_args(
             log_args,
             network_args,
@@ -83,11 +84,13 @@ fn main() -> Result<()> {
             },
             da_dispersal: config.da_dispersal,
             da_network: config.da_network,
+            da_membership: config.da_membership,
             da_indexer: config.da_indexer,
             da_sampling: config.da_sampling,
             da_verifier: config.da_verifier,
             cryptarchia: config.cryptarchia,
             storage: config.storage,
+            status: (),
             system_sig: (),
         },
         None,
@@ -0,0 +1,208 @@
+// std
+use std::{fmt::Debug, hash::Hash};
+// crates
+use axum::{http::HeaderValue, routing, Router, Server};
+use hyper::header::{CONTENT_TYPE, USER_AGENT};
+use rand::{RngCore, SeedableRng};
+use serde::{de::DeserializeOwned, Serialize};
+use tower_http::{
+    cors::{Any, CorsLayer},
+    trace::TraceLayer,
+    validate_request::ValidateRequestHeaderLayer,
+};
+use utoipa::OpenApi;
+use utoipa_swagger_ui::SwaggerUi;
+// internal
+use super::handlers::light_availability;
+use crate::api::paths;
+use nomos_api::Backend;
+use nomos_core::da::blob::info::DispersedBlobInfo;
+use nomos_core::da::BlobId;
+use nomos_core::tx::Transaction;
+use nomos_da_network_service::backends::libp2p::validator::DaNetworkValidatorBackend;
+use nomos_da_sampling::backend::DaSamplingServiceBackend;
+use nomos_node::api::handlers::{
+    events_da_sampling, health, libp2p_info, metrics, ready, reset_tracing_filter,
+    set_tracing_filter, tracing_filter,
+};
+use nomos_node::NomosDaMembership;
+use nomos_storage::backends::StorageSerde;
+use overwatch_rs::overwatch::handle::OverwatchHandle;
+
+/// Configuration for the Http Server
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct AxumBackendSettings {
+    /// Socket where the server will be listening on for incoming requests.
+    pub address: std::net::SocketAddr,
+    /// Allowed origins for this server deployment requests.
+    pub cors_origins: Vec<String>,
+    /// Bearer token required by the administrative endpoints, which are not served if unset.
+    #[serde(default)]
+    pub admin_token: Option<String>,
+}
+
+pub struct AxumBackend<
+    Tx,
+    DaBlobInfo,
+    DaStorageSerializer,
+    SamplingBackend,
+    SamplingNetworkAdapter,
+    SamplingRng,
+    SamplingStorage,
+> {
+    settings: AxumBackendSettings,
+    #[allow(clippy::type_complexity)]
+    _phantom: core::marker::PhantomData<(
+        Tx,
+        DaBlobInfo,
+        DaStorageSerializer,
+        SamplingBackend,
+        SamplingNetworkAdapter,
+        SamplingRng,
+        SamplingStorage,
+    )>,
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+    ),
+    tags(
+        (name = "da", description = "data availibility related APIs")
+    )
+)]
+struct ApiDoc;
+
+#[async_trait::async_trait]
+impl<
+        Tx,
+        DaBlobInfo,
+        DaStorageSerializer,
+        SamplingBackend,
+        SamplingNetworkAdapter,
+        SamplingRng,
+        SamplingStorage,
+    > Backend
+    for AxumBackend<
+        Tx,
+        DaBlobInfo,
+        DaStorageSerializer,
+        SamplingBackend,
+        SamplingNetworkAdapter,
+        SamplingRng,
+        SamplingStorage,
+    >
+where
+    Tx: Transaction
+        + Clone
+        + Debug
+        + Eq
+        + Hash
+        + Serialize
+        + DeserializeOwned
+        + Send
+        + Sync
+        + 'static,
+    DaBlobInfo: DispersedBlobInfo<BlobId = [u8; 32]>
+        + Clone
+        + Eq
+        + Hash
+        + Serialize
+        + DeserializeOwned
+        + Send
+        + Sync
+        + 'static,
+    DaStorageSerializer: StorageSerde + Send + Sync + 'static,
+    SamplingRng: SeedableRng + RngCore + Send + 'static,
+    SamplingBackend: DaSamplingServiceBackend<SamplingRng, BlobId = BlobId> + Send + 'static,
+    SamplingBackend::Settings: Clone,
+    SamplingBackend::Blob: Debug + 'static,
+    SamplingNetworkAdapter: nomos_da_sampling::network::NetworkAdapter + Send + 'static,
+    SamplingStorage: nomos_da_sampling::storage::DaStorageAdapter + Send + 'static,
+{
+    type Error = hyper::Error;
+    type Settings = AxumBackendSettings;
+
+    async fn new(settings: Self::Settings) -> Result<Self, Self::Error>
+    where
+        Self: Sized,
+    {
+        Ok(Self {
+            settings,
+            _phantom: core::marker::PhantomData,
+        })
+    }
+
+    async fn serve(self, handle: OverwatchHandle) -> Result<(), Self::Error> {
+        let mut builder = CorsLayer::new();
+        if self.settings.cors_origins.is_empty() {
+            builder = builder.allow_origin(Any);
+        }
+
+        for origin in &self.settings.cors_origins {
+            builder = builder.allow_origin(
+                origin
+                    .as_str()
+                    .parse::<HeaderValue>()
+                    .expect("fail to parse origin"),
+            );
+        }
+
+        let mut app = Router::new()
+            .layer(
+                builder
+                    .allow_headers([CONTENT_TYPE, USER_AGENT])
+                    .allow_methods(Any),
+            )
+            .layer(TraceLayer::new_for_http())
+            .merge(SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", ApiDoc::openapi()))
+            .route(paths::HEALTH, routing::get(health))
+            .route(paths::READY, routing::get(ready))
+            .route(paths::METRICS, routing::get(metrics))
+            .route(paths::NETWORK_INFO, routing::get(libp2p_info))
+            .route(
+                paths::EVENTS_DA_SAMPLING,
+                routing::get(
+                    events_da_sampling::<
+                        SamplingBackend,
+                        SamplingNetworkAdapter,
+                        SamplingRng,
+                        SamplingStorage,
+                    >,
+                ),
+            )
+            .route(
+                paths::DA_LIGHT_AVAILABILITY,
+                routing::post(
+                    light_availability::<
+                        Tx,
+                        DaBlobInfo,
+                        DaStorageSerializer,
+                        SamplingBackend,
+                        SamplingNetworkAdapter,
+                        SamplingRng,
+                        SamplingStorage,
+                        DaNetworkValidatorBackend<NomosDaMembership>,
+                    >,
+                ),
+            );
+
+        if let Some(token) = &self.settings.admin_token {
+            app = app.merge(
+                Router::new()
+                    .route(
+                        paths::TRACING_FILTER,
+                        routing::get(tracing_filter)
+                            .post(set_tracing_filter)
+                            .delete(reset_tracing_filter),
+                    )
+                    .route_layer(ValidateRequestHeaderLayer::bearer(token)),
+            );
+        }
+        let app = app.with_state(handle);
+
+        Server::bind(&self.settings.address)
+            .serve(app.into_make_service())
+            .await
+    }
+}
@@ -0,0 +1,81 @@
+// std
+use std::fmt::Debug;
+use std::hash::Hash;
+// crates
+use axum::{extract::State, response::Response, Json};
+use rand::{RngCore, SeedableRng};
+use serde::{de::DeserializeOwned, Serialize};
+// internal
+use super::paths;
+use nomos_api::http::da;
+use nomos_core::da::blob::info::DispersedBlobInfo;
+use nomos_core::da::BlobId;
+use nomos_core::header::HeaderId;
+use nomos_core::tx::Transaction;
+use nomos_da_network_service::backends::NetworkBackend as DaNetworkBackend;
+use nomos_da_sampling::backend::DaSamplingServiceBackend;
+use nomos_node::make_request_and_return_response;
+use nomos_storage::backends::StorageSerde;
+use overwatch_rs::overwatch::handle::OverwatchHandle;
+
+#[utoipa::path(
+    post,
+    path = paths::DA_LIGHT_AVAILABILITY,
+    responses(
+        (status = 200, description = "Sampling result of the blobs referenced by the block, null if the block was not seen"),
+        (status = 500, description = "Internal server error", body = String),
+    )
+)]
+pub async fn light_availability<
+    Tx,
+    C,
+    SS,
+    SamplingBackend,
+    SamplingNetworkAdapter,
+    SamplingRng,
+    SamplingStorage,
+    DaNetwork,
+>(
+    State(handle): State<OverwatchHandle>,
+    Json(id): Json<HeaderId>,
+) -> Response
+where
+    Tx: Transaction
+        + Eq
+        + Clone
+        + Debug
+        + Hash
+        + Serialize
+        + DeserializeOwned
+        + Send
+        + Sync
+        + 'static,
+    C: DispersedBlobInfo<BlobId = [u8; 32]>
+        + Clone
+        + Eq
+        + Hash
+        + Serialize
+        + DeserializeOwned
+        + Send
+        + Sync
+        + 'static,
+    SS: StorageSerde + Send + Sync + 'static,
+    SamplingRng: SeedableRng + RngCore,
+    SamplingBackend: DaSamplingServiceBackend<SamplingRng, BlobId = BlobId> + Send,
+    SamplingBackend::Settings: Clone,
+    SamplingBackend::Blob: Debug + 'static,
+    SamplingNetworkAdapter: nomos_da_sampling::network::NetworkAdapter,
+    SamplingStorage: nomos_da_sampling::storage::DaStorageAdapter,
+    DaNetwork: DaNetworkBackend + Send + 'static,
+{
+    make_request_and_return_response!(da::light_availability::<
+        Tx,
+        C,
+        SS,
+        SamplingBackend,
+        SamplingNetworkAdapter,
+        SamplingRng,
+        SamplingStorage,
+        DaNetwork,
+    >(&handle, id))
+}
@@ -0,0 +1,3 @@
+pub mod backend;
+pub mod handlers;
+pub mod paths;
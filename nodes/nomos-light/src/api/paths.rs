@@ -0,0 +1,3 @@
+pub use nomos_node::api::paths::*;
+
+pub const DA_LIGHT_AVAILABILITY: &str = "/da/light/availability";
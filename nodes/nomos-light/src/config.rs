@@ -0,0 +1,62 @@
+// std
+// crates
+use color_eyre::eyre::Result;
+use nomos_da_network_service::backends::libp2p::validator::DaNetworkValidatorBackend;
+use nomos_da_network_service::NetworkService as DaNetworkService;
+use nomos_network::backends::libp2p::Libp2p as NetworkBackend;
+use nomos_node::{
+    config::{update_network, update_tracing},
+    HttpArgs, LogArgs, NetworkArgs, NetworkService, Tracing, Wire,
+};
+use nomos_storage::backends::rocksdb::RocksBackend;
+use overwatch_rs::services::ServiceData;
+use serde::{Deserialize, Serialize};
+use subnetworks_assignations::versions::v1::FillFromNodeList;
+// internal
+use crate::LightApiService;
+
+#[derive(Deserialize, Debug, Clone, Serialize)]
+pub struct Config {
+    pub tracing: <Tracing as ServiceData>::Settings,
+    pub network: <NetworkService<NetworkBackend> as ServiceData>::Settings,
+    pub da_network:
+        <DaNetworkService<DaNetworkValidatorBackend<FillFromNodeList>> as ServiceData>::Settings,
+    pub da_sampling: <crate::LightDaSampling as ServiceData>::Settings,
+    pub da_light: <crate::DaLight as ServiceData>::Settings,
+    pub http: <LightApiService as ServiceData>::Settings,
+    pub storage: <crate::StorageService<RocksBackend<Wire>> as ServiceData>::Settings,
+}
+
+impl Config {
+    pub fn update_from_args(
+        mut self,
+        log_args: LogArgs,
+        network_args: NetworkArgs,
+        http_args: HttpArgs,
+    ) -> Result<Self> {
+        update_tracing(&mut self.tracing, log_args)?;
+        update_network(&mut self.network, network_args)?;
+        update_http(&mut self.http, http_args)?;
+        Ok(self)
+    }
+}
+
+pub fn update_http(
+    http: &mut <LightApiService as ServiceData>::Settings,
+    http_args: HttpArgs,
+) -> Result<()> {
+    let HttpArgs {
+        http_addr,
+        cors_origins,
+    } = http_args;
+
+    if let Some(addr) = http_addr {
+        http.backend_settings.address = addr;
+    }
+
+    if let Some(cors) = cors_origins {
+        http.backend_settings.cors_origins = cors;
+    }
+
+    Ok(())
+}
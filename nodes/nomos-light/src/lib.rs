@@ -0,0 +1,61 @@
+pub mod api;
+pub mod config;
+
+// std
+// crates
+use rand_chacha::ChaCha20Rng;
+// internal
+use api::backend::AxumBackend;
+use kzgrs_backend::common::blob::DaBlob;
+use nomos_api::ApiService;
+use nomos_da_light::DaLightService;
+use nomos_da_network_service::backends::libp2p::validator::DaNetworkValidatorBackend;
+use nomos_da_sampling::backend::kzgrs::KzgrsSamplingBackend;
+use nomos_da_sampling::network::adapters::validator::Libp2pAdapter as SamplingLibp2pAdapter;
+use nomos_da_sampling::storage::adapters::rocksdb::RocksAdapter as SamplingStorageAdapter;
+use nomos_node::{
+    BlobInfo, DaNetworkService, NetworkBackend, NetworkService, NodeDaSampling, NomosDaMembership,
+    RocksBackend, StatusService, StorageService, SystemSig, Tracing, Tx, Wire,
+};
+use overwatch_derive::Services;
+use overwatch_rs::services::handle::ServiceHandle;
+
+pub type LightApiService = ApiService<
+    AxumBackend<
+        Tx,
+        BlobInfo,
+        Wire,
+        KzgrsSamplingBackend<ChaCha20Rng>,
+        SamplingLibp2pAdapter<NomosDaMembership>,
+        ChaCha20Rng,
+        SamplingStorageAdapter<DaBlob, Wire>,
+    >,
+>;
+
+pub type LightDaSampling = NodeDaSampling;
+
+pub type DaLight = DaLightService<
+    cryptarchia_consensus::network::adapters::libp2p::LibP2pAdapter<Tx, BlobInfo>,
+    KzgrsSamplingBackend<ChaCha20Rng>,
+    SamplingLibp2pAdapter<NomosDaMembership>,
+    ChaCha20Rng,
+    SamplingStorageAdapter<DaBlob, Wire>,
+    RocksBackend<Wire>,
+    DaNetworkValidatorBackend<NomosDaMembership>,
+>;
+
+/// Node following block headers and sampling the blobs they reference, without running
+/// consensus, mempools or the rest of the DA services
+#[derive(Services)]
+pub struct NomosLight {
+    #[cfg(feature = "tracing")]
+    tracing: ServiceHandle<Tracing>,
+    network: ServiceHandle<NetworkService<NetworkBackend>>,
+    da_network: ServiceHandle<DaNetworkService<DaNetworkValidatorBackend<NomosDaMembership>>>,
+    da_sampling: ServiceHandle<LightDaSampling>,
+    da_light: ServiceHandle<DaLight>,
+    http: ServiceHandle<LightApiService>,
+    storage: ServiceHandle<StorageService<RocksBackend<Wire>>>,
+    status: ServiceHandle<StatusService>,
+    system_sig: ServiceHandle<SystemSig>,
+}
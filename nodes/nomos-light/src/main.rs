@@ -0,0 +1,64 @@
+// std
+// crates
+use clap::Parser;
+use color_eyre::eyre::{eyre, Result};
+use nomos_light::config::Config as LightConfig;
+use nomos_light::{NomosLight, NomosLightServiceSettings};
+use nomos_node::{HttpArgs, LogArgs, NetworkArgs};
+use overwatch_rs::overwatch::*;
+use tracing::{span, Level};
+use uuid::Uuid;
+// internal
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Path for a yaml-encoded network config file
+    config: std::path::PathBuf,
+    /// Overrides log config.
+    #[clap(flatten)]
+    log_args: LogArgs,
+    /// Overrides network config.
+    #[clap(flatten)]
+    network_args: NetworkArgs,
+    /// Overrides http config.
+    #[clap(flatten)]
+    http_args: HttpArgs,
+}
+
+fn main() -> Result<()> {
+    let Args {
+        config,
+        log_args,
+        http_args,
+        network_args,
+    } = Args::parse();
+    let config = serde_yaml::from_reader::<_, LightConfig>(std::fs::File::open(config)?)?
+        .update_from_args(log_args, network_args, http_args)?;
+
+    #[cfg(debug_assertions)]
+    let debug_span = {
+        let debug_id = Uuid::new_v4();
+        span!(Level::DEBUG, "Nomos", debug_id = debug_id.to_string())
+    };
+    #[cfg(debug_assertions)]
+    let _guard = debug_span.enter();
+    let app = OverwatchRunner::<NomosLight>::run(
+        NomosLightServiceSettings {
+            network: config.network,
+            #[cfg(feature = "tracing")]
+            tracing: config.tracing,
+            http: config.http,
+            da_network: config.da_network,
+            da_sampling: config.da_sampling,
+            da_light: config.da_light,
+            storage: config.storage,
+            status: (),
+            system_sig: (),
+        },
+        None,
+    )
+    .map_err(|e| eyre!("Error encountered: {}", e))?;
+    app.wait_finished();
+    Ok(())
+}
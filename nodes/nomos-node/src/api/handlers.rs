@@ -5,27 +5,40 @@ use std::{fmt::Debug, hash::Hash};
 // crates
 use axum::{
     extract::{Query, State},
-    response::Response,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
     Json,
 };
+use futures::{Stream, StreamExt};
+use hyper::{header::CONTENT_TYPE, StatusCode};
 use rand::{RngCore, SeedableRng};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 // internal
 use super::paths;
-use nomos_api::http::{cl, consensus, da, libp2p, mempool, storage};
+use nomos_api::http::{
+    cl, consensus, da,
+    events::{self, StreamEvent},
+    libp2p, mempool, storage, tracing, DynError,
+};
 use nomos_core::da::blob::info::DispersedBlobInfo;
 use nomos_core::da::blob::metadata::Metadata;
 use nomos_core::da::{BlobId, DaVerifier as CoreDaVerifier};
 use nomos_core::{da::blob::Blob, header::HeaderId, tx::Transaction};
+use nomos_da_indexer::IndexView;
 use nomos_da_network_core::SubnetworkId;
 use nomos_da_sampling::backend::DaSamplingServiceBackend;
 use nomos_da_verifier::backend::VerifierBackend;
 use nomos_libp2p::PeerId;
 use nomos_mempool::network::adapters::libp2p::Libp2pAdapter as MempoolNetworkAdapter;
 use nomos_network::backends::libp2p::Libp2p as NetworkBackend;
+use nomos_service_status::HealthReport;
 use nomos_storage::backends::StorageSerde;
+use nomos_tracing::metrics::prometheus;
+use nomos_tracing_service::{FilterStatus, FilterUpdate};
 use overwatch_rs::overwatch::handle::OverwatchHandle;
-use subnetworks_assignations::MembershipHandler;
+use subnetworks_assignations::{MembershipCreator, MembershipHandler};
 
 #[macro_export]
 macro_rules! make_request_and_return_response {
@@ -43,6 +56,74 @@ macro_rules! make_request_and_return_response {
     }};
 }
 
+#[utoipa::path(
+    get,
+    path = paths::HEALTH,
+    responses(
+        (status = 200, description = "All the services are running", body = HealthReport),
+        (status = 503, description = "Some service stopped", body = HealthReport),
+    )
+)]
+pub async fn health(State(handle): State<OverwatchHandle>) -> Response {
+    let report = match nomos_service_status::health(&handle).await {
+        Ok(report) => report,
+        Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, e.to_string()).into_response(),
+    };
+    let status = if report.is_alive() {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(report)).into_response()
+}
+
+#[utoipa::path(
+    get,
+    path = paths::READY,
+    responses(
+        (status = 200, description = "All the services are ready, possibly degraded", body = HealthReport),
+        (status = 503, description = "Some service is starting or stopped", body = HealthReport),
+    )
+)]
+pub async fn ready(State(handle): State<OverwatchHandle>) -> Response {
+    let report = match nomos_service_status::health(&handle).await {
+        Ok(report) => report,
+        Err(e) => return (StatusCode::SERVICE_UNAVAILABLE, e.to_string()).into_response(),
+    };
+    let status = if report.is_ready() {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(report)).into_response()
+}
+
+#[utoipa::path(
+    get,
+    path = paths::METRICS,
+    responses(
+        (status = 200, description = "Node metrics in the Prometheus text format", body = String),
+        (status = 404, description = "Prometheus metrics are not enabled", body = String),
+        (status = 500, description = "Internal server error", body = String),
+    )
+)]
+pub async fn metrics() -> Response {
+    match prometheus::encode_metrics() {
+        Some(Ok(metrics)) => (
+            StatusCode::OK,
+            [(CONTENT_TYPE, prometheus::CONTENT_TYPE)],
+            metrics,
+        )
+            .into_response(),
+        Some(Err(e)) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+        None => (
+            StatusCode::NOT_FOUND,
+            "prometheus metrics are not enabled".to_string(),
+        )
+            .into_response(),
+    }
+}
+
 #[utoipa::path(
     get,
     path = paths::CL_METRICS,
@@ -67,6 +148,50 @@ where
     make_request_and_return_response!(cl::cl_mempool_metrics::<T>(&handle))
 }
 
+#[utoipa::path(
+    get,
+    path = paths::DA_METRICS,
+    responses(
+        (status = 200, description = "Get the mempool metrics of the da service", body = MempoolMetrics),
+        (status = 500, description = "Internal server error", body = String),
+    )
+)]
+pub async fn da_metrics<B, SamplingBackend, SamplingAdapter, SamplingRng, SamplingStorage>(
+    State(handle): State<OverwatchHandle>,
+) -> Response
+where
+    B: DispersedBlobInfo
+        + Clone
+        + Debug
+        + Hash
+        + Serialize
+        + DeserializeOwned
+        + Send
+        + Sync
+        + 'static,
+    <B as DispersedBlobInfo>::BlobId: std::cmp::Ord + Clone + Debug + Hash + Send + Sync + 'static,
+    SamplingBackend: DaSamplingServiceBackend<SamplingRng, BlobId = <B as DispersedBlobInfo>::BlobId>
+        + Send
+        + 'static,
+    SamplingBackend::Settings: Clone,
+    SamplingBackend::Blob: Debug + 'static,
+    SamplingBackend::BlobId: Debug + 'static,
+    SamplingAdapter: nomos_da_sampling::network::NetworkAdapter + Send + 'static,
+    SamplingRng: SeedableRng + RngCore + Send + 'static,
+    SamplingStorage: nomos_da_sampling::storage::DaStorageAdapter,
+{
+    make_request_and_return_response!(mempool::da_mempool_metrics::<
+        NetworkBackend,
+        MempoolNetworkAdapter<B, <B as DispersedBlobInfo>::BlobId>,
+        B,
+        <B as DispersedBlobInfo>::BlobId,
+        SamplingBackend,
+        SamplingAdapter,
+        SamplingRng,
+        SamplingStorage,
+    >(&handle))
+}
+
 #[utoipa::path(
     post,
     path = paths::CL_STATUS,
@@ -214,7 +339,8 @@ where
     B: Blob + Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
     <B as Blob>::BlobId: AsRef<[u8]> + Send + Sync + 'static,
     <B as Blob>::ColumnIndex: AsRef<[u8]> + Send + Sync + 'static,
-    M: MembershipHandler<NetworkId = SubnetworkId, Id = PeerId>
+    M: MembershipCreator
+        + MembershipHandler<NetworkId = SubnetworkId, Id = PeerId>
         + Clone
         + Debug
         + Send
@@ -236,6 +362,9 @@ where
 {
     pub app_id: <V as Metadata>::AppId,
     pub range: Range<<V as Metadata>::Index>,
+    /// Read pending entries as well as final ones, or only final ones
+    #[serde(default)]
+    pub view: IndexView,
 }
 
 #[utoipa::path(
@@ -243,6 +372,7 @@ where
     path = paths::DA_GET_RANGE,
     responses(
         (status = 200, description = "Range of blobs", body = Vec<([u8;8], Vec<DaBlob>)>),
+        (status = 410, description = "Blobs in the range were pruned", body = String),
         (status = 500, description = "Internal server error", body = String),
     )
 )]
@@ -258,7 +388,11 @@ pub async fn get_range<
     const SIZE: usize,
 >(
     State(handle): State<OverwatchHandle>,
-    Json(GetRangeReq { app_id, range }): Json<GetRangeReq<V>>,
+    Json(GetRangeReq {
+        app_id,
+        range,
+        view,
+    }): Json<GetRangeReq<V>>,
 ) -> Response
 where
     Tx: Transaction
@@ -307,7 +441,7 @@ where
     SamplingNetworkAdapter: nomos_da_sampling::network::NetworkAdapter,
     SamplingStorage: nomos_da_sampling::storage::DaStorageAdapter,
 {
-    make_request_and_return_response!(da::get_range::<
+    let range = da::get_range::<
         Tx,
         C,
         V,
@@ -317,7 +451,13 @@ where
         SamplingRng,
         SamplingStorage,
         SIZE,
-    >(&handle, app_id, range))
+    >(&handle, app_id, range, view)
+    .await;
+    match range {
+        Ok(Ok(blobs)) => (StatusCode::OK, Json(blobs)).into_response(),
+        Ok(Err(pruned)) => (StatusCode::GONE, pruned.to_string()).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
 }
 
 #[utoipa::path(
@@ -329,7 +469,46 @@ where
     )
 )]
 pub async fn libp2p_info(State(handle): State<OverwatchHandle>) -> Response {
-    make_request_and_return_response!(libp2p::libp2p_info(&handle))
+    make_request_and_return_response!(libp2p::libp2p_info::<NetworkBackend>(&handle))
+}
+
+#[utoipa::path(
+    get,
+    path = paths::TRACING_FILTER,
+    responses(
+        (status = 200, description = "Directives filtering the logs of the node, if any", body = FilterStatus),
+        (status = 500, description = "Internal server error", body = String),
+    )
+)]
+pub async fn tracing_filter(State(handle): State<OverwatchHandle>) -> Response {
+    make_request_and_return_response!(tracing::filter(&handle))
+}
+
+#[utoipa::path(
+    post,
+    path = paths::TRACING_FILTER,
+    responses(
+        (status = 200, description = "Replace the directives filtering the logs of the node, for a limited time if a duration is given", body = FilterStatus),
+        (status = 500, description = "Internal server error", body = String),
+    )
+)]
+pub async fn set_tracing_filter(
+    State(handle): State<OverwatchHandle>,
+    Json(update): Json<FilterUpdate>,
+) -> Response {
+    make_request_and_return_response!(tracing::set_filter(&handle, update))
+}
+
+#[utoipa::path(
+    delete,
+    path = paths::TRACING_FILTER,
+    responses(
+        (status = 200, description = "Restore the configured directives filtering the logs of the node", body = FilterStatus),
+        (status = 500, description = "Internal server error", body = String),
+    )
+)]
+pub async fn reset_tracing_filter(State(handle): State<OverwatchHandle>) -> Response {
+    make_request_and_return_response!(tracing::reset_filter(&handle))
 }
 
 #[utoipa::path(
@@ -351,6 +530,94 @@ where
     make_request_and_return_response!(storage::block_req::<S, Tx>(&handle, id))
 }
 
+#[derive(Deserialize)]
+pub struct BlockByHeightQuery {
+    height: u64,
+}
+
+#[utoipa::path(
+    get,
+    path = paths::STORAGE_BLOCK_BY_HEIGHT,
+    responses(
+        (status = 200, description = "Get the canonical block at the given height", body = Block<Tx, kzgrs_backend::dispersal::BlobInfo>),
+        (status = 500, description = "Internal server error", body = String),
+    )
+)]
+pub async fn block_by_height<S, Tx>(
+    State(handle): State<OverwatchHandle>,
+    Query(BlockByHeightQuery { height }): Query<BlockByHeightQuery>,
+) -> Response
+where
+    Tx: serde::Serialize + serde::de::DeserializeOwned + Clone + Eq + core::hash::Hash,
+    S: StorageSerde + Send + Sync + 'static,
+{
+    make_request_and_return_response!(storage::block_by_height_req::<S, Tx>(&handle, height))
+}
+
+#[derive(Deserialize)]
+pub struct BlocksRangeQuery {
+    from: u64,
+    limit: Option<usize>,
+}
+
+#[utoipa::path(
+    get,
+    path = paths::STORAGE_BLOCKS,
+    responses(
+        (status = 200, description = "Get a page of canonical blocks starting at the given height", body = Vec<Block<Tx, kzgrs_backend::dispersal::BlobInfo>>),
+        (status = 500, description = "Internal server error", body = String),
+    )
+)]
+pub async fn blocks<S, Tx>(
+    State(handle): State<OverwatchHandle>,
+    Query(BlocksRangeQuery { from, limit }): Query<BlocksRangeQuery>,
+) -> Response
+where
+    Tx: serde::Serialize + serde::de::DeserializeOwned + Clone + Eq + core::hash::Hash,
+    S: StorageSerde + Send + Sync + 'static,
+{
+    let limit = limit.unwrap_or(storage::BLOCKS_RANGE_LIMIT);
+    make_request_and_return_response!(storage::blocks_range_req::<S, Tx>(&handle, from, limit))
+}
+
+#[utoipa::path(
+    post,
+    path = paths::STORAGE_TX_LOCATION,
+    responses(
+        (status = 200, description = "Get the canonical block including the transaction", body = Option<cryptarchia_consensus::history::InclusionLocation>),
+        (status = 500, description = "Internal server error", body = String),
+    )
+)]
+pub async fn tx_location<S, Tx>(
+    State(handle): State<OverwatchHandle>,
+    Json(hash): Json<<Tx as Transaction>::Hash>,
+) -> Response
+where
+    Tx: Transaction,
+    <Tx as Transaction>::Hash: Serialize + DeserializeOwned,
+    S: StorageSerde + Send + Sync + 'static,
+{
+    make_request_and_return_response!(storage::tx_location_req::<S, _>(&handle, hash))
+}
+
+#[utoipa::path(
+    post,
+    path = paths::STORAGE_BLOB_LOCATION,
+    responses(
+        (status = 200, description = "Get the canonical block including the blob", body = Option<cryptarchia_consensus::history::InclusionLocation>),
+        (status = 500, description = "Internal server error", body = String),
+    )
+)]
+pub async fn blob_location<S>(
+    State(handle): State<OverwatchHandle>,
+    Json(blob_id): Json<BlobId>,
+) -> Response
+where
+    S: StorageSerde + Send + Sync + 'static,
+{
+    make_request_and_return_response!(storage::blob_location_req::<S, _>(&handle, blob_id))
+}
+
 #[utoipa::path(
     post,
     path = paths::MEMPOOL_ADD_TX,
@@ -416,3 +683,287 @@ where
         SamplingStorage,
     >(&handle, blob_info, DispersedBlobInfo::blob_id))
 }
+
+#[derive(Deserialize)]
+pub struct ResumeQuery {
+    from_height: Option<u64>,
+}
+
+fn sse_response<S>(events: S) -> Response
+where
+    S: Stream<Item = Result<Event, DynError>> + Send + 'static,
+{
+    Sse::new(events)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+fn sse_event<T: Serialize>(
+    name: &str,
+    event: Result<StreamEvent<T>, DynError>,
+    id: impl Fn(&T) -> Option<u64>,
+) -> Result<Event, DynError> {
+    match event? {
+        StreamEvent::Item(item) => {
+            let sse = Event::default().event(name);
+            let sse = match id(&item) {
+                Some(id) => sse.id(id.to_string()),
+                None => sse,
+            };
+            sse.json_data(item).map_err(Into::into)
+        }
+        StreamEvent::Lagged { skipped } => Event::default()
+            .event("lagged")
+            .json_data(serde_json::json!({ "skipped": skipped }))
+            .map_err(Into::into),
+    }
+}
+
+macro_rules! subscribe_or_return_response {
+    ($cond:expr) => {{
+        match $cond.await {
+            ::std::result::Result::Ok(val) => val,
+            ::std::result::Result::Err(e) => {
+                return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
+            }
+        }
+    }};
+}
+
+#[utoipa::path(
+    get,
+    path = paths::EVENTS_BLOCKS,
+    responses(
+        (status = 200, description = "Stream of new canonical blocks, starting at `from_height` if given or at the next block otherwise"),
+        (status = 500, description = "Internal server error", body = String),
+    )
+)]
+pub async fn events_blocks<
+    Tx,
+    SS,
+    SamplingBackend,
+    SamplingNetworkAdapter,
+    SamplingRng,
+    SamplingStorage,
+    const SIZE: usize,
+>(
+    State(handle): State<OverwatchHandle>,
+    Query(ResumeQuery { from_height }): Query<ResumeQuery>,
+) -> Response
+where
+    Tx: Transaction
+        + Eq
+        + Clone
+        + Debug
+        + Hash
+        + Serialize
+        + DeserializeOwned
+        + Send
+        + Sync
+        + 'static,
+    <Tx as Transaction>::Hash: std::cmp::Ord + Debug + Send + Sync + 'static,
+    SS: StorageSerde + Send + Sync + 'static,
+    SamplingRng: SeedableRng + RngCore,
+    SamplingBackend: DaSamplingServiceBackend<SamplingRng, BlobId = BlobId> + Send,
+    SamplingBackend::Settings: Clone,
+    SamplingBackend::Blob: Debug + 'static,
+    SamplingBackend::BlobId: Debug + 'static,
+    SamplingNetworkAdapter: nomos_da_sampling::network::NetworkAdapter,
+    SamplingStorage: nomos_da_sampling::storage::DaStorageAdapter,
+{
+    // subscribe before looking at the tip so no block is missed in between
+    let tips = subscribe_or_return_response!(consensus::cryptarchia_tip_subscribe::<
+        Tx,
+        SS,
+        SamplingBackend,
+        SamplingNetworkAdapter,
+        SamplingRng,
+        SamplingStorage,
+        SIZE,
+    >(&handle));
+    let from_height = match from_height {
+        Some(from_height) => from_height,
+        None => {
+            subscribe_or_return_response!(consensus::cryptarchia_info::<
+                Tx,
+                SS,
+                SamplingBackend,
+                SamplingNetworkAdapter,
+                SamplingRng,
+                SamplingStorage,
+                SIZE,
+            >(&handle))
+            .height
+                + 1
+        }
+    };
+    let blocks = events::canonical_blocks_stream::<SS, Tx>(handle, tips, from_height);
+    sse_response(blocks.map(|event| sse_event("block", event, |(height, _)| Some(*height))))
+}
+
+#[utoipa::path(
+    get,
+    path = paths::EVENTS_TIPS,
+    responses(
+        (status = 200, description = "Stream of canonical tip changes, including reorgs. Replays the chain from `from_height` if given"),
+        (status = 500, description = "Internal server error", body = String),
+    )
+)]
+pub async fn events_tips<
+    Tx,
+    SS,
+    SamplingBackend,
+    SamplingNetworkAdapter,
+    SamplingRng,
+    SamplingStorage,
+    const SIZE: usize,
+>(
+    State(handle): State<OverwatchHandle>,
+    Query(ResumeQuery { from_height }): Query<ResumeQuery>,
+) -> Response
+where
+    Tx: Transaction
+        + Eq
+        + Clone
+        + Debug
+        + Hash
+        + Serialize
+        + DeserializeOwned
+        + Send
+        + Sync
+        + 'static,
+    <Tx as Transaction>::Hash: std::cmp::Ord + Debug + Send + Sync + 'static,
+    SS: StorageSerde + Send + Sync + 'static,
+    SamplingRng: SeedableRng + RngCore,
+    SamplingBackend: DaSamplingServiceBackend<SamplingRng, BlobId = BlobId> + Send,
+    SamplingBackend::Settings: Clone,
+    SamplingBackend::Blob: Debug + 'static,
+    SamplingBackend::BlobId: Debug + 'static,
+    SamplingNetworkAdapter: nomos_da_sampling::network::NetworkAdapter,
+    SamplingStorage: nomos_da_sampling::storage::DaStorageAdapter,
+{
+    let tips = subscribe_or_return_response!(consensus::cryptarchia_tip_subscribe::<
+        Tx,
+        SS,
+        SamplingBackend,
+        SamplingNetworkAdapter,
+        SamplingRng,
+        SamplingStorage,
+        SIZE,
+    >(&handle));
+    let tips = events::tips_stream::<SS, Tx>(handle, tips, from_height);
+    sse_response(tips.map(|event| sse_event("tip", event, |tip| Some(tip.height))))
+}
+
+#[utoipa::path(
+    get,
+    path = paths::EVENTS_MEMPOOL_TX,
+    responses(
+        (status = 200, description = "Stream of transactions admitted into the cl mempool"),
+        (status = 500, description = "Internal server error", body = String),
+    )
+)]
+pub async fn events_mempool_tx<T>(State(handle): State<OverwatchHandle>) -> Response
+where
+    T: Transaction
+        + Clone
+        + Debug
+        + Hash
+        + Serialize
+        + for<'de> Deserialize<'de>
+        + Send
+        + Sync
+        + 'static,
+    <T as nomos_core::tx::Transaction>::Hash: std::cmp::Ord + Debug + Send + Sync + 'static,
+{
+    let txs = subscribe_or_return_response!(cl::cl_mempool_subscribe::<T>(&handle));
+    sse_response(events::live_stream(txs).map(|event| sse_event("tx", Ok(event), |_| None)))
+}
+
+#[utoipa::path(
+    get,
+    path = paths::EVENTS_MEMPOOL_BLOB_INFO,
+    responses(
+        (status = 200, description = "Stream of blob info admitted into the da mempool"),
+        (status = 500, description = "Internal server error", body = String),
+    )
+)]
+pub async fn events_mempool_blob_info<
+    B,
+    SamplingBackend,
+    SamplingAdapter,
+    SamplingRng,
+    SamplingStorage,
+>(
+    State(handle): State<OverwatchHandle>,
+) -> Response
+where
+    B: DispersedBlobInfo
+        + Clone
+        + Debug
+        + Hash
+        + Serialize
+        + DeserializeOwned
+        + Send
+        + Sync
+        + 'static,
+    <B as DispersedBlobInfo>::BlobId: std::cmp::Ord + Clone + Debug + Hash + Send + Sync + 'static,
+    SamplingBackend: DaSamplingServiceBackend<SamplingRng, BlobId = <B as DispersedBlobInfo>::BlobId>
+        + Send
+        + 'static,
+    SamplingBackend::Settings: Clone,
+    SamplingBackend::Blob: Debug + 'static,
+    SamplingBackend::BlobId: Debug + 'static,
+    SamplingAdapter: nomos_da_sampling::network::NetworkAdapter + Send + 'static,
+    SamplingRng: SeedableRng + RngCore + Send + 'static,
+    SamplingStorage: nomos_da_sampling::storage::DaStorageAdapter,
+{
+    let blobs = subscribe_or_return_response!(mempool::da_mempool_subscribe::<
+        NetworkBackend,
+        MempoolNetworkAdapter<B, <B as DispersedBlobInfo>::BlobId>,
+        B,
+        <B as DispersedBlobInfo>::BlobId,
+        SamplingBackend,
+        SamplingAdapter,
+        SamplingRng,
+        SamplingStorage,
+    >(&handle));
+    sse_response(
+        events::live_stream(blobs).map(|event| sse_event("blob_info", Ok(event), |_| None)),
+    )
+}
+
+#[utoipa::path(
+    get,
+    path = paths::EVENTS_DA_SAMPLING,
+    responses(
+        (status = 200, description = "Stream of DA sampling results"),
+        (status = 500, description = "Internal server error", body = String),
+    )
+)]
+pub async fn events_da_sampling<
+    SamplingBackend,
+    SamplingNetworkAdapter,
+    SamplingRng,
+    SamplingStorage,
+>(
+    State(handle): State<OverwatchHandle>,
+) -> Response
+where
+    SamplingRng: SeedableRng + RngCore,
+    SamplingBackend: DaSamplingServiceBackend<SamplingRng, BlobId = BlobId> + Send,
+    SamplingBackend::Settings: Clone,
+    SamplingBackend::Blob: Debug + 'static,
+    SamplingNetworkAdapter: nomos_da_sampling::network::NetworkAdapter,
+    SamplingStorage: nomos_da_sampling::storage::DaStorageAdapter,
+{
+    let outcomes = subscribe_or_return_response!(da::sampling_subscribe::<
+        SamplingBackend,
+        SamplingNetworkAdapter,
+        SamplingRng,
+        SamplingStorage,
+    >(&handle));
+    sse_response(
+        events::live_stream(outcomes).map(|event| sse_event("sampling", Ok(event), |_| None)),
+    )
+}
@@ -1,10 +1,24 @@
+pub const HEALTH: &str = "/health";
+pub const READY: &str = "/ready";
+pub const METRICS: &str = "/metrics";
 pub const CL_METRICS: &str = "/cl/metrics";
 pub const CL_STATUS: &str = "/cl/status";
 pub const CRYPTARCHIA_INFO: &str = "/cryptarchia/info";
 pub const CRYPTARCHIA_HEADERS: &str = "/cryptarchia/headers";
 pub const DA_ADD_BLOB: &str = "/da/add-blob";
 pub const DA_GET_RANGE: &str = "/da/get-range";
+pub const DA_METRICS: &str = "/da/metrics";
 pub const NETWORK_INFO: &str = "/network/info";
+pub const TRACING_FILTER: &str = "/tracing/filter";
 pub const STORAGE_BLOCK: &str = "/storage/block";
+pub const STORAGE_BLOCK_BY_HEIGHT: &str = "/storage/block/height";
+pub const STORAGE_BLOCKS: &str = "/storage/blocks";
+pub const STORAGE_TX_LOCATION: &str = "/storage/tx-location";
+pub const STORAGE_BLOB_LOCATION: &str = "/storage/blob-location";
 pub const MEMPOOL_ADD_TX: &str = "/mempool/add/tx";
 pub const MEMPOOL_ADD_BLOB_INFO: &str = "/mempool/add/blobinfo";
+pub const EVENTS_BLOCKS: &str = "/events/blocks";
+pub const EVENTS_TIPS: &str = "/events/tips";
+pub const EVENTS_MEMPOOL_TX: &str = "/events/mempool/tx";
+pub const EVENTS_MEMPOOL_BLOB_INFO: &str = "/events/mempool/blobinfo";
+pub const EVENTS_DA_SAMPLING: &str = "/events/da/sampling";
@@ -16,6 +16,7 @@ use crate::{NomosApiService, NomosDaMembership, Wire};
 use nomos_core::{proofs::covenant::CovenantProof, staking::NMO_UNIT};
 use nomos_da_network_service::backends::libp2p::validator::DaNetworkValidatorBackend;
 use nomos_da_network_service::NetworkService as DaNetworkService;
+use nomos_keystore::{KeyType, Keystore, SecretKey as KeystoreKey};
 use nomos_libp2p::{ed25519::SecretKey, Multiaddr};
 use nomos_mix_service::backends::libp2p::Libp2pMixBackend as MixBackend;
 use nomos_mix_service::network::libp2p::Libp2pAdapter as MixNetworkAdapter;
@@ -139,14 +140,43 @@ pub struct Config {
     pub da_indexer: <crate::NodeDaIndexer as ServiceData>::Settings,
     pub da_verifier: <crate::NodeDaVerifier as ServiceData>::Settings,
     pub da_sampling: <crate::NodeDaSampling as ServiceData>::Settings,
+    #[serde(default)]
+    pub da_membership: <crate::NodeDaMembership as ServiceData>::Settings,
     pub http: <NomosApiService as ServiceData>::Settings,
     pub cryptarchia: <crate::Cryptarchia<
         nomos_da_sampling::network::adapters::validator::Libp2pAdapter<NomosDaMembership>,
     > as ServiceData>::Settings,
     pub storage: <crate::StorageService<RocksBackend<Wire>> as ServiceData>::Settings,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keystore: Option<KeystoreConfig>,
 }
 
 impl Config {
+    /// Replaces the keys of the services with the ones named in the keystore config, if any,
+    /// and fails if a secret key was neither in the settings nor in the keystore
+    pub fn update_from_keystore(mut self) -> Result<Self> {
+        if let Some(keystore) = &self.keystore {
+            let keys = keystore.load()?;
+            if let Some(node_key) = keys.network {
+                self.network.backend.inner.node_key = node_key;
+            }
+            if let Some(node_key) = keys.da_network {
+                self.da_network.backend.node_key = node_key;
+            }
+            if let Some(sk) = keys.da_verifier {
+                self.da_verifier.verifier_settings.sk = Some(sk);
+            }
+            update_mix_from_keystore(&mut self.mix, &keys);
+            update_cryptarchia_from_keystore(&mut self.cryptarchia, &keys);
+        }
+        check_secret_keys(
+            self.da_verifier.verifier_settings.sk.as_deref(),
+            &self.mix,
+            &self.cryptarchia,
+        )?;
+        Ok(self)
+    }
+
     pub fn update_from_args(
         mut self,
         log_args: LogArgs,
@@ -164,6 +194,135 @@ impl Config {
     }
 }
 
+/// Keystore the node keys are read from instead of the hex values in the service settings.
+#[derive(Deserialize, Debug, Clone, Serialize)]
+pub struct KeystoreConfig {
+    pub path: PathBuf,
+    /// Environment variable holding the passphrase of an encrypted keystore.
+    #[serde(default = "default_passphrase_env")]
+    pub passphrase_env: String,
+    /// Names of the keys to use, the settings keep their keys for the ones not set.
+    #[serde(default)]
+    pub network: Option<String>,
+    #[serde(default)]
+    pub mix_network: Option<String>,
+    #[serde(default)]
+    pub mix: Option<String>,
+    #[serde(default)]
+    pub da_network: Option<String>,
+    #[serde(default)]
+    pub da_verifier: Option<String>,
+    #[serde(default)]
+    pub leader: Option<String>,
+}
+
+fn default_passphrase_env() -> String {
+    "NOMOS_KEYSTORE_PASSPHRASE".to_string()
+}
+
+/// Keys read from the keystore
+#[derive(Default)]
+pub struct KeystoreKeys {
+    pub network: Option<SecretKey>,
+    pub mix_network: Option<SecretKey>,
+    pub mix: Option<[u8; 32]>,
+    pub da_network: Option<SecretKey>,
+    /// Hex encoded, as in the verifier settings
+    pub da_verifier: Option<String>,
+    pub leader: Option<NullifierSecret>,
+}
+
+impl KeystoreConfig {
+    pub fn load(&self) -> Result<KeystoreKeys> {
+        let passphrase = std::env::var(&self.passphrase_env).ok();
+        let keystore = Keystore::open(&self.path, passphrase.as_deref())
+            .map_err(|e| eyre!("Could not open keystore {}: {e}", self.path.display()))?;
+        let read = |name: &Option<String>, key_type| {
+            name.as_deref()
+                .map(|name| keystore.get_typed(name, key_type))
+                .transpose()
+        };
+        Ok(KeystoreKeys {
+            network: read(&self.network, KeyType::Network)?.map(network_key),
+            mix_network: read(&self.mix_network, KeyType::Network)?.map(network_key),
+            mix: read(&self.mix, KeyType::Mix)?.map(|key| {
+                key.to_bytes()
+                    .try_into()
+                    .expect("mix keys should be 32 bytes long")
+            }),
+            da_network: read(&self.da_network, KeyType::Network)?.map(network_key),
+            da_verifier: read(&self.da_verifier, KeyType::DaVerifier)?
+                .map(|key| hex::encode(key.to_bytes())),
+            leader: read(&self.leader, KeyType::Leader)?.map(|key| match key {
+                KeystoreKey::Leader(nf_sk) => nf_sk,
+                _ => unreachable!("key type was checked"),
+            }),
+        })
+    }
+}
+
+fn network_key(key: KeystoreKey) -> SecretKey {
+    match key {
+        KeystoreKey::Network(node_key) => node_key,
+        _ => unreachable!("key type was checked"),
+    }
+}
+
+pub fn update_mix_from_keystore(
+    mix: &mut <MixService<MixBackend, MixNetworkAdapter> as ServiceData>::Settings,
+    keys: &KeystoreKeys,
+) {
+    if let Some(node_key) = &keys.mix_network {
+        mix.backend.node_key = node_key.clone();
+    }
+    if let Some(private_key) = keys.mix {
+        mix.message_blend.cryptographic_processor.private_key = Some(private_key);
+    }
+}
+
+pub fn update_cryptarchia_from_keystore(
+    cryptarchia: &mut <crate::NodeCryptarchia as ServiceData>::Settings,
+    keys: &KeystoreKeys,
+) {
+    if let Some(nf_sk) = keys.leader {
+        cryptarchia.leader_config.nf_sk = Some(nf_sk);
+    }
+}
+
+/// The secret keys can be left out of the settings when the keystore names them, fails if one
+/// of them is still unset after reading the keystore.
+pub fn check_secret_keys(
+    da_verifier_sk: Option<&str>,
+    mix: &<MixService<MixBackend, MixNetworkAdapter> as ServiceData>::Settings,
+    cryptarchia: &<crate::NodeCryptarchia as ServiceData>::Settings,
+) -> Result<()> {
+    let missing = [
+        (da_verifier_sk.is_none(), "da_verifier.verifier_settings.sk"),
+        (
+            mix.message_blend
+                .cryptographic_processor
+                .private_key
+                .is_none(),
+            "mix.message_blend.cryptographic_processor.private_key",
+        ),
+        (
+            cryptarchia.leader_config.nf_sk.is_none(),
+            "cryptarchia.leader_config.nf_sk",
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(missing, key)| missing.then_some(key))
+    .collect::<Vec<_>>();
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(eyre!(
+            "Secret keys not set in the settings nor named in the keystore: {}",
+            missing.join(", ")
+        ))
+    }
+}
+
 pub fn update_tracing(
     tracing: &mut <Tracing as ServiceData>::Settings,
     tracing_args: LogArgs,
@@ -189,6 +348,7 @@ pub fn update_tracing(
             LoggerLayerType::File => LoggerLayer::File(FileConfig {
                 directory: directory.ok_or_else(|| eyre!("File backend requires a directory."))?,
                 prefix,
+                rotation: Default::default(),
             }),
             LoggerLayerType::Stdout => LoggerLayer::Stdout,
             LoggerLayerType::Stderr => LoggerLayer::Stderr,
@@ -324,7 +484,7 @@ pub fn update_cryptarchia_consensus(
 
     if let Some(sk) = note_secret_key {
         let sk = <[u8; 16]>::from_hex(sk)?;
-        cryptarchia.leader_config.nf_sk = NullifierSecret::from_bytes(sk);
+        cryptarchia.leader_config.nf_sk = Some(NullifierSecret::from_bytes(sk));
     }
 
     Ok(())
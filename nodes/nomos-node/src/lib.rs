@@ -19,6 +19,7 @@ pub use nomos_core::{header::HeaderId, tx::Transaction, wire};
 use nomos_da_indexer::consensus::adapters::cryptarchia::CryptarchiaConsensusAdapter;
 use nomos_da_indexer::storage::adapters::rocksdb::RocksAdapter as IndexerStorageAdapter;
 use nomos_da_indexer::DataIndexerService;
+use nomos_da_membership::DaMembershipService;
 pub use nomos_da_network_service::backends::libp2p::validator::DaNetworkValidatorBackend;
 pub use nomos_da_network_service::NetworkService as DaNetworkService;
 use nomos_da_sampling::backend::kzgrs::KzgrsSamplingBackend;
@@ -40,6 +41,7 @@ pub use nomos_mix_service::network::libp2p::Libp2pAdapter as MixNetworkAdapter;
 pub use nomos_mix_service::MixService;
 pub use nomos_network::backends::libp2p::Libp2p as NetworkBackend;
 pub use nomos_network::NetworkService;
+pub use nomos_service_status::StatusService;
 pub use nomos_storage::{
     backends::{
         rocksdb::{RocksBackend, RocksBackendSettings},
@@ -155,6 +157,11 @@ pub type DaVerifier<VerifierAdapter> =
 
 pub type NodeDaVerifier = DaVerifier<VerifierNetworkAdapter<FillFromNodeList>>;
 
+pub type DaMembership<Backend, Consensus> = DaMembershipService<Backend, Consensus>;
+
+pub type NodeDaMembership =
+    DaMembership<DaNetworkValidatorBackend<NomosDaMembership>, NodeCryptarchia>;
+
 #[derive(Services)]
 pub struct Nomos {
     #[cfg(feature = "tracing")]
@@ -165,11 +172,13 @@ pub struct Nomos {
     da_verifier: ServiceHandle<NodeDaVerifier>,
     da_sampling: ServiceHandle<NodeDaSampling>,
     da_network: ServiceHandle<DaNetworkService<DaNetworkValidatorBackend<NomosDaMembership>>>,
+    da_membership: ServiceHandle<NodeDaMembership>,
     cl_mempool: ServiceHandle<TxMempool>,
     da_mempool: ServiceHandle<DaMempool>,
     cryptarchia: ServiceHandle<NodeCryptarchia>,
     http: ServiceHandle<NomosApiService>,
     storage: ServiceHandle<StorageService<RocksBackend<Wire>>>,
+    status: ServiceHandle<StatusService>,
     system_sig: ServiceHandle<SystemSig>,
 }
 
@@ -0,0 +1,488 @@
+// std
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+// crates
+use cl::{NoteWitness, NullifierSecret};
+use cryptarchia_consensus::{CryptarchiaSettings, LeaderConfig, TimeConfig};
+use nomos_core::staking::NMO_UNIT;
+use nomos_da_dispersal::backend::kzgrs::{DispersalKZGRSBackendSettings, EncoderSettings};
+use nomos_da_dispersal::DispersalServiceSettings;
+use nomos_da_indexer::storage::adapters::rocksdb::RocksAdapterSettings as IndexerStorageAdapterSettings;
+use nomos_da_indexer::IndexerSettings;
+use nomos_da_network_service::backends::libp2p::common::DaNetworkBackendSettings;
+use nomos_da_network_service::backends::libp2p::executor::DaNetworkExecutorBackendSettings;
+use nomos_da_network_service::NetworkConfig as DaNetworkConfig;
+use nomos_da_sampling::storage::adapters::rocksdb::RocksAdapterSettings as SamplingStorageAdapterSettings;
+use nomos_da_sampling::{backend::kzgrs::KzgrsSamplingBackendSettings, DaSamplingServiceSettings};
+use nomos_da_verifier::storage::adapters::rocksdb::RocksAdapterSettings as VerifierStorageAdapterSettings;
+use nomos_da_verifier::{backend::kzgrs::KzgrsDaVerifierSettings, DaVerifierServiceSettings};
+use nomos_executor::config::Config as ExecutorConfig;
+use nomos_ledger::LedgerState;
+use nomos_libp2p::{ed25519, Multiaddr, PeerId, Protocol, SwarmConfig};
+use nomos_mix::membership::Node;
+use nomos_mix::message_blend::{
+    CryptographicProcessorSettings, MessageBlendSettings, TemporalSchedulerSettings,
+};
+use nomos_mix_service::backends::libp2p::Libp2pMixBackendSettings;
+use nomos_network::{backends::libp2p::Libp2pConfig, NetworkConfig};
+use nomos_node::{Config as ValidatorConfig, NomosDaMembership, RocksBackendSettings};
+use nomos_tracing_service::TracingSettings;
+use rand::thread_rng;
+use subnetworks_assignations::MembershipHandler;
+use time::OffsetDateTime;
+// internal
+
+type ConsensusSettings = CryptarchiaSettings<
+    (),
+    (),
+    cryptarchia_consensus::network::adapters::libp2p::LibP2pAdapterSettings,
+    cryptarchia_consensus::mix::adapters::libp2p::LibP2pAdapterSettings<
+        nomos_mix_service::network::libp2p::Libp2pBroadcastSettings,
+    >,
+>;
+
+/// Parameters of a single node network, which can be joined by other nodes once the
+/// membership and genesis state are extended with theirs.
+pub struct NodeParams {
+    pub host: Ipv4Addr,
+    pub network_port: u16,
+    pub da_network_port: u16,
+    pub mix_port: u16,
+    pub api_address: SocketAddr,
+    pub data_dir: PathBuf,
+    pub global_params_path: String,
+    pub num_subnets: u16,
+    pub security_param: u32,
+    pub active_slot_coeff: f64,
+    pub slot_duration: Duration,
+}
+
+/// Keys and shared state of a generated node
+struct NodeKeys {
+    node_key: ed25519::SecretKey,
+    peer_id: PeerId,
+    mix_private_key: [u8; 32],
+    verifier_sk: String,
+    membership: NomosDaMembership,
+    leader_config: LeaderConfig,
+    genesis_state: LedgerState,
+}
+
+impl NodeParams {
+    fn keys(&self) -> NodeKeys {
+        let node_key = ed25519::SecretKey::generate();
+        let peer_id =
+            PeerId::from_public_key(&ed25519::Keypair::from(node_key.clone()).public().into());
+        let membership = NomosDaMembership::new(&[peer_id], self.num_subnets as usize, 1);
+        let verifier_sk = {
+            let mut ikm = [0u8; 32];
+            rand::RngCore::fill_bytes(&mut thread_rng(), &mut ikm);
+            blst::min_sig::SecretKey::key_gen(&ikm, &[]).expect("32 bytes of key material")
+        };
+        let nf_sk = NullifierSecret::random(thread_rng());
+        let note = NoteWitness::basic(1, NMO_UNIT, thread_rng());
+        let genesis_state = LedgerState::from_commitments([note.commit(nf_sk.commit())], 1.into());
+        NodeKeys {
+            node_key,
+            peer_id,
+            mix_private_key: x25519_dalek::StaticSecret::random_from_rng(thread_rng()).to_bytes(),
+            verifier_sk: hex::encode(verifier_sk.to_bytes()),
+            membership,
+            leader_config: LeaderConfig {
+                notes: vec![note],
+                nf_sk: Some(nf_sk),
+            },
+            genesis_state,
+        }
+    }
+
+    fn quic_address(&self, port: u16) -> Multiaddr {
+        Multiaddr::from(self.host)
+            .with(Protocol::Udp(port))
+            .with(Protocol::QuicV1)
+    }
+
+    fn path(&self, name: &str) -> PathBuf {
+        self.data_dir.join(name)
+    }
+
+    pub fn validator_config(&self) -> ValidatorConfig {
+        let keys = self.keys();
+        let blob_storage_directory = self.path("blobs");
+        ValidatorConfig {
+            tracing: TracingSettings::default(),
+            network: NetworkConfig {
+                backend: Libp2pConfig {
+                    inner: SwarmConfig {
+                        host: self.host,
+                        port: self.network_port,
+                        node_key: keys.node_key.clone(),
+                        ..Default::default()
+                    },
+                    initial_peers: vec![],
+                },
+            },
+            mix: nomos_mix_service::MixConfig {
+                backend: Libp2pMixBackendSettings {
+                    listening_address: self.quic_address(self.mix_port),
+                    node_key: keys.node_key.clone(),
+                    peering_degree: 1,
+                },
+                persistent_transmission: Default::default(),
+                message_blend: MessageBlendSettings {
+                    cryptographic_processor: CryptographicProcessorSettings {
+                        private_key: Some(keys.mix_private_key),
+                        num_mix_layers: 1,
+                    },
+                    temporal_processor: TemporalSchedulerSettings {
+                        max_delay_seconds: 2,
+                    },
+                },
+                // the mock mix message uses the private key as the node public key
+                membership: vec![Node {
+                    address: self.quic_address(self.mix_port),
+                    public_key: keys.mix_private_key,
+                }],
+            },
+            da_network: DaNetworkConfig {
+                backend: DaNetworkBackendSettings {
+                    node_key: keys.node_key.clone(),
+                    membership: keys.membership.clone(),
+                    addresses: self.da_addresses(&keys),
+                    listening_address: self.quic_address(self.da_network_port),
+                    sampling: Default::default(),
+                    replication: Default::default(),
+                },
+            },
+            da_indexer: IndexerSettings {
+                storage: IndexerStorageAdapterSettings {
+                    blob_storage_directory: blob_storage_directory.clone(),
+                },
+                retention: Default::default(),
+            },
+            da_verifier: DaVerifierServiceSettings {
+                verifier_settings: KzgrsDaVerifierSettings {
+                    sk: Some(keys.verifier_sk.clone()),
+                    index: keys.membership.membership(&keys.peer_id),
+                    global_params_path: self.global_params_path.clone(),
+                    domain_size: self.num_subnets as usize,
+                },
+                network_adapter_settings: (),
+                storage_adapter_settings: VerifierStorageAdapterSettings {
+                    blob_storage_directory: blob_storage_directory.clone(),
+                },
+            },
+            da_membership: Default::default(),
+            da_sampling: DaSamplingServiceSettings {
+                sampling_settings: self.sampling_settings(),
+                storage_adapter_settings: SamplingStorageAdapterSettings {
+                    blob_storage_directory,
+                },
+                network_adapter_settings: (),
+            },
+            http: nomos_api::ApiServiceSettings {
+                backend_settings: nomos_node::api::backend::AxumBackendSettings {
+                    address: self.api_address,
+                    cors_origins: vec![],
+                    admin_token: None,
+                },
+            },
+            cryptarchia: self.cryptarchia_settings(&keys),
+            storage: self.storage_settings(),
+            keystore: None,
+        }
+    }
+
+    pub fn executor_config(&self) -> ExecutorConfig {
+        let keys = self.keys();
+        let blob_storage_directory = self.path("blobs");
+        ExecutorConfig {
+            tracing: TracingSettings::default(),
+            network: NetworkConfig {
+                backend: Libp2pConfig {
+                    inner: SwarmConfig {
+                        host: self.host,
+                        port: self.network_port,
+                        node_key: keys.node_key.clone(),
+                        ..Default::default()
+                    },
+                    initial_peers: vec![],
+                },
+            },
+            mix: nomos_mix_service::MixConfig {
+                backend: Libp2pMixBackendSettings {
+                    listening_address: self.quic_address(self.mix_port),
+                    node_key: keys.node_key.clone(),
+                    peering_degree: 1,
+                },
+                persistent_transmission: Default::default(),
+                message_blend: MessageBlendSettings {
+                    cryptographic_processor: CryptographicProcessorSettings {
+                        private_key: Some(keys.mix_private_key),
+                        num_mix_layers: 1,
+                    },
+                    temporal_processor: TemporalSchedulerSettings {
+                        max_delay_seconds: 2,
+                    },
+                },
+                membership: vec![Node {
+                    address: self.quic_address(self.mix_port),
+                    public_key: keys.mix_private_key,
+                }],
+            },
+            da_dispersal: DispersalServiceSettings {
+                backend: DispersalKZGRSBackendSettings {
+                    encoder_settings: EncoderSettings {
+                        num_columns: self.num_subnets as usize,
+                        with_cache: false,
+                        global_params_path: self.global_params_path.clone(),
+                    },
+                    dispersal_timeout: Duration::from_secs(20),
+                },
+            },
+            da_network: DaNetworkConfig {
+                backend: DaNetworkExecutorBackendSettings {
+                    validator_settings: DaNetworkBackendSettings {
+                        node_key: keys.node_key.clone(),
+                        membership: keys.membership.clone(),
+                        addresses: self.da_addresses(&keys),
+                        listening_address: self.quic_address(self.da_network_port),
+                        sampling: Default::default(),
+                        replication: Default::default(),
+                    },
+                    num_subnets: self.num_subnets,
+                },
+            },
+            da_indexer: IndexerSettings {
+                storage: IndexerStorageAdapterSettings {
+                    blob_storage_directory: blob_storage_directory.clone(),
+                },
+                retention: Default::default(),
+            },
+            da_verifier: DaVerifierServiceSettings {
+                verifier_settings: KzgrsDaVerifierSettings {
+                    sk: Some(keys.verifier_sk.clone()),
+                    index: keys.membership.membership(&keys.peer_id),
+                    global_params_path: self.global_params_path.clone(),
+                    domain_size: self.num_subnets as usize,
+                },
+                network_adapter_settings: (),
+                storage_adapter_settings: VerifierStorageAdapterSettings {
+                    blob_storage_directory: blob_storage_directory.clone(),
+                },
+            },
+            da_membership: Default::default(),
+            da_sampling: DaSamplingServiceSettings {
+                sampling_settings: self.sampling_settings(),
+                storage_adapter_settings: SamplingStorageAdapterSettings {
+                    blob_storage_directory,
+                },
+                network_adapter_settings: (),
+            },
+            http: nomos_api::ApiServiceSettings {
+                backend_settings: nomos_executor::api::backend::AxumBackendSettings {
+                    address: self.api_address,
+                    cors_origins: vec![],
+                    admin_token: None,
+                },
+            },
+            cryptarchia: self.cryptarchia_settings(&keys),
+            storage: self.storage_settings(),
+            keystore: None,
+        }
+    }
+
+    fn da_addresses(&self, keys: &NodeKeys) -> HashMap<PeerId, Multiaddr> {
+        [(keys.peer_id, self.quic_address(self.da_network_port))]
+            .into_iter()
+            .collect()
+    }
+
+    fn sampling_settings(&self) -> KzgrsSamplingBackendSettings {
+        KzgrsSamplingBackendSettings {
+            num_samples: 1,
+            required_samples: 1,
+            num_subnets: self.num_subnets,
+            max_retries: 3,
+            retry_backoff: Duration::from_millis(500),
+            old_blobs_check_interval: Duration::from_secs(5),
+            blobs_validity_duration: Duration::from_secs(60),
+        }
+    }
+
+    fn cryptarchia_settings(&self, keys: &NodeKeys) -> ConsensusSettings {
+        CryptarchiaSettings {
+            transaction_selector_settings: (),
+            blob_selector_settings: (),
+            config: nomos_ledger::Config {
+                epoch_stake_distribution_stabilization: 3,
+                epoch_period_nonce_buffer: 3,
+                epoch_period_nonce_stabilization: 4,
+                consensus_config: cryptarchia_engine::Config {
+                    security_param: self.security_param,
+                    active_slot_coeff: self.active_slot_coeff,
+                },
+            },
+            genesis_state: keys.genesis_state.clone(),
+            time: TimeConfig {
+                slot_duration: self.slot_duration,
+                chain_start_time: OffsetDateTime::now_utc(),
+            },
+            leader_config: keys.leader_config.clone(),
+            network_adapter_settings:
+                cryptarchia_consensus::network::adapters::libp2p::LibP2pAdapterSettings {
+                    topic: String::from(nomos_node::CONSENSUS_TOPIC),
+                },
+            mix_adapter_settings:
+                cryptarchia_consensus::mix::adapters::libp2p::LibP2pAdapterSettings {
+                    broadcast_settings:
+                        nomos_mix_service::network::libp2p::Libp2pBroadcastSettings {
+                            topic: String::from(nomos_node::CONSENSUS_TOPIC),
+                        },
+                },
+        }
+    }
+
+    fn storage_settings(&self) -> RocksBackendSettings {
+        RocksBackendSettings {
+            db_path: self.path("db"),
+            read_only: false,
+            column_family: Some("blocks".into()),
+        }
+    }
+}
+
+/// Comments written above the settings they describe, by their path in the config
+const ANNOTATIONS: &[(&str, &str)] = &[
+    (
+        "tracing",
+        "Logging, tracing and metrics layers, and the filter applied to them",
+    ),
+    (
+        "network",
+        "Gossipsub network carrying blocks and transactions",
+    ),
+    (
+        "network.backend.node_key",
+        "ed25519 key in hex, also used by the mix and DA networks",
+    ),
+    (
+        "network.backend.initial_peers",
+        "Multiaddrs dialed on startup, add the other nodes here",
+    ),
+    ("mix", "Mix network the node's blocks are broadcast through"),
+    (
+        "mix.membership",
+        "Mix nodes, each with the address and public key of their mix service",
+    ),
+    ("da_network", "Network blobs are dispersed and sampled over"),
+    (
+        "da_network.backend.membership",
+        "Peers of each subnetwork, one subnetwork per column of the encoded blobs",
+    ),
+    (
+        "da_network.backend.addresses",
+        "Addresses of the peers in the membership",
+    ),
+    (
+        "da_network.backend.num_subnets",
+        "Must be the number of subnetworks of the membership",
+    ),
+    (
+        "da_dispersal",
+        "Encoding and dispersal of the blobs sent to this executor",
+    ),
+    (
+        "da_dispersal.backend.encoder_settings.num_columns",
+        "Must be the number of DA subnetworks",
+    ),
+    ("da_indexer", "Index of the blobs included in blocks"),
+    (
+        "da_verifier",
+        "Verification and storage of the blobs of the node's subnetworks",
+    ),
+    (
+        "da_verifier.verifier_settings.index",
+        "Subnetworks the node is a member of, as assigned by the DA membership",
+    ),
+    (
+        "da_verifier.verifier_settings.domain_size",
+        "Must be the number of DA subnetworks",
+    ),
+    (
+        "da_sampling",
+        "Sampling of the blobs of the blocks the node receives",
+    ),
+    ("http", "HTTP API"),
+    (
+        "http.backend_settings.admin_token",
+        "Bearer token enabling the admin endpoints, which are disabled if not set",
+    ),
+    ("cryptarchia", "Consensus"),
+    (
+        "cryptarchia.config",
+        "Epoch schedule and consensus parameters shared by all nodes",
+    ),
+    (
+        "cryptarchia.genesis_state",
+        "Must be the same for all nodes, with the commitments of their leader notes",
+    ),
+    (
+        "cryptarchia.leader_config",
+        "Notes the node proposes blocks with, and the secret of their nullifiers",
+    ),
+    ("storage", "Block storage"),
+    (
+        "keystore",
+        "Keystore the keys are read from instead of the settings above, see `nomos-cli keys`",
+    ),
+];
+
+/// Serializes `config` to YAML, commenting the main settings
+pub fn annotated_yaml<T: serde::Serialize>(
+    config: &T,
+    header: &str,
+) -> Result<String, serde_yaml::Error> {
+    let yaml = serde_yaml::to_string(config)?;
+    let mut output = String::new();
+    for line in header.lines() {
+        output.push_str(&format!("# {line}\n"));
+    }
+    // keys of the mappings enclosing the current line, with their indentation
+    let mut parents: Vec<(usize, String)> = Vec::new();
+    for line in yaml.lines() {
+        let indent = line.len() - line.trim_start().len();
+        let content = line.trim_start();
+        let (indent, content) = match content.strip_prefix("- ") {
+            Some(item) => (indent + 2, item),
+            None => (indent, content),
+        };
+        if let Some((key, _)) = content.split_once(':') {
+            if !key.contains(' ') && !key.starts_with(['\'', '"', '#']) {
+                parents.retain(|(parent_indent, _)| *parent_indent < indent);
+                parents.push((indent, key.to_string()));
+                let path = parents
+                    .iter()
+                    .map(|(_, key)| key.as_str())
+                    .collect::<Vec<_>>()
+                    .join(".");
+                if let Some((_, comment)) = ANNOTATIONS.iter().find(|(p, _)| *p == path) {
+                    let indentation = " ".repeat(line.len() - line.trim_start().len());
+                    if parents.len() == 1 {
+                        output.push('\n');
+                    }
+                    output.push_str(&format!("{indentation}# {comment}\n"));
+                }
+            }
+        }
+        output.push_str(line);
+        output.push('\n');
+    }
+    Ok(output)
+}
+
+pub fn default_data_dir() -> PathBuf {
+    Path::new(".").join("nomos-data")
+}
@@ -0,0 +1,162 @@
+pub mod generate;
+pub mod validate;
+
+// std
+use std::net::{Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
+use std::time::Duration;
+// crates
+use clap::{Args, Subcommand, ValueEnum};
+// internal
+use generate::{annotated_yaml, default_data_dir, NodeParams};
+use validate::{summary, validate_executor, validate_file, validate_validator, ConfigIssue};
+
+#[derive(Args, Debug)]
+pub struct Config {
+    #[clap(subcommand)]
+    command: ConfigCommand,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum Role {
+    Validator,
+    Executor,
+}
+
+#[derive(Debug, Subcommand)]
+enum ConfigCommand {
+    /// Generate a complete config for a single node network, with fresh keys.
+    Generate(Generate),
+    /// Check a config for settings that can't be parsed or don't match each other.
+    Validate(Validate),
+}
+
+#[derive(Args, Debug)]
+struct Generate {
+    #[clap(long, value_enum)]
+    role: Role,
+    /// File to write the config to, printed if not set.
+    #[clap(short, long)]
+    output: Option<PathBuf>,
+    #[clap(long, default_value_t = Ipv4Addr::UNSPECIFIED)]
+    host: Ipv4Addr,
+    #[clap(long, default_value_t = 3000)]
+    network_port: u16,
+    #[clap(long, default_value_t = 3300)]
+    da_network_port: u16,
+    #[clap(long, default_value_t = 3400)]
+    mix_port: u16,
+    #[clap(long, default_value = "127.0.0.1:8080")]
+    api_address: SocketAddr,
+    /// Directory of the node databases and blobs.
+    #[clap(long, default_value_os_t = default_data_dir())]
+    data_dir: PathBuf,
+    /// KZG trusted setup, as written by `nomos-cli kzg-setup`.
+    #[clap(long)]
+    global_params_path: String,
+    /// DA subnetworks, so also the number of columns of the encoded blobs.
+    #[clap(long, default_value_t = 2)]
+    num_subnets: u16,
+    #[clap(long, default_value_t = 10)]
+    security_param: u32,
+    #[clap(long, default_value_t = 0.9)]
+    active_slot_coeff: f64,
+    /// Slot duration in seconds.
+    #[clap(long, default_value_t = 2)]
+    slot_duration: u64,
+}
+
+#[derive(Args, Debug)]
+struct Validate {
+    #[clap(long, value_enum)]
+    role: Role,
+    /// Config file to validate.
+    config: PathBuf,
+}
+
+impl Config {
+    pub fn run(self) -> Result<(), Box<dyn std::error::Error>> {
+        match self.command {
+            ConfigCommand::Generate(cmd) => cmd.run(),
+            ConfigCommand::Validate(cmd) => cmd.run(),
+        }
+    }
+}
+
+impl Generate {
+    fn run(self) -> Result<(), Box<dyn std::error::Error>> {
+        let params = NodeParams {
+            host: self.host,
+            network_port: self.network_port,
+            da_network_port: self.da_network_port,
+            mix_port: self.mix_port,
+            api_address: self.api_address,
+            data_dir: self.data_dir,
+            global_params_path: self.global_params_path,
+            num_subnets: self.num_subnets,
+            security_param: self.security_param,
+            active_slot_coeff: self.active_slot_coeff,
+            slot_duration: Duration::from_secs(self.slot_duration),
+        };
+        let header = format!(
+            "{:?} config of a single node network, generated by nomos-cli.\n\
+             Other nodes join by adding themselves to the mix and DA memberships, and their\n\
+             leader notes to the genesis state, which must then be the same in all configs.",
+            self.role
+        );
+        let (yaml, issues) = match self.role {
+            Role::Validator => {
+                let config = params.validator_config();
+                (
+                    annotated_yaml(&config, &header)?,
+                    validate_validator(&config),
+                )
+            }
+            Role::Executor => {
+                let config = params.executor_config();
+                (
+                    annotated_yaml(&config, &header)?,
+                    validate_executor(&config),
+                )
+            }
+        };
+        // a missing trusted setup is expected if it is generated afterwards
+        report(&issues);
+        match self.output {
+            Some(output) => {
+                std::fs::write(&output, yaml)?;
+                println!("Config written to {}", output.display());
+            }
+            None => print!("{yaml}"),
+        }
+        Ok(())
+    }
+}
+
+impl Validate {
+    fn run(self) -> Result<(), Box<dyn std::error::Error>> {
+        let yaml = std::fs::read_to_string(&self.config)?;
+        let issues = match self.role {
+            Role::Validator => validate_file(&yaml, validate_validator),
+            Role::Executor => validate_file(&yaml, validate_executor),
+        };
+        if issues.is_empty() {
+            println!("{} is valid", self.config.display());
+            return Ok(());
+        }
+        report(&issues);
+        Err(format!(
+            "{} has {} issue(s) ({})",
+            self.config.display(),
+            issues.len(),
+            summary(&issues)
+        )
+        .into())
+    }
+}
+
+fn report(issues: &[ConfigIssue]) {
+    for issue in issues {
+        eprintln!("{issue}");
+    }
+}
@@ -0,0 +1,508 @@
+// std
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+// crates
+use cryptarchia_consensus::{CryptarchiaSettings, TimeConfig};
+use nomos_da_network_service::backends::libp2p::common::DaNetworkBackendSettings;
+use nomos_da_sampling::backend::kzgrs::{KzgrsSamplingBackendSettings, ALL_SAMPLES};
+use nomos_da_verifier::backend::kzgrs::KzgrsDaVerifierSettings;
+use nomos_executor::config::Config as ExecutorConfig;
+use nomos_libp2p::{ed25519, PeerId};
+use nomos_mix_service::backends::libp2p::Libp2pMixBackendSettings;
+use nomos_mix_service::MixConfig;
+use nomos_node::{Config as ValidatorConfig, NomosDaMembership};
+use serde::de::DeserializeOwned;
+use subnetworks_assignations::MembershipHandler;
+// internal
+
+/// Problem found in a config, located by the dotted path of the setting
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigIssue {
+    pub path: String,
+    pub message: String,
+}
+
+impl Display for ConfigIssue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+#[derive(Default)]
+struct Issues(Vec<ConfigIssue>);
+
+impl Issues {
+    fn push(&mut self, path: impl Into<String>, message: impl Into<String>) {
+        self.0.push(ConfigIssue {
+            path: path.into(),
+            message: message.into(),
+        });
+    }
+
+    fn check(&mut self, condition: bool, path: impl Into<String>, message: impl Into<String>) {
+        if !condition {
+            self.push(path, message);
+        }
+    }
+}
+
+/// Parses a YAML config, reporting the path of the setting that could not be deserialized
+pub fn parse<T: DeserializeOwned>(yaml: &str) -> Result<T, ConfigIssue> {
+    let deserializer = serde_yaml::Deserializer::from_str(yaml);
+    serde_path_to_error::deserialize(deserializer).map_err(|e| {
+        let path = e.path().to_string();
+        ConfigIssue {
+            path: if path == "." { "<root>".into() } else { path },
+            message: e.into_inner().to_string(),
+        }
+    })
+}
+
+pub fn validate_validator(config: &ValidatorConfig) -> Vec<ConfigIssue> {
+    let mut issues = Issues::default();
+    // keys are checked against the rest of the config as the node would use them
+    let config = &match config.clone().update_from_keystore() {
+        Ok(config) => config,
+        Err(e) => {
+            issues.push("keystore", format!("{e:#}"));
+            config.clone()
+        }
+    };
+    let da_network = &config.da_network.backend;
+    check_da_network(&mut issues, "da_network.backend", da_network);
+    check_da_verifier(
+        &mut issues,
+        &config.da_verifier.verifier_settings,
+        da_network,
+    );
+    check_da_sampling(
+        &mut issues,
+        &config.da_sampling.sampling_settings,
+        &da_network.membership,
+    );
+    check_mix(&mut issues, &config.mix);
+    check_cryptarchia(&mut issues, &config.cryptarchia);
+    issues.check(
+        config.da_indexer.security_param
+            == config.cryptarchia.config.consensus_config.security_param,
+        "da_indexer.security_param",
+        "differs from cryptarchia.config.consensus_config.security_param",
+    );
+    issues.0
+}
+
+pub fn validate_executor(config: &ExecutorConfig) -> Vec<ConfigIssue> {
+    let mut issues = Issues::default();
+    // keys are checked against the rest of the config as the node would use them
+    let config = &match config.clone().update_from_keystore() {
+        Ok(config) => config,
+        Err(e) => {
+            issues.push("keystore", format!("{e:#}"));
+            config.clone()
+        }
+    };
+    let da_network = &config.da_network.backend.validator_settings;
+    check_da_network(
+        &mut issues,
+        "da_network.backend.validator_settings",
+        da_network,
+    );
+    let num_subnetworks = da_network.membership.subnetwork_size;
+    issues.check(
+        config.da_network.backend.num_subnets as usize == num_subnetworks,
+        "da_network.backend.num_subnets",
+        format!(
+            "is {} but the membership has {num_subnetworks} subnetworks",
+            config.da_network.backend.num_subnets
+        ),
+    );
+    let encoder = &config.da_dispersal.backend.encoder_settings;
+    issues.check(
+        encoder.num_columns == num_subnetworks,
+        "da_dispersal.backend.encoder_settings.num_columns",
+        format!(
+            "is {} but blobs are dispersed over {num_subnetworks} subnetworks, one per column",
+            encoder.num_columns
+        ),
+    );
+    check_file(
+        &mut issues,
+        "da_dispersal.backend.encoder_settings.global_params_path",
+        &encoder.global_params_path,
+    );
+    check_da_verifier(
+        &mut issues,
+        &config.da_verifier.verifier_settings,
+        da_network,
+    );
+    check_da_sampling(
+        &mut issues,
+        &config.da_sampling.sampling_settings,
+        &da_network.membership,
+    );
+    check_mix(&mut issues, &config.mix);
+    check_cryptarchia(&mut issues, &config.cryptarchia);
+    issues.check(
+        config.da_indexer.security_param
+            == config.cryptarchia.config.consensus_config.security_param,
+        "da_indexer.security_param",
+        "differs from cryptarchia.config.consensus_config.security_param",
+    );
+    issues.0
+}
+
+fn peer_id(node_key: &ed25519::SecretKey) -> PeerId {
+    PeerId::from_public_key(&ed25519::Keypair::from(node_key.clone()).public().into())
+}
+
+fn check_file(issues: &mut Issues, path: &str, file: &str) {
+    issues.check(
+        Path::new(file).is_file(),
+        path,
+        format!("file {file} does not exist"),
+    );
+}
+
+fn check_da_network(
+    issues: &mut Issues,
+    path: &str,
+    settings: &DaNetworkBackendSettings<NomosDaMembership>,
+) {
+    let membership = &settings.membership;
+    let membership_path = format!("{path}.membership");
+    issues.check(
+        membership.assignations.len() == membership.subnetwork_size,
+        format!("{membership_path}.assignations"),
+        format!(
+            "has {} subnetworks but subnetwork_size is {}",
+            membership.assignations.len(),
+            membership.subnetwork_size
+        ),
+    );
+    for (index, members) in membership.assignations.iter().enumerate() {
+        issues.check(
+            members.len() >= membership.dispersal_factor,
+            format!("{membership_path}.assignations[{index}]"),
+            format!(
+                "has {} members, fewer than the dispersal_factor of {}",
+                members.len(),
+                membership.dispersal_factor
+            ),
+        );
+    }
+    let own_peer_id = peer_id(&settings.node_key);
+    for member in membership.members() {
+        issues.check(
+            member == own_peer_id || settings.addresses.contains_key(&member),
+            format!("{path}.addresses"),
+            format!("has no address for member {member}"),
+        );
+    }
+}
+
+fn check_da_verifier(
+    issues: &mut Issues,
+    settings: &KzgrsDaVerifierSettings,
+    da_network: &DaNetworkBackendSettings<NomosDaMembership>,
+) {
+    let path = "da_verifier.verifier_settings";
+    let num_subnetworks = da_network.membership.subnetwork_size;
+    issues.check(
+        settings.domain_size == num_subnetworks,
+        format!("{path}.domain_size"),
+        format!(
+            "is {} but the DA membership has {num_subnetworks} subnetworks, one per column",
+            settings.domain_size
+        ),
+    );
+    let assigned = da_network
+        .membership
+        .membership(&peer_id(&da_network.node_key));
+    issues.check(
+        settings.index == assigned,
+        format!("{path}.index"),
+        format!(
+            "is {:?} but the DA membership assigns the node to {:?}",
+            sorted(&settings.index),
+            sorted(&assigned)
+        ),
+    );
+    let key_valid = settings
+        .sk
+        .as_deref()
+        .and_then(|sk| hex::decode(sk).ok())
+        .is_some_and(|bytes| blst::min_sig::SecretKey::from_bytes(&bytes).is_ok());
+    issues.check(
+        key_valid,
+        format!("{path}.sk"),
+        "is not a hex encoded BLS secret key",
+    );
+    check_file(
+        issues,
+        &format!("{path}.global_params_path"),
+        &settings.global_params_path,
+    );
+}
+
+fn sorted(set: &std::collections::HashSet<u32>) -> Vec<u32> {
+    let mut values = set.iter().copied().collect::<Vec<_>>();
+    values.sort();
+    values
+}
+
+fn check_da_sampling(
+    issues: &mut Issues,
+    settings: &KzgrsSamplingBackendSettings,
+    membership: &NomosDaMembership,
+) {
+    let path = "da_sampling.sampling_settings";
+    issues.check(
+        settings.num_subnets as usize == membership.subnetwork_size,
+        format!("{path}.num_subnets"),
+        format!(
+            "is {} but the DA membership has {} subnetworks",
+            settings.num_subnets, membership.subnetwork_size
+        ),
+    );
+    issues.check(
+        settings.num_samples > 0 && settings.num_samples <= settings.num_subnets,
+        format!("{path}.num_samples"),
+        format!(
+            "must be between 1 and num_subnets ({})",
+            settings.num_subnets
+        ),
+    );
+    issues.check(
+        settings.required_samples <= settings.num_samples
+            || settings.required_samples == ALL_SAMPLES,
+        format!("{path}.required_samples"),
+        format!("exceeds num_samples ({})", settings.num_samples),
+    );
+}
+
+fn check_mix(issues: &mut Issues, settings: &MixConfig<Libp2pMixBackendSettings>) {
+    let members = settings.membership.len();
+    let num_mix_layers = settings
+        .message_blend
+        .cryptographic_processor
+        .num_mix_layers;
+    issues.check(
+        num_mix_layers > 0 && num_mix_layers <= members,
+        "mix.message_blend.cryptographic_processor.num_mix_layers",
+        format!("must be between 1 and the number of mix nodes ({members})"),
+    );
+    // the mock mix message uses the private key as the node public key
+    let private_key = settings.message_blend.cryptographic_processor.private_key;
+    issues.check(
+        private_key.is_some_and(|private_key| {
+            settings
+                .membership
+                .iter()
+                .any(|node| node.public_key == private_key)
+        }),
+        "mix.membership",
+        "does not contain the node itself",
+    );
+}
+
+fn check_cryptarchia<Ts, Bs, N, M>(
+    issues: &mut Issues,
+    settings: &CryptarchiaSettings<Ts, Bs, N, M>,
+) {
+    let config = &settings.config;
+    let consensus = &config.consensus_config;
+    let path = "cryptarchia.config";
+    issues.check(
+        consensus.security_param > 0,
+        format!("{path}.consensus_config.security_param"),
+        "must be positive",
+    );
+    let coeff_valid = consensus.active_slot_coeff > 0.0 && consensus.active_slot_coeff <= 1.0;
+    issues.check(
+        coeff_valid,
+        format!("{path}.consensus_config.active_slot_coeff"),
+        "must be in (0, 1]",
+    );
+    for (name, value) in [
+        (
+            "epoch_stake_distribution_stabilization",
+            config.epoch_stake_distribution_stabilization,
+        ),
+        (
+            "epoch_period_nonce_buffer",
+            config.epoch_period_nonce_buffer,
+        ),
+        (
+            "epoch_period_nonce_stabilization",
+            config.epoch_period_nonce_stabilization,
+        ),
+    ] {
+        issues.check(value > 0, format!("{path}.{name}"), "must be positive");
+    }
+    if coeff_valid && consensus.security_param > 0 {
+        issues.check(
+            config.base_period_length() > 0,
+            format!("{path}.consensus_config"),
+            "security_param / active_slot_coeff must be at least 1 slot",
+        );
+    }
+    check_time(issues, &settings.time);
+
+    let genesis = &settings.genesis_state;
+    let path = "cryptarchia.genesis_state";
+    issues.check(
+        u64::from(genesis.slot()) == 0,
+        format!("{path}.slot"),
+        "must be 0",
+    );
+    issues.check(
+        u32::from(genesis.epoch_state().epoch()) == 0,
+        format!("{path}.epoch_state.epoch"),
+        "must be 0",
+    );
+    issues.check(
+        u32::from(genesis.next_epoch_state().epoch()) == 1,
+        format!("{path}.next_epoch_state.epoch"),
+        "must be 1",
+    );
+    issues.check(
+        genesis.epoch_state().total_stake() > 0,
+        format!("{path}.epoch_state.total_stake"),
+        "must be positive",
+    );
+    issues.check(
+        !genesis.lead_commitments().commitments().is_empty(),
+        format!("{path}.lead_commitments"),
+        "is empty, no node could propose blocks",
+    );
+
+    let leader = &settings.leader_config;
+    let nf_pk = leader.nf_sk.as_ref().map(|nf_sk| nf_sk.commit());
+    let commitments = genesis.lead_commitments().commitments();
+    let mut leader_stake = 0u64;
+    for (index, note) in leader.notes.iter().enumerate() {
+        leader_stake += note.value;
+        issues.check(
+            nf_pk.is_some_and(|nf_pk| {
+                let commitment = note.commit(nf_pk);
+                commitments.iter().any(|c| *c == commitment)
+            }),
+            format!("cryptarchia.leader_config.notes[{index}]"),
+            "is not in the genesis lead commitments, or was not created with nf_sk",
+        );
+    }
+    issues.check(
+        leader_stake <= genesis.epoch_state().total_stake(),
+        "cryptarchia.leader_config.notes",
+        format!(
+            "hold {leader_stake} but the genesis total stake is {}",
+            genesis.epoch_state().total_stake()
+        ),
+    );
+}
+
+fn check_time(issues: &mut Issues, time: &TimeConfig) {
+    issues.check(
+        !time.slot_duration.is_zero(),
+        "cryptarchia.time.slot_duration",
+        "must be positive",
+    );
+}
+
+/// Parses a YAML config and reports the issues `validate` finds in it
+pub fn validate_file<T: DeserializeOwned>(
+    yaml: &str,
+    validate: impl Fn(&T) -> Vec<ConfigIssue>,
+) -> Vec<ConfigIssue> {
+    match parse::<T>(yaml) {
+        Ok(config) => validate(&config),
+        Err(issue) => vec![issue],
+    }
+}
+
+/// Counts the issues by their top level setting, for the summary line
+pub fn summary(issues: &[ConfigIssue]) -> String {
+    let mut sections: HashMap<&str, usize> = HashMap::new();
+    for issue in issues {
+        let section = issue.path.split(['.', '[']).next().unwrap_or_default();
+        *sections.entry(section).or_default() += 1;
+    }
+    let mut sections = sections.into_iter().collect::<Vec<_>>();
+    sections.sort();
+    sections
+        .into_iter()
+        .map(|(section, count)| format!("{section}: {count}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cmds::config::generate::NodeParams;
+    use std::time::Duration;
+
+    fn params(global_params_path: String) -> NodeParams {
+        NodeParams {
+            host: [127, 0, 0, 1].into(),
+            network_port: 3000,
+            da_network_port: 3300,
+            mix_port: 3400,
+            api_address: "127.0.0.1:8080".parse().unwrap(),
+            data_dir: "./data".into(),
+            global_params_path,
+            num_subnets: 4,
+            security_param: 10,
+            active_slot_coeff: 0.9,
+            slot_duration: Duration::from_secs(2),
+        }
+    }
+
+    #[test]
+    fn generated_configs_are_valid() {
+        let global_params = tempfile::NamedTempFile::new().unwrap();
+        let params = params(global_params.path().to_string_lossy().into_owned());
+
+        let yaml = serde_yaml::to_string(&params.validator_config()).unwrap();
+        assert_eq!(validate_file(&yaml, validate_validator), vec![]);
+        let yaml = serde_yaml::to_string(&params.executor_config()).unwrap();
+        assert_eq!(validate_file(&yaml, validate_executor), vec![]);
+    }
+
+    #[test]
+    fn reports_cross_field_issues() {
+        let global_params = tempfile::NamedTempFile::new().unwrap();
+        let mut config =
+            params(global_params.path().to_string_lossy().into_owned()).executor_config();
+        config.da_verifier.verifier_settings.domain_size = 2;
+        config.da_network.backend.num_subnets = 8;
+        config.cryptarchia.leader_config.nf_sk = Some(cl::NullifierSecret::zero());
+
+        let paths = validate_executor(&config)
+            .into_iter()
+            .map(|issue| issue.path)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            vec![
+                "da_network.backend.num_subnets",
+                "da_verifier.verifier_settings.domain_size",
+                "cryptarchia.leader_config.notes[0]",
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_parse_error_path() {
+        let global_params = tempfile::NamedTempFile::new().unwrap();
+        let config = params(global_params.path().to_string_lossy().into_owned()).validator_config();
+        let yaml = serde_yaml::to_string(&config)
+            .unwrap()
+            .replace("num_samples: 1", "num_samples: many");
+
+        let issues = validate_file(&yaml, validate_validator);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].path, "da_sampling.sampling_settings.num_samples");
+    }
+}
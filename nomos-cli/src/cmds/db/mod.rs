@@ -0,0 +1,200 @@
+pub mod namespace;
+pub mod snapshot;
+
+// std
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+// crates
+use clap::{Args, Subcommand};
+use cryptarchia_consensus::history::height_key;
+use rocksdb::{IteratorMode, Options, DB};
+use serde_json::{json, Value};
+// internal
+use namespace::Namespace;
+use snapshot::open_read_only;
+
+#[derive(Args, Debug)]
+pub struct Db {
+    #[clap(subcommand)]
+    command: DbCommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum DbCommand {
+    /// Count the entries and their sizes in each key namespace.
+    Stats {
+        /// Node database, as set in `storage.db_path`.
+        db: PathBuf,
+    },
+    /// Print the decoded entries of the database, one JSON object per line.
+    Show {
+        db: PathBuf,
+        #[clap(long, value_enum)]
+        namespace: Option<Namespace>,
+        /// Only entries whose key starts with these hex encoded bytes.
+        #[clap(long)]
+        key_prefix: Option<String>,
+        #[clap(long)]
+        limit: Option<usize>,
+    },
+    /// Print a block of the canonical chain, by id or height.
+    Block {
+        db: PathBuf,
+        #[clap(long, conflicts_with = "height", required_unless_present = "height")]
+        id: Option<String>,
+        #[clap(long)]
+        height: Option<u64>,
+    },
+    /// Copy the database into a snapshot directory, which nodes can be bootstrapped from.
+    /// The node may keep running, the snapshot holds what it had written when the export started.
+    Export {
+        db: PathBuf,
+        /// Snapshot directory, which must not exist.
+        #[clap(short, long)]
+        output: PathBuf,
+    },
+    /// Restore a snapshot as the database of a node that wasn't started yet.
+    Import {
+        snapshot: PathBuf,
+        /// Node database, which must not exist or be empty.
+        #[clap(long)]
+        db: PathBuf,
+    },
+}
+
+#[derive(Default)]
+struct NamespaceStats {
+    entries: u64,
+    key_bytes: u64,
+    value_bytes: u64,
+}
+
+impl Db {
+    pub fn run(self) -> Result<(), Box<dyn std::error::Error>> {
+        match self.command {
+            DbCommand::Stats { db } => stats(db),
+            DbCommand::Show {
+                db,
+                namespace,
+                key_prefix,
+                limit,
+            } => {
+                let key_prefix = key_prefix.map(hex::decode).transpose()?.unwrap_or_default();
+                show(db, namespace, &key_prefix, limit.unwrap_or(usize::MAX))
+            }
+            DbCommand::Block { db, id, height } => block(db, id, height),
+            DbCommand::Export { db, output } => {
+                let manifest = snapshot::export(&db, &output)?;
+                println!(
+                    "Exported {} entries to {}",
+                    manifest.column_families.values().sum::<u64>(),
+                    output.display()
+                );
+                Ok(())
+            }
+            DbCommand::Import { snapshot, db } => {
+                let manifest = snapshot::import(&snapshot, &db)?;
+                println!(
+                    "Imported {} entries exported from {} into {}",
+                    manifest.column_families.values().sum::<u64>(),
+                    manifest.source.display(),
+                    db.display()
+                );
+                Ok(())
+            }
+        }
+    }
+}
+
+fn stats(path: PathBuf) -> Result<(), Box<dyn std::error::Error>> {
+    let db = open_read_only(&path)?;
+    let mut stats: BTreeMap<(String, Namespace), NamespaceStats> = BTreeMap::new();
+    for name in DB::list_cf(&Options::default(), &path)? {
+        let cf = db.cf_handle(&name).expect("column family was just listed");
+        for entry in db.iterator_cf(cf, IteratorMode::Start) {
+            let (key, value) = entry?;
+            let entry_stats = stats
+                .entry((name.clone(), Namespace::of(&key)))
+                .or_default();
+            entry_stats.entries += 1;
+            entry_stats.key_bytes += key.len() as u64;
+            entry_stats.value_bytes += value.len() as u64;
+        }
+    }
+    println!(
+        "{:<16} {:<24} {:>10} {:>14} {:>14}",
+        "column family", "namespace", "entries", "key bytes", "value bytes"
+    );
+    for ((cf, namespace), entry_stats) in stats {
+        println!(
+            "{:<16} {:<24} {:>10} {:>14} {:>14}",
+            cf,
+            namespace.to_string(),
+            entry_stats.entries,
+            entry_stats.key_bytes,
+            entry_stats.value_bytes
+        );
+    }
+    Ok(())
+}
+
+fn show(
+    path: PathBuf,
+    namespace: Option<Namespace>,
+    key_prefix: &[u8],
+    limit: usize,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let db = open_read_only(&path)?;
+    let mut shown = 0;
+    for name in DB::list_cf(&Options::default(), &path)? {
+        let cf = db.cf_handle(&name).expect("column family was just listed");
+        for entry in db.iterator_cf(cf, IteratorMode::Start) {
+            if shown == limit {
+                return Ok(());
+            }
+            let (key, value) = entry?;
+            let entry_namespace = Namespace::of(&key);
+            if !key.starts_with(key_prefix)
+                || namespace.is_some_and(|namespace| namespace != entry_namespace)
+            {
+                continue;
+            }
+            // an entry that can't be decoded is reported, as it's likely what is being looked for
+            let decoded = entry_namespace
+                .decode(&key, &value)
+                .unwrap_or_else(|e| json!({ "key": hex::encode(&key), "error": e.to_string() }));
+            let mut line = json!({
+                "column_family": name,
+                "namespace": entry_namespace.to_string(),
+            });
+            if let (Value::Object(line), Value::Object(decoded)) = (&mut line, decoded) {
+                line.extend(decoded);
+            }
+            println!("{line}");
+            shown += 1;
+        }
+    }
+    Ok(())
+}
+
+fn block(
+    path: PathBuf,
+    id: Option<String>,
+    height: Option<u64>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let db = open_read_only(&path)?;
+    // header ids serialize to their raw bytes, so they are used as is as block keys
+    let key = match (id, height) {
+        (Some(id), _) => hex::decode(id.trim_start_matches("0x"))?,
+        (None, Some(height)) => db
+            .get(height_key(height))?
+            .ok_or_else(|| format!("no block at height {height} in the canonical chain"))?,
+        (None, None) => unreachable!("clap requires either the id or the height"),
+    };
+    let value = db
+        .get(&key)?
+        .ok_or_else(|| format!("block 0x{} not found", hex::encode(&key)))?;
+    let block = Namespace::Blocks.decode(&key, &value)?;
+    println!("{}", serde_json::to_string_pretty(&block)?);
+    Ok(())
+}
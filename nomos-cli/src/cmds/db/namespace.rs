@@ -0,0 +1,260 @@
+// std
+use std::fmt::{Display, Formatter};
+// crates
+use clap::ValueEnum;
+use cryptarchia_consensus::history::{
+    InclusionLocation, CHAIN_BLOB_KEY_PREFIX, CHAIN_HEIGHT_KEY_PREFIX, CHAIN_TX_KEY_PREFIX,
+};
+use nomos_core::block::Block;
+use nomos_core::da::blob::info::DispersedBlobInfo;
+use nomos_core::da::blob::metadata::Metadata;
+use nomos_core::header::{Header, HeaderId};
+use nomos_core::wire;
+use nomos_da_light::{Availability, BlockAvailability};
+use nomos_da_storage::rocksdb::{
+    DA_FINALIZED_HEIGHT_KEY, DA_INCLUDED_KEY_PREFIX, DA_LIGHT_AVAILABILITY_KEY_PREFIX,
+    DA_LIGHT_HEADER_KEY_PREFIX, DA_PENDING_VID_KEY_PREFIX, DA_PRUNED_HEIGHT_KEY,
+    DA_PRUNED_INDEX_KEY_PREFIX, DA_VERIFIED_KEY_PREFIX, DA_VID_KEY_PREFIX,
+};
+use nomos_ledger::leader_proof::LeaderProof;
+use nomos_node::{BlobInfo, Tx};
+use serde_json::{json, Value};
+// internal
+
+/// Blocks are stored under their serialized header id, without any prefix
+const BLOCK_KEY_LEN: usize = 32;
+const APP_ID_LEN: usize = 32;
+const BLOB_ID_LEN: usize = 32;
+
+/// Group of keys written by the same service, and so holding the same kind of values
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum Namespace {
+    Blocks,
+    ChainHeight,
+    ChainTx,
+    ChainBlob,
+    DaVid,
+    DaPendingVid,
+    DaPrunedIndex,
+    DaVerified,
+    DaIncluded,
+    DaIndexer,
+    DaLightHeader,
+    DaLightAvailability,
+    Unknown,
+}
+
+impl Namespace {
+    const PREFIXED: [(Namespace, &'static str); 10] = [
+        (Namespace::ChainHeight, CHAIN_HEIGHT_KEY_PREFIX),
+        (Namespace::ChainTx, CHAIN_TX_KEY_PREFIX),
+        (Namespace::ChainBlob, CHAIN_BLOB_KEY_PREFIX),
+        (Namespace::DaVid, DA_VID_KEY_PREFIX),
+        (Namespace::DaPendingVid, DA_PENDING_VID_KEY_PREFIX),
+        (Namespace::DaPrunedIndex, DA_PRUNED_INDEX_KEY_PREFIX),
+        (Namespace::DaVerified, DA_VERIFIED_KEY_PREFIX),
+        (Namespace::DaIncluded, DA_INCLUDED_KEY_PREFIX),
+        (Namespace::DaLightHeader, DA_LIGHT_HEADER_KEY_PREFIX),
+        (
+            Namespace::DaLightAvailability,
+            DA_LIGHT_AVAILABILITY_KEY_PREFIX,
+        ),
+    ];
+
+    pub fn of(key: &[u8]) -> Self {
+        if key == DA_FINALIZED_HEIGHT_KEY.as_bytes() || key == DA_PRUNED_HEIGHT_KEY.as_bytes() {
+            return Namespace::DaIndexer;
+        }
+        Self::PREFIXED
+            .into_iter()
+            .find(|(_, prefix)| key.starts_with(prefix.as_bytes()))
+            .map(|(namespace, _)| namespace)
+            .unwrap_or(if key.len() == BLOCK_KEY_LEN {
+                Namespace::Blocks
+            } else {
+                Namespace::Unknown
+            })
+    }
+
+    fn prefix(&self) -> &'static str {
+        Self::PREFIXED
+            .into_iter()
+            .find(|(namespace, _)| namespace == self)
+            .map(|(_, prefix)| prefix)
+            .unwrap_or_default()
+    }
+
+    /// Decode an entry of this namespace into JSON, failing if it wasn't written by a
+    /// node of the same version
+    pub fn decode(&self, key: &[u8], value: &[u8]) -> Result<Value, wire::Error> {
+        let suffix = &key[self.prefix().len()..];
+        Ok(match self {
+            Namespace::Blocks => {
+                let block: Block<Tx, BlobInfo> = wire::deserialize(value)?;
+                block_json(&block)
+            }
+            Namespace::ChainHeight => json!({
+                "height": be_u64(suffix),
+                "block": wire::deserialize::<HeaderId>(value)?,
+            }),
+            Namespace::ChainTx => json!({
+                "tx": wire::deserialize::<String>(suffix).unwrap_or_else(|_| hex::encode(suffix)),
+                "location": wire::deserialize::<InclusionLocation>(value)?,
+            }),
+            Namespace::ChainBlob => json!({
+                "blob_id": hex::encode(suffix),
+                "location": wire::deserialize::<InclusionLocation>(value)?,
+            }),
+            Namespace::DaVid | Namespace::DaPendingVid => {
+                let (app_id, index) = suffix.split_at(APP_ID_LEN.min(suffix.len()));
+                json!({
+                    "app_id": hex::encode(app_id),
+                    "index": be_u64(index),
+                    "blob_id": hex::encode(value),
+                })
+            }
+            Namespace::DaPrunedIndex => json!({
+                "app_id": hex::encode(suffix),
+                "index": be_u64(value),
+            }),
+            Namespace::DaIncluded => json!({
+                "blob_id": hex::encode(suffix),
+            }),
+            Namespace::DaVerified => {
+                let (blob_id, column) = suffix.split_at(BLOB_ID_LEN.min(suffix.len()));
+                json!({
+                    "blob_id": hex::encode(blob_id),
+                    "column": be_u64(column),
+                    "attestation": hex::encode(value),
+                })
+            }
+            Namespace::DaIndexer => json!({
+                "key": String::from_utf8_lossy(key),
+                "height": be_u64(value),
+            }),
+            Namespace::DaLightHeader => {
+                let header: Header = wire::deserialize(value)?;
+                header_json(&header)
+            }
+            Namespace::DaLightAvailability => {
+                let availability: BlockAvailability = wire::deserialize(value)?;
+                availability_json(suffix, &availability)
+            }
+            Namespace::Unknown => json!({
+                "key": hex::encode(key),
+                "value": hex::encode(value),
+            }),
+        })
+    }
+}
+
+impl Display for Namespace {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = self
+            .to_possible_value()
+            .expect("namespaces are not skipped");
+        f.write_str(name.get_name())
+    }
+}
+
+fn be_u64(bytes: &[u8]) -> u64 {
+    let mut buffer = [0u8; 8];
+    let len = bytes.len().min(8);
+    buffer[8 - len..].copy_from_slice(&bytes[bytes.len() - len..]);
+    u64::from_be_bytes(buffer)
+}
+
+fn header_json(header: &Header) -> Value {
+    // leader proofs are too large to be useful in a dump, the nullifier identifies them
+    json!({
+        "id": header.id(),
+        "parent": header.parent(),
+        "slot": header.slot(),
+        "leader_nullifier": hex::encode(header.leader_proof().nullifier().as_bytes()),
+        "orphaned_proofs": header.orphaned_proofs().len(),
+    })
+}
+
+fn block_json(block: &Block<Tx, BlobInfo>) -> Value {
+    let blobs = block
+        .blobs()
+        .map(|blob| {
+            let (app_id, index) = blob.metadata();
+            json!({
+                "blob_id": hex::encode(blob.blob_id()),
+                "app_id": hex::encode(app_id),
+                "index": index.to_u64(),
+            })
+        })
+        .collect::<Vec<_>>();
+    json!({
+        "header": header_json(block.header()),
+        "transactions": block.transactions().map(|tx| &tx.0).collect::<Vec<_>>(),
+        "blobs": blobs,
+    })
+}
+
+fn availability_json(block: &[u8], availability: &BlockAvailability) -> Value {
+    let blobs = |ids: &[[u8; 32]]| ids.iter().map(hex::encode).collect::<Vec<_>>();
+    let (status, missing) = match &availability.status {
+        Availability::Pending => ("pending", Vec::new()),
+        Availability::Available => ("available", Vec::new()),
+        Availability::Unavailable { missing } => ("unavailable", blobs(missing)),
+    };
+    json!({
+        "block": hex::encode(block),
+        "blobs": blobs(&availability.blobs),
+        "status": status,
+        "missing": missing,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cryptarchia_consensus::history::{height_key, key_bytes};
+
+    #[test]
+    fn keys_are_grouped_by_prefix() {
+        assert_eq!(Namespace::of(&height_key(7)), Namespace::ChainHeight);
+        assert_eq!(
+            Namespace::of(&key_bytes(CHAIN_BLOB_KEY_PREFIX, [1; 32])),
+            Namespace::ChainBlob
+        );
+        assert_eq!(
+            Namespace::of(DA_FINALIZED_HEIGHT_KEY.as_bytes()),
+            Namespace::DaIndexer
+        );
+        assert_eq!(
+            Namespace::of(&wire::serialize(&HeaderId::from([2; 32])).unwrap()),
+            Namespace::Blocks
+        );
+        assert_eq!(Namespace::of(b"something/else"), Namespace::Unknown);
+        assert_eq!(Namespace::DaPendingVid.to_string(), "da-pending-vid");
+    }
+
+    #[test]
+    fn decodes_chain_entries() {
+        let id = HeaderId::from([3; 32]);
+        let height = Namespace::ChainHeight
+            .decode(&height_key(5), &wire::serialize(&id).unwrap())
+            .unwrap();
+        assert_eq!(
+            height,
+            json!({ "height": 5, "block": hex::encode([3; 32]) })
+        );
+
+        let location = InclusionLocation {
+            block: id,
+            height: 5,
+            index: 1,
+        };
+        let tx_key = key_bytes(CHAIN_TX_KEY_PREFIX, wire::serialize(&"tx").unwrap());
+        let tx = Namespace::ChainTx
+            .decode(&tx_key, &wire::serialize(&location).unwrap())
+            .unwrap();
+        assert_eq!(tx["tx"], "tx");
+        assert_eq!(tx["location"]["height"], 5);
+        assert!(Namespace::ChainHeight.decode(&height_key(5), b"").is_err());
+    }
+}
@@ -0,0 +1,173 @@
+// std
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+// crates
+use rocksdb::{IteratorMode, Options, WriteBatch, DB};
+use serde::{Deserialize, Serialize};
+// internal
+
+/// Directory of the database inside a snapshot
+pub const SNAPSHOT_DB_DIR: &str = "db";
+pub const SNAPSHOT_MANIFEST: &str = "manifest.json";
+const SECONDARY_DIR: &str = "secondary";
+const BATCH_SIZE: usize = 1024;
+
+/// Written next to the copied database, to check that an import restored all of it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub source: PathBuf,
+    pub created_at_unix: u64,
+    /// Number of entries of each column family
+    pub column_families: BTreeMap<String, u64>,
+}
+
+/// Open all the column families of a database without locking it, so it can be inspected
+/// while the node is running. The instance only sees what was written before it was opened.
+pub fn open_read_only(path: &Path) -> Result<DB, rocksdb::Error> {
+    let opts = Options::default();
+    let column_families = DB::list_cf(&opts, path)?;
+    DB::open_cf_for_read_only(&opts, path, column_families, false)
+}
+
+/// Open a database as a secondary instance, caught up with the writes of the node. Unlike a
+/// read-only instance, it keeps the files it reads open so that the compactions of the node
+/// can't remove them from under it, and its view doesn't change until it catches up again.
+fn open_secondary(path: &Path, secondary_path: &Path) -> Result<DB, rocksdb::Error> {
+    let mut opts = Options::default();
+    opts.set_max_open_files(-1);
+    let column_families = DB::list_cf(&opts, path)?;
+    let db = DB::open_cf_as_secondary(&opts, path, secondary_path, column_families)?;
+    db.try_catch_up_with_primary()?;
+    Ok(db)
+}
+
+/// Copy every entry of `source` into a new database at `target`, with the same column
+/// families, returning the number of entries copied for each of them.
+fn copy_db(
+    source: &DB,
+    source_path: &Path,
+    target: &Path,
+) -> Result<BTreeMap<String, u64>, rocksdb::Error> {
+    let column_families = DB::list_cf(&Options::default(), source_path)?;
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.create_missing_column_families(true);
+    let target = DB::open_cf(&opts, target, &column_families)?;
+
+    let mut counts = BTreeMap::new();
+    for name in column_families {
+        let source_cf = source
+            .cf_handle(&name)
+            .expect("column families were listed from the source");
+        let target_cf = target
+            .cf_handle(&name)
+            .expect("column families were created in the target");
+        let mut batch = WriteBatch::default();
+        let mut count = 0;
+        for entry in source.iterator_cf(source_cf, IteratorMode::Start) {
+            let (key, value) = entry?;
+            batch.put_cf(target_cf, key, value);
+            count += 1;
+            if batch.len() >= BATCH_SIZE {
+                target.write(std::mem::take(&mut batch))?;
+            }
+        }
+        target.write(batch)?;
+        target.flush_cf(target_cf)?;
+        counts.insert(name, count);
+    }
+    Ok(counts)
+}
+
+/// Copy the database at `db_path` into a new snapshot directory, while the node may be running.
+/// The source is read through a secondary instance which is caught up once, so the copy holds
+/// what the node had written at that point.
+pub fn export(db_path: &Path, output: &Path) -> Result<Manifest, Box<dyn std::error::Error>> {
+    if output.exists() {
+        return Err(format!("{} already exists", output.display()).into());
+    }
+    std::fs::create_dir_all(output)?;
+    // the secondary instance keeps its own logs, out of the snapshot
+    let secondary_path = output.join(SECONDARY_DIR);
+    let column_families = {
+        let source = open_secondary(db_path, &secondary_path)?;
+        copy_db(&source, db_path, &output.join(SNAPSHOT_DB_DIR))?
+    };
+    std::fs::remove_dir_all(secondary_path)?;
+    let manifest = Manifest {
+        source: db_path.to_path_buf(),
+        created_at_unix: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+        column_families,
+    };
+    std::fs::write(
+        output.join(SNAPSHOT_MANIFEST),
+        serde_json::to_vec_pretty(&manifest)?,
+    )?;
+    Ok(manifest)
+}
+
+/// Restore a snapshot into a new database at `db_path`, to be used as the storage of a
+/// node that wasn't started yet.
+pub fn import(snapshot: &Path, db_path: &Path) -> Result<Manifest, Box<dyn std::error::Error>> {
+    if db_path.exists() && db_path.read_dir()?.next().is_some() {
+        return Err(format!("{} is not empty", db_path.display()).into());
+    }
+    let manifest: Manifest =
+        serde_json::from_slice(&std::fs::read(snapshot.join(SNAPSHOT_MANIFEST))?)?;
+    let snapshot_db = snapshot.join(SNAPSHOT_DB_DIR);
+    let column_families = copy_db(&open_read_only(&snapshot_db)?, &snapshot_db, db_path)?;
+    if column_families != manifest.column_families {
+        return Err(format!(
+            "imported entries {column_families:?} don't match the snapshot manifest {:?}",
+            manifest.column_families
+        )
+        .into());
+    }
+    Ok(manifest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn export_and_import_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("node");
+        // kept open, as by a running node
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.create_missing_column_families(true);
+        let db = DB::open_cf(&opts, &db_path, ["blocks"]).unwrap();
+        for i in 0..(BATCH_SIZE as u64 + 10) {
+            db.put(i.to_be_bytes(), b"value").unwrap();
+        }
+        db.put_cf(db.cf_handle("blocks").unwrap(), b"block", b"data")
+            .unwrap();
+
+        let snapshot = dir.path().join("snapshot");
+        let exported = export(&db_path, &snapshot).unwrap();
+        assert!(!snapshot.join(SECONDARY_DIR).exists());
+        assert_eq!(
+            exported.column_families,
+            BTreeMap::from([
+                ("blocks".to_string(), 1),
+                ("default".to_string(), BATCH_SIZE as u64 + 10)
+            ])
+        );
+        assert!(export(&db_path, &snapshot).is_err());
+
+        let restored = dir.path().join("restored");
+        assert_eq!(import(&snapshot, &restored).unwrap(), exported);
+        let db = open_read_only(&restored).unwrap();
+        assert_eq!(
+            db.get_cf(db.cf_handle("blocks").unwrap(), b"block")
+                .unwrap()
+                .as_deref(),
+            Some(&b"data"[..])
+        );
+        assert!(import(&snapshot, &restored).is_err());
+    }
+}
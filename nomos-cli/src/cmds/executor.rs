@@ -2,11 +2,16 @@
 use std::path::PathBuf;
 use std::sync::mpsc::Sender;
 // crates
+use blst::min_sig::SecretKey;
 use clap::Args;
+use kzgrs_backend::dispersal::Metadata;
+use kzgrs_backend::manifest::{
+    pad_to_chunk_size, split_chunks, Manifest, SignedManifest, DEFAULT_CHUNK_SIZE,
+};
+use nomos_core::da::BlobId;
 use reqwest::Url;
 // internal
 use executor_http_client::ExecutorHttpClient;
-use kzgrs_backend::{dispersal::Metadata, encoder::DaEncoderParams};
 
 #[derive(Args, Debug)]
 pub struct Disseminate {
@@ -20,11 +25,20 @@ pub struct Disseminate {
     #[clap(long)]
     pub app_id: String,
     /// Index for the Blob associated with Application ID.
+    /// Data larger than a chunk is described by a manifest at this index, and its chunks are
+    /// stored at the following indexes.
     #[clap(long)]
     pub index: u64,
     /// Executor address which is responsible for dissemination.
     #[clap(long)]
     pub addr: Url,
+    /// Maximum bytes of data in a single blob.
+    #[clap(long, default_value_t = DEFAULT_CHUNK_SIZE)]
+    pub chunk_size: usize,
+    /// Hex encoded BLS secret key signing the manifest of chunked data.
+    /// Required for data larger than a chunk, data is always chunked when it is set.
+    #[clap(long)]
+    pub sk: Option<String>,
 }
 
 impl Disseminate {
@@ -34,32 +48,49 @@ impl Disseminate {
 
         let client = ExecutorHttpClient::new(reqwest::Client::new(), self.addr.clone());
 
-        let mut bytes: Vec<u8> = if let Some(data) = &self.data {
+        let bytes: Vec<u8> = if let Some(data) = &self.data {
             data.clone().into_bytes()
         } else {
             let file_path = self.file.as_ref().unwrap();
             std::fs::read(file_path)?
         };
 
-        let remainder = bytes.len() % DaEncoderParams::MAX_BLS12_381_ENCODING_CHUNK_SIZE;
-        if remainder != 0 {
-            bytes.resize(
-                bytes.len() + (DaEncoderParams::MAX_BLS12_381_ENCODING_CHUNK_SIZE - remainder),
-                0,
-            );
-        }
-
         let app_id: [u8; 32] = hex::decode(&self.app_id)?
             .try_into()
             .map_err(|_| "Invalid app_id")?;
-        let metadata = Metadata::new(app_id, self.index.into());
+        if self.chunk_size == 0 {
+            return Err("Chunk size should be positive".into());
+        }
+        let sk = match &self.sk {
+            Some(sk) => {
+                Some(SecretKey::from_bytes(&hex::decode(sk)?).map_err(|_| "Invalid secret key")?)
+            }
+            None => None,
+        };
+        let dispersal = match sk {
+            Some(sk) => Dispersal::Chunked {
+                data: bytes,
+                chunk_size: self.chunk_size,
+                sk,
+            },
+            None if bytes.len() <= self.chunk_size => Dispersal::Single(bytes),
+            None => {
+                return Err(
+                    "Data larger than a chunk needs a secret key to sign its manifest".into(),
+                )
+            }
+        };
 
+        let index = self.index;
         let (res_sender, res_receiver) = std::sync::mpsc::channel();
-        std::thread::spawn(move || disperse_data(res_sender, client, bytes, metadata));
+        std::thread::spawn(move || disperse_data(res_sender, client, dispersal, app_id, index));
 
         match res_receiver.recv() {
             Ok(update) => match update {
-                Ok(_) => tracing::info!("Data successfully disseminated."),
+                Ok(blob_id) => tracing::info!(
+                    "Data successfully disseminated at index {index}, blob id {}",
+                    hex::encode(blob_id)
+                ),
                 Err(e) => {
                     tracing::error!("Error disseminating data: {e}");
                     return Err(e.into());
@@ -76,16 +107,74 @@ impl Disseminate {
     }
 }
 
+enum Dispersal {
+    Single(Vec<u8>),
+    /// Chunks at consecutive indexes, described by a manifest signed with `sk`
+    Chunked {
+        data: Vec<u8>,
+        chunk_size: usize,
+        sk: SecretKey,
+    },
+}
+
+/// Disperse the data at `index`, returns the id of the blob at that index
 #[tokio::main]
 async fn disperse_data(
-    res_sender: Sender<Result<(), String>>,
+    res_sender: Sender<Result<BlobId, String>>,
     client: ExecutorHttpClient,
-    bytes: Vec<u8>,
-    metadata: Metadata,
+    dispersal: Dispersal,
+    app_id: [u8; 32],
+    index: u64,
 ) {
-    let res = client
-        .publish_blob(bytes, metadata)
-        .await
-        .map_err(|err| format!("Failed to publish blob: {:?}", err));
+    let res = match dispersal {
+        Dispersal::Single(bytes) => {
+            publish_blob(&client, pad_to_chunk_size(&bytes), app_id, index).await
+        }
+        Dispersal::Chunked {
+            data,
+            chunk_size,
+            sk,
+        } => disperse_chunks(&client, &data, chunk_size, &sk, app_id, index).await,
+    };
     res_sender.send(res).unwrap();
 }
+
+async fn disperse_chunks(
+    client: &ExecutorHttpClient,
+    data: &[u8],
+    chunk_size: usize,
+    sk: &SecretKey,
+    app_id: [u8; 32],
+    index: u64,
+) -> Result<BlobId, String> {
+    let mut chunks = Vec::new();
+    for (chunk_index, chunk) in (index + 1..).zip(split_chunks(data, chunk_size)) {
+        let blob_id = publish_blob(client, chunk, app_id, chunk_index).await?;
+        tracing::info!(
+            "Chunk disseminated at index {chunk_index}, blob id {}",
+            hex::encode(blob_id)
+        );
+        chunks.push(blob_id);
+    }
+    let manifest: SignedManifest =
+        Manifest::new(app_id, (index + 1).into(), data, chunk_size, chunks).sign(sk);
+    publish_blob(
+        client,
+        pad_to_chunk_size(&manifest.to_bytes()),
+        app_id,
+        index,
+    )
+    .await
+}
+
+async fn publish_blob(
+    client: &ExecutorHttpClient,
+    bytes: Vec<u8>,
+    app_id: [u8; 32],
+    index: u64,
+) -> Result<BlobId, String> {
+    client
+        .publish_blob(bytes, Metadata::new(app_id, index.into()))
+        .await
+        .map_err(|err| format!("Failed to publish blob at index {index}: {:?}", err))
+}
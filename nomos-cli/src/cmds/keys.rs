@@ -0,0 +1,177 @@
+// std
+use std::path::PathBuf;
+// crates
+use clap::{Args, Subcommand};
+use nomos_keystore::{Identity, KeyType, Keystore, SecretKey};
+// internal
+
+const DEFAULT_PASSPHRASE_ENV: &str = "NOMOS_KEYSTORE_PASSPHRASE";
+
+#[derive(Args, Debug)]
+pub struct Keys {
+    #[clap(subcommand)]
+    command: KeysCommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum KeysCommand {
+    /// Generate a new key, stored in the keystore if one is given or printed as hex otherwise.
+    Generate {
+        /// One of network, mix, da-verifier or leader.
+        #[clap(long = "type")]
+        key_type: KeyType,
+        #[clap(flatten)]
+        target: Option<KeystoreEntry>,
+    },
+    /// Add a hex encoded secret key to the keystore.
+    Import {
+        #[clap(long = "type")]
+        key_type: KeyType,
+        #[clap(long)]
+        secret: String,
+        #[clap(flatten)]
+        target: KeystoreEntry,
+    },
+    /// Print a secret key of the keystore as hex, as expected by the node configs.
+    Export {
+        #[clap(flatten)]
+        source: KeystoreEntry,
+    },
+    /// Print the public identity of a key from the keystore or given as hex.
+    Show {
+        #[clap(long = "type", requires = "secret", conflicts_with = "keystore")]
+        key_type: Option<KeyType>,
+        #[clap(long, requires = "key_type")]
+        secret: Option<String>,
+        #[clap(flatten)]
+        source: Option<KeystoreEntry>,
+    },
+    /// Print the identities of all the keys in the keystore.
+    List {
+        #[clap(long)]
+        keystore: PathBuf,
+        #[clap(flatten)]
+        passphrase: Passphrase,
+    },
+}
+
+#[derive(Args, Debug)]
+struct KeystoreEntry {
+    /// Keystore file, created if it doesn't exist.
+    #[clap(long, requires = "name")]
+    keystore: PathBuf,
+    /// Name of the key in the keystore.
+    #[clap(long, requires = "keystore")]
+    name: String,
+    #[clap(flatten)]
+    passphrase: Passphrase,
+}
+
+#[derive(Args, Debug)]
+struct Passphrase {
+    /// Environment variable holding the keystore passphrase. New keystores are left
+    /// unencrypted if it is not set.
+    #[clap(long, default_value = DEFAULT_PASSPHRASE_ENV)]
+    passphrase_env: String,
+}
+
+impl Passphrase {
+    fn read(&self) -> Option<String> {
+        std::env::var(&self.passphrase_env).ok()
+    }
+}
+
+impl KeystoreEntry {
+    fn open(&self) -> Result<Keystore, Box<dyn std::error::Error>> {
+        Ok(Keystore::open(
+            &self.keystore,
+            self.passphrase.read().as_deref(),
+        )?)
+    }
+
+    fn open_or_create(&self) -> Result<Keystore, Box<dyn std::error::Error>> {
+        let passphrase = self.passphrase.read();
+        if self.keystore.exists() {
+            Ok(Keystore::open(&self.keystore, passphrase.as_deref())?)
+        } else {
+            Ok(Keystore::create(&self.keystore, passphrase.as_deref())?)
+        }
+    }
+
+    fn store(&self, key: &SecretKey) -> Result<(), Box<dyn std::error::Error>> {
+        let mut keystore = self.open_or_create()?;
+        let identity = keystore.insert(&self.name, key)?;
+        print_identity(Some(&self.name), &identity);
+        Ok(())
+    }
+}
+
+impl Keys {
+    pub fn run(self) -> Result<(), Box<dyn std::error::Error>> {
+        match self.command {
+            KeysCommand::Generate { key_type, target } => {
+                let key = SecretKey::generate(key_type, rand::thread_rng());
+                match target {
+                    Some(target) => target.store(&key)?,
+                    None => {
+                        print_identity(None, &key.identity());
+                        println!("secret: {}", hex::encode(key.to_bytes()));
+                    }
+                }
+            }
+            KeysCommand::Import {
+                key_type,
+                secret,
+                target,
+            } => {
+                let key = SecretKey::from_bytes(key_type, &hex::decode(secret)?)?;
+                target.store(&key)?;
+            }
+            KeysCommand::Export { source } => {
+                let key = source.open()?.get(&source.name)?;
+                println!("{}", hex::encode(key.to_bytes()));
+            }
+            KeysCommand::Show {
+                key_type,
+                secret,
+                source,
+            } => match (key_type, secret, source) {
+                (Some(key_type), Some(secret), None) => {
+                    let key = SecretKey::from_bytes(key_type, &hex::decode(secret)?)?;
+                    print_identity(None, &key.identity());
+                }
+                (None, None, Some(source)) => {
+                    // identities are stored in clear, but decrypting checks they match the secret
+                    let key = source.open()?.get(&source.name)?;
+                    print_identity(Some(&source.name), &key.identity());
+                }
+                _ => {
+                    return Err(
+                        "either --type and --secret or --keystore and --name are required".into(),
+                    )
+                }
+            },
+            KeysCommand::List {
+                keystore,
+                passphrase,
+            } => {
+                let keystore = Keystore::open(&keystore, passphrase.read().as_deref())?;
+                for (name, identity) in keystore.identities() {
+                    print_identity(Some(name), identity);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn print_identity(name: Option<&str>, identity: &Identity) {
+    if let Some(name) = name {
+        println!("name: {name}");
+    }
+    println!("type: {}", identity.key_type);
+    println!("public key: {}", identity.public_key);
+    if let Some(peer_id) = &identity.peer_id {
+        println!("peer id: {peer_id}");
+    }
+}
@@ -0,0 +1,31 @@
+// std
+use std::path::PathBuf;
+// crates
+use clap::Args;
+// internal
+use kzgrs_backend::global::{
+    global_parameters_from_file, global_parameters_to_file, global_parameters_with_degree,
+};
+
+#[derive(Args, Debug)]
+pub struct KzgSetup {
+    /// File to write the trusted setup to.
+    #[clap(short, long)]
+    pub output: PathBuf,
+    /// Highest degree of the committed polynomials, so the number of DA columns or rows.
+    #[clap(long, default_value_t = 8192)]
+    pub max_degree: usize,
+}
+
+impl KzgSetup {
+    /// Generate a development trusted setup. The secret comes from the local rng and is not
+    /// discarded by a ceremony, so the file is not fit for production networks.
+    pub fn run(self) -> Result<(), Box<dyn std::error::Error>> {
+        let params = global_parameters_with_degree(self.max_degree, &mut rand::thread_rng());
+        global_parameters_to_file(&params, &self.output)?;
+        // make sure the written file passes the same checks as the nodes do when loading it
+        global_parameters_from_file(&self.output)?;
+        println!("Trusted setup written to {}", self.output.display());
+        Ok(())
+    }
+}
@@ -1,4 +1,8 @@
+pub mod config;
+pub mod db;
 pub mod executor;
+pub mod keys;
+pub mod kzg;
 pub mod validator;
 
 // std
@@ -11,6 +15,16 @@ pub enum Command {
     /// Send data to the executor for encoding and dispersal.
     Disseminate(executor::Disseminate),
     Retrieve(validator::Retrieve),
+    /// Retrieve a file from its manifest and chunks, checking its content hash.
+    RetrieveFile(validator::RetrieveFile),
+    /// Generate a development KZG trusted setup file.
+    KzgSetup(kzg::KzgSetup),
+    /// Generate, import, export and inspect node keys.
+    Keys(keys::Keys),
+    /// Generate and validate node configs.
+    Config(config::Config),
+    /// Inspect a node database and export or import snapshots of it.
+    Db(db::Db),
 }
 
 impl Command {
@@ -18,6 +32,11 @@ impl Command {
         match self {
             Command::Disseminate(cmd) => cmd.run(),
             Command::Retrieve(cmd) => cmd.run(),
+            Command::RetrieveFile(cmd) => cmd.run(),
+            Command::KzgSetup(cmd) => cmd.run(),
+            Command::Keys(cmd) => cmd.run(),
+            Command::Config(cmd) => cmd.run(),
+            Command::Db(cmd) => cmd.run(),
         }?;
         Ok(())
     }
@@ -1,17 +1,25 @@
 // std
+use std::collections::BTreeMap;
+use std::path::PathBuf;
 use std::sync::mpsc::Sender;
 use std::{error::Error, ops::Range};
 // crates
+use blst::min_sig::PublicKey;
 use clap::Args;
-use reqwest::{Client, Url};
-use serde::{de::DeserializeOwned, Serialize};
+use reqwest::Url;
 // internal
-use kzgrs_backend::{common::blob::DaBlob, dispersal::Index};
-use nomos_core::da::blob::metadata;
-use nomos_node::api::{handlers::GetRangeReq, paths};
+use kzgrs_backend::{
+    common::{blob::DaBlob, ColumnIndex},
+    dispersal::{Index, Metadata},
+    manifest::{Manifest, SignedManifest},
+    reconstruction::reconstruct_without_missing_data,
+};
+use node_http_client::{IndexView, NodeHttpClient};
 use nomos_node::wire;
 
 type RetrievalRes<Index> = Result<Vec<(Index, Vec<Vec<u8>>)>, Box<dyn Error + Send + Sync>>;
+/// Columns of each blob found in a range, by index and blob id
+type RangeBlobs = BTreeMap<(Index, Vec<u8>), BTreeMap<ColumnIndex, DaBlob>>;
 
 #[derive(Args, Debug)]
 pub struct Retrieve {
@@ -27,6 +35,9 @@ pub struct Retrieve {
     /// Node address to retrieve appid blobs.
     #[clap(long)]
     pub addr: Url,
+    /// Only retrieve blobs from final blocks.
+    #[clap(long)]
+    pub finalized: bool,
 }
 
 impl Retrieve {
@@ -40,9 +51,14 @@ impl Retrieve {
         let addr = self.addr;
         let from: Index = self.from.into();
         let to: Index = self.to.into();
+        let view = if self.finalized {
+            IndexView::Final
+        } else {
+            IndexView::Pending
+        };
 
         let (res_sender, res_receiver) = std::sync::mpsc::channel();
-        std::thread::spawn(move || retrieve_data(res_sender, addr, app_id, from..to));
+        std::thread::spawn(move || retrieve_data(res_sender, addr, app_id, from..to, view));
 
         match res_receiver.recv() {
             Ok(update) => match update {
@@ -77,41 +93,187 @@ async fn retrieve_data(
     url: Url,
     app_id: [u8; 32],
     range: Range<Index>,
+    view: IndexView,
 ) {
-    let res = get_app_data_range_from_node::<kzgrs_backend::dispersal::Metadata>(
-        reqwest::Client::new(),
-        url,
-        app_id,
-        range,
-    )
-    .await;
+    let client = NodeHttpClient::new(reqwest::Client::new(), url);
+    let res = client
+        .get_range::<Metadata>(app_id, range, view)
+        .await
+        .map_err(Into::into);
     res_sender.send(res).unwrap();
 }
 
-async fn get_app_data_range_from_node<Metadata>(
-    client: Client,
-    url: Url,
-    app_id: Metadata::AppId,
-    range: Range<Metadata::Index>,
-) -> RetrievalRes<Metadata::Index>
-where
-    Metadata: metadata::Metadata + Serialize,
-    <Metadata as metadata::Metadata>::Index: Serialize + DeserializeOwned,
-    <Metadata as metadata::Metadata>::AppId: Serialize + DeserializeOwned,
-{
-    let url = url
-        .join(paths::DA_GET_RANGE)
-        .expect("Url should build properly");
-    let req = &GetRangeReq::<Metadata> { app_id, range };
-
-    Ok(client
-        .post(url)
-        .header("Content-Type", "application/json")
-        .json(&req)
-        .send()
-        .await
-        .unwrap()
-        .json::<Vec<(Metadata::Index, Vec<Vec<u8>>)>>()
-        .await
-        .unwrap())
+#[derive(Args, Debug)]
+pub struct RetrieveFile {
+    /// Application ID of data in Indexer.
+    #[clap(long)]
+    pub app_id: String,
+    /// Index of the manifest of the file.
+    #[clap(long)]
+    pub index: u64,
+    /// Node addresses to retrieve blobs from, their columns are combined.
+    #[clap(long, required = true)]
+    pub addr: Vec<Url>,
+    /// Columns of the encoded blobs, same as the executor `num_columns`.
+    #[clap(long)]
+    pub domain_size: usize,
+    /// Hex encoded BLS public key expected to sign the manifest.
+    /// Anyone can publish a manifest at an index, so the signer is always checked.
+    #[clap(long)]
+    pub pk: String,
+    /// File to write the retrieved data to.
+    #[clap(short, long)]
+    pub output: PathBuf,
+    /// Only retrieve blobs from final blocks.
+    #[clap(long)]
+    pub finalized: bool,
+}
+
+impl RetrieveFile {
+    pub fn run(self) -> Result<(), Box<dyn std::error::Error>> {
+        tracing::subscriber::set_global_default(tracing_subscriber::FmtSubscriber::new())
+            .expect("setting tracing default failed");
+
+        let app_id: [u8; 32] = hex::decode(&self.app_id)?
+            .try_into()
+            .map_err(|_| "Invalid app_id")?;
+        let pk =
+            PublicKey::from_bytes(&hex::decode(&self.pk)?).map_err(|_| "Invalid public key")?;
+        let view = if self.finalized {
+            IndexView::Final
+        } else {
+            IndexView::Pending
+        };
+        let clients = self
+            .addr
+            .into_iter()
+            .map(|addr| NodeHttpClient::new(reqwest::Client::new(), addr))
+            .collect();
+        let (index, domain_size) = (self.index, self.domain_size);
+
+        let (res_sender, res_receiver) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            retrieve_file(res_sender, clients, app_id, index, domain_size, pk, view)
+        });
+
+        match res_receiver.recv() {
+            Ok(Ok(data)) => {
+                std::fs::write(&self.output, data)?;
+                tracing::info!("File written to {}", self.output.display());
+            }
+            Ok(Err(e)) => {
+                tracing::error!("Error retrieving file: {e}");
+                return Err(e);
+            }
+            Err(e) => {
+                tracing::error!("Failed to receive from client thread: {e}");
+                return Err(Box::new(e));
+            }
+        }
+
+        tracing::info!("Done");
+        Ok(())
+    }
+}
+
+#[tokio::main]
+async fn retrieve_file(
+    res_sender: Sender<Result<Vec<u8>, Box<dyn Error + Send + Sync>>>,
+    clients: Vec<NodeHttpClient>,
+    app_id: [u8; 32],
+    index: u64,
+    domain_size: usize,
+    pk: PublicKey,
+    view: IndexView,
+) {
+    let res = fetch_file(&clients, app_id, index, domain_size, pk, view).await;
+    res_sender.send(res).unwrap();
+}
+
+async fn fetch_file(
+    clients: &[NodeHttpClient],
+    app_id: [u8; 32],
+    index: u64,
+    domain_size: usize,
+    pk: PublicKey,
+    view: IndexView,
+) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>> {
+    let manifest = fetch_blobs(clients, app_id, index.into()..(index + 1).into(), view)
+        .await?
+        .into_values()
+        .filter_map(|columns| reconstruct(&columns, domain_size))
+        .filter_map(|data| SignedManifest::from_bytes(&data).ok())
+        .find(|signed| signed.manifest.app_id == app_id && signed.public_key == pk)
+        .ok_or("No manifest signed by the public key at index")?
+        .manifest;
+    tracing::info!(
+        "Manifest lists {} chunks, {} bytes",
+        manifest.chunks.len(),
+        manifest.size
+    );
+
+    let chunks = fetch_chunks(clients, &manifest, domain_size, view).await?;
+    Ok(manifest.reassemble(&chunks)?)
+}
+
+async fn fetch_chunks(
+    clients: &[NodeHttpClient],
+    manifest: &Manifest,
+    domain_size: usize,
+    view: IndexView,
+) -> Result<Vec<Vec<u8>>, Box<dyn Error + Send + Sync>> {
+    let Some(first) = manifest.chunk_indexes().next() else {
+        return Ok(Vec::new());
+    };
+    let end = (first.to_u64() + manifest.chunks.len() as u64).into();
+    let mut blobs = fetch_blobs(clients, manifest.app_id, first..end, view).await?;
+    let mut chunks = Vec::with_capacity(manifest.chunks.len());
+    for (index, blob_id) in manifest.chunk_indexes().zip(&manifest.chunks) {
+        let columns = blobs
+            .remove(&(index, blob_id.to_vec()))
+            .ok_or_else(|| format!("Chunk at index {} not found", index.to_u64()))?;
+        let chunk = reconstruct(&columns, domain_size).ok_or_else(|| {
+            format!(
+                "Missing original columns of chunk at index {}",
+                index.to_u64()
+            )
+        })?;
+        chunks.push(chunk);
+    }
+    Ok(chunks)
+}
+
+/// Combine the columns that each node has for the blobs in `range`
+async fn fetch_blobs(
+    clients: &[NodeHttpClient],
+    app_id: [u8; 32],
+    range: Range<Index>,
+    view: IndexView,
+) -> Result<RangeBlobs, Box<dyn Error + Send + Sync>> {
+    let mut blobs = RangeBlobs::new();
+    for client in clients {
+        let app_blobs = client
+            .get_range::<Metadata>(app_id, range.clone(), view)
+            .await?;
+        for (index, index_blobs) in app_blobs {
+            for blob in index_blobs {
+                let blob = wire::deserialize::<DaBlob>(&blob)?;
+                blobs
+                    .entry((index, blob.id()))
+                    .or_default()
+                    .insert(blob.column_idx, blob);
+            }
+        }
+    }
+    Ok(blobs)
+}
+
+/// Data of a blob, if all the columns holding original data are present
+fn reconstruct(columns: &BTreeMap<ColumnIndex, DaBlob>, domain_size: usize) -> Option<Vec<u8>> {
+    let original_columns = (domain_size / 2) as ColumnIndex;
+    if !(0..original_columns).all(|column_idx| columns.contains_key(&column_idx)) {
+        return None;
+    }
+    let blobs: Vec<DaBlob> = columns.values().cloned().collect();
+    Some(reconstruct_without_missing_data(&blobs, domain_size))
 }
@@ -66,3 +66,58 @@ fn verify<const SIZE: usize>(bencher: Bencher, column_size: usize) {
         .input_counter(|_| BytesCount::new(SIZE))
         .bench_refs(|(verifier, blob)| black_box(verifier.verify(blob, column_size)));
 }
+
+fn encoded_blobs(
+    data_size: usize,
+    column_size: usize,
+    blobs_count: usize,
+) -> (DaVerifier, Vec<DaBlob>) {
+    let params = DaEncoderParams::new(column_size, true, GLOBAL_PARAMETERS.clone());
+    let encoder = DaEncoder::new(params);
+    let data = rand_data(data_size * MB / DaEncoderParams::MAX_BLS12_381_ENCODING_CHUNK_SIZE);
+    let encoded_data = encoder.encode(&data).unwrap();
+    let mut buff = [0u8; 32];
+    thread_rng().fill_bytes(&mut buff);
+    let sk = SecretKey::key_gen(&buff, &[]).unwrap();
+    let verifier = DaVerifier::new(
+        sk,
+        (0..column_size as u32).collect(),
+        GLOBAL_PARAMETERS.clone(),
+    );
+    let blobs = encoded_data
+        .extended_data
+        .columns()
+        .take(blobs_count)
+        .enumerate()
+        .map(|(i, column)| DaBlob {
+            column,
+            column_idx: i as u16,
+            column_commitment: encoded_data.column_commitments[i],
+            aggregated_column_commitment: encoded_data.aggregated_column_commitment,
+            aggregated_column_proof: encoded_data.aggregated_column_proofs[i],
+            rows_commitments: encoded_data.row_commitments.clone(),
+            rows_proofs: encoded_data.rows_proofs.iter().map(|row| row[i]).collect(),
+        })
+        .collect();
+    (verifier, blobs)
+}
+
+/// Blobs verified one by one, with a pairing check per row
+#[divan::bench(consts = [32, 128, 512], args = [1, 8, 32], sample_count = 1, sample_size = 30)]
+fn verify_blobs<const SIZE: usize>(bencher: Bencher, blobs_count: usize) {
+    bencher
+        .with_inputs(|| encoded_blobs(SIZE, 2048, blobs_count))
+        .input_counter(move |_| BytesCount::new(SIZE * blobs_count))
+        .bench_refs(|(verifier, blobs)| {
+            black_box(blobs.iter().all(|blob| verifier.verify(blob, 2048)))
+        });
+}
+
+/// Same blobs checked with a single pairing check
+#[divan::bench(consts = [32, 128, 512], args = [1, 8, 32], sample_count = 1, sample_size = 30)]
+fn verify_blobs_batched<const SIZE: usize>(bencher: Bencher, blobs_count: usize) {
+    bencher
+        .with_inputs(|| encoded_blobs(SIZE, 2048, blobs_count))
+        .input_counter(move |_| BytesCount::new(SIZE * blobs_count))
+        .bench_refs(|(verifier, blobs)| black_box(verifier.verify_batch(blobs, 2048)));
+}
@@ -1,6 +1,7 @@
 pub mod blob;
 
 // std
+use ark_poly::EvaluationDomain;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use serde::ser::SerializeSeq;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
@@ -11,7 +12,7 @@ use blake2::digest::{Update, VariableOutput};
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 use sha3::{Digest, Sha3_256};
 // internal
-use kzgrs::Commitment;
+use kzgrs::{Commitment, GlobalParameters, KzgRsError, PolynomialEvaluationDomain};
 
 #[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
 pub struct Chunk(pub Vec<u8>);
@@ -139,6 +140,26 @@ impl FromIterator<Row> for ChunksMatrix {
     }
 }
 
+/// Evaluation domain of the extended rows of blobs encoded with `domain_size` columns.
+/// Rows are committed to with the global parameters, so they need at least one power per column.
+pub fn rows_domain(
+    domain_size: usize,
+    global_parameters: &GlobalParameters,
+) -> Result<PolynomialEvaluationDomain, KzgRsError> {
+    // rows are encoded to twice their size, and the columns must fill the whole domain
+    if domain_size < 2 || !domain_size.is_power_of_two() {
+        return Err(KzgRsError::InvalidDomainSize(domain_size));
+    }
+    let powers = global_parameters.powers_of_g.len();
+    if domain_size > powers {
+        return Err(KzgRsError::DomainSizeTooBig {
+            domain_size,
+            powers,
+        });
+    }
+    PolynomialEvaluationDomain::new(domain_size).ok_or(KzgRsError::InvalidDomainSize(domain_size))
+}
+
 pub fn hash_column_and_commitment<const HASH_SIZE: usize>(
     column: &Column,
     commitment: &Commitment,
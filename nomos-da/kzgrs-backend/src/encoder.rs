@@ -37,6 +37,10 @@ impl DaEncoderParams {
         }
     }
 
+    pub fn column_count(&self) -> usize {
+        self.column_count
+    }
+
     pub fn default_with(column_count: usize) -> Self {
         Self {
             column_count,
@@ -1,9 +1,13 @@
 use kzgrs::{global_parameters_from_randomness, GlobalParameters};
 use once_cell::sync::Lazy;
 
-// Reexport global parameters loading from file.
-pub use kzgrs::global_parameters_from_file;
+// Reexport trusted setup files handling.
+pub use kzgrs::{
+    global_parameters_from_file, global_parameters_to_file, global_parameters_with_degree,
+    GlobalParametersError,
+};
 
+/// Parameters from a random secret, for tests only. Nodes load them from a trusted setup file.
 pub static GLOBAL_PARAMETERS: Lazy<GlobalParameters> = Lazy::new(|| {
     println!("WARNING: Global parameters are randomly generated. Use for development only.");
     let mut rng = rand::thread_rng();
@@ -12,10 +16,7 @@ pub static GLOBAL_PARAMETERS: Lazy<GlobalParameters> = Lazy::new(|| {
 
 #[cfg(test)]
 mod tests {
-    use std::fs::File;
-
-    use ark_serialize::{CanonicalSerialize, Write};
-    use kzgrs::global_parameters_from_randomness;
+    use kzgrs::{global_parameters_from_randomness, global_parameters_to_file};
 
     #[test]
     #[ignore = "for testing purposes only"]
@@ -23,10 +24,6 @@ mod tests {
         let mut rng = rand::thread_rng();
         let params = global_parameters_from_randomness(&mut rng);
 
-        let mut serialized_data = Vec::new();
-        params.serialize_uncompressed(&mut serialized_data).unwrap();
-
-        let mut file = File::create("./kzgrs_test_params").unwrap();
-        file.write_all(&serialized_data).unwrap();
+        global_parameters_to_file(&params, "./kzgrs_test_params").unwrap();
     }
 }
@@ -2,6 +2,7 @@ pub mod common;
 pub mod dispersal;
 pub mod encoder;
 pub mod global;
+pub mod manifest;
 pub mod reconstruction;
 pub mod verifier;
 
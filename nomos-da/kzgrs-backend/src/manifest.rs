@@ -0,0 +1,337 @@
+// std
+// crates
+use blst::min_sig::{PublicKey, SecretKey, Signature};
+use blst::BLST_ERROR;
+use nomos_core::da::BlobId;
+use sha3::{Digest, Sha3_256};
+use thiserror::Error;
+// internal
+use crate::dispersal::Index;
+use crate::encoder::DaEncoderParams;
+
+/// Magic bytes at the start of an encoded manifest
+pub const MANIFEST_MAGIC: &[u8; 8] = b"NOMOSMNF";
+/// Domain separation tag of manifest signatures
+pub const MANIFEST_DST: &[u8] = b"NOMOS_DA_MANIFEST";
+/// Chunk size fitting in a single blob for any number of columns with the default trusted setup
+pub const DEFAULT_CHUNK_SIZE: usize = 4096 * DaEncoderParams::MAX_BLS12_381_ENCODING_CHUNK_SIZE;
+
+const PUBLIC_KEY_SIZE: usize = 96;
+const SIGNATURE_SIZE: usize = 48;
+
+#[derive(Error, Debug)]
+pub enum ManifestError {
+    #[error("Not a manifest")]
+    InvalidMagic,
+    #[error("Manifest is truncated")]
+    Truncated,
+    #[error("Invalid manifest signature")]
+    InvalidSignature,
+    #[error("Expected {expected} chunks, got {actual}")]
+    ChunksCount { expected: usize, actual: usize },
+    #[error("Chunk {0} is shorter than expected")]
+    ChunkTooShort(usize),
+    #[error("Content hash mismatch")]
+    ContentHashMismatch,
+    #[error("Size of {size} bytes doesn't fit in the chunks")]
+    InvalidSize { size: u64 },
+}
+
+/// Description of a file dispersed as several blobs, stored at consecutive indexes starting at
+/// `first_index`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Manifest {
+    pub app_id: [u8; 32],
+    pub first_index: Index,
+    /// Size of the file, before padding
+    pub size: u64,
+    /// Bytes of the file in each chunk, but the last one
+    pub chunk_size: u64,
+    /// Sha3-256 of the file
+    pub content_hash: [u8; 32],
+    pub chunks: Vec<BlobId>,
+}
+
+impl Manifest {
+    pub fn new(
+        app_id: [u8; 32],
+        first_index: Index,
+        data: &[u8],
+        chunk_size: usize,
+        chunks: Vec<BlobId>,
+    ) -> Self {
+        Self {
+            app_id,
+            first_index,
+            size: data.len() as u64,
+            chunk_size: chunk_size as u64,
+            content_hash: Sha3_256::digest(data).into(),
+            chunks,
+        }
+    }
+
+    /// Indexes of the chunks, in order
+    pub fn chunk_indexes(&self) -> impl Iterator<Item = Index> {
+        let first = self.first_index.to_u64();
+        (first..first + self.chunks.len() as u64).map(Index::from)
+    }
+
+    pub fn sign(self, sk: &SecretKey) -> SignedManifest {
+        let signature = sk.sign(&self.to_bytes(), MANIFEST_DST, &[]);
+        SignedManifest {
+            manifest: self,
+            public_key: sk.sk_to_pk(),
+            signature,
+        }
+    }
+
+    /// Concatenate the data of the chunks, which may still hold their dispersal padding, and
+    /// check it against the content hash
+    pub fn reassemble(&self, chunks: &[Vec<u8>]) -> Result<Vec<u8>, ManifestError> {
+        // the size is read from the network, check it before allocating for it
+        self.check_size()?;
+        if chunks.len() != self.chunks.len() {
+            return Err(ManifestError::ChunksCount {
+                expected: self.chunks.len(),
+                actual: chunks.len(),
+            });
+        }
+        let mut data = Vec::with_capacity(self.size as usize);
+        let mut remaining = self.size as usize;
+        for (i, chunk) in chunks.iter().enumerate() {
+            let len = remaining.min(self.chunk_size as usize);
+            let chunk = chunk.get(..len).ok_or(ManifestError::ChunkTooShort(i))?;
+            data.extend_from_slice(chunk);
+            remaining -= len;
+        }
+        if remaining != 0 || <[u8; 32]>::from(Sha3_256::digest(&data)) != self.content_hash {
+            return Err(ManifestError::ContentHashMismatch);
+        }
+        Ok(data)
+    }
+
+    /// Check that the chunks can hold `size` bytes
+    fn check_size(&self) -> Result<(), ManifestError> {
+        let capacity = (self.chunks.len() as u64).checked_mul(self.chunk_size);
+        if capacity.map_or(true, |capacity| self.size > capacity) {
+            return Err(ManifestError::InvalidSize { size: self.size });
+        }
+        Ok(())
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(MANIFEST_MAGIC);
+        bytes.extend_from_slice(&self.app_id);
+        bytes.extend_from_slice(self.first_index.as_ref());
+        bytes.extend_from_slice(&self.size.to_le_bytes());
+        bytes.extend_from_slice(&self.chunk_size.to_le_bytes());
+        bytes.extend_from_slice(&self.content_hash);
+        bytes.extend_from_slice(&(self.chunks.len() as u32).to_le_bytes());
+        for chunk in &self.chunks {
+            bytes.extend_from_slice(chunk);
+        }
+        bytes
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SignedManifest {
+    pub manifest: Manifest,
+    pub public_key: PublicKey,
+    pub signature: Signature,
+}
+
+impl SignedManifest {
+    /// Encode the manifest followed by the signer public key and the signature over the manifest
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.manifest.to_bytes();
+        bytes.extend_from_slice(&self.public_key.compress());
+        bytes.extend_from_slice(&self.signature.compress());
+        bytes
+    }
+
+    /// Decode and check the signature of a manifest, ignoring any trailing padding
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ManifestError> {
+        let mut reader = Reader(bytes);
+        if reader.take(MANIFEST_MAGIC.len())? != MANIFEST_MAGIC {
+            return Err(ManifestError::InvalidMagic);
+        }
+        let app_id = reader.array()?;
+        let first_index = u64::from_be_bytes(reader.array()?).into();
+        let size = u64::from_le_bytes(reader.array()?);
+        let chunk_size = u64::from_le_bytes(reader.array()?);
+        let content_hash = reader.array()?;
+        let chunks_count = u32::from_le_bytes(reader.array()?) as usize;
+        let chunks = (0..chunks_count)
+            .map(|_| reader.array())
+            .collect::<Result<Vec<BlobId>, _>>()?;
+        let signed_len = bytes.len() - reader.0.len();
+        let public_key = PublicKey::uncompress(reader.take(PUBLIC_KEY_SIZE)?)
+            .map_err(|_| ManifestError::InvalidSignature)?;
+        let signature = Signature::uncompress(reader.take(SIGNATURE_SIZE)?)
+            .map_err(|_| ManifestError::InvalidSignature)?;
+        if signature.verify(
+            true,
+            &bytes[..signed_len],
+            MANIFEST_DST,
+            &[],
+            &public_key,
+            true,
+        ) != BLST_ERROR::BLST_SUCCESS
+        {
+            return Err(ManifestError::InvalidSignature);
+        }
+        let manifest = Manifest {
+            app_id,
+            first_index,
+            size,
+            chunk_size,
+            content_hash,
+            chunks,
+        };
+        manifest.check_size()?;
+        Ok(Self {
+            manifest,
+            public_key,
+            signature,
+        })
+    }
+}
+
+/// Split `data` in chunks of `chunk_size` bytes, each padded to a whole number of field elements
+/// as expected by the encoder
+pub fn split_chunks(data: &[u8], chunk_size: usize) -> impl Iterator<Item = Vec<u8>> + '_ {
+    data.chunks(chunk_size).map(pad_to_chunk_size)
+}
+
+/// Pad `data` with zeroes to a whole number of field elements
+pub fn pad_to_chunk_size(data: &[u8]) -> Vec<u8> {
+    let mut data = data.to_vec();
+    let remainder = data.len() % DaEncoderParams::MAX_BLS12_381_ENCODING_CHUNK_SIZE;
+    if remainder != 0 {
+        data.resize(
+            data.len() + (DaEncoderParams::MAX_BLS12_381_ENCODING_CHUNK_SIZE - remainder),
+            0,
+        );
+    }
+    data
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], ManifestError> {
+        if self.0.len() < len {
+            return Err(ManifestError::Truncated);
+        }
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], ManifestError> {
+        Ok(self
+            .take(N)?
+            .try_into()
+            .expect("Slice should have the requested length"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{thread_rng, RngCore};
+
+    fn signed_manifest(data: &[u8], chunk_size: usize) -> SignedManifest {
+        let mut ikm = [0u8; 32];
+        thread_rng().fill_bytes(&mut ikm);
+        let sk = SecretKey::key_gen(&ikm, &[]).unwrap();
+        let chunks = split_chunks(data, chunk_size)
+            .map(|chunk| Sha3_256::digest(chunk).into())
+            .collect();
+        let manifest = Manifest::new([1; 32], 10.into(), data, chunk_size, chunks);
+        manifest.sign(&sk)
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let mut data = vec![0u8; 1000];
+        thread_rng().fill_bytes(&mut data);
+        let signed = signed_manifest(&data, 310);
+        assert_eq!(signed.manifest.chunks.len(), 4);
+        assert_eq!(
+            signed
+                .manifest
+                .chunk_indexes()
+                .map(Index::to_u64)
+                .collect::<Vec<_>>(),
+            vec![10, 11, 12, 13]
+        );
+
+        // manifests are read back from padded blobs
+        let bytes = pad_to_chunk_size(&signed.to_bytes());
+        let decoded = SignedManifest::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.manifest, signed.manifest);
+        assert_eq!(decoded.public_key, signed.public_key);
+
+        let chunks: Vec<Vec<u8>> = split_chunks(&data, 310).collect();
+        assert_eq!(decoded.manifest.reassemble(&chunks).unwrap(), data);
+    }
+
+    #[test]
+    fn test_tampered_manifest() {
+        let signed = signed_manifest(&[1; 100], 31);
+        let mut bytes = signed.to_bytes();
+        // flip a bit of the content hash
+        bytes[MANIFEST_MAGIC.len() + 32 + 8 + 8 + 8] ^= 1;
+        assert!(matches!(
+            SignedManifest::from_bytes(&bytes),
+            Err(ManifestError::InvalidSignature)
+        ));
+        assert!(matches!(
+            SignedManifest::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(ManifestError::Truncated)
+        ));
+        assert!(matches!(
+            SignedManifest::from_bytes(&[0; 200]),
+            Err(ManifestError::InvalidMagic)
+        ));
+    }
+
+    #[test]
+    fn test_reassemble_corrupted_chunk() {
+        let signed = signed_manifest(&[7; 100], 62);
+        let mut chunks: Vec<Vec<u8>> = split_chunks(&[7; 100], 62).collect();
+        assert!(matches!(
+            signed.manifest.reassemble(&chunks[..1]),
+            Err(ManifestError::ChunksCount { .. })
+        ));
+        chunks[1][0] = 0;
+        assert!(matches!(
+            signed.manifest.reassemble(&chunks),
+            Err(ManifestError::ContentHashMismatch)
+        ));
+        chunks[1].truncate(10);
+        assert!(matches!(
+            signed.manifest.reassemble(&chunks),
+            Err(ManifestError::ChunkTooShort(1))
+        ));
+    }
+
+    #[test]
+    fn test_oversized_manifest() {
+        let mut manifest = signed_manifest(&[7; 100], 62).manifest;
+        let chunks: Vec<Vec<u8>> = split_chunks(&[7; 100], 62).collect();
+        manifest.size = u64::MAX;
+        assert!(matches!(
+            manifest.reassemble(&chunks),
+            Err(ManifestError::InvalidSize { .. })
+        ));
+        manifest.size = 125;
+        assert!(matches!(
+            manifest.reassemble(&chunks),
+            Err(ManifestError::InvalidSize { size: 125 })
+        ));
+    }
+}
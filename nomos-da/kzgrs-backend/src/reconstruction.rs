@@ -2,13 +2,16 @@ use crate::common::blob::DaBlob;
 use crate::common::Chunk;
 use kzgrs::BYTES_PER_FIELD_ELEMENT;
 
-/// Reconstruct original data from a set of `DaBlob`
+/// Reconstruct original data from a set of `DaBlob` encoded with `domain_size` columns
 /// Warning! This does not interpolate so it should not be used on blobs which doesn't represent
 /// the original set of data.
-pub fn reconstruct_without_missing_data(blobs: &[DaBlob]) -> Vec<u8> {
+pub fn reconstruct_without_missing_data(blobs: &[DaBlob], domain_size: usize) -> Vec<u8> {
+    // original data lives in the first half of the columns, the rest is the rs extension
+    let original_columns = domain_size / 2;
     // pick positions from columns
     let mut data: Vec<((usize, usize), Vec<u8>)> = blobs
         .iter()
+        .filter(|blob| (blob.column_idx as usize) < original_columns)
         .flat_map(|blob| {
             blob.column
                 .iter()
@@ -36,6 +39,7 @@ mod test {
     use crate::encoder::{DaEncoder, DaEncoderParams, EncodedData};
     use crate::reconstruction::reconstruct_without_missing_data;
     use nomos_core::da::DaEncoder as _;
+    use proptest::prelude::*;
 
     #[test]
     fn test_reconstruct() {
@@ -57,6 +61,33 @@ mod test {
                 rows_proofs: vec![],
             })
             .collect();
-        assert_eq!(data, reconstruct_without_missing_data(&blobs));
+        assert_eq!(data, reconstruct_without_missing_data(&blobs, 4));
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(8))]
+        #[test]
+        fn reconstruct_any_domain_size(columns_exp in 1u32..6, rows_exp in 0u32..4) {
+            let domain_size = 2usize.pow(columns_exp);
+            let data: Vec<u8> = rand_data((domain_size / 2) * 2usize.pow(rows_exp));
+            let encoder = DaEncoder::new(DaEncoderParams::default_with(domain_size));
+            let encoded_data: EncodedData = encoder.encode(&data).unwrap();
+            // the extension columns are ignored
+            let blobs: Vec<DaBlob> = encoded_data
+                .extended_data
+                .columns()
+                .enumerate()
+                .map(|(idx, column)| DaBlob {
+                    column,
+                    column_idx: idx as ColumnIndex,
+                    column_commitment: Default::default(),
+                    aggregated_column_commitment: Default::default(),
+                    aggregated_column_proof: Default::default(),
+                    rows_commitments: vec![],
+                    rows_proofs: vec![],
+                })
+                .collect();
+            prop_assert_eq!(data, reconstruct_without_missing_data(&blobs, domain_size));
+        }
     }
 }
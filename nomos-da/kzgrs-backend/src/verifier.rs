@@ -6,13 +6,16 @@ use blst::min_sig::SecretKey;
 use itertools::{izip, Itertools};
 use kzgrs::common::field_element_from_bytes_le;
 use kzgrs::{
-    bytes_to_polynomial, commit_polynomial, verify_element_proof, Commitment, GlobalParameters,
-    PolynomialEvaluationDomain, Proof, BYTES_PER_FIELD_ELEMENT,
+    bytes_to_polynomial, commit_polynomial, verify_element_proof, verify_element_proofs_batch,
+    Commitment, FieldElement, GlobalParameters, KzgRsError, PolynomialEvaluationDomain, Proof,
+    BYTES_PER_FIELD_ELEMENT,
 };
 use nomos_core::da::blob::Blob;
+#[cfg(feature = "parallel")]
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 // internal
 use crate::common::blob::DaBlob;
-use crate::common::{hash_column_and_commitment, Chunk, Column};
+use crate::common::{hash_column_and_commitment, rows_domain, Chunk, Column};
 use crate::encoder::DaEncoderParams;
 
 pub struct DaVerifier {
@@ -31,38 +34,53 @@ impl DaVerifier {
         }
     }
 
-    fn verify_column(
+    /// Check that blobs encoded with `domain_size` columns can be verified with the global
+    /// parameters of this verifier
+    pub fn check_domain_size(&self, domain_size: usize) -> Result<(), KzgRsError> {
+        rows_domain(domain_size, &self.global_parameters).map(|_| ())
+    }
+
+    /// Element of the aggregated column polynomial for `column`, `None` if the column does not
+    /// match its commitment
+    fn column_element(
         global_parameters: &GlobalParameters,
         column: &Column,
         column_commitment: &Commitment,
-        aggregated_column_commitment: &Commitment,
-        aggregated_column_proof: &Proof,
-        index: usize,
-        rows_domain: PolynomialEvaluationDomain,
-    ) -> bool {
+    ) -> Option<FieldElement> {
         let column_domain =
             PolynomialEvaluationDomain::new(column.len()).expect("Domain should be able to build");
         // 1. compute commitment for column
-        let Ok((_, polynomial)) = bytes_to_polynomial::<BYTES_PER_FIELD_ELEMENT>(
+        let (_, polynomial) = bytes_to_polynomial::<BYTES_PER_FIELD_ELEMENT>(
             column.as_bytes().as_slice(),
             column_domain,
-        ) else {
-            return false;
-        };
-        let Ok(computed_column_commitment) = commit_polynomial(&polynomial, global_parameters)
-        else {
-            return false;
-        };
+        )
+        .ok()?;
+        let computed_column_commitment = commit_polynomial(&polynomial, global_parameters).ok()?;
         // 2. if computed column commitment != column commitment, fail
         if &computed_column_commitment != column_commitment {
-            return false;
+            return None;
         }
         // 3. compute column hash
         let column_hash = hash_column_and_commitment::<
             { DaEncoderParams::MAX_BLS12_381_ENCODING_CHUNK_SIZE },
         >(column, column_commitment);
+        Some(field_element_from_bytes_le(column_hash.as_slice()))
+    }
+
+    fn verify_column(
+        global_parameters: &GlobalParameters,
+        column: &Column,
+        column_commitment: &Commitment,
+        aggregated_column_commitment: &Commitment,
+        aggregated_column_proof: &Proof,
+        index: usize,
+        rows_domain: PolynomialEvaluationDomain,
+    ) -> bool {
+        let Some(element) = Self::column_element(global_parameters, column, column_commitment)
+        else {
+            return false;
+        };
         // 4. check proof with commitment and proof over the aggregated column commitment
-        let element = field_element_from_bytes_le(column_hash.as_slice());
         verify_element_proof(
             index,
             &element,
@@ -115,11 +133,19 @@ impl DaVerifier {
         true
     }
 
+    /// Verify a blob encoded with `rows_domain_size` columns
     pub fn verify(&self, blob: &DaBlob, rows_domain_size: usize) -> bool {
-        let rows_domain = PolynomialEvaluationDomain::new(rows_domain_size)
-            .expect("Domain should be able to build");
+        let Ok(rows_domain) = rows_domain(rows_domain_size, &self.global_parameters) else {
+            return false;
+        };
+        // the column must be one of the extended row positions
+        if blob.column_idx as usize >= rows_domain_size {
+            return false;
+        }
         let blob_col_idx = &u16::from_be_bytes(blob.column_idx()).into();
-        let index = self.index.get(blob_col_idx).unwrap();
+        let Some(index) = self.index.get(blob_col_idx) else {
+            return false;
+        };
 
         let is_column_verified = DaVerifier::verify_column(
             &self.global_parameters,
@@ -147,6 +173,91 @@ impl DaVerifier {
         }
         true
     }
+
+    /// Verify blobs encoded with `rows_domain_size` columns, checking the proofs of all their
+    /// chunks and columns with a single pairing check over a random linear combination of them.
+    /// Fails as a whole if any of the blobs is invalid.
+    pub fn verify_batch(&self, blobs: &[DaBlob], rows_domain_size: usize) -> bool {
+        let Ok(rows_domain) = rows_domain(rows_domain_size, &self.global_parameters) else {
+            return false;
+        };
+        let mut indexes = Vec::with_capacity(blobs.len());
+        for blob in blobs {
+            // the column must be one of the extended row positions
+            if blob.column_idx as usize >= rows_domain_size {
+                return false;
+            }
+            let blob_col_idx = &u16::from_be_bytes(blob.column_idx()).into();
+            let Some(index) = self.index.get(blob_col_idx) else {
+                return false;
+            };
+            if ![
+                blob.column.len(),
+                blob.rows_commitments.len(),
+                blob.rows_proofs.len(),
+            ]
+            .iter()
+            .all_equal()
+            {
+                return false;
+            }
+            indexes.push(*index as usize);
+        }
+
+        // commitments of the columns are recomputed independently, no pairing involved
+        let column_elements: Option<Vec<FieldElement>> = {
+            #[cfg(not(feature = "parallel"))]
+            {
+                blobs.iter()
+            }
+            #[cfg(feature = "parallel")]
+            {
+                blobs.par_iter()
+            }
+        }
+        .map(|blob| {
+            Self::column_element(
+                &self.global_parameters,
+                &blob.column,
+                &blob.column_commitment,
+            )
+        })
+        .collect();
+        let Some(column_elements) = column_elements else {
+            return false;
+        };
+
+        let proofs_count = blobs.iter().map(|blob| blob.column.len() + 1).sum();
+        let mut elements_indexes = Vec::with_capacity(proofs_count);
+        let mut elements = Vec::with_capacity(proofs_count);
+        let mut commitments = Vec::with_capacity(proofs_count);
+        let mut proofs = Vec::with_capacity(proofs_count);
+        for (blob, index, column_element) in izip!(blobs, indexes, column_elements) {
+            elements_indexes.push(index);
+            elements.push(column_element);
+            commitments.push(blob.aggregated_column_commitment);
+            proofs.push(blob.aggregated_column_proof);
+            for (chunk, commitment, proof) in izip!(
+                blob.column.iter(),
+                &blob.rows_commitments,
+                &blob.rows_proofs
+            ) {
+                elements_indexes.push(index);
+                elements.push(field_element_from_bytes_le(chunk.as_bytes().as_slice()));
+                commitments.push(*commitment);
+                proofs.push(*proof);
+            }
+        }
+        verify_element_proofs_batch(
+            &elements_indexes,
+            &elements,
+            &commitments,
+            &proofs,
+            rows_domain,
+            &self.global_parameters,
+            &mut rand::thread_rng(),
+        )
+    }
 }
 
 #[cfg(test)]
@@ -154,7 +265,7 @@ mod test {
     use crate::common::blob::DaBlob;
     use crate::common::{hash_column_and_commitment, Chunk, Column};
     use crate::encoder::test::{rand_data, ENCODER};
-    use crate::encoder::DaEncoderParams;
+    use crate::encoder::{DaEncoder, DaEncoderParams};
     use crate::global::GLOBAL_PARAMETERS;
     use crate::verifier::DaVerifier;
     use ark_bls12_381::Fr;
@@ -167,6 +278,7 @@ mod test {
     };
     use nomos_core::da::DaEncoder;
     use once_cell::sync::Lazy;
+    use proptest::prelude::*;
     use rand::{thread_rng, RngCore};
 
     pub struct ColumnVerifyData {
@@ -405,4 +517,137 @@ mod test {
             assert!(verifier.verify(&da_blob, domain_size));
         }
     }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(8))]
+        #[test]
+        fn verify_any_domain_size(columns_exp in 1u32..6, rows_exp in 0u32..4) {
+            let domain_size = 2usize.pow(columns_exp);
+            let encoder = DaEncoder::new(DaEncoderParams::default_with(domain_size));
+            let data = rand_data((domain_size / 2) * 2usize.pow(rows_exp));
+            let encoded_data = encoder.encode(&data).unwrap();
+            let verifier = DaVerifier::new(
+                SecretKey::key_gen(&[0u8; 32], &[]).unwrap(),
+                (0..domain_size as u32).collect(),
+                GLOBAL_PARAMETERS.clone(),
+            );
+            prop_assert!(verifier.check_domain_size(domain_size).is_ok());
+            for (i, column) in encoded_data.extended_data.columns().enumerate() {
+                let da_blob = DaBlob {
+                    column,
+                    column_idx: i as u16,
+                    column_commitment: encoded_data.column_commitments[i],
+                    aggregated_column_commitment: encoded_data.aggregated_column_commitment,
+                    aggregated_column_proof: encoded_data.aggregated_column_proofs[i],
+                    rows_commitments: encoded_data.row_commitments.clone(),
+                    rows_proofs: encoded_data
+                        .rows_proofs
+                        .iter()
+                        .map(|proofs| proofs.get(i).cloned().unwrap())
+                        .collect(),
+                };
+                prop_assert!(verifier.verify(&da_blob, domain_size));
+                // blobs only verify against the domain they were encoded with
+                prop_assert!(!verifier.verify(&da_blob, domain_size * 2));
+            }
+        }
+    }
+
+    #[test]
+    fn test_check_domain_size() {
+        let verifier = DaVerifier::new(
+            SecretKey::key_gen(&[0u8; 32], &[]).unwrap(),
+            [0].into(),
+            GLOBAL_PARAMETERS.clone(),
+        );
+        assert!(verifier.check_domain_size(2).is_ok());
+        assert!(verifier.check_domain_size(0).is_err());
+        assert!(verifier.check_domain_size(1).is_err());
+        assert!(verifier.check_domain_size(6).is_err());
+        assert!(verifier.check_domain_size(1 << 20).is_err());
+    }
+
+    fn encoded_blobs(domain_size: usize) -> Vec<DaBlob> {
+        let encoder = &ENCODER;
+        let encoded_data = encoder.encode(&rand_data(32)).unwrap();
+        encoded_data
+            .extended_data
+            .columns()
+            .take(domain_size)
+            .enumerate()
+            .map(|(i, column)| DaBlob {
+                column,
+                column_idx: i as u16,
+                column_commitment: encoded_data.column_commitments[i],
+                aggregated_column_commitment: encoded_data.aggregated_column_commitment,
+                aggregated_column_proof: encoded_data.aggregated_column_proofs[i],
+                rows_commitments: encoded_data.row_commitments.clone(),
+                rows_proofs: encoded_data
+                    .rows_proofs
+                    .iter()
+                    .map(|proofs| proofs.get(i).cloned().unwrap())
+                    .collect(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_verify_batch() {
+        let domain_size = 16usize;
+        let verifier = DaVerifier::new(
+            SecretKey::key_gen(&[0u8; 32], &[]).unwrap(),
+            (0..domain_size as u32).collect(),
+            GLOBAL_PARAMETERS.clone(),
+        );
+        let blobs = encoded_blobs(domain_size);
+        assert!(verifier.verify_batch(&blobs, domain_size));
+        assert!(verifier.verify_batch(&blobs[..1], domain_size));
+        assert!(verifier.verify_batch(&[], domain_size));
+        // blobs only verify against the domain they were encoded with
+        assert!(!verifier.verify_batch(&blobs, domain_size * 2));
+
+        // columns outside of the verifier index are rejected
+        let verifier = DaVerifier::new(
+            SecretKey::key_gen(&[0u8; 32], &[]).unwrap(),
+            [0].into(),
+            GLOBAL_PARAMETERS.clone(),
+        );
+        assert!(verifier.verify_batch(&blobs[..1], domain_size));
+        assert!(!verifier.verify_batch(&blobs[..2], domain_size));
+        // as they are when verified one by one after the batch failed
+        assert!(verifier.verify(&blobs[0], domain_size));
+        assert!(!verifier.verify(&blobs[1], domain_size));
+    }
+
+    #[test]
+    fn test_verify_batch_corrupted_proof() {
+        let domain_size = 16usize;
+        let verifier = DaVerifier::new(
+            SecretKey::key_gen(&[0u8; 32], &[]).unwrap(),
+            (0..domain_size as u32).collect(),
+            GLOBAL_PARAMETERS.clone(),
+        );
+        let blobs = encoded_blobs(domain_size);
+
+        // a single row proof swapped in one blob
+        let mut corrupted = blobs.clone();
+        corrupted[5].rows_proofs.swap(0, 1);
+        assert!(!verifier.verify(&corrupted[5], domain_size));
+        assert!(!verifier.verify_batch(&corrupted, domain_size));
+
+        // a row proof taken from another column
+        let mut corrupted = blobs.clone();
+        corrupted[3].rows_proofs[0] = blobs[4].rows_proofs[0];
+        assert!(!verifier.verify_batch(&corrupted, domain_size));
+
+        // a single aggregated column proof taken from another column
+        let mut corrupted = blobs.clone();
+        corrupted[domain_size - 1].aggregated_column_proof = blobs[0].aggregated_column_proof;
+        assert!(!verifier.verify_batch(&corrupted, domain_size));
+
+        // a single chunk altered
+        let mut corrupted = blobs;
+        corrupted[0].column.0[0] = Chunk(vec![0; 31]);
+        assert!(!verifier.verify_batch(&corrupted, domain_size));
+    }
 }
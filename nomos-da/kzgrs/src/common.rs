@@ -54,6 +54,12 @@ pub enum KzgRsError {
         attestations_count: usize,
         signers_count: usize,
     },
+    #[error("Domain size should be a power of two greater than 1, got {0}")]
+    InvalidDomainSize(usize),
+    #[error(
+        "Domain size {domain_size} needs more powers than the {powers} in the global parameters"
+    )]
+    DomainSizeTooBig { domain_size: usize, powers: usize },
     #[error(transparent)]
     PolyCommitError(#[from] ark_poly_commit::Error),
     #[error("BLST error: {0}")]
@@ -1,24 +1,223 @@
 // std
-use std::{error::Error, fs::File};
+use std::{fs::File, path::Path};
 // crates
-use ark_bls12_381::{fr::Fr, Bls12_381};
+use ark_bls12_381::{fr::Fr, Bls12_381, G1Projective, G2Projective};
+use ark_ec::{pairing::Pairing, VariableBaseMSM};
+use ark_ff::UniformRand;
 use ark_poly::polynomial::univariate::DensePolynomial;
 use ark_poly_commit::kzg10::{UniversalParams, KZG10};
-use ark_serialize::{CanonicalDeserialize, Read};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
 use rand::Rng;
+use sha3::{Digest, Sha3_256};
+use thiserror::Error;
 // internal
 use super::GlobalParameters;
 
+/// Magic bytes at the start of a trusted setup file
+pub const TRUSTED_SETUP_MAGIC: &[u8; 8] = b"NOMOSKZG";
+/// Version of the trusted setup file layout written by this crate
+pub const TRUSTED_SETUP_VERSION: u32 = 1;
+
+const HEADER_SIZE: usize = TRUSTED_SETUP_MAGIC.len() + 4 + 8;
+const CHECKSUM_SIZE: usize = 32;
+const DEFAULT_MAX_DEGREE: usize = 8192;
+
+#[derive(Error, Debug)]
+pub enum GlobalParametersError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Not a trusted setup file")]
+    InvalidMagic,
+    #[error("Unsupported trusted setup version {0}")]
+    UnsupportedVersion(u32),
+    #[error("Trusted setup payload should be {expected} bytes long, got {actual}")]
+    InvalidLength { expected: usize, actual: usize },
+    #[error("Trusted setup checksum mismatch")]
+    ChecksumMismatch,
+    #[error(transparent)]
+    Serialization(#[from] SerializationError),
+    #[error("Trusted setup should contain at least two powers of tau")]
+    NotEnoughPowers,
+    #[error("Inconsistent powers of tau in {0}")]
+    InconsistentPowers(&'static str),
+}
+
 pub fn global_parameters_from_randomness<R: Rng>(rng: &mut R) -> GlobalParameters {
-    KZG10::<Bls12_381, DensePolynomial<Fr>>::setup(8192, true, rng).unwrap()
+    global_parameters_with_degree(DEFAULT_MAX_DEGREE, rng)
+}
+
+/// Powers of tau from a random secret, only fit for development as the secret is not discarded
+/// by a ceremony
+pub fn global_parameters_with_degree<R: Rng>(max_degree: usize, rng: &mut R) -> GlobalParameters {
+    KZG10::<Bls12_381, DensePolynomial<Fr>>::setup(max_degree, true, rng).unwrap()
 }
 
-pub fn global_parameters_from_file(file_path: &str) -> Result<GlobalParameters, Box<dyn Error>> {
+/// Load and validate the global parameters of a trusted setup file.
+///
+/// Layout (version 1):
+/// `magic (8) | version (u32 le) | payload length (u64 le) | payload | sha3-256 checksum (32)`
+/// where the payload holds the G1 and G2 powers of tau in arkworks uncompressed encoding, and the
+/// checksum covers everything before it.
+pub fn global_parameters_from_file(
+    file_path: impl AsRef<Path>,
+) -> Result<GlobalParameters, GlobalParametersError> {
     let mut file = File::open(file_path)?;
     let mut serialized_data = Vec::new();
     file.read_to_end(&mut serialized_data)?;
 
-    let params =
-        UniversalParams::<Bls12_381>::deserialize_uncompressed_unchecked(&*serialized_data)?;
+    let params = global_parameters_from_bytes(&serialized_data)?;
+    check_powers_consistency(&params, &mut rand::thread_rng())?;
     Ok(params)
 }
+
+pub fn global_parameters_to_file(
+    params: &GlobalParameters,
+    file_path: impl AsRef<Path>,
+) -> Result<(), GlobalParametersError> {
+    let mut file = File::create(file_path)?;
+    file.write_all(&global_parameters_to_bytes(params)?)?;
+    Ok(())
+}
+
+fn global_parameters_to_bytes(params: &GlobalParameters) -> Result<Vec<u8>, SerializationError> {
+    let mut payload = Vec::new();
+    params.serialize_uncompressed(&mut payload)?;
+
+    let mut bytes = Vec::with_capacity(HEADER_SIZE + payload.len() + CHECKSUM_SIZE);
+    bytes.extend_from_slice(TRUSTED_SETUP_MAGIC);
+    bytes.extend_from_slice(&TRUSTED_SETUP_VERSION.to_le_bytes());
+    bytes.extend_from_slice(&(payload.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(&payload);
+    let checksum = Sha3_256::digest(&bytes);
+    bytes.extend_from_slice(&checksum);
+    Ok(bytes)
+}
+
+/// Decode the global parameters, checking that every point is on its curve and in the right
+/// subgroup
+fn global_parameters_from_bytes(bytes: &[u8]) -> Result<GlobalParameters, GlobalParametersError> {
+    if bytes.len() < HEADER_SIZE + CHECKSUM_SIZE {
+        return Err(GlobalParametersError::InvalidLength {
+            expected: HEADER_SIZE + CHECKSUM_SIZE,
+            actual: bytes.len(),
+        });
+    }
+    let (header, rest) = bytes.split_at(HEADER_SIZE);
+    let (magic, header) = header.split_at(TRUSTED_SETUP_MAGIC.len());
+    if magic != TRUSTED_SETUP_MAGIC {
+        return Err(GlobalParametersError::InvalidMagic);
+    }
+    let (version, payload_len) = header.split_at(4);
+    let version = u32::from_le_bytes(version.try_into().expect("Version is 4 bytes long"));
+    if version != TRUSTED_SETUP_VERSION {
+        return Err(GlobalParametersError::UnsupportedVersion(version));
+    }
+    let payload_len = u64::from_le_bytes(
+        payload_len
+            .try_into()
+            .expect("Payload length is 8 bytes long"),
+    ) as usize;
+    let (payload, checksum) = rest.split_at(rest.len() - CHECKSUM_SIZE);
+    if payload.len() != payload_len {
+        return Err(GlobalParametersError::InvalidLength {
+            expected: payload_len,
+            actual: payload.len(),
+        });
+    }
+    let expected = Sha3_256::digest(&bytes[..bytes.len() - CHECKSUM_SIZE]);
+    if expected.as_slice() != checksum {
+        return Err(GlobalParametersError::ChecksumMismatch);
+    }
+
+    Ok(UniversalParams::<Bls12_381>::deserialize_uncompressed(
+        payload,
+    )?)
+}
+
+/// Check that the points are successive powers of the same secret, using random linear
+/// combinations so that the whole setup is covered by a couple of pairings.
+fn check_powers_consistency<R: Rng>(
+    params: &GlobalParameters,
+    rng: &mut R,
+) -> Result<(), GlobalParametersError> {
+    let powers_of_g = &params.powers_of_g;
+    if powers_of_g.len() < 2 {
+        return Err(GlobalParametersError::NotEnoughPowers);
+    }
+
+    // e(sum r_i * tau^(i+1) * g, h) == e(sum r_i * tau^i * g, tau * h)
+    let scalars: Vec<Fr> = (1..powers_of_g.len()).map(|_| Fr::rand(rng)).collect();
+    let shifted = G1Projective::msm(&powers_of_g[1..], &scalars)
+        .map_err(|_| GlobalParametersError::InconsistentPowers("powers of g"))?;
+    let unshifted = G1Projective::msm(&powers_of_g[..powers_of_g.len() - 1], &scalars)
+        .map_err(|_| GlobalParametersError::InconsistentPowers("powers of g"))?;
+    if Bls12_381::pairing(shifted, params.h) != Bls12_381::pairing(unshifted, params.beta_h) {
+        return Err(GlobalParametersError::InconsistentPowers("powers of g"));
+    }
+
+    // e(tau * g, sum r_i * tau^-(i+1) * h) == e(g, sum r_i * tau^-i * h)
+    let (shifted, unshifted): (Vec<_>, Vec<_>) = params
+        .neg_powers_of_h
+        .iter()
+        .filter_map(|(i, point)| {
+            params
+                .neg_powers_of_h
+                .get(&(i + 1))
+                .map(|next| (*next, *point))
+        })
+        .unzip();
+    if shifted.is_empty() {
+        return Ok(());
+    }
+    let scalars: Vec<Fr> = (0..shifted.len()).map(|_| Fr::rand(rng)).collect();
+    let shifted = G2Projective::msm(&shifted, &scalars)
+        .map_err(|_| GlobalParametersError::InconsistentPowers("negative powers of h"))?;
+    let unshifted = G2Projective::msm(&unshifted, &scalars)
+        .map_err(|_| GlobalParametersError::InconsistentPowers("negative powers of h"))?;
+    if Bls12_381::pairing(powers_of_g[1], shifted) != Bls12_381::pairing(powers_of_g[0], unshifted)
+    {
+        return Err(GlobalParametersError::InconsistentPowers(
+            "negative powers of h",
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::G1Affine;
+    use ark_ec::AffineRepr;
+
+    use super::*;
+
+    #[test]
+    fn roundtrip() {
+        let params = global_parameters_with_degree(16, &mut rand::thread_rng());
+        let bytes = global_parameters_to_bytes(&params).unwrap();
+        let decoded = global_parameters_from_bytes(&bytes).unwrap();
+        check_powers_consistency(&decoded, &mut rand::thread_rng()).unwrap();
+        assert_eq!(params.powers_of_g, decoded.powers_of_g);
+    }
+
+    #[test]
+    fn corrupted_checksum() {
+        let params = global_parameters_with_degree(16, &mut rand::thread_rng());
+        let mut bytes = global_parameters_to_bytes(&params).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 1;
+        assert!(matches!(
+            global_parameters_from_bytes(&bytes),
+            Err(GlobalParametersError::ChecksumMismatch)
+        ));
+    }
+
+    #[test]
+    fn inconsistent_powers() {
+        let mut params = global_parameters_with_degree(16, &mut rand::thread_rng());
+        params.powers_of_g[3] = (params.powers_of_g[3] + G1Affine::generator()).into();
+        assert!(matches!(
+            check_powers_consistency(&params, &mut rand::thread_rng()),
+            Err(GlobalParametersError::InconsistentPowers(_))
+        ));
+    }
+}
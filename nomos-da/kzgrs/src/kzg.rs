@@ -1,11 +1,14 @@
 use crate::common::KzgRsError;
 use crate::Evaluations;
-use ark_bls12_381::{Bls12_381, Fr};
+use ark_bls12_381::{Bls12_381, Fr, G1Affine, G1Projective};
 use ark_ec::pairing::Pairing;
+use ark_ec::VariableBaseMSM;
+use ark_ff::UniformRand;
 use ark_poly::univariate::DensePolynomial;
 use ark_poly::{DenseUVPolynomial, EvaluationDomain, GeneralEvaluationDomain};
 use ark_poly_commit::kzg10::{Commitment, Powers, Proof, UniversalParams, KZG10};
-use num_traits::One;
+use num_traits::{One, Zero};
+use rand::Rng;
 use std::borrow::Cow;
 use std::ops::{Mul, Neg};
 
@@ -65,10 +68,63 @@ pub fn verify_element_proof(
     lhs == rhs
 }
 
+/// Verify proofs for many elements with a single pairing check.
+/// Each proof satisfies `w * (beta - u) = C - v`, so a random linear combination of them satisfies
+/// `e(sum r * (C - v + u * w), h) = e(sum r * w, beta * h)`, and a single invalid proof makes the
+/// check fail except with negligible probability.
+pub fn verify_element_proofs_batch<R: Rng>(
+    elements_indexes: &[usize],
+    elements: &[Fr],
+    commitments: &[Commitment<Bls12_381>],
+    proofs: &[Proof<Bls12_381>],
+    domain: GeneralEvaluationDomain<Fr>,
+    global_parameters: &UniversalParams<Bls12_381>,
+    rng: &mut R,
+) -> bool {
+    let len = elements.len();
+    if [elements_indexes.len(), commitments.len(), proofs.len()]
+        .iter()
+        .any(|l| *l != len)
+    {
+        return false;
+    }
+    if len == 0 {
+        return true;
+    }
+    let randomness: Vec<Fr> = (0..len).map(|_| Fr::rand(rng)).collect();
+
+    let mut bases: Vec<G1Affine> = Vec::with_capacity(2 * len + 1);
+    let mut scalars: Vec<Fr> = Vec::with_capacity(2 * len + 1);
+    let mut evaluations = Fr::zero();
+    for i in 0..len {
+        let r = randomness[i];
+        bases.push(commitments[i].0);
+        scalars.push(r);
+        bases.push(proofs[i].w);
+        scalars.push(r * domain.element(elements_indexes[i]));
+        evaluations += r * elements[i];
+    }
+    bases.push(global_parameters.powers_of_g[0]);
+    scalars.push(-evaluations);
+
+    let witnesses: Vec<G1Affine> = proofs.iter().map(|proof| proof.w).collect();
+    let (Ok(lhs), Ok(rhs)) = (
+        G1Projective::msm(&bases, &scalars),
+        G1Projective::msm(&witnesses, &randomness),
+    ) else {
+        return false;
+    };
+    Bls12_381::pairing(lhs, global_parameters.h)
+        == Bls12_381::pairing(rhs, global_parameters.beta_h)
+}
+
 #[cfg(test)]
 mod test {
     use crate::common::bytes_to_polynomial;
-    use crate::kzg::{commit_polynomial, generate_element_proof, verify_element_proof};
+    use crate::kzg::{
+        commit_polynomial, generate_element_proof, verify_element_proof,
+        verify_element_proofs_batch,
+    };
     use ark_bls12_381::{Bls12_381, Fr};
     use ark_poly::univariate::DensePolynomial;
     use ark_poly::{DenseUVPolynomial, EvaluationDomain, GeneralEvaluationDomain};
@@ -141,4 +197,41 @@ mod test {
                 }
             });
     }
+
+    #[test]
+    fn batch_validate() {
+        let mut bytes: [u8; 310] = [0; 310];
+        let mut rng = thread_rng();
+        bytes.try_fill(&mut rng).unwrap();
+        let (eval, poly) = bytes_to_polynomial::<31>(&bytes, *DOMAIN).unwrap();
+        let commitment = commit_polynomial(&poly, &GLOBAL_PARAMETERS).unwrap();
+        let indexes: Vec<usize> = (0..10).collect();
+        let mut proofs: Vec<_> = indexes
+            .iter()
+            .map(|i| generate_element_proof(*i, &poly, &eval, &GLOBAL_PARAMETERS, *DOMAIN).unwrap())
+            .collect();
+        let commitments = vec![commitment; indexes.len()];
+
+        assert!(verify_element_proofs_batch(
+            &indexes,
+            &eval.evals[..10],
+            &commitments,
+            &proofs,
+            *DOMAIN,
+            &GLOBAL_PARAMETERS,
+            &mut rng
+        ));
+
+        // a single proof for the wrong element is enough to fail
+        proofs.swap(3, 4);
+        assert!(!verify_element_proofs_batch(
+            &indexes,
+            &eval.evals[..10],
+            &commitments,
+            &proofs,
+            *DOMAIN,
+            &GLOBAL_PARAMETERS,
+            &mut rng
+        ));
+    }
 }
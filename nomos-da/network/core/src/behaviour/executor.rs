@@ -6,12 +6,14 @@ use libp2p::PeerId;
 use libp2p::swarm::NetworkBehaviour;
 // internal
 use crate::address_book::AddressBook;
+use crate::SubnetworkId;
 use crate::{
     protocols::dispersal::executor::behaviour::DispersalExecutorBehaviour,
     protocols::dispersal::validator::behaviour::DispersalValidatorBehaviour,
-    protocols::replication::behaviour::ReplicationBehaviour,
-    protocols::sampling::behaviour::SamplingBehaviour,
+    protocols::replication::behaviour::{ReplicationBehaviour, ReplicationConfig},
+    protocols::sampling::behaviour::{SamplingBehaviour, SamplingConfig},
 };
+use subnetworks_assignations::epochs::Epoch;
 use subnetworks_assignations::MembershipHandler;
 
 /// Aggregated `NetworkBehaviour` composed of:
@@ -37,28 +39,31 @@ where
     Membership: MembershipHandler + Clone + Send + 'static,
     <Membership as MembershipHandler>::NetworkId: Send,
 {
-    pub fn new(key: &Keypair, membership: Membership, addresses: AddressBook) -> Self {
+    pub fn new(
+        key: &Keypair,
+        membership: Membership,
+        addresses: AddressBook,
+        sampling_config: SamplingConfig,
+        replication_config: ReplicationConfig,
+    ) -> Self {
         let peer_id = PeerId::from_public_key(&key.public());
         Self {
-            sampling: SamplingBehaviour::new(peer_id, membership.clone(), addresses.clone()),
+            sampling: SamplingBehaviour::with_config(
+                peer_id,
+                membership.clone(),
+                addresses.clone(),
+                sampling_config,
+            ),
             executor_dispersal: DispersalExecutorBehaviour::new(
                 peer_id,
                 membership.clone(),
                 addresses.clone(),
             ),
             validator_dispersal: DispersalValidatorBehaviour::new(membership.clone()),
-            replication: ReplicationBehaviour::new(peer_id, membership),
+            replication: ReplicationBehaviour::with_config(peer_id, membership, replication_config),
         }
     }
 
-    pub fn update_membership(&mut self, membership: Membership) {
-        // TODO: share membership
-        self.sampling.update_membership(membership.clone());
-        self.executor_dispersal
-            .update_membership(membership.clone());
-        self.replication.update_membership(membership);
-    }
-
     pub fn sampling_behaviour(&self) -> &SamplingBehaviour<Membership> {
         &self.sampling
     }
@@ -95,3 +100,19 @@ where
         &mut self.replication
     }
 }
+
+impl<Membership> ExecutorBehaviour<Membership>
+where
+    Membership: MembershipHandler<Id = PeerId, NetworkId = SubnetworkId> + Clone + Send + 'static,
+{
+    /// Swap in the membership of a new epoch
+    pub fn update_membership(&mut self, epoch: Epoch, membership: Membership) {
+        // TODO: share membership
+        self.sampling.update_membership(epoch, membership.clone());
+        self.executor_dispersal
+            .update_membership(epoch, membership.clone());
+        self.validator_dispersal
+            .update_membership(membership.clone());
+        self.replication.update_membership(membership);
+    }
+}
@@ -8,9 +8,10 @@ use libp2p::swarm::NetworkBehaviour;
 use crate::address_book::AddressBook;
 use crate::{
     protocols::dispersal::validator::behaviour::DispersalValidatorBehaviour,
-    protocols::replication::behaviour::ReplicationBehaviour,
-    protocols::sampling::behaviour::SamplingBehaviour,
+    protocols::replication::behaviour::{ReplicationBehaviour, ReplicationConfig},
+    protocols::sampling::behaviour::{SamplingBehaviour, SamplingConfig},
 };
+use subnetworks_assignations::epochs::Epoch;
 use subnetworks_assignations::MembershipHandler;
 
 /// Aggregated `NetworkBehaviour` composed of:
@@ -34,18 +35,30 @@ where
     Membership: MembershipHandler + Clone + Send + 'static,
     <Membership as MembershipHandler>::NetworkId: Send,
 {
-    pub fn new(key: &Keypair, membership: Membership, addresses: AddressBook) -> Self {
+    pub fn new(
+        key: &Keypair,
+        membership: Membership,
+        addresses: AddressBook,
+        sampling_config: SamplingConfig,
+        replication_config: ReplicationConfig,
+    ) -> Self {
         let peer_id = PeerId::from_public_key(&key.public());
         Self {
-            sampling: SamplingBehaviour::new(peer_id, membership.clone(), addresses),
+            sampling: SamplingBehaviour::with_config(
+                peer_id,
+                membership.clone(),
+                addresses,
+                sampling_config,
+            ),
             dispersal: DispersalValidatorBehaviour::new(membership.clone()),
-            replication: ReplicationBehaviour::new(peer_id, membership),
+            replication: ReplicationBehaviour::with_config(peer_id, membership, replication_config),
         }
     }
 
-    pub fn update_membership(&mut self, membership: Membership) {
+    /// Swap in the membership of a new epoch
+    pub fn update_membership(&mut self, epoch: Epoch, membership: Membership) {
         // TODO: share membership
-        self.sampling.update_membership(membership.clone());
+        self.sampling.update_membership(epoch, membership.clone());
         self.dispersal.update_membership(membership.clone());
         self.replication.update_membership(membership);
     }
@@ -21,7 +21,7 @@ use thiserror::Error;
 use tokio::sync::mpsc;
 use tokio::sync::mpsc::UnboundedSender;
 use tokio_stream::wrappers::UnboundedReceiverStream;
-use tracing::error;
+use tracing::{error, instrument};
 // internal
 use crate::address_book::AddressBook;
 use crate::protocol::DISPERSAL_PROTOCOL;
@@ -33,6 +33,7 @@ use nomos_da_messages::common::Blob;
 use nomos_da_messages::dispersal::dispersal_res::MessageType;
 use nomos_da_messages::dispersal::{DispersalErr, DispersalReq, DispersalRes};
 use nomos_da_messages::{pack_message, unpack_from_reader};
+use subnetworks_assignations::epochs::{Epoch, EpochMemberships};
 use subnetworks_assignations::MembershipHandler;
 
 #[derive(Debug, Error)]
@@ -163,8 +164,10 @@ pub struct DispersalExecutorBehaviour<Membership: MembershipHandler> {
     tasks: FuturesUnordered<StreamHandlerFuture>,
     /// Streams which didn't have any pending task
     idle_streams: HashMap<PeerId, DispersalStream>,
-    /// Subnetworks membership information
-    membership: Membership,
+    /// Subnetworks membership information of the latest epochs
+    memberships: EpochMemberships<Membership>,
+    /// Epoch whose membership is used to disperse each blob
+    blob_epochs: HashMap<BlobId, Epoch>,
     /// Addresses of known peers in the DA network
     addresses: AddressBook,
     /// Pending blobs that need to be dispersed by PeerId
@@ -180,9 +183,9 @@ pub struct DispersalExecutorBehaviour<Membership: MembershipHandler> {
     /// Pending to open streams
     pending_out_streams: BoxStream<'static, Result<DispersalStream, DispersalError>>,
     /// Dispersal hook of pending blobs channel
-    pending_blobs_sender: UnboundedSender<(Membership::NetworkId, DaBlob)>,
+    pending_blobs_sender: UnboundedSender<(Membership::NetworkId, Epoch, DaBlob)>,
     /// Pending blobs stream
-    pending_blobs_stream: BoxStream<'static, (Membership::NetworkId, DaBlob)>,
+    pending_blobs_stream: BoxStream<'static, (Membership::NetworkId, Epoch, DaBlob)>,
 }
 
 impl<Membership> DispersalExecutorBehaviour<Membership>
@@ -212,7 +215,8 @@ where
             local_peer_id,
             stream_behaviour,
             tasks,
-            membership,
+            memberships: EpochMemberships::new(0, membership),
+            blob_epochs: HashMap::new(),
             addresses,
             to_disperse,
             disconnected_pending_blobs,
@@ -226,10 +230,6 @@ where
         }
     }
 
-    pub fn update_membership(&mut self, membership: Membership) {
-        self.membership = membership;
-    }
-
     /// Open a new stream from the underlying control to the provided peer
     async fn open_stream(
         peer_id: PeerId,
@@ -247,13 +247,23 @@ where
         self.pending_out_streams_sender.clone()
     }
 
-    /// Get a hook to the sender channel of the blobs dispersal events
-    pub fn blobs_sender(&self) -> UnboundedSender<(Membership::NetworkId, DaBlob)> {
+    /// Get a hook to the sender channel of the blobs dispersal events.
+    /// Blobs are sent along with the epoch they are dispersed in.
+    pub fn blobs_sender(&self) -> UnboundedSender<(Membership::NetworkId, Epoch, DaBlob)> {
         self.pending_blobs_sender.clone()
     }
 
     /// Task for handling streams, one message at a time
     /// Writes the blob to the stream and waits for an acknowledgment response
+    #[instrument(
+        name = "disperse_share",
+        skip_all,
+        fields(
+            blob_id = %hex::encode(message.id()),
+            peer_id = %stream.peer_id,
+            subnetwork_id = subnetwork_id
+        )
+    )]
     async fn stream_disperse(
         mut stream: DispersalStream,
         message: DaBlob,
@@ -336,11 +346,41 @@ where
             .get_mut(peer_id)
             .and_then(|queue| queue.pop_front())
     }
+
+    /// Membership of the epoch the blob started being dispersed in
+    fn blob_membership<'m>(
+        memberships: &'m EpochMemberships<Membership>,
+        blob_epochs: &HashMap<BlobId, Epoch>,
+        blob: &DaBlob,
+    ) -> &'m Membership {
+        // Safety: blob_id should always be a 32bytes hash
+        let blob_id: BlobId = blob.id().try_into().unwrap();
+        blob_epochs
+            .get(&blob_id)
+            .map(|epoch| memberships.get_or_current(*epoch))
+            .unwrap_or_else(|| memberships.current())
+    }
 }
 
 impl<Membership: MembershipHandler<Id = PeerId, NetworkId = SubnetworkId> + 'static>
     DispersalExecutorBehaviour<Membership>
 {
+    /// Swap in the membership of a new epoch.
+    /// Blobs that started being dispersed in a retained epoch keep using that epoch membership.
+    pub fn update_membership(&mut self, epoch: Epoch, membership: Membership) {
+        self.memberships.update(epoch, membership);
+        let memberships = &self.memberships;
+        self.blob_epochs
+            .retain(|_, epoch| memberships.get(*epoch).is_some());
+        // subnetworks covered by the already open streams changed with the membership
+        let membership = self.memberships.current();
+        self.subnetwork_open_streams = self
+            .connected_peers
+            .keys()
+            .flat_map(|peer_id| membership.membership(peer_id))
+            .collect();
+    }
+
     /// Schedule a new task for sending the blob, if stream is not available queue messages for later
     /// processing.
     fn disperse_blob(
@@ -376,18 +416,24 @@ impl<Membership: MembershipHandler<Id = PeerId, NetworkId = SubnetworkId> + 'sta
 
     fn reschedule_blobs_for_peer_stream(
         stream: &DispersalStream,
-        membership: &Membership,
+        memberships: &EpochMemberships<Membership>,
+        blob_epochs: &HashMap<BlobId, Epoch>,
         to_disperse: &mut HashMap<PeerId, VecDeque<(SubnetworkId, DaBlob)>>,
         disconnected_pending_blobs: &mut HashMap<SubnetworkId, VecDeque<DaBlob>>,
     ) {
         let peer_id = stream.peer_id;
-        let subnetworks = membership.membership(&peer_id);
         let entry = to_disperse.entry(peer_id).or_default();
-        for subnetwork in subnetworks {
-            if let Some(blobs) = disconnected_pending_blobs.remove(&subnetwork) {
-                entry.extend(blobs.into_iter().map(|blob| (subnetwork, blob)));
-            }
+        for (subnetwork, blobs) in disconnected_pending_blobs.iter_mut() {
+            // peer must be a member of the subnetwork in the epoch of each blob
+            let (peer_blobs, pending): (VecDeque<_>, VecDeque<_>) =
+                blobs.drain(..).partition(|blob| {
+                    Self::blob_membership(memberships, blob_epochs, blob)
+                        .is_member_of(&peer_id, subnetwork)
+                });
+            entry.extend(peer_blobs.into_iter().map(|blob| (*subnetwork, blob)));
+            *blobs = pending;
         }
+        disconnected_pending_blobs.retain(|_, blobs| !blobs.is_empty());
     }
 
     fn filter_peers_for_subnetworks<'s>(
@@ -396,7 +442,8 @@ impl<Membership: MembershipHandler<Id = PeerId, NetworkId = SubnetworkId> + 'sta
         subnetworks: impl Iterator<Item = SubnetworkId> + 's,
     ) -> impl Iterator<Item = HashSet<PeerId>> + 's {
         subnetworks.map(move |subnetwork_id| {
-            self.membership
+            self.memberships
+                .current()
                 .members_of(&subnetwork_id)
                 .iter()
                 .filter(|&&peer| peer != peer_id && peer != self.local_peer_id)
@@ -424,7 +471,7 @@ impl<Membership: MembershipHandler<Id = PeerId, NetworkId = SubnetworkId> + 'sta
         peers
     }
     fn open_streams_for_disconnected_subnetworks_selected_peer(&mut self, peer_id: PeerId) {
-        let subnetworks = self.membership.membership(&peer_id);
+        let subnetworks = self.memberships.current().membership(&peer_id);
         // open stream will result in dialing if we are not yet connected to the peer
         for peer in self.find_subnetworks_candidates_excluding_peer(peer_id, &subnetworks) {
             if let Err(e) = self.pending_out_streams_sender.send(peer) {
@@ -475,7 +522,7 @@ impl<Membership: MembershipHandler<Id = PeerId, NetworkId = SubnetworkId> + 'sta
     }
 
     fn handle_connection_closed(&mut self, peer_id: PeerId) {
-        let peer_subnetworks = self.membership.membership(&peer_id);
+        let peer_subnetworks = self.memberships.current().membership(&peer_id);
         self.subnetwork_open_streams
             .retain(|subnetwork_id| !peer_subnetworks.contains(subnetwork_id));
         if self.connected_peers.remove(&peer_id).is_some() {
@@ -558,7 +605,8 @@ impl<M: MembershipHandler<Id = PeerId, NetworkId = SubnetworkId> + 'static> Netw
             pending_out_streams,
             pending_out_streams_sender,
             pending_blobs_stream,
-            membership,
+            memberships,
+            blob_epochs,
             addresses,
             connected_peers,
             subnetwork_open_streams,
@@ -600,7 +648,15 @@ impl<M: MembershipHandler<Id = PeerId, NetworkId = SubnetworkId> + 'static> Netw
             }
         }
         // poll pending blobs
-        if let Poll::Ready(Some((subnetwork_id, blob))) = pending_blobs_stream.poll_next_unpin(cx) {
+        if let Poll::Ready(Some((subnetwork_id, epoch, blob))) =
+            pending_blobs_stream.poll_next_unpin(cx)
+        {
+            // every column of a blob is dispersed with the membership of the epoch of its first one
+            // Safety: blob_id should always be a 32bytes hash
+            let epoch = *blob_epochs
+                .entry(blob.id().try_into().unwrap())
+                .or_insert(epoch);
+            let membership = memberships.get_or_current(epoch);
             if subnetwork_open_streams.contains(&subnetwork_id) {
                 Self::disperse_blob(
                     tasks,
@@ -626,10 +682,12 @@ impl<M: MembershipHandler<Id = PeerId, NetworkId = SubnetworkId> + 'static> Netw
         if let Poll::Ready(Some(res)) = pending_out_streams.poll_next_unpin(cx) {
             match res {
                 Ok(stream) => {
-                    subnetwork_open_streams.extend(membership.membership(&stream.peer_id));
+                    subnetwork_open_streams
+                        .extend(memberships.current().membership(&stream.peer_id));
                     Self::reschedule_blobs_for_peer_stream(
                         &stream,
-                        membership,
+                        memberships,
+                        blob_epochs,
                         to_disperse,
                         disconnected_pending_blobs,
                     );
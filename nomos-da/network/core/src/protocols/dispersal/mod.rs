@@ -125,6 +125,7 @@ pub mod test {
             info!("Sending blob: {i}");
             executor_disperse_blob_sender
                 .send((
+                    0,
                     0,
                     DaBlob {
                         column_idx: 0,
@@ -18,6 +18,7 @@ use nomos_da_messages::{pack_message, unpack_from_reader};
 use std::io::Error;
 use std::task::{Context, Poll};
 use subnetworks_assignations::MembershipHandler;
+use tracing::{field, instrument, Span};
 
 #[derive(Debug)]
 pub enum DispersalEvent {
@@ -27,7 +28,7 @@ pub enum DispersalEvent {
 pub struct DispersalValidatorBehaviour<Membership> {
     stream_behaviour: libp2p_stream::Behaviour,
     incoming_streams: IncomingStreams,
-    tasks: FuturesUnordered<BoxFuture<'static, Result<(DispersalReq, Stream), Error>>>,
+    tasks: FuturesUnordered<BoxFuture<'static, Result<(DispersalReq, PeerId, Stream), Error>>>,
     membership: Membership,
 }
 
@@ -54,16 +55,25 @@ impl<Membership: MembershipHandler> DispersalValidatorBehaviour<Membership> {
     /// Stream handling messages task.
     /// This task handles a single message receive. Then it writes up the acknowledgment into the same
     /// stream as response and finish.
-    async fn handle_new_stream(mut stream: Stream) -> Result<(DispersalReq, Stream), Error> {
+    #[instrument(
+        name = "receive_share",
+        skip_all,
+        fields(blob_id = field::Empty, peer_id = %peer_id)
+    )]
+    async fn handle_new_stream(
+        peer_id: PeerId,
+        mut stream: Stream,
+    ) -> Result<(DispersalReq, PeerId, Stream), Error> {
         let message: DispersalReq = unpack_from_reader(&mut stream).await?;
         let blob_id = message.blob.clone().unwrap().blob_id;
+        Span::current().record("blob_id", field::display(hex::encode(&blob_id)));
         let response = DispersalRes {
             message_type: Some(MessageType::BlobId(blob_id)),
         };
         let message_bytes = pack_message(&response)?;
         stream.write_all(&message_bytes).await?;
         stream.flush().await?;
-        Ok((message, stream))
+        Ok((message, peer_id, stream))
     }
 }
 
@@ -126,8 +136,8 @@ impl<M: MembershipHandler<Id = PeerId, NetworkId = SubnetworkId> + 'static> Netw
             ..
         } = self;
         match tasks.poll_next_unpin(cx) {
-            Poll::Ready(Some(Ok((message, stream)))) => {
-                tasks.push(Self::handle_new_stream(stream).boxed());
+            Poll::Ready(Some(Ok((message, peer_id, stream)))) => {
+                tasks.push(Self::handle_new_stream(peer_id, stream).boxed());
                 return Poll::Ready(ToSwarm::GenerateEvent(DispersalEvent::IncomingMessage {
                     message,
                 }));
@@ -137,8 +147,8 @@ impl<M: MembershipHandler<Id = PeerId, NetworkId = SubnetworkId> + 'static> Netw
             }
             _ => {}
         }
-        if let Poll::Ready(Some((_peer_id, stream))) = incoming_streams.poll_next_unpin(cx) {
-            tasks.push(Self::handle_new_stream(stream).boxed());
+        if let Poll::Ready(Some((peer_id, stream))) = incoming_streams.poll_next_unpin(cx) {
+            tasks.push(Self::handle_new_stream(peer_id, stream).boxed());
         }
         // TODO: probably must be smarter when to wake this
         cx.waker().wake_by_ref();
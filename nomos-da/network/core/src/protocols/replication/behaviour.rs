@@ -1,9 +1,13 @@
 // std
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::task::{Context, Poll, Waker};
+use std::time::Duration;
 
 // crates
 use either::Either;
+use futures::future::BoxFuture;
+use futures::stream::FuturesUnordered;
+use futures::{FutureExt, StreamExt};
 use indexmap::IndexSet;
 use libp2p::core::Endpoint;
 use libp2p::swarm::{
@@ -11,7 +15,9 @@ use libp2p::swarm::{
     THandler, THandlerInEvent, THandlerOutEvent, ToSwarm,
 };
 use libp2p::{Multiaddr, PeerId};
-use log::{error, trace};
+use log::{debug, error, trace};
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
 
 use subnetworks_assignations::MembershipHandler;
 
@@ -19,16 +25,55 @@ use crate::SubnetworkId;
 
 // internal
 use super::handler::{
-    BehaviourEventToHandler, DaMessage, HandlerEventToBehaviour, ReplicationHandler,
+    BehaviourEventToHandler, DaMessage, HandlerEventToBehaviour, OutgoingMessage,
+    ReplicationHandler, ReplicationHandlerConfig,
 };
 
 type SwarmEvent = ToSwarm<ReplicationEvent, Either<BehaviourEventToHandler, void::Void>>;
+type RetryFuture = BoxFuture<'static, (PeerId, OutgoingMessage)>;
 
 /// Nomos DA BroadcastEvents to be bubble up to logic layers
-#[allow(dead_code)] // todo: remove when used in tests
 #[derive(Debug)]
 pub enum ReplicationEvent {
-    IncomingMessage { peer_id: PeerId, message: DaMessage },
+    IncomingMessage {
+        peer_id: PeerId,
+        message: DaMessage,
+    },
+    /// The message was not replicated to the peer because its outgoing queue was full
+    OutgoingMessageDropped {
+        peer_id: PeerId,
+        message: DaMessage,
+    },
+    /// The message couldn't be replicated to the peer, retries included
+    OutgoingMessageFailed {
+        peer_id: PeerId,
+        message: DaMessage,
+    },
+}
+
+/// Limits of the replication protocol
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReplicationConfig {
+    /// Number of replicated messages remembered to suppress duplicates
+    pub seen_message_cache_size: usize,
+    /// Messages waiting to be sent to a single peer before new ones are dropped
+    pub max_queued_messages: usize,
+    /// Times sending a message to a peer is retried
+    pub max_retries: u32,
+    /// Wait before the first retry, doubled on every following one
+    pub retry_backoff: Duration,
+}
+
+impl Default for ReplicationConfig {
+    fn default() -> Self {
+        Self {
+            seen_message_cache_size: 10_000,
+            max_queued_messages: ReplicationHandlerConfig::default().max_queued_messages,
+            max_retries: 3,
+            retry_backoff: Duration::from_millis(500),
+        }
+    }
 }
 
 /// Nomos DA broadcas network behaviour
@@ -49,21 +94,30 @@ pub struct ReplicationBehaviour<Membership> {
     connected: HashMap<PeerId, HashSet<ConnectionId>>,
     /// Outgoing event queue
     outgoing_events: VecDeque<SwarmEvent>,
-    /// Seen messages cache holds a record of seen messages, messages will be removed from this
-    /// set after some time to keep it
+    /// Seen messages cache holds a record of seen messages, the least recently seen ones are
+    /// evicted once it reaches `seen_message_cache_size`
     seen_message_cache: IndexSet<(Vec<u8>, SubnetworkId)>,
+    /// Failed messages waiting for their backoff to be sent again
+    retries: FuturesUnordered<RetryFuture>,
+    config: ReplicationConfig,
     /// Waker that handles polling
     waker: Option<Waker>,
 }
 
 impl<Membership> ReplicationBehaviour<Membership> {
     pub fn new(peer_id: PeerId, membership: Membership) -> Self {
+        Self::with_config(peer_id, membership, ReplicationConfig::default())
+    }
+
+    pub fn with_config(peer_id: PeerId, membership: Membership, config: ReplicationConfig) -> Self {
         Self {
             local_peer_id: peer_id,
             membership,
             connected: Default::default(),
             outgoing_events: Default::default(),
             seen_message_cache: Default::default(),
+            retries: Default::default(),
+            config,
             waker: None,
         }
     }
@@ -87,6 +141,12 @@ where
             > 0
     }
 
+    fn new_handler(&self) -> ReplicationHandler {
+        ReplicationHandler::new(ReplicationHandlerConfig::new(
+            self.config.max_queued_messages,
+        ))
+    }
+
     fn no_loopback_member_peers_of(&self, subnetwork: &SubnetworkId) -> HashSet<PeerId> {
         let mut peers = self.membership.members_of(subnetwork);
         // no loopback
@@ -99,13 +159,35 @@ where
             message.blob.as_ref().unwrap().blob_id.clone(),
             message.subnetwork_id,
         );
-        if self.seen_message_cache.contains(&message_id) {
+        if !self.mark_seen(message_id) {
             return;
         }
-        self.seen_message_cache.insert(message_id);
         self.send_message(message)
     }
 
+    /// Record a message as seen, returns false if it was already
+    fn mark_seen(&mut self, message_id: (Vec<u8>, SubnetworkId)) -> bool {
+        if let Some(index) = self.seen_message_cache.get_index_of(&message_id) {
+            // refresh, the first entries are the least recently seen
+            let last = self.seen_message_cache.len() - 1;
+            self.seen_message_cache.move_index(index, last);
+            return false;
+        }
+        self.seen_message_cache.insert(message_id);
+        if self.seen_message_cache.len() > self.config.seen_message_cache_size {
+            self.seen_message_cache.shift_remove_index(0);
+        }
+        true
+    }
+
+    #[instrument(
+        name = "replicate",
+        skip_all,
+        fields(
+            blob_id = %message.blob.as_ref().map(|blob| hex::encode(&blob.blob_id)).unwrap_or_default(),
+            subnetwork_id = message.subnetwork_id
+        )
+    )]
     pub fn send_message(&mut self, message: DaMessage) {
         // push a message in the queue for every single peer connected that is a member of the
         // selected subnetwork_id
@@ -124,6 +206,7 @@ where
                     handler: NotifyHandler::One(*connection_id),
                     event: Either::Left(BehaviourEventToHandler::OutgoingMessage {
                         message: message.clone(),
+                        attempt: 0,
                     }),
                 })
             }
@@ -131,6 +214,27 @@ where
         self.try_wake();
     }
 
+    /// Schedule sending again a message that failed, or give up on it if out of retries
+    fn retry_message(&mut self, peer_id: PeerId, (message, attempt): OutgoingMessage) {
+        if attempt >= self.config.max_retries {
+            self.outgoing_events.push_back(ToSwarm::GenerateEvent(
+                ReplicationEvent::OutgoingMessageFailed { peer_id, message },
+            ));
+            return;
+        }
+        let backoff = self
+            .config
+            .retry_backoff
+            .saturating_mul(2u32.saturating_pow(attempt));
+        self.retries.push(
+            async move {
+                tokio::time::sleep(backoff).await;
+                (peer_id, (message, attempt + 1))
+            }
+            .boxed(),
+        );
+    }
+
     pub fn try_wake(&mut self) {
         if let Some(waker) = self.waker.take() {
             waker.wake();
@@ -161,7 +265,7 @@ where
             .entry(peer_id)
             .or_default()
             .insert(connection_id);
-        Ok(Either::Left(ReplicationHandler::new()))
+        Ok(Either::Left(self.new_handler()))
     }
 
     fn handle_established_outbound_connection(
@@ -176,7 +280,7 @@ where
             .entry(peer_id)
             .or_default()
             .insert(connection_id);
-        Ok(Either::Left(ReplicationHandler::new()))
+        Ok(Either::Left(self.new_handler()))
     }
 
     fn on_swarm_event(&mut self, event: FromSwarm) {
@@ -212,9 +316,26 @@ where
                     ReplicationEvent::IncomingMessage { message, peer_id },
                 ));
             }
-            HandlerEventToBehaviour::OutgoingMessageError { error } => {
-                error!("Couldn't send message due to {error}");
-                todo!("Retry?")
+            HandlerEventToBehaviour::OutgoingMessageError { error, messages } => {
+                error!("Couldn't send messages to {peer_id} due to {error}");
+                for message in messages {
+                    self.retry_message(peer_id, message);
+                }
+            }
+            HandlerEventToBehaviour::ConnectionClosed { messages } => {
+                debug!(
+                    "Connection to {peer_id} closed with {} messages queued",
+                    messages.len()
+                );
+                // sent through another connection to the peer if any, otherwise reported failed
+                for message in messages {
+                    self.retry_message(peer_id, message);
+                }
+            }
+            HandlerEventToBehaviour::OutgoingMessageDropped { message } => {
+                self.outgoing_events.push_back(ToSwarm::GenerateEvent(
+                    ReplicationEvent::OutgoingMessageDropped { peer_id, message },
+                ));
             }
         }
         self.try_wake();
@@ -225,11 +346,31 @@ where
         cx: &mut Context<'_>,
     ) -> Poll<ToSwarm<Self::ToSwarm, THandlerInEvent<Self>>> {
         if let Some(event) = self.outgoing_events.pop_front() {
-            Poll::Ready(event)
-        } else {
-            self.waker = Some(cx.waker().clone());
-            Poll::Pending
+            return Poll::Ready(event);
+        }
+        if let Poll::Ready(Some((peer_id, (message, attempt)))) = self.retries.poll_next_unpin(cx) {
+            let connection_id = self
+                .connected
+                .get(&peer_id)
+                .and_then(|connections| connections.iter().next());
+            return Poll::Ready(match connection_id {
+                Some(connection_id) => ToSwarm::NotifyHandler {
+                    peer_id,
+                    handler: NotifyHandler::One(*connection_id),
+                    event: Either::Left(BehaviourEventToHandler::OutgoingMessage {
+                        message,
+                        attempt,
+                    }),
+                },
+                // the peer went away meanwhile
+                None => ToSwarm::GenerateEvent(ReplicationEvent::OutgoingMessageFailed {
+                    peer_id,
+                    message,
+                }),
+            });
         }
+        self.waker = Some(cx.waker().clone());
+        Poll::Pending
     }
 }
 
@@ -425,7 +566,9 @@ mod tests {
                             peer_id,
                             handler: NotifyHandler::One(connection_id),
                             event:
-                                Either::Left(BehaviourEventToHandler::OutgoingMessage { message }),
+                                Either::Left(BehaviourEventToHandler::OutgoingMessage {
+                                    message, ..
+                                }),
                         } = event
                         {
                             // Deliver the message to the appropriate peer's handler.
@@ -475,4 +618,128 @@ mod tests {
         // Ensure the number of peers with the message matches the expected count
         assert_eq!(subnet_0_behaviours.len(), num_instances / 2);
     }
+
+    fn message(id: u8) -> DaMessage {
+        DaMessage {
+            blob: Some(Blob {
+                blob_id: vec![id],
+                data: vec![id],
+            }),
+            subnetwork_id: 0,
+        }
+    }
+
+    fn outgoing_error(
+        messages: Vec<OutgoingMessage>,
+    ) -> THandlerOutEvent<ReplicationBehaviour<MockMembershipHandler>> {
+        Either::Left(HandlerEventToBehaviour::OutgoingMessageError {
+            error: std::io::Error::new(std::io::ErrorKind::Other, "stream closed"),
+            messages,
+        })
+    }
+
+    /// Behaviour connected to a single peer in the same subnetwork
+    fn connected_behaviour(
+        config: ReplicationConfig,
+    ) -> (ReplicationBehaviour<MockMembershipHandler>, PeerId) {
+        let local_peer_id = PeerId::from(identity::Keypair::generate_ed25519().public());
+        let peer_id = PeerId::from(identity::Keypair::generate_ed25519().public());
+        let membership = MockMembershipHandler {
+            membership: [
+                (local_peer_id, HashSet::from([0])),
+                (peer_id, HashSet::from([0])),
+            ]
+            .into(),
+        };
+        let mut behaviour = ReplicationBehaviour::with_config(local_peer_id, membership, config);
+        behaviour
+            .handle_established_outbound_connection(
+                ConnectionId::new_unchecked(0),
+                peer_id,
+                &Multiaddr::empty(),
+                Endpoint::Dialer,
+            )
+            .unwrap();
+        (behaviour, peer_id)
+    }
+
+    #[test]
+    fn test_seen_message_cache_is_bounded() {
+        let (mut behaviour, _) = connected_behaviour(ReplicationConfig {
+            seen_message_cache_size: 2,
+            ..Default::default()
+        });
+        behaviour.replicate_message(message(1));
+        behaviour.replicate_message(message(2));
+        // seeing the first message again leaves the second as the least recently seen
+        behaviour.replicate_message(message(1));
+        behaviour.replicate_message(message(3));
+
+        assert_eq!(behaviour.seen_message_cache.len(), 2);
+        assert!(behaviour.seen_message_cache.contains(&(vec![1], 0)));
+        assert!(!behaviour.seen_message_cache.contains(&(vec![2], 0)));
+        assert!(behaviour.seen_message_cache.contains(&(vec![3], 0)));
+        // duplicates are not sent again
+        assert_eq!(behaviour.outgoing_events.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_failed_messages_are_retried() {
+        let (mut behaviour, peer_id) = connected_behaviour(ReplicationConfig {
+            max_retries: 1,
+            retry_backoff: Duration::from_millis(10),
+            ..Default::default()
+        });
+        let connection_id = ConnectionId::new_unchecked(0);
+
+        behaviour.on_connection_handler_event(
+            peer_id,
+            connection_id,
+            outgoing_error(vec![(message(1), 0)]),
+        );
+        let event = futures::future::poll_fn(|cx| behaviour.poll(cx)).await;
+        assert!(matches!(
+            event,
+            ToSwarm::NotifyHandler {
+                peer_id: retried_peer_id,
+                event: Either::Left(BehaviourEventToHandler::OutgoingMessage { attempt: 1, .. }),
+                ..
+            } if retried_peer_id == peer_id
+        ));
+
+        // out of retries
+        behaviour.on_connection_handler_event(
+            peer_id,
+            connection_id,
+            outgoing_error(vec![(message(1), 1)]),
+        );
+        let event = futures::future::poll_fn(|cx| behaviour.poll(cx)).await;
+        assert!(matches!(
+            event,
+            ToSwarm::GenerateEvent(ReplicationEvent::OutgoingMessageFailed {
+                peer_id: failed_peer_id,
+                ..
+            }) if failed_peer_id == peer_id
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_dropped_messages_are_reported() {
+        let (mut behaviour, peer_id) = connected_behaviour(Default::default());
+        behaviour.on_connection_handler_event(
+            peer_id,
+            ConnectionId::new_unchecked(0),
+            Either::Left(HandlerEventToBehaviour::OutgoingMessageDropped {
+                message: message(1),
+            }),
+        );
+        let event = futures::future::poll_fn(|cx| behaviour.poll(cx)).await;
+        assert!(matches!(
+            event,
+            ToSwarm::GenerateEvent(ReplicationEvent::OutgoingMessageDropped {
+                peer_id: dropped_peer_id,
+                ..
+            }) if dropped_peer_id == peer_id
+        ));
+    }
 }
@@ -1,5 +1,6 @@
 // std
-use std::io::Error;
+use std::collections::VecDeque;
+use std::io::{Error, ErrorKind};
 use std::task::{Context, Poll};
 
 use futures::Future;
@@ -7,7 +8,9 @@ use futures::Future;
 use futures::future::BoxFuture;
 use futures::prelude::*;
 use libp2p::core::upgrade::ReadyUpgrade;
-use libp2p::swarm::handler::{ConnectionEvent, FullyNegotiatedInbound, FullyNegotiatedOutbound};
+use libp2p::swarm::handler::{
+    ConnectionEvent, DialUpgradeError, FullyNegotiatedInbound, FullyNegotiatedOutbound,
+};
 use libp2p::swarm::{ConnectionHandler, ConnectionHandlerEvent, SubstreamProtocol};
 use libp2p::{Stream, StreamProtocol};
 use log::trace;
@@ -20,65 +23,95 @@ use crate::protocol::REPLICATION_PROTOCOL;
 
 pub type DaMessage = nomos_da_messages::replication::ReplicationReq;
 
+/// Message to be sent along with the number of previous failed attempts to send it
+pub type OutgoingMessage = (DaMessage, u32);
+
 /// Events that bubbles up from the `BroadcastHandler` to the `NetworkBehaviour`
 #[derive(Debug)]
 pub enum HandlerEventToBehaviour {
-    IncomingMessage { message: DaMessage },
-    OutgoingMessageError { error: Error },
+    IncomingMessage {
+        message: DaMessage,
+    },
+    /// Writing to the stream failed, `messages` were not sent
+    OutgoingMessageError {
+        error: Error,
+        messages: Vec<OutgoingMessage>,
+    },
+    /// The outgoing queue was full so `message` was not queued
+    OutgoingMessageDropped {
+        message: DaMessage,
+    },
+    /// The connection closed before `messages` were sent
+    ConnectionClosed {
+        messages: Vec<OutgoingMessage>,
+    },
 }
 
 /// Events that bubbles up from the `NetworkBehaviour` to the `BroadcastHandler`
 #[derive(Debug)]
 pub enum BehaviourEventToHandler {
-    OutgoingMessage { message: DaMessage },
+    OutgoingMessage { message: DaMessage, attempt: u32 },
 }
 
 /// Broadcast configuration
-pub(crate) struct ReplicationHandlerConfig {}
+#[derive(Debug, Clone)]
+pub struct ReplicationHandlerConfig {
+    /// Messages waiting to be sent in the connection before new ones are dropped
+    pub max_queued_messages: usize,
+}
 
 impl ReplicationHandlerConfig {
-    pub fn new() -> Self {
-        Self {}
+    pub fn new(max_queued_messages: usize) -> Self {
+        Self {
+            max_queued_messages,
+        }
     }
 }
 
 impl Default for ReplicationHandlerConfig {
     fn default() -> Self {
-        Self::new()
+        Self::new(256)
     }
 }
 
+type SendFuture = BoxFuture<'static, Result<Stream, (Error, Vec<OutgoingMessage>)>>;
+
 /// State handling for outgoing broadcast messages
 enum OutboundState {
     OpenStream,
     Idle(Stream),
-    Sending(future::BoxFuture<'static, Result<Stream, Error>>),
+    Sending(SendFuture),
 }
 
 /// Broadcasting handler for the broadcast protocol
 /// Forwards and read messages
 pub struct ReplicationHandler {
+    config: ReplicationHandlerConfig,
     // incoming messages stream
     inbound: Option<BoxFuture<'static, Result<(DaMessage, Stream), Error>>>,
     // outgoing messages stream
     outbound: Option<OutboundState>,
     // pending messages not propagated in the connection
-    outgoing_messages: Vec<DaMessage>,
+    outgoing_messages: Vec<OutgoingMessage>,
+    // events for the behaviour that are not related to the streams
+    pending_events: VecDeque<HandlerEventToBehaviour>,
 }
 
 impl ReplicationHandler {
-    pub fn new() -> Self {
+    pub fn new(config: ReplicationHandlerConfig) -> Self {
         Self {
+            config,
             inbound: None,
             outbound: None,
             outgoing_messages: Default::default(),
+            pending_events: Default::default(),
         }
     }
 }
 
 impl Default for ReplicationHandler {
     fn default() -> Self {
-        Self::new()
+        Self::new(Default::default())
     }
 }
 
@@ -86,21 +119,32 @@ impl ReplicationHandler {
     fn send_pending_messages(
         &mut self,
         mut stream: Stream,
-    ) -> impl Future<Output = Result<Stream, Error>> {
+    ) -> impl Future<Output = Result<Stream, (Error, Vec<OutgoingMessage>)>> {
         trace!("Sending messages");
         let mut pending_messages = Vec::new();
         std::mem::swap(&mut self.outgoing_messages, &mut pending_messages);
         async {
             trace!("Writing {} messages", pending_messages.len());
-            for message in pending_messages {
-                let bytes = pack_message(&message)?;
-                stream.write_all(&bytes).await?;
-                stream.flush().await?;
+            let mut pending_messages = pending_messages.into_iter();
+            while let Some((message, attempt)) = pending_messages.next() {
+                if let Err(error) = Self::write_message(&mut stream, &message).await {
+                    // hand back whatever was not sent so it can be retried
+                    let unsent = std::iter::once((message, attempt))
+                        .chain(pending_messages)
+                        .collect();
+                    return Err((error, unsent));
+                }
             }
             Ok(stream)
         }
     }
 
+    async fn write_message(stream: &mut Stream, message: &DaMessage) -> Result<(), Error> {
+        let bytes = pack_message(message)?;
+        stream.write_all(&bytes).await?;
+        stream.flush().await
+    }
+
     fn read_message(
         &mut self,
         mut stream: Stream,
@@ -140,7 +184,7 @@ impl ReplicationHandler {
         cx: &mut Context<'_>,
     ) -> Result<
         Option<ConnectionHandlerEvent<ReadyUpgrade<StreamProtocol>, (), HandlerEventToBehaviour>>,
-        Error,
+        (Error, Vec<OutgoingMessage>),
     > {
         // Propagate incoming messages
         match self.outbound.take() {
@@ -159,9 +203,8 @@ impl ReplicationHandler {
                 Poll::Ready(Ok(stream)) => {
                     self.outbound = Some(OutboundState::Idle(stream));
                 }
-                Poll::Ready(Err(e)) => {
-                    error!("{e:?}");
-                }
+                // the stream is dropped and a new one requested on the next poll
+                Poll::Ready(Err(e)) => return Err(e),
                 Poll::Pending => {
                     self.outbound = Some(OutboundState::Sending(future));
                 }
@@ -194,15 +237,18 @@ impl ConnectionHandler for ReplicationHandler {
     ) -> Poll<
         ConnectionHandlerEvent<Self::OutboundProtocol, Self::OutboundOpenInfo, Self::ToBehaviour>,
     > {
+        if let Some(event) = self.pending_events.pop_front() {
+            return Poll::Ready(ConnectionHandlerEvent::NotifyBehaviour(event));
+        }
         match self.poll_pending_outgoing_messages(cx) {
             Ok(Some(event)) => {
                 // bubble up event
                 return Poll::Ready(event);
             }
-            Err(error) => {
+            Err((error, messages)) => {
                 error!("Outgoing message error: {error:?}");
                 return Poll::Ready(ConnectionHandlerEvent::NotifyBehaviour(
-                    HandlerEventToBehaviour::OutgoingMessageError { error },
+                    HandlerEventToBehaviour::OutgoingMessageError { error, messages },
                 ));
             }
             _ => {}
@@ -223,11 +269,28 @@ impl ConnectionHandler for ReplicationHandler {
         Poll::Pending
     }
 
+    fn poll_close(&mut self, _cx: &mut Context<'_>) -> Poll<Option<Self::ToBehaviour>> {
+        if let Some(event) = self.pending_events.pop_front() {
+            return Poll::Ready(Some(event));
+        }
+        if self.outgoing_messages.is_empty() {
+            return Poll::Ready(None);
+        }
+        Poll::Ready(Some(HandlerEventToBehaviour::ConnectionClosed {
+            messages: std::mem::take(&mut self.outgoing_messages),
+        }))
+    }
+
     fn on_behaviour_event(&mut self, event: Self::FromBehaviour) {
         match event {
-            BehaviourEventToHandler::OutgoingMessage { message } => {
+            BehaviourEventToHandler::OutgoingMessage { message, attempt } => {
                 trace!("Received outgoing message");
-                self.outgoing_messages.push(message);
+                if self.outgoing_messages.len() >= self.config.max_queued_messages {
+                    self.pending_events
+                        .push_back(HandlerEventToBehaviour::OutgoingMessageDropped { message });
+                    return;
+                }
+                self.outgoing_messages.push((message, attempt));
             }
         }
     }
@@ -257,13 +320,111 @@ impl ConnectionHandler for ReplicationHandler {
                 trace!("Received outbound stream");
                 self.outbound = Some(OutboundState::Idle(stream));
             }
-            ConnectionEvent::DialUpgradeError(error) => {
+            ConnectionEvent::DialUpgradeError(DialUpgradeError { error, .. }) => {
                 trace!("Upgrage error: {error:?}");
                 if is_outbound {
                     self.outbound = None;
                 }
+                // the stream could not be opened, hand the queued messages back to be retried
+                if !self.outgoing_messages.is_empty() {
+                    self.pending_events
+                        .push_back(HandlerEventToBehaviour::OutgoingMessageError {
+                            error: Error::new(ErrorKind::Other, error.to_string()),
+                            messages: std::mem::take(&mut self.outgoing_messages),
+                        });
+                }
             }
             _ => {}
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::task::noop_waker_ref;
+    use libp2p::swarm::StreamUpgradeError;
+    use nomos_da_messages::common::Blob;
+
+    #[test]
+    fn test_outgoing_queue_is_capped() {
+        let mut handler = ReplicationHandler::new(ReplicationHandlerConfig::new(1));
+        for id in 0..2u8 {
+            handler.on_behaviour_event(BehaviourEventToHandler::OutgoingMessage {
+                message: DaMessage {
+                    blob: Some(Blob {
+                        blob_id: vec![id],
+                        data: vec![id],
+                    }),
+                    subnetwork_id: 0,
+                },
+                attempt: 0,
+            });
+        }
+        assert_eq!(handler.outgoing_messages.len(), 1);
+
+        let mut cx = Context::from_waker(noop_waker_ref());
+        let Poll::Ready(ConnectionHandlerEvent::NotifyBehaviour(
+            HandlerEventToBehaviour::OutgoingMessageDropped { message },
+        )) = handler.poll(&mut cx)
+        else {
+            panic!("Second message should be dropped");
+        };
+        assert_eq!(message.blob.unwrap().blob_id, vec![1]);
+    }
+
+    #[test]
+    fn test_queued_messages_are_handed_back_on_close() {
+        let mut handler = ReplicationHandler::default();
+        handler.on_behaviour_event(BehaviourEventToHandler::OutgoingMessage {
+            message: DaMessage {
+                blob: Some(Blob {
+                    blob_id: vec![0],
+                    data: vec![0],
+                }),
+                subnetwork_id: 0,
+            },
+            attempt: 1,
+        });
+
+        let mut cx = Context::from_waker(noop_waker_ref());
+        let Poll::Ready(Some(HandlerEventToBehaviour::ConnectionClosed { messages })) =
+            handler.poll_close(&mut cx)
+        else {
+            panic!("Queued message should be handed back");
+        };
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].1, 1);
+        assert!(matches!(handler.poll_close(&mut cx), Poll::Ready(None)));
+    }
+
+    #[test]
+    fn test_queued_messages_are_handed_back_on_upgrade_error() {
+        let mut handler = ReplicationHandler::default();
+        handler.on_behaviour_event(BehaviourEventToHandler::OutgoingMessage {
+            message: DaMessage {
+                blob: Some(Blob {
+                    blob_id: vec![0],
+                    data: vec![0],
+                }),
+                subnetwork_id: 0,
+            },
+            attempt: 1,
+        });
+        handler.on_connection_event(ConnectionEvent::DialUpgradeError(DialUpgradeError {
+            info: (),
+            error: StreamUpgradeError::Timeout,
+        }));
+
+        let mut cx = Context::from_waker(noop_waker_ref());
+        let Poll::Ready(ConnectionHandlerEvent::NotifyBehaviour(
+            HandlerEventToBehaviour::OutgoingMessageError { messages, .. },
+        )) = handler.poll(&mut cx)
+        else {
+            panic!("Queued message should be handed back");
+        };
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].1, 1);
+        assert!(handler.outgoing_messages.is_empty());
+    }
+}
@@ -1,6 +1,8 @@
 // std
 use std::collections::{HashMap, HashSet, VecDeque};
+use std::future::Future;
 use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
 // crates
 use either::Either;
 use futures::channel::oneshot;
@@ -22,18 +24,27 @@ use nomos_core::da::BlobId;
 use nomos_da_messages::sampling::sample_err::SampleErrType;
 use nomos_da_messages::sampling::{sample_res, SampleErr, SampleReq, SampleRes};
 use nomos_da_messages::{common, pack_message, unpack_from_reader};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use subnetworks_assignations::epochs::{Epoch, EpochMemberships};
 use subnetworks_assignations::MembershipHandler;
 use thiserror::Error;
 use tokio::sync::mpsc;
 use tokio::sync::mpsc::UnboundedSender;
 use tokio_stream::wrappers::UnboundedReceiverStream;
-use tracing::error;
+use tracing::{error, field, instrument, Span};
 // internal
 use crate::address_book::AddressBook;
 use crate::protocol::SAMPLING_PROTOCOL;
 use crate::protocols::clone_deserialize_error;
 use crate::SubnetworkId;
 
+/// Time a peer has to reply to a sample request, dialing included
+const SAMPLE_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Time a peer is not sampled from after timing out or replying with an invalid share
+const PEER_EXCLUSION_PERIOD: Duration = Duration::from_secs(60);
+
 #[derive(Debug, Error)]
 pub enum SamplingError {
     #[error("Stream disconnected: {error}")]
@@ -41,17 +52,33 @@ pub enum SamplingError {
         peer_id: PeerId,
         error: std::io::Error,
     },
+    #[error("Sample stream disconnected: {error}")]
+    Stream {
+        blob_id: BlobId,
+        subnetwork_id: SubnetworkId,
+        peer_id: PeerId,
+        error: std::io::Error,
+    },
     #[error("Dispersal response error: {error:?}")]
     Protocol {
+        blob_id: BlobId,
         subnetwork_id: SubnetworkId,
         peer_id: PeerId,
         error: SampleErr,
     },
     #[error("Error dialing peer [{peer_id}]: {error}")]
     OpenStream {
+        blob_id: BlobId,
+        subnetwork_id: SubnetworkId,
         peer_id: PeerId,
         error: OpenStreamError,
     },
+    #[error("Sample request to peer [{peer_id}] timed out")]
+    Timeout {
+        blob_id: BlobId,
+        subnetwork_id: SubnetworkId,
+        peer_id: PeerId,
+    },
     #[error("Unable to deserialize blob response: {error}")]
     Deserialize {
         blob_id: BlobId,
@@ -59,6 +86,12 @@ pub enum SamplingError {
         peer_id: PeerId,
         error: bincode::Error,
     },
+    #[error("Invalid share for subnetwork {subnetwork_id} from peer [{peer_id}]")]
+    InvalidShare {
+        blob_id: BlobId,
+        subnetwork_id: SubnetworkId,
+        peer_id: PeerId,
+    },
     #[error("Error sending request: {request:?}")]
     RequestChannel {
         request: BehaviourSampleReq,
@@ -70,28 +103,66 @@ pub enum SamplingError {
     BlobNotFound { peer_id: PeerId, blob_id: Vec<u8> },
     #[error("Canceled response: {error}")]
     ResponseChannel { error: Canceled, peer_id: PeerId },
+    #[error("Peer [{peer_id}] outside of the membership exceeded its sample requests")]
+    RateLimited { peer_id: PeerId },
 }
 
 impl SamplingError {
     pub fn peer_id(&self) -> &PeerId {
         match self {
             SamplingError::Io { peer_id, .. } => peer_id,
+            SamplingError::Stream { peer_id, .. } => peer_id,
             SamplingError::Protocol { peer_id, .. } => peer_id,
             SamplingError::OpenStream { peer_id, .. } => peer_id,
+            SamplingError::Timeout { peer_id, .. } => peer_id,
             SamplingError::Deserialize { peer_id, .. } => peer_id,
+            SamplingError::InvalidShare { peer_id, .. } => peer_id,
             SamplingError::RequestChannel { peer_id, .. } => peer_id,
             SamplingError::ResponseChannel { peer_id, .. } => peer_id,
             SamplingError::InvalidBlobId { peer_id, .. } => peer_id,
             SamplingError::BlobNotFound { peer_id, .. } => peer_id,
+            SamplingError::RateLimited { peer_id } => peer_id,
         }
     }
 
+    /// Blob of the outgoing sample request that failed, if any
     pub fn blob_id(&self) -> Option<&BlobId> {
         match self {
+            SamplingError::Stream { blob_id, .. } => Some(blob_id),
+            SamplingError::Protocol { blob_id, .. } => Some(blob_id),
+            SamplingError::OpenStream { blob_id, .. } => Some(blob_id),
+            SamplingError::Timeout { blob_id, .. } => Some(blob_id),
             SamplingError::Deserialize { blob_id, .. } => Some(blob_id),
+            SamplingError::InvalidShare { blob_id, .. } => Some(blob_id),
+            _ => None,
+        }
+    }
+
+    /// Subnetwork of the outgoing sample request that failed, if any
+    pub fn subnetwork_id(&self) -> Option<&SubnetworkId> {
+        match self {
+            SamplingError::Stream { subnetwork_id, .. } => Some(subnetwork_id),
+            SamplingError::Protocol { subnetwork_id, .. } => Some(subnetwork_id),
+            SamplingError::OpenStream { subnetwork_id, .. } => Some(subnetwork_id),
+            SamplingError::Timeout { subnetwork_id, .. } => Some(subnetwork_id),
+            SamplingError::Deserialize { subnetwork_id, .. } => Some(subnetwork_id),
+            SamplingError::InvalidShare { subnetwork_id, .. } => Some(subnetwork_id),
             _ => None,
         }
     }
+
+    /// Whether the sampled peer was unreachable or replied with an invalid share.
+    /// A peer that just doesn't hold the sample is not at fault.
+    pub fn is_peer_fault(&self) -> bool {
+        matches!(
+            self,
+            SamplingError::Stream { .. }
+                | SamplingError::OpenStream { .. }
+                | SamplingError::Timeout { .. }
+                | SamplingError::Deserialize { .. }
+                | SamplingError::InvalidShare { .. }
+        )
+    }
 }
 
 impl Clone for SamplingError {
@@ -101,16 +172,36 @@ impl Clone for SamplingError {
                 peer_id: *peer_id,
                 error: std::io::Error::new(error.kind(), error.to_string()),
             },
+            SamplingError::Stream {
+                blob_id,
+                subnetwork_id,
+                peer_id,
+                error,
+            } => SamplingError::Stream {
+                blob_id: *blob_id,
+                subnetwork_id: *subnetwork_id,
+                peer_id: *peer_id,
+                error: std::io::Error::new(error.kind(), error.to_string()),
+            },
             SamplingError::Protocol {
+                blob_id,
                 subnetwork_id,
                 peer_id,
                 error,
             } => SamplingError::Protocol {
+                blob_id: *blob_id,
                 subnetwork_id: *subnetwork_id,
                 peer_id: *peer_id,
                 error: error.clone(),
             },
-            SamplingError::OpenStream { peer_id, error } => SamplingError::OpenStream {
+            SamplingError::OpenStream {
+                blob_id,
+                subnetwork_id,
+                peer_id,
+                error,
+            } => SamplingError::OpenStream {
+                blob_id: *blob_id,
+                subnetwork_id: *subnetwork_id,
                 peer_id: *peer_id,
                 error: match error {
                     OpenStreamError::UnsupportedProtocol(protocol) => {
@@ -125,6 +216,15 @@ impl Clone for SamplingError {
                     )),
                 },
             },
+            SamplingError::Timeout {
+                blob_id,
+                subnetwork_id,
+                peer_id,
+            } => SamplingError::Timeout {
+                blob_id: *blob_id,
+                subnetwork_id: *subnetwork_id,
+                peer_id: *peer_id,
+            },
             SamplingError::Deserialize {
                 blob_id,
                 subnetwork_id,
@@ -136,6 +236,15 @@ impl Clone for SamplingError {
                 peer_id: *peer_id,
                 error: clone_deserialize_error(error),
             },
+            SamplingError::InvalidShare {
+                blob_id,
+                subnetwork_id,
+                peer_id,
+            } => SamplingError::InvalidShare {
+                blob_id: *blob_id,
+                subnetwork_id: *subnetwork_id,
+                peer_id: *peer_id,
+            },
             SamplingError::RequestChannel { request, peer_id } => SamplingError::RequestChannel {
                 request: request.clone(),
                 peer_id: *peer_id,
@@ -152,6 +261,9 @@ impl Clone for SamplingError {
                 peer_id: *peer_id,
                 blob_id: blob_id.clone(),
             },
+            SamplingError::RateLimited { peer_id } => {
+                SamplingError::RateLimited { peer_id: *peer_id }
+            }
         }
     }
 }
@@ -242,6 +354,28 @@ type StreamHandlerFutureSuccess = (BlobId, SubnetworkId, SampleRes, SampleStream
 type OutgoingStreamHandlerFuture =
     BoxFuture<'static, Result<StreamHandlerFutureSuccess, SamplingError>>;
 type IncomingStreamHandlerFuture = BoxFuture<'static, Result<SampleStream, SamplingError>>;
+
+/// Serving of samples to peers outside of the DA membership, such as light nodes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SamplingConfig {
+    /// Accept sample requests from peers outside of the membership
+    pub serve_non_members: bool,
+    /// Sample requests a peer outside of the membership can make per `non_member_rate_window`
+    pub non_member_max_requests: u32,
+    pub non_member_rate_window: Duration,
+}
+
+impl Default for SamplingConfig {
+    fn default() -> Self {
+        Self {
+            serve_non_members: false,
+            non_member_max_requests: 64,
+            non_member_rate_window: Duration::from_secs(1),
+        }
+    }
+}
+
 /// Executor sampling protocol
 /// Takes care of sending and replying sampling requests
 pub struct SamplingBehaviour<Membership: MembershipHandler> {
@@ -259,16 +393,23 @@ pub struct SamplingBehaviour<Membership: MembershipHandler> {
     outgoing_tasks: FuturesUnordered<OutgoingStreamHandlerFuture>,
     /// Pending incoming running tasks (one task per stream)
     incoming_tasks: FuturesUnordered<IncomingStreamHandlerFuture>,
-    /// Subnetworks membership information
-    membership: Membership,
+    /// Subnetworks membership information of the latest epochs
+    memberships: EpochMemberships<Membership>,
+    /// Epoch whose membership is used to sample each blob
+    blob_epochs: HashMap<BlobId, Epoch>,
     /// Pending blobs that need to be dispersed by PeerId
     to_sample: HashMap<PeerId, VecDeque<(Membership::NetworkId, BlobId)>>,
     /// Already connected peers connection Ids
     connected_peers: HashSet<PeerId>,
+    /// Peers that timed out or replied with invalid shares, and since when
+    excluded_peers: HashMap<PeerId, Instant>,
     /// Hook of pending samples channel
     samples_request_sender: UnboundedSender<(Membership::NetworkId, BlobId)>,
     /// Pending samples stream
     samples_request_stream: BoxStream<'static, (Membership::NetworkId, BlobId)>,
+    /// Requests served to each peer outside of the membership in its current rate window
+    non_member_requests: HashMap<PeerId, (Instant, u32)>,
+    config: SamplingConfig,
 }
 
 impl<Membership> SamplingBehaviour<Membership>
@@ -277,6 +418,15 @@ where
     Membership::NetworkId: Send,
 {
     pub fn new(peer_id: PeerId, membership: Membership, addresses: AddressBook) -> Self {
+        Self::with_config(peer_id, membership, addresses, SamplingConfig::default())
+    }
+
+    pub fn with_config(
+        peer_id: PeerId,
+        membership: Membership,
+        addresses: AddressBook,
+        config: SamplingConfig,
+    ) -> Self {
         let stream_behaviour = libp2p_stream::Behaviour::new();
         let mut control = stream_behaviour.new_control();
 
@@ -292,6 +442,7 @@ where
         let (samples_request_sender, receiver) = mpsc::unbounded_channel();
         let samples_request_stream = UnboundedReceiverStream::new(receiver).boxed();
         let connected_peers = HashSet::new();
+        let excluded_peers = HashMap::new();
         Self {
             peer_id,
             addresses,
@@ -300,27 +451,43 @@ where
             control,
             outgoing_tasks,
             incoming_tasks,
-            membership,
+            memberships: EpochMemberships::new(0, membership),
+            blob_epochs: HashMap::new(),
             to_sample,
             connected_peers,
+            excluded_peers,
             samples_request_sender,
             samples_request_stream,
+            non_member_requests: HashMap::new(),
+            config,
         }
     }
 
-    pub fn update_membership(&mut self, membership: Membership) {
-        self.membership = membership;
+    /// Swap in the membership of a new epoch.
+    /// Blobs that started sampling in a retained epoch keep using that epoch membership.
+    pub fn update_membership(&mut self, epoch: Epoch, membership: Membership) {
+        self.memberships.update(epoch, membership);
+        let memberships = &self.memberships;
+        self.blob_epochs
+            .retain(|_, epoch| memberships.get(*epoch).is_some());
     }
 
     /// Open a new stream from the underlying control to the provided peer
     async fn open_stream(
         peer_id: PeerId,
         mut control: Control,
+        subnetwork_id: SubnetworkId,
+        blob_id: BlobId,
     ) -> Result<SampleStream, SamplingError> {
         let stream = control
             .open_stream(peer_id, SAMPLING_PROTOCOL)
             .await
-            .map_err(|error| SamplingError::OpenStream { peer_id, error })?;
+            .map_err(|error| SamplingError::OpenStream {
+                blob_id,
+                subnetwork_id,
+                peer_id,
+                error,
+            })?;
         Ok(SampleStream { stream, peer_id })
     }
 
@@ -331,45 +498,59 @@ where
 
     /// Task for handling streams, one message at a time
     /// Writes the request to the stream and waits for a response
+    #[instrument(
+        name = "sample",
+        skip_all,
+        fields(
+            blob_id = %hex::encode(blob_id),
+            peer_id = %stream.peer_id,
+            subnetwork_id = subnetwork_id
+        )
+    )]
     async fn stream_sample(
         mut stream: SampleStream,
         message: SampleReq,
         subnetwork_id: SubnetworkId,
         blob_id: BlobId,
     ) -> Result<StreamHandlerFutureSuccess, SamplingError> {
-        let bytes = pack_message(&message).map_err(|error| SamplingError::Io {
-            peer_id: stream.peer_id,
+        let peer_id = stream.peer_id;
+        let into_error = |error| SamplingError::Stream {
+            blob_id,
+            subnetwork_id,
+            peer_id,
             error,
-        })?;
-        stream
-            .stream
-            .write_all(&bytes)
-            .await
-            .map_err(|error| SamplingError::Io {
-                peer_id: stream.peer_id,
-                error,
-            })?;
-        stream
-            .stream
-            .flush()
+        };
+        let bytes = pack_message(&message).map_err(into_error)?;
+        stream.stream.write_all(&bytes).await.map_err(into_error)?;
+        stream.stream.flush().await.map_err(into_error)?;
+        let response: SampleRes = unpack_from_reader(&mut stream.stream)
             .await
-            .map_err(|error| SamplingError::Io {
-                peer_id: stream.peer_id,
-                error,
-            })?;
-        let response: SampleRes =
-            unpack_from_reader(&mut stream.stream)
-                .await
-                .map_err(|error| SamplingError::Io {
-                    peer_id: stream.peer_id,
-                    error,
-                })?;
+            .map_err(into_error)?;
         // Safety: blob_id should always be a 32bytes hash, currently is abstracted into a `Vec<u8>`
         // but probably we should have a `[u8; 32]` wrapped in a custom type `BlobId`
         // TODO: use blob_id when changing types to [u8; 32]
         Ok((blob_id, subnetwork_id, response, stream))
     }
 
+    /// Bound an outgoing sample task to [`SAMPLE_REQUEST_TIMEOUT`]
+    fn with_timeout(
+        task: impl Future<Output = Result<StreamHandlerFutureSuccess, SamplingError>> + Send + 'static,
+        peer_id: PeerId,
+        subnetwork_id: SubnetworkId,
+        blob_id: BlobId,
+    ) -> OutgoingStreamHandlerFuture {
+        async move {
+            tokio::time::timeout(SAMPLE_REQUEST_TIMEOUT, task)
+                .await
+                .unwrap_or(Err(SamplingError::Timeout {
+                    blob_id,
+                    subnetwork_id,
+                    peer_id,
+                }))
+        }
+        .boxed()
+    }
+
     /// Get a pending outgoing request if its available
     fn next_request(
         peer_id: &PeerId,
@@ -395,8 +576,12 @@ where
                 blob_id: blob_id.to_vec(),
                 column_idx: subnetwork_id,
             };
-            outgoing_tasks
-                .push(Self::stream_sample(stream, sample_request, subnetwork_id, blob_id).boxed());
+            outgoing_tasks.push(Self::with_timeout(
+                Self::stream_sample(stream, sample_request, subnetwork_id, blob_id),
+                peer,
+                subnetwork_id,
+                blob_id,
+            ));
         // if not pop stream from connected ones
         } else {
             tokio::task::spawn(async move {
@@ -410,6 +595,11 @@ where
 
     /// Handler incoming streams
     /// Pull a request from the stream and replies if possible
+    #[instrument(
+        name = "serve_sample",
+        skip_all,
+        fields(blob_id = field::Empty, peer_id = %stream.peer_id)
+    )]
     async fn handle_incoming_stream(
         mut stream: SampleStream,
         channel: ResponseChannel,
@@ -426,6 +616,7 @@ where
                 blob_id,
             }
         })?;
+        Span::current().record("blob_id", field::display(hex::encode(request.blob_id)));
         channel
             .request_sender
             .send(request)
@@ -487,45 +678,98 @@ impl<Membership: MembershipHandler<Id = PeerId, NetworkId = SubnetworkId> + 'sta
 {
     /// Schedule a new task for sample the blob, if stream is not available queue messages for later
     /// processing.
-    #[allow(clippy::too_many_arguments)]
-    fn sample(
-        peer_id: PeerId,
-        outgoing_tasks: &mut FuturesUnordered<OutgoingStreamHandlerFuture>,
-        membership: &mut Membership,
-        connected_peers: &mut HashSet<PeerId>,
-        to_sample: &mut HashMap<PeerId, VecDeque<(Membership::NetworkId, BlobId)>>,
-        subnetwork_id: SubnetworkId,
-        blob_id: BlobId,
-        control: &Control,
-    ) {
-        let members = membership.members_of(&subnetwork_id);
-        // TODO: peer selection for sampling should be randomly selected (?) filtering ourselves
-        // currently we assume optimal setup which is one peer per blob
-        let peer = members
-            .iter()
-            .filter(|&id| id != &peer_id)
-            .copied()
-            .next()
-            .expect("At least a single node should be a member of the subnetwork");
+    fn sample(&mut self, subnetwork_id: SubnetworkId, blob_id: BlobId) {
+        self.excluded_peers
+            .retain(|_, excluded_at| excluded_at.elapsed() < PEER_EXCLUSION_PERIOD);
+        let peer = select_peer(
+            self.sampling_members(subnetwork_id, blob_id),
+            &self.peer_id,
+            &self.excluded_peers,
+            &mut rand::thread_rng(),
+        )
+        .expect("At least a single node should be a member of the subnetwork");
         // if its connected means we are already working on some other sample, enqueue message
-        if connected_peers.contains(&peer) {
-            to_sample
+        if self.connected_peers.contains(&peer) {
+            self.to_sample
                 .entry(peer)
                 .or_default()
                 .push_back((subnetwork_id, blob_id));
         } else {
-            connected_peers.insert(peer);
-            let control = control.clone();
+            self.connected_peers.insert(peer);
+            let control = self.control.clone();
             let sample_request = SampleReq {
                 blob_id: blob_id.to_vec(),
                 column_idx: subnetwork_id,
             };
-            let with_dial_task: OutgoingStreamHandlerFuture = async move {
-                let stream = Self::open_stream(peer, control).await?;
+            let with_dial_task = async move {
+                let stream = Self::open_stream(peer, control, subnetwork_id, blob_id).await?;
                 Self::stream_sample(stream, sample_request, subnetwork_id, blob_id).await
+            };
+            self.outgoing_tasks.push(Self::with_timeout(
+                with_dial_task,
+                peer,
+                subnetwork_id,
+                blob_id,
+            ));
+        }
+    }
+
+    /// Members of the subnetwork in the epoch the blob started sampling in
+    pub(crate) fn sampling_members(
+        &mut self,
+        subnetwork_id: SubnetworkId,
+        blob_id: BlobId,
+    ) -> HashSet<PeerId> {
+        let epoch = *self
+            .blob_epochs
+            .entry(blob_id)
+            .or_insert(self.memberships.current_epoch());
+        self.memberships.members_of(epoch, &subnetwork_id)
+    }
+
+    /// Whether a sample request from the peer can be served.
+    /// Members are always served, peers outside of the membership only if enabled and within
+    /// their rate limit.
+    pub(crate) fn allow_request(&mut self, peer_id: &PeerId) -> bool {
+        if self.memberships.is_allowed(peer_id) {
+            return true;
+        }
+        if !self.config.serve_non_members {
+            return false;
+        }
+        let window = self.config.non_member_rate_window;
+        self.non_member_requests
+            .retain(|_, (window_start, _)| window_start.elapsed() < window);
+        let (_, requests) = self
+            .non_member_requests
+            .entry(*peer_id)
+            .or_insert((Instant::now(), 0));
+        *requests += 1;
+        *requests <= self.config.non_member_max_requests
+    }
+
+    /// Drop an incoming stream whose requests can't be served anymore
+    fn close_incoming_stream(mut stream: SampleStream) {
+        tokio::task::spawn(async move {
+            if let Err(error) = stream.stream.close().await {
+                error!("Error closing sampling stream: {error}");
+            };
+        });
+    }
+
+    /// Stop sampling from a peer for a while, samples queued for it are sent to other members of
+    /// their subnetworks.
+    fn exclude_peer(&mut self, peer_id: PeerId) {
+        self.excluded_peers.insert(peer_id, Instant::now());
+        self.resample_queued(peer_id);
+    }
+
+    /// Schedule again the samples queued for a peer
+    fn resample_queued(&mut self, peer_id: PeerId) {
+        if let Some(queued) = self.to_sample.remove(&peer_id) {
+            for (subnetwork_id, blob_id) in queued {
+                self.sample(subnetwork_id, blob_id);
             }
-            .boxed();
-            outgoing_tasks.push(with_dial_task);
         }
     }
 
@@ -535,19 +779,16 @@ impl<Membership: MembershipHandler<Id = PeerId, NetworkId = SubnetworkId> + 'sta
         subnetwork_id: SubnetworkId,
         sample_response: SampleRes,
         peer_id: PeerId,
-    ) -> Option<Poll<ToSwarm<<Self as NetworkBehaviour>::ToSwarm, THandlerInEvent<Self>>>> {
+    ) -> Result<SamplingEvent, SamplingError> {
         match sample_response {
             SampleRes {
                 message_type: Some(sample_res::MessageType::Err(error)),
-            } => Some(Poll::Ready(ToSwarm::GenerateEvent(
-                SamplingEvent::SamplingError {
-                    error: SamplingError::Protocol {
-                        subnetwork_id,
-                        error,
-                        peer_id,
-                    },
-                },
-            ))),
+            } => Err(SamplingError::Protocol {
+                blob_id,
+                subnetwork_id,
+                error,
+                peer_id,
+            }),
             SampleRes {
                 message_type: Some(sample_res::MessageType::Blob(da_blob)),
             } => {
@@ -559,28 +800,51 @@ impl<Membership: MembershipHandler<Id = PeerId, NetworkId = SubnetworkId> + 'sta
                             peer_id,
                             error,
                         }
+                    })?;
+                // columns are sampled from the subnetwork with the same index
+                if SubnetworkId::from(blob.column_idx) != subnetwork_id {
+                    return Err(SamplingError::InvalidShare {
+                        blob_id,
+                        subnetwork_id,
+                        peer_id,
                     });
-                match blob {
-                    Ok(blob) => Some(Poll::Ready(ToSwarm::GenerateEvent(
-                        SamplingEvent::SamplingSuccess {
-                            blob_id,
-                            subnetwork_id,
-                            blob: Box::new(blob),
-                        },
-                    ))),
-                    Err(error) => Some(Poll::Ready(ToSwarm::GenerateEvent(
-                        SamplingEvent::SamplingError { error },
-                    ))),
                 }
+                Ok(SamplingEvent::SamplingSuccess {
+                    blob_id,
+                    subnetwork_id,
+                    blob: Box::new(blob),
+                })
             }
             _ => {
                 error!("Invalid sampling response received, empty body");
-                None
+                Err(SamplingError::InvalidShare {
+                    blob_id,
+                    subnetwork_id,
+                    peer_id,
+                })
             }
         }
     }
 }
 
+/// Pick a random member of a subnetwork to sample from, other than ourselves.
+/// Excluded peers are only picked if no other member is available.
+pub(crate) fn select_peer(
+    members: HashSet<PeerId>,
+    local_peer_id: &PeerId,
+    excluded_peers: &HashMap<PeerId, Instant>,
+    rng: &mut impl Rng,
+) -> Option<PeerId> {
+    let (available, excluded): (Vec<PeerId>, Vec<PeerId>) = members
+        .into_iter()
+        .filter(|id| id != local_peer_id)
+        .partition(|id| !excluded_peers.contains_key(id));
+    available
+        .choose(rng)
+        .or_else(|| excluded.choose(rng))
+        .copied()
+}
+
 impl<M: MembershipHandler<Id = PeerId, NetworkId = SubnetworkId> + 'static> NetworkBehaviour
     for SamplingBehaviour<M>
 {
@@ -597,7 +861,8 @@ impl<M: MembershipHandler<Id = PeerId, NetworkId = SubnetworkId> + 'static> Netw
         local_addr: &Multiaddr,
         remote_addr: &Multiaddr,
     ) -> Result<THandler<Self>, ConnectionDenied> {
-        if !self.membership.is_allowed(&peer) {
+        // peers outside of the membership, such as light nodes, are only served if enabled
+        if !self.memberships.is_allowed(&peer) && !self.config.serve_non_members {
             return Ok(Either::Right(libp2p::swarm::dummy::ConnectionHandler));
         }
         self.stream_behaviour
@@ -612,7 +877,7 @@ impl<M: MembershipHandler<Id = PeerId, NetworkId = SubnetworkId> + 'static> Netw
         addr: &Multiaddr,
         role_override: Endpoint,
     ) -> Result<THandler<Self>, ConnectionDenied> {
-        if !self.membership.is_allowed(&peer) {
+        if !self.memberships.is_allowed(&peer) {
             return Ok(Either::Right(libp2p::swarm::dummy::ConnectionHandler));
         }
         self.stream_behaviour
@@ -639,58 +904,49 @@ impl<M: MembershipHandler<Id = PeerId, NetworkId = SubnetworkId> + 'static> Netw
         &mut self,
         cx: &mut Context<'_>,
     ) -> Poll<ToSwarm<Self::ToSwarm, THandlerInEvent<Self>>> {
-        let Self {
-            peer_id,
-            outgoing_tasks,
-            incoming_tasks,
-            to_sample,
-            samples_request_stream,
-            connected_peers,
-            incoming_streams,
-            membership,
-            control,
-            ..
-        } = self;
         // poll pending outgoing samples
         if let Poll::Ready(Some((subnetwork_id, blob_id))) =
-            samples_request_stream.poll_next_unpin(cx)
+            self.samples_request_stream.poll_next_unpin(cx)
         {
-            Self::sample(
-                *peer_id,
-                outgoing_tasks,
-                membership,
-                connected_peers,
-                to_sample,
-                subnetwork_id,
-                blob_id,
-                control,
-            );
+            self.sample(subnetwork_id, blob_id);
         }
         // poll outgoing tasks
-        if let Poll::Ready(Some(future_result)) = outgoing_tasks.poll_next_unpin(cx) {
+        if let Poll::Ready(Some(future_result)) = self.outgoing_tasks.poll_next_unpin(cx) {
             match future_result {
                 Ok((blob_id, subnetwork_id, sample_response, stream)) => {
-                    let peer_id = stream.peer_id;
-                    // handle the free stream then return the success
-                    Self::handle_outgoing_stream(
-                        outgoing_tasks,
-                        to_sample,
-                        connected_peers,
-                        stream,
-                    );
-                    // return an error if there was an error on the other side of the wire
-                    if let Some(event) = Self::handle_sample_response(
+                    let result = Self::handle_sample_response(
                         blob_id,
                         subnetwork_id,
                         sample_response,
-                        peer_id,
-                    ) {
-                        return event;
+                        stream.peer_id,
+                    );
+                    // exclude before handling the stream so pending samples go to other peers
+                    if let Err(error) = &result {
+                        if error.is_peer_fault() {
+                            self.exclude_peer(stream.peer_id);
+                        }
                     }
+                    // handle the free stream then return the result
+                    Self::handle_outgoing_stream(
+                        &mut self.outgoing_tasks,
+                        &mut self.to_sample,
+                        &mut self.connected_peers,
+                        stream,
+                    );
+                    // return an error if there was an error on the other side of the wire
+                    let event =
+                        result.unwrap_or_else(|error| SamplingEvent::SamplingError { error });
+                    return Poll::Ready(ToSwarm::GenerateEvent(event));
                 }
                 // Something went up on our side of the wire, bubble it up
                 Err(error) => {
-                    connected_peers.remove(error.peer_id());
+                    let peer_id = *error.peer_id();
+                    self.connected_peers.remove(&peer_id);
+                    if error.is_peer_fault() {
+                        self.excluded_peers.insert(peer_id, Instant::now());
+                    }
+                    // the stream is gone, samples queued behind it need a new one
+                    self.resample_queued(peer_id);
                     return Poll::Ready(ToSwarm::GenerateEvent(SamplingEvent::SamplingError {
                         error,
                     }));
@@ -698,21 +954,36 @@ impl<M: MembershipHandler<Id = PeerId, NetworkId = SubnetworkId> + 'static> Netw
             }
         }
         // poll incoming streams
-        if let Poll::Ready(Some((peer_id, stream))) = incoming_streams.poll_next_unpin(cx) {
+        if let Poll::Ready(Some((peer_id, stream))) = self.incoming_streams.poll_next_unpin(cx) {
             let sample_stream = SampleStream { stream, peer_id };
+            if !self.allow_request(&peer_id) {
+                Self::close_incoming_stream(sample_stream);
+                return Poll::Ready(ToSwarm::GenerateEvent(SamplingEvent::SamplingError {
+                    error: SamplingError::RateLimited { peer_id },
+                }));
+            }
             let (request_receiver, response_sender) =
-                Self::schedule_incoming_stream_task(incoming_tasks, sample_stream);
+                Self::schedule_incoming_stream_task(&mut self.incoming_tasks, sample_stream);
             return Poll::Ready(ToSwarm::GenerateEvent(SamplingEvent::IncomingSample {
                 request_receiver,
                 response_sender,
             }));
         }
         // poll incoming tasks
-        if let Poll::Ready(Some(res)) = incoming_tasks.poll_next_unpin(cx) {
+        if let Poll::Ready(Some(res)) = self.incoming_tasks.poll_next_unpin(cx) {
             match res {
                 Ok(sample_stream) => {
-                    let (request_receiver, response_sender) =
-                        Self::schedule_incoming_stream_task(incoming_tasks, sample_stream);
+                    let peer_id = sample_stream.peer_id;
+                    if !self.allow_request(&peer_id) {
+                        Self::close_incoming_stream(sample_stream);
+                        return Poll::Ready(ToSwarm::GenerateEvent(SamplingEvent::SamplingError {
+                            error: SamplingError::RateLimited { peer_id },
+                        }));
+                    }
+                    let (request_receiver, response_sender) = Self::schedule_incoming_stream_task(
+                        &mut self.incoming_tasks,
+                        sample_stream,
+                    );
                     return Poll::Ready(ToSwarm::GenerateEvent(SamplingEvent::IncomingSample {
                         request_receiver,
                         response_sender,
@@ -4,18 +4,22 @@ pub mod behaviour;
 mod test {
     use crate::address_book::AddressBook;
     use crate::protocols::sampling::behaviour::{
-        BehaviourSampleRes, SamplingBehaviour, SamplingEvent,
+        select_peer, BehaviourSampleRes, SamplingBehaviour, SamplingConfig, SamplingError,
+        SamplingEvent,
     };
-    use crate::test_utils::AllNeighbours;
+    use crate::test_utils::{get_available_port, AllNeighbours, MockMembership};
     use crate::SubnetworkId;
     use futures::StreamExt;
     use kzgrs_backend::common::blob::DaBlob;
-    use kzgrs_backend::common::Column;
+    use kzgrs_backend::common::{Column, ColumnIndex};
     use libp2p::identity::Keypair;
     use libp2p::swarm::SwarmEvent;
     use libp2p::{quic, Multiaddr, PeerId, Swarm, SwarmBuilder};
     use log::debug;
-    use std::time::Duration;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::collections::{HashMap, HashSet};
+    use std::time::{Duration, Instant};
     use subnetworks_assignations::MembershipHandler;
     use tracing_subscriber::fmt::TestWriter;
     use tracing_subscriber::EnvFilter;
@@ -159,4 +163,262 @@ mod test {
         let res2 = t2.await.unwrap();
         assert_eq!(res1, res2);
     }
+
+    fn blob_with_column(column_idx: ColumnIndex) -> DaBlob {
+        DaBlob {
+            column: Column(vec![]),
+            column_idx,
+            column_commitment: Default::default(),
+            aggregated_column_commitment: Default::default(),
+            aggregated_column_proof: Default::default(),
+            rows_commitments: vec![],
+            rows_proofs: vec![],
+        }
+    }
+
+    fn random_peer_id() -> PeerId {
+        PeerId::from_public_key(&Keypair::generate_ed25519().public())
+    }
+
+    #[test]
+    fn test_select_peer_randomly() {
+        let local = random_peer_id();
+        let members: HashSet<PeerId> = (0..3).map(|_| random_peer_id()).collect();
+        let membership = MockMembership {
+            subnetworks: [(0, members.iter().copied().chain([local]).collect())].into(),
+        };
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let selected: HashSet<PeerId> = (0..100)
+            .map(|_| {
+                select_peer(membership.members_of(&0), &local, &HashMap::new(), &mut rng).unwrap()
+            })
+            .collect();
+        assert_eq!(selected, members);
+    }
+
+    #[test]
+    fn test_select_peer_skips_excluded() {
+        let local = random_peer_id();
+        let faulty = random_peer_id();
+        let healthy = random_peer_id();
+        let membership = MockMembership {
+            subnetworks: [
+                (0, [local, faulty, healthy].into()),
+                (1, [local, faulty].into()),
+            ]
+            .into(),
+        };
+        let excluded: HashMap<PeerId, Instant> = [(faulty, Instant::now())].into();
+        let mut rng = StdRng::seed_from_u64(42);
+
+        for _ in 0..100 {
+            assert_eq!(
+                select_peer(membership.members_of(&0), &local, &excluded, &mut rng),
+                Some(healthy)
+            );
+        }
+        // excluded peers are still sampled when they are the only option
+        assert_eq!(
+            select_peer(membership.members_of(&1), &local, &excluded, &mut rng),
+            Some(faulty)
+        );
+        assert_eq!(
+            select_peer(membership.members_of(&2), &local, &excluded, &mut rng),
+            None
+        );
+    }
+
+    #[test]
+    fn test_sampling_members_follow_blob_epoch() {
+        let local = random_peer_id();
+        let previous = random_peer_id();
+        let next = random_peer_id();
+        let mut behaviour = SamplingBehaviour::new(
+            local,
+            MockMembership {
+                subnetworks: [(0, [local, previous].into())].into(),
+            },
+            AddressBook::empty(),
+        );
+        let started_blob = [1; 32];
+        let new_blob = [2; 32];
+        assert_eq!(
+            behaviour.sampling_members(0, started_blob),
+            [local, previous].into()
+        );
+
+        behaviour.update_membership(
+            1,
+            MockMembership {
+                subnetworks: [(0, [local, next].into())].into(),
+            },
+        );
+        // blobs keep the membership of the epoch they started sampling in
+        assert_eq!(
+            behaviour.sampling_members(0, started_blob),
+            [local, previous].into()
+        );
+        assert_eq!(
+            behaviour.sampling_members(0, new_blob),
+            [local, next].into()
+        );
+
+        // once the epoch is not retained anymore the current membership is used
+        behaviour.update_membership(
+            2,
+            MockMembership {
+                subnetworks: [(0, [local, next].into())].into(),
+            },
+        );
+        assert_eq!(
+            behaviour.sampling_members(0, started_blob),
+            [local, next].into()
+        );
+    }
+
+    #[test]
+    fn test_non_member_requests_are_rate_limited() {
+        let local = random_peer_id();
+        let member = random_peer_id();
+        let non_member = random_peer_id();
+        let membership = MockMembership {
+            subnetworks: [(0, [local, member].into())].into(),
+        };
+        let mut behaviour = SamplingBehaviour::new(local, membership.clone(), AddressBook::empty());
+        // peers outside of the membership are not served by default
+        assert!(behaviour.allow_request(&member));
+        assert!(!behaviour.allow_request(&non_member));
+
+        let mut behaviour = SamplingBehaviour::with_config(
+            local,
+            membership,
+            AddressBook::empty(),
+            SamplingConfig {
+                serve_non_members: true,
+                non_member_max_requests: 2,
+                non_member_rate_window: Duration::from_secs(60),
+            },
+        );
+        assert!(behaviour.allow_request(&non_member));
+        assert!(behaviour.allow_request(&non_member));
+        assert!(!behaviour.allow_request(&non_member));
+        // members are never limited
+        for _ in 0..3 {
+            assert!(behaviour.allow_request(&member));
+        }
+    }
+
+    /// Reply to every incoming sample with a column of index `column_idx`
+    async fn serve_samples(
+        mut swarm: Swarm<
+            SamplingBehaviour<
+                impl MembershipHandler<Id = PeerId, NetworkId = SubnetworkId> + 'static,
+            >,
+        >,
+        column_idx: ColumnIndex,
+    ) {
+        loop {
+            if let Some(SwarmEvent::Behaviour(SamplingEvent::IncomingSample {
+                request_receiver,
+                response_sender,
+            })) = swarm.next().await
+            {
+                // spawn here because otherwise we block polling
+                tokio::spawn(async move {
+                    if let Ok(request) = request_receiver.await {
+                        let _ = response_sender.send(BehaviourSampleRes::SamplingSuccess {
+                            blob_id: request.blob_id,
+                            subnetwork_id: SubnetworkId::from(column_idx),
+                            blob: Box::new(blob_with_column(column_idx)),
+                        });
+                    }
+                });
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sampling_excludes_invalid_share_peer() {
+        let _ = tracing_subscriber::fmt()
+            .with_env_filter(EnvFilter::from_default_env())
+            .compact()
+            .with_writer(TestWriter::default())
+            .try_init();
+        let sampler_key = Keypair::generate_ed25519();
+        let faulty_key = Keypair::generate_ed25519();
+        let healthy_key = Keypair::generate_ed25519();
+        let sampler_id = PeerId::from_public_key(&sampler_key.public());
+        let faulty_id = PeerId::from_public_key(&faulty_key.public());
+        let healthy_id = PeerId::from_public_key(&healthy_key.public());
+        let membership = MockMembership {
+            subnetworks: [
+                (0, [faulty_id, healthy_id].into()),
+                (1, [sampler_id].into()),
+            ]
+            .into(),
+        };
+
+        let faulty_address = format!("/ip4/127.0.0.1/udp/{}/quic-v1", get_available_port())
+            .parse::<Multiaddr>()
+            .unwrap()
+            .with_p2p(faulty_id)
+            .unwrap();
+        let healthy_address = format!("/ip4/127.0.0.1/udp/{}/quic-v1", get_available_port())
+            .parse::<Multiaddr>()
+            .unwrap()
+            .with_p2p(healthy_id)
+            .unwrap();
+        let mut sampler = sampling_swarm(
+            sampler_key,
+            membership.clone(),
+            [
+                (faulty_id, faulty_address.clone()),
+                (healthy_id, healthy_address.clone()),
+            ]
+            .into_iter()
+            .collect(),
+        );
+        let mut faulty = sampling_swarm(faulty_key, membership.clone(), AddressBook::empty());
+        let mut healthy = sampling_swarm(healthy_key, membership, AddressBook::empty());
+        faulty.listen_on(faulty_address).unwrap();
+        healthy.listen_on(healthy_address).unwrap();
+        // the faulty peer replies with columns of a subnetwork that wasn't asked for
+        let faulty_task = tokio::spawn(serve_samples(faulty, 1));
+        let healthy_task = tokio::spawn(serve_samples(healthy, 0));
+        tokio::time::sleep(Duration::from_secs(1)).await;
+
+        const MSG_COUNT: usize = 10;
+        let request_sender = sampler.behaviour().sample_request_channel();
+        for i in 0..MSG_COUNT {
+            request_sender.send((0, [i as u8; 32])).unwrap();
+        }
+        let mut sampled = HashSet::new();
+        let mut invalid_shares = 0;
+        while sampled.len() < MSG_COUNT {
+            match sampler.next().await {
+                Some(SwarmEvent::Behaviour(SamplingEvent::SamplingSuccess { blob_id, .. })) => {
+                    sampled.insert(blob_id);
+                }
+                Some(SwarmEvent::Behaviour(SamplingEvent::SamplingError { error })) => {
+                    debug!("Error during sampling: {error}");
+                    if let SamplingError::InvalidShare { peer_id, .. } = &error {
+                        assert_eq!(peer_id, &faulty_id);
+                        invalid_shares += 1;
+                    }
+                    // retry as the sampling service would
+                    if let (Some(blob_id), Some(subnetwork_id)) =
+                        (error.blob_id(), error.subnetwork_id())
+                    {
+                        request_sender.send((*subnetwork_id, *blob_id)).unwrap();
+                    }
+                }
+                event => debug!("{event:?}"),
+            }
+        }
+        // once the faulty peer sends an invalid share it is not sampled anymore
+        assert!(invalid_shares <= 1);
+        faulty_task.abort();
+        healthy_task.abort();
+    }
 }
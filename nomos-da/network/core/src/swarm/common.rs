@@ -1,11 +1,19 @@
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+use crate::address_book::AddressBook;
 use crate::protocols::dispersal::validator::behaviour::DispersalEvent;
 use crate::protocols::replication::behaviour::{ReplicationBehaviour, ReplicationEvent};
 use crate::protocols::sampling::behaviour::SamplingEvent;
 use crate::SubnetworkId;
 use kzgrs_backend::common::blob::DaBlob;
-use libp2p::PeerId;
+use libp2p::swarm::dial_opts::DialOpts;
+use libp2p::swarm::NetworkBehaviour;
+use libp2p::{PeerId, Swarm};
 use log::{debug, error};
 use nomos_da_messages::replication::ReplicationReq;
+use opentelemetry::metrics::{Counter, Gauge};
+use opentelemetry::{global, KeyValue};
 use subnetworks_assignations::MembershipHandler;
 use tokio::sync::mpsc::UnboundedSender;
 
@@ -52,17 +60,89 @@ pub async fn handle_replication_event(
     validation_events_sender: &mut UnboundedSender<DaBlob>,
     event: ReplicationEvent,
 ) {
-    let ReplicationEvent::IncomingMessage { message, .. } = event;
-    if let Ok(blob) = bincode::deserialize::<DaBlob>(
-        message
-            .blob
-            .as_ref()
-            .expect("Message blob should not be empty")
-            .data
-            .as_slice(),
-    ) {
-        if let Err(e) = validation_events_sender.send(blob) {
-            error!("Error sending blob to validation: {e:?}");
+    match event {
+        ReplicationEvent::IncomingMessage { message, .. } => {
+            if let Ok(blob) = bincode::deserialize::<DaBlob>(
+                message
+                    .blob
+                    .as_ref()
+                    .expect("Message blob should not be empty")
+                    .data
+                    .as_slice(),
+            ) {
+                if let Err(e) = validation_events_sender.send(blob) {
+                    error!("Error sending blob to validation: {e:?}");
+                }
+            }
+        }
+        ReplicationEvent::OutgoingMessageDropped { peer_id, message } => {
+            debug!("Replication queue to {peer_id} is full, message dropped");
+            record_replication_failure("dropped", message.subnetwork_id);
+        }
+        ReplicationEvent::OutgoingMessageFailed { peer_id, message } => {
+            error!("Couldn't replicate message to {peer_id}");
+            record_replication_failure("failed", message.subnetwork_id);
+        }
+    }
+}
+
+fn record_replication_failure(reason: &'static str, subnetwork_id: SubnetworkId) {
+    static REPLICATION_FAILURES: OnceLock<Counter<u64>> = OnceLock::new();
+    REPLICATION_FAILURES
+        .get_or_init(|| {
+            global::meter("da-network")
+                .u64_counter("da_replication_failures")
+                .with_description("Replication messages dropped or not delivered to a peer")
+                .init()
+        })
+        .add(
+            1,
+            &[
+                KeyValue::new("reason", reason),
+                KeyValue::new("subnetwork", i64::from(subnetwork_id)),
+            ],
+        );
+}
+
+pub fn record_connected_peers(peers: usize) {
+    static CONNECTED_PEERS: OnceLock<Gauge<u64>> = OnceLock::new();
+    CONNECTED_PEERS
+        .get_or_init(|| {
+            // shared with the network service, which records the same gauge for its own swarm
+            global::meter("libp2p")
+                .u64_gauge("libp2p_connected_peers")
+                .with_description("Peers with at least one open connection")
+                .init()
+        })
+        .record(peers as u64, &[KeyValue::new("network", "da")]);
+}
+
+/// Dial the peers sharing a subnetwork with the local peer that are not connected yet
+pub fn dial_subnetwork_peers<Membership, Behaviour>(
+    swarm: &mut Swarm<Behaviour>,
+    membership: &Membership,
+    addresses: &AddressBook,
+) where
+    Membership: MembershipHandler<NetworkId = SubnetworkId, Id = PeerId>,
+    Behaviour: NetworkBehaviour,
+{
+    let local_peer_id = *swarm.local_peer_id();
+    let peers: HashSet<PeerId> = membership
+        .membership(&local_peer_id)
+        .iter()
+        .flat_map(|subnetwork_id| membership.members_of(subnetwork_id))
+        .filter(|peer_id| peer_id != &local_peer_id && !swarm.is_connected(peer_id))
+        .collect();
+    for peer_id in peers {
+        let Some(address) = addresses.get_address(&peer_id) else {
+            debug!("No known address for subnetwork peer {peer_id}");
+            continue;
+        };
+        let opts = DialOpts::peer_id(peer_id)
+            .addresses(vec![address.clone()])
+            .build();
+        if let Err(e) = swarm.dial(opts) {
+            error!("Error dialing subnetwork peer {peer_id}: {e}");
         }
     }
 }
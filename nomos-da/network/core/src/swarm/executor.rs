@@ -10,7 +10,7 @@ use libp2p::swarm::{DialError, SwarmEvent};
 use libp2p::{Multiaddr, PeerId, Swarm, SwarmBuilder, TransportError};
 use log::debug;
 use nomos_core::da::BlobId;
-use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
 use tokio_stream::wrappers::UnboundedReceiverStream;
 // internal
 use crate::address_book::AddressBook;
@@ -19,14 +19,16 @@ use crate::protocols::{
     dispersal::{
         executor::behaviour::DispersalExecutorEvent, validator::behaviour::DispersalEvent,
     },
-    replication::behaviour::ReplicationEvent,
-    sampling::behaviour::SamplingEvent,
+    replication::behaviour::{ReplicationConfig, ReplicationEvent},
+    sampling::behaviour::{SamplingConfig, SamplingEvent},
 };
 use crate::swarm::common::{
-    handle_replication_event, handle_sampling_event, handle_validator_dispersal_event,
+    dial_subnetwork_peers, handle_replication_event, handle_sampling_event,
+    handle_validator_dispersal_event, record_connected_peers,
 };
 use crate::swarm::validator::ValidatorEventsStream;
 use crate::SubnetworkId;
+use subnetworks_assignations::epochs::Epoch;
 use subnetworks_assignations::MembershipHandler;
 
 pub struct ExecutorEventsStream {
@@ -38,6 +40,9 @@ pub struct ExecutorSwarm<
     Membership: MembershipHandler<NetworkId = SubnetworkId, Id = PeerId> + 'static,
 > {
     swarm: Swarm<ExecutorBehaviour<Membership>>,
+    addresses: AddressBook,
+    membership_updates_sender: UnboundedSender<(Epoch, Membership)>,
+    membership_updates_receiver: UnboundedReceiver<(Epoch, Membership)>,
     sampling_events_sender: UnboundedSender<SamplingEvent>,
     validation_events_sender: UnboundedSender<DaBlob>,
     dispersal_events_sender: UnboundedSender<DispersalExecutorEvent>,
@@ -51,7 +56,10 @@ where
         key: Keypair,
         membership: Membership,
         addresses: AddressBook,
+        sampling_config: SamplingConfig,
+        replication_config: ReplicationConfig,
     ) -> (Self, ExecutorEventsStream) {
+        let (membership_updates_sender, membership_updates_receiver) = unbounded_channel();
         let (sampling_events_sender, sampling_events_receiver) = unbounded_channel();
         let (validation_events_sender, validation_events_receiver) = unbounded_channel();
         let (dispersal_events_sender, dispersal_events_receiver) = unbounded_channel();
@@ -60,7 +68,16 @@ where
         let dispersal_events_receiver = UnboundedReceiverStream::new(dispersal_events_receiver);
         (
             Self {
-                swarm: Self::build_swarm(key, membership, addresses),
+                swarm: Self::build_swarm(
+                    key,
+                    membership,
+                    addresses.clone(),
+                    sampling_config,
+                    replication_config,
+                ),
+                addresses,
+                membership_updates_sender,
+                membership_updates_receiver,
                 sampling_events_sender,
                 validation_events_sender,
                 dispersal_events_sender,
@@ -78,11 +95,21 @@ where
         key: Keypair,
         membership: Membership,
         addresses: AddressBook,
+        sampling_config: SamplingConfig,
+        replication_config: ReplicationConfig,
     ) -> Swarm<ExecutorBehaviour<Membership>> {
         SwarmBuilder::with_existing_identity(key)
             .with_tokio()
             .with_quic()
-            .with_behaviour(|key| ExecutorBehaviour::new(key, membership, addresses))
+            .with_behaviour(|key| {
+                ExecutorBehaviour::new(
+                    key,
+                    membership,
+                    addresses,
+                    sampling_config,
+                    replication_config,
+                )
+            })
             .expect("Validator behaviour should build")
             .with_swarm_config(|cfg| {
                 cfg.with_idle_connection_timeout(Duration::from_secs(u64::MAX))
@@ -109,7 +136,9 @@ where
             .sample_request_channel()
     }
 
-    pub fn dispersal_blobs_channel(&mut self) -> UnboundedSender<(Membership::NetworkId, DaBlob)> {
+    pub fn dispersal_blobs_channel(
+        &mut self,
+    ) -> UnboundedSender<(Membership::NetworkId, Epoch, DaBlob)> {
         self.swarm
             .behaviour()
             .dispersal_executor_behaviour()
@@ -123,6 +152,11 @@ where
             .open_stream_sender()
     }
 
+    /// Hook to swap in the membership of a new epoch while the swarm is running
+    pub fn membership_update_channel(&self) -> UnboundedSender<(Epoch, Membership)> {
+        self.membership_updates_sender.clone()
+    }
+
     pub fn local_peer_id(&self) -> &PeerId {
         self.swarm.local_peer_id()
     }
@@ -175,32 +209,48 @@ where
         }
     }
 
+    /// Swap in the membership of a new epoch, dialing the new subnetwork neighbours
+    fn update_membership(&mut self, epoch: Epoch, membership: Membership) {
+        dial_subnetwork_peers(&mut self.swarm, &membership, &self.addresses);
+        self.swarm
+            .behaviour_mut()
+            .update_membership(epoch, membership);
+    }
+
     pub async fn run(mut self) {
         loop {
-            if let Some(event) = self.swarm.next().await {
-                tracing::info!("Da swarm event received: {event:?}");
-                match event {
-                    SwarmEvent::Behaviour(behaviour_event) => {
-                        self.handle_behaviour_event(behaviour_event).await;
-                    }
-                    SwarmEvent::ConnectionEstablished { .. } => {}
-                    SwarmEvent::ConnectionClosed { .. } => {}
-                    SwarmEvent::IncomingConnection { .. } => {}
-                    SwarmEvent::IncomingConnectionError { .. } => {}
-                    SwarmEvent::OutgoingConnectionError { .. } => {}
-                    SwarmEvent::NewListenAddr { .. } => {}
-                    SwarmEvent::ExpiredListenAddr { .. } => {}
-                    SwarmEvent::ListenerClosed { .. } => {}
-                    SwarmEvent::ListenerError { .. } => {}
-                    SwarmEvent::Dialing { .. } => {}
-                    SwarmEvent::NewExternalAddrCandidate { .. } => {}
-                    SwarmEvent::ExternalAddrConfirmed { .. } => {}
-                    SwarmEvent::ExternalAddrExpired { .. } => {}
-                    SwarmEvent::NewExternalAddrOfPeer { .. } => {}
-                    event => {
-                        debug!("Unsupported validator swarm event: {event:?}");
+            tokio::select! {
+                Some(event) = self.swarm.next() => {
+                    tracing::info!("Da swarm event received: {event:?}");
+                    match event {
+                        SwarmEvent::Behaviour(behaviour_event) => {
+                            self.handle_behaviour_event(behaviour_event).await;
+                        }
+                        SwarmEvent::ConnectionEstablished { .. }
+                        | SwarmEvent::ConnectionClosed { .. } => {
+                            record_connected_peers(self.swarm.network_info().num_peers());
+                        }
+                        SwarmEvent::IncomingConnection { .. } => {}
+                        SwarmEvent::IncomingConnectionError { .. } => {}
+                        SwarmEvent::OutgoingConnectionError { .. } => {}
+                        SwarmEvent::NewListenAddr { .. } => {}
+                        SwarmEvent::ExpiredListenAddr { .. } => {}
+                        SwarmEvent::ListenerClosed { .. } => {}
+                        SwarmEvent::ListenerError { .. } => {}
+                        SwarmEvent::Dialing { .. } => {}
+                        SwarmEvent::NewExternalAddrCandidate { .. } => {}
+                        SwarmEvent::ExternalAddrConfirmed { .. } => {}
+                        SwarmEvent::ExternalAddrExpired { .. } => {}
+                        SwarmEvent::NewExternalAddrOfPeer { .. } => {}
+                        event => {
+                            debug!("Unsupported validator swarm event: {event:?}");
+                        }
                     }
                 }
+                Some((epoch, membership)) = self.membership_updates_receiver.recv() => {
+                    debug!("Da membership updated for epoch {epoch}");
+                    self.update_membership(epoch, membership);
+                }
             }
         }
     }
@@ -10,19 +10,22 @@ use libp2p::swarm::{DialError, SwarmEvent};
 use libp2p::{Multiaddr, PeerId, Swarm, SwarmBuilder, TransportError};
 use log::debug;
 use nomos_core::da::BlobId;
-use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
 use tokio_stream::wrappers::UnboundedReceiverStream;
 // internal
 use crate::address_book::AddressBook;
 use crate::behaviour::validator::{ValidatorBehaviour, ValidatorBehaviourEvent};
 use crate::protocols::{
-    dispersal::validator::behaviour::DispersalEvent, replication::behaviour::ReplicationEvent,
-    sampling::behaviour::SamplingEvent,
+    dispersal::validator::behaviour::DispersalEvent,
+    replication::behaviour::{ReplicationConfig, ReplicationEvent},
+    sampling::behaviour::{SamplingConfig, SamplingEvent},
 };
 use crate::swarm::common::{
-    handle_replication_event, handle_sampling_event, handle_validator_dispersal_event,
+    dial_subnetwork_peers, handle_replication_event, handle_sampling_event,
+    handle_validator_dispersal_event, record_connected_peers,
 };
 use crate::SubnetworkId;
+use subnetworks_assignations::epochs::Epoch;
 use subnetworks_assignations::MembershipHandler;
 
 pub struct ValidatorEventsStream {
@@ -34,6 +37,9 @@ pub struct ValidatorSwarm<
     Membership: MembershipHandler<NetworkId = SubnetworkId, Id = PeerId> + 'static,
 > {
     swarm: Swarm<ValidatorBehaviour<Membership>>,
+    addresses: AddressBook,
+    membership_updates_sender: UnboundedSender<(Epoch, Membership)>,
+    membership_updates_receiver: UnboundedReceiver<(Epoch, Membership)>,
     sampling_events_sender: UnboundedSender<SamplingEvent>,
     validation_events_sender: UnboundedSender<DaBlob>,
 }
@@ -46,7 +52,10 @@ where
         key: Keypair,
         membership: Membership,
         addresses: AddressBook,
+        sampling_config: SamplingConfig,
+        replication_config: ReplicationConfig,
     ) -> (Self, ValidatorEventsStream) {
+        let (membership_updates_sender, membership_updates_receiver) = unbounded_channel();
         let (sampling_events_sender, sampling_events_receiver) = unbounded_channel();
         let (validation_events_sender, validation_events_receiver) = unbounded_channel();
 
@@ -54,7 +63,16 @@ where
         let validation_events_receiver = UnboundedReceiverStream::new(validation_events_receiver);
         (
             Self {
-                swarm: Self::build_swarm(key, membership, addresses),
+                swarm: Self::build_swarm(
+                    key,
+                    membership,
+                    addresses.clone(),
+                    sampling_config,
+                    replication_config,
+                ),
+                addresses,
+                membership_updates_sender,
+                membership_updates_receiver,
                 sampling_events_sender,
                 validation_events_sender,
             },
@@ -68,11 +86,21 @@ where
         key: Keypair,
         membership: Membership,
         addresses: AddressBook,
+        sampling_config: SamplingConfig,
+        replication_config: ReplicationConfig,
     ) -> Swarm<ValidatorBehaviour<Membership>> {
         SwarmBuilder::with_existing_identity(key)
             .with_tokio()
             .with_quic()
-            .with_behaviour(|key| ValidatorBehaviour::new(key, membership, addresses))
+            .with_behaviour(|key| {
+                ValidatorBehaviour::new(
+                    key,
+                    membership,
+                    addresses,
+                    sampling_config,
+                    replication_config,
+                )
+            })
             .expect("Validator behaviour should build")
             .with_swarm_config(|cfg| {
                 cfg.with_idle_connection_timeout(Duration::from_secs(u64::MAX))
@@ -99,6 +127,11 @@ where
             .sample_request_channel()
     }
 
+    /// Hook to swap in the membership of a new epoch while the swarm is running
+    pub fn membership_update_channel(&self) -> UnboundedSender<(Epoch, Membership)> {
+        self.membership_updates_sender.clone()
+    }
+
     pub fn local_peer_id(&self) -> &PeerId {
         self.swarm.local_peer_id()
     }
@@ -142,32 +175,48 @@ where
         }
     }
 
+    /// Swap in the membership of a new epoch, dialing the new subnetwork neighbours
+    fn update_membership(&mut self, epoch: Epoch, membership: Membership) {
+        dial_subnetwork_peers(&mut self.swarm, &membership, &self.addresses);
+        self.swarm
+            .behaviour_mut()
+            .update_membership(epoch, membership);
+    }
+
     pub async fn run(mut self) {
         loop {
-            if let Some(event) = self.swarm.next().await {
-                debug!("Da swarm event received: {event:?}");
-                match event {
-                    SwarmEvent::Behaviour(behaviour_event) => {
-                        self.handle_behaviour_event(behaviour_event).await;
-                    }
-                    SwarmEvent::ConnectionEstablished { .. } => {}
-                    SwarmEvent::ConnectionClosed { .. } => {}
-                    SwarmEvent::IncomingConnection { .. } => {}
-                    SwarmEvent::IncomingConnectionError { .. } => {}
-                    SwarmEvent::OutgoingConnectionError { .. } => {}
-                    SwarmEvent::NewListenAddr { .. } => {}
-                    SwarmEvent::ExpiredListenAddr { .. } => {}
-                    SwarmEvent::ListenerClosed { .. } => {}
-                    SwarmEvent::ListenerError { .. } => {}
-                    SwarmEvent::Dialing { .. } => {}
-                    SwarmEvent::NewExternalAddrCandidate { .. } => {}
-                    SwarmEvent::ExternalAddrConfirmed { .. } => {}
-                    SwarmEvent::ExternalAddrExpired { .. } => {}
-                    SwarmEvent::NewExternalAddrOfPeer { .. } => {}
-                    event => {
-                        debug!("Unsupported validator swarm event: {event:?}");
+            tokio::select! {
+                Some(event) = self.swarm.next() => {
+                    debug!("Da swarm event received: {event:?}");
+                    match event {
+                        SwarmEvent::Behaviour(behaviour_event) => {
+                            self.handle_behaviour_event(behaviour_event).await;
+                        }
+                        SwarmEvent::ConnectionEstablished { .. }
+                        | SwarmEvent::ConnectionClosed { .. } => {
+                            record_connected_peers(self.swarm.network_info().num_peers());
+                        }
+                        SwarmEvent::IncomingConnection { .. } => {}
+                        SwarmEvent::IncomingConnectionError { .. } => {}
+                        SwarmEvent::OutgoingConnectionError { .. } => {}
+                        SwarmEvent::NewListenAddr { .. } => {}
+                        SwarmEvent::ExpiredListenAddr { .. } => {}
+                        SwarmEvent::ListenerClosed { .. } => {}
+                        SwarmEvent::ListenerError { .. } => {}
+                        SwarmEvent::Dialing { .. } => {}
+                        SwarmEvent::NewExternalAddrCandidate { .. } => {}
+                        SwarmEvent::ExternalAddrConfirmed { .. } => {}
+                        SwarmEvent::ExternalAddrExpired { .. } => {}
+                        SwarmEvent::NewExternalAddrOfPeer { .. } => {}
+                        event => {
+                            debug!("Unsupported validator swarm event: {event:?}");
+                        }
                     }
                 }
+                Some((epoch, membership)) = self.membership_updates_receiver.recv() => {
+                    debug!("Da membership updated for epoch {epoch}");
+                    self.update_membership(epoch, membership);
+                }
             }
         }
     }
@@ -1,5 +1,6 @@
 use libp2p::PeerId;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::net::UdpSocket;
 use subnetworks_assignations::MembershipHandler;
 
 #[derive(Clone)]
@@ -27,3 +28,45 @@ impl MembershipHandler for AllNeighbours {
         self.neighbours.clone()
     }
 }
+
+/// Membership with explicit members for each subnetwork
+#[derive(Clone, Default)]
+pub struct MockMembership {
+    pub subnetworks: HashMap<u32, HashSet<PeerId>>,
+}
+
+impl MembershipHandler for MockMembership {
+    type NetworkId = u32;
+    type Id = PeerId;
+
+    fn membership(&self, id: &Self::Id) -> HashSet<Self::NetworkId> {
+        self.subnetworks
+            .iter()
+            .filter(|(_, members)| members.contains(id))
+            .map(|(subnetwork_id, _)| *subnetwork_id)
+            .collect()
+    }
+
+    fn is_allowed(&self, id: &Self::Id) -> bool {
+        self.members().contains(id)
+    }
+
+    fn members_of(&self, network_id: &Self::NetworkId) -> HashSet<Self::Id> {
+        self.subnetworks
+            .get(network_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn members(&self) -> HashSet<Self::Id> {
+        self.subnetworks.values().flatten().copied().collect()
+    }
+}
+
+/// UDP port picked by the OS, so that tests running in parallel don't share addresses
+pub fn get_available_port() -> u16 {
+    UdpSocket::bind(("127.0.0.1", 0))
+        .and_then(|socket| socket.local_addr())
+        .expect("Failed to bind a UDP socket")
+        .port()
+}
@@ -0,0 +1,128 @@
+use crate::MembershipHandler;
+use std::collections::{BTreeMap, HashSet};
+
+/// Epoch number as tracked by consensus
+pub type Epoch = u32;
+
+/// Default number of epochs kept around so requests started in a previous epoch can finish
+pub const DEFAULT_RETAINED_EPOCHS: usize = 2;
+
+/// Memberships for the latest epochs.
+/// Newer epochs are swapped in as current, older ones are kept (up to `retained_epochs`)
+/// so that in flight requests keep using the membership of the epoch they started in.
+#[derive(Debug, Clone)]
+pub struct EpochMemberships<Membership> {
+    current: Epoch,
+    memberships: BTreeMap<Epoch, Membership>,
+    retained_epochs: usize,
+}
+
+impl<Membership> EpochMemberships<Membership> {
+    pub fn new(epoch: Epoch, membership: Membership) -> Self {
+        Self::with_retained_epochs(epoch, membership, DEFAULT_RETAINED_EPOCHS)
+    }
+
+    pub fn with_retained_epochs(
+        epoch: Epoch,
+        membership: Membership,
+        retained_epochs: usize,
+    ) -> Self {
+        Self {
+            current: epoch,
+            memberships: BTreeMap::from([(epoch, membership)]),
+            retained_epochs: retained_epochs.max(1),
+        }
+    }
+
+    pub fn current_epoch(&self) -> Epoch {
+        self.current
+    }
+
+    pub fn current(&self) -> &Membership {
+        self.memberships
+            .get(&self.current)
+            .expect("Current epoch membership is always retained")
+    }
+
+    /// Membership for `epoch` if it is still retained
+    pub fn get(&self, epoch: Epoch) -> Option<&Membership> {
+        self.memberships.get(&epoch)
+    }
+
+    /// Membership for `epoch`, falling back to the current one if it was already pruned
+    pub fn get_or_current(&self, epoch: Epoch) -> &Membership {
+        self.get(epoch).unwrap_or_else(|| self.current())
+    }
+
+    /// Swap in the membership for a new epoch, pruning the oldest ones
+    pub fn update(&mut self, epoch: Epoch, membership: Membership) {
+        self.memberships.insert(epoch, membership);
+        self.current = epoch;
+        while self.memberships.len() > self.retained_epochs {
+            let oldest = *self
+                .memberships
+                .keys()
+                .find(|&&epoch| epoch != self.current)
+                .expect("More than one epoch is retained");
+            self.memberships.remove(&oldest);
+        }
+    }
+
+    pub fn epochs(&self) -> impl Iterator<Item = Epoch> + '_ {
+        self.memberships.keys().copied()
+    }
+}
+
+impl<Membership: MembershipHandler> EpochMemberships<Membership> {
+    /// True if the id is allowed in any of the retained epochs
+    pub fn is_allowed(&self, id: &Membership::Id) -> bool {
+        self.memberships
+            .values()
+            .any(|membership| membership.is_allowed(id))
+    }
+
+    /// Members of a subnetwork for the given epoch
+    pub fn members_of(
+        &self,
+        epoch: Epoch,
+        network_id: &Membership::NetworkId,
+    ) -> HashSet<Membership::Id> {
+        self.get_or_current(epoch).members_of(network_id)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::epochs::EpochMemberships;
+    use crate::versions::v1::FillFromNodeList;
+    use crate::{MembershipCreator, MembershipHandler};
+    use libp2p_identity::PeerId;
+
+    #[test]
+    fn test_epoch_memberships_are_pruned() {
+        let nodes: Vec<_> = std::iter::repeat_with(PeerId::random).take(10).collect();
+        let membership = FillFromNodeList::new(&nodes, 16, 2);
+        let mut memberships = EpochMemberships::new(0, membership.clone());
+        let first = membership.update(nodes.iter().copied().collect(), [1; 32]);
+        let second = membership.update(nodes.iter().copied().collect(), [2; 32]);
+
+        memberships.update(1, first.clone());
+        assert_eq!(memberships.current_epoch(), 1);
+        assert_eq!(
+            memberships.get(0).unwrap().assignations,
+            membership.assignations
+        );
+
+        memberships.update(2, second.clone());
+        assert!(memberships.get(0).is_none());
+        assert_eq!(memberships.get(1).unwrap().assignations, first.assignations);
+        assert_eq!(memberships.current().assignations, second.assignations);
+        // pruned epochs resolve to the current membership
+        assert_eq!(
+            memberships.get_or_current(0).assignations,
+            second.assignations
+        );
+        assert!(nodes.iter().all(|node| memberships.is_allowed(node)));
+        assert_eq!(memberships.members_of(1, &0), first.members_of(&0));
+    }
+}
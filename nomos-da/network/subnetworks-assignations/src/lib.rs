@@ -1,3 +1,4 @@
+pub mod epochs;
 pub mod versions;
 
 use std::collections::HashSet;
@@ -26,3 +27,12 @@ pub trait MembershipHandler {
     /// Returns the set of all members
     fn members(&self) -> HashSet<Self::Id>;
 }
+
+/// Membership that can be assigned again from a new set of members.
+/// Used to rotate subnetworks at every epoch.
+pub trait MembershipCreator: MembershipHandler + Sized {
+    /// Assign `members` to subnetworks with the same parameters as `self`.
+    /// The `seed` (ie: the epoch nonce) randomizes the assignation, every node using the same
+    /// seed computes the same membership.
+    fn update(&self, members: HashSet<Self::Id>, seed: [u8; 32]) -> Self;
+}
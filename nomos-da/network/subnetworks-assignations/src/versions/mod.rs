@@ -1,2 +1,23 @@
 pub mod v1;
 pub mod v2;
+
+use libp2p_identity::PeerId;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use std::collections::HashSet;
+
+/// Sorted list of peers, so assignations are deterministic
+pub(crate) fn sorted_peers(peers: &[PeerId]) -> Vec<PeerId> {
+    let mut peers = peers.to_vec();
+    peers.sort_unstable();
+    peers
+}
+
+/// Peers shuffled with the provided seed.
+/// Every node using the same seed (ie: the epoch nonce) gets the same order.
+pub(crate) fn shuffled_peers(peers: HashSet<PeerId>, seed: [u8; 32]) -> Vec<PeerId> {
+    let mut peers = sorted_peers(&peers.into_iter().collect::<Vec<_>>());
+    peers.shuffle(&mut ChaCha20Rng::from_seed(seed));
+    peers
+}
@@ -1,4 +1,5 @@
-use crate::MembershipHandler;
+use crate::versions::{shuffled_peers, sorted_peers};
+use crate::{MembershipCreator, MembershipHandler};
 use libp2p_identity::PeerId;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
@@ -14,21 +15,19 @@ pub struct FillFromNodeList {
 impl FillFromNodeList {
     pub fn new(peers: &[PeerId], subnetwork_size: usize, dispersal_factor: usize) -> Self {
         Self {
-            assignations: Self::fill(peers, subnetwork_size, dispersal_factor),
+            // sort list to make it deterministic
+            assignations: Self::fill(sorted_peers(peers), subnetwork_size, dispersal_factor),
             subnetwork_size,
             dispersal_factor,
         }
     }
 
     fn fill(
-        peers: &[PeerId],
+        peers: Vec<PeerId>,
         subnetwork_size: usize,
         replication_factor: usize,
     ) -> Vec<HashSet<PeerId>> {
         assert!(!peers.is_empty());
-        // sort list to make it deterministic
-        let mut peers = peers.to_vec();
-        peers.sort_unstable();
         // take n peers and fill a subnetwork until all subnetworks are filled
         let mut cycle = peers.into_iter().cycle();
         (0..subnetwork_size)
@@ -41,6 +40,20 @@ impl FillFromNodeList {
     }
 }
 
+impl MembershipCreator for FillFromNodeList {
+    fn update(&self, peers: HashSet<Self::Id>, seed: [u8; 32]) -> Self {
+        Self {
+            assignations: Self::fill(
+                shuffled_peers(peers, seed),
+                self.subnetwork_size,
+                self.dispersal_factor,
+            ),
+            subnetwork_size: self.subnetwork_size,
+            dispersal_factor: self.dispersal_factor,
+        }
+    }
+}
+
 impl MembershipHandler for FillFromNodeList {
     type NetworkId = u32;
     type Id = PeerId;
@@ -78,6 +91,7 @@ impl MembershipHandler for FillFromNodeList {
 #[cfg(test)]
 mod test {
     use crate::versions::v1::FillFromNodeList;
+    use crate::{MembershipCreator, MembershipHandler};
     use libp2p_identity::PeerId;
 
     #[test]
@@ -91,4 +105,19 @@ mod test {
             assert_eq!(subnetwork.len(), dispersal_factor);
         }
     }
+
+    #[test]
+    fn test_update_fill_from_node_list() {
+        let nodes: Vec<_> = std::iter::repeat_with(PeerId::random).take(100).collect();
+        let distribution = FillFromNodeList::new(&nodes, 1024, 2);
+        let seed = [1; 32];
+        let updated = distribution.update(nodes.iter().copied().collect(), seed);
+        assert_eq!(updated.assignations.len(), 1024);
+        assert_eq!(updated.members(), distribution.members());
+        // same seed gives the same assignations on every node
+        let again = distribution.update(nodes.iter().copied().collect(), seed);
+        assert_eq!(updated.assignations, again.assignations);
+        let other = distribution.update(nodes.iter().copied().collect(), [2; 32]);
+        assert_ne!(updated.assignations, other.assignations);
+    }
 }
@@ -1,4 +1,5 @@
-use crate::MembershipHandler;
+use crate::versions::{shuffled_peers, sorted_peers};
+use crate::{MembershipCreator, MembershipHandler};
 use libp2p_identity::PeerId;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
@@ -21,8 +22,9 @@ impl FillWithOriginalReplication {
         pivot: u16,
     ) -> Self {
         Self {
+            // sort list to make it deterministic
             assignations: Self::fill(
-                peers,
+                sorted_peers(peers),
                 subnetwork_size,
                 dispersal_factor,
                 original_replication,
@@ -35,16 +37,13 @@ impl FillWithOriginalReplication {
         }
     }
     fn fill(
-        peers: &[PeerId],
+        peers: Vec<PeerId>,
         subnetwork_size: usize,
         dispersal_factor: usize,
         original_replication: usize,
         pivot: u16,
     ) -> Vec<HashSet<PeerId>> {
         assert!(!peers.is_empty());
-        // sort list to make it deterministic
-        let mut peers = peers.to_vec();
-        peers.sort_unstable();
         // take n peers and fill a subnetwork until all subnetworks are filled
         let mut cycle = peers.into_iter().cycle();
         (0..subnetwork_size)
@@ -64,6 +63,24 @@ impl FillWithOriginalReplication {
     }
 }
 
+impl MembershipCreator for FillWithOriginalReplication {
+    fn update(&self, peers: HashSet<Self::Id>, seed: [u8; 32]) -> Self {
+        Self {
+            assignations: Self::fill(
+                shuffled_peers(peers, seed),
+                self.subnetwork_size,
+                self.dispersal_factor,
+                self.original_replication,
+                self.pivot,
+            ),
+            subnetwork_size: self.subnetwork_size,
+            dispersal_factor: self.dispersal_factor,
+            original_replication: self.original_replication,
+            pivot: self.pivot,
+        }
+    }
+}
+
 impl MembershipHandler for FillWithOriginalReplication {
     type NetworkId = u32;
     type Id = PeerId;
@@ -1,5 +1,6 @@
 // std
 use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
 // crates
 use bytes::Bytes;
 use tokio::{
@@ -67,6 +68,69 @@ pub async fn load_blob(
     Ok(Bytes::from(data))
 }
 
+/// Remove all the column files stored for `blob_id`, returning the indexes of the removed columns
+pub async fn remove_blobs(
+    base_dir: PathBuf,
+    blob_id: &[u8],
+) -> Result<Vec<Vec<u8>>, std::io::Error> {
+    let blob_id = hex::encode(blob_id);
+
+    let mut path = base_dir;
+    path.push(blob_id);
+
+    let mut column_files = match fs::read_dir(&path).await {
+        Ok(entries) => entries,
+        // nothing was stored for this blob, or it was already removed
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+
+    let mut columns = Vec::new();
+    while let Some(entry) = column_files.next_entry().await? {
+        match hex::decode(entry.file_name().as_encoded_bytes()) {
+            Ok(column_idx) => columns.push(column_idx),
+            Err(e) => tracing::error!("Unexpected column file name: {}", e),
+        }
+    }
+
+    fs::remove_dir_all(path).await?;
+    Ok(columns)
+}
+
+/// Ids of the blobs none of whose column files were written in the last `age`
+pub async fn blobs_older_than(
+    base_dir: PathBuf,
+    age: Duration,
+) -> Result<Vec<[u8; 32]>, std::io::Error> {
+    let mut blob_dirs = match fs::read_dir(&base_dir).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+
+    let now = SystemTime::now();
+    let mut blob_ids = Vec::new();
+    while let Some(entry) = blob_dirs.next_entry().await? {
+        let metadata = entry.metadata().await?;
+        if !metadata.is_dir() {
+            continue;
+        }
+        // adding a column file to the directory of a blob updates its modification time
+        let written = now.duration_since(metadata.modified()?).unwrap_or_default();
+        if written <= age {
+            continue;
+        }
+        // other directories can share the base one, ie: the node database
+        let Ok(blob_id) = hex::decode(entry.file_name().as_encoded_bytes()) else {
+            continue;
+        };
+        if let Ok(blob_id) = blob_id.try_into() {
+            blob_ids.push(blob_id);
+        }
+    }
+    Ok(blob_ids)
+}
+
 pub async fn write_blob(
     base_dir: PathBuf,
     blob_id: &[u8],
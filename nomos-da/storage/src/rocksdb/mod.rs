@@ -2,6 +2,21 @@ use bytes::{Bytes, BytesMut};
 
 pub const DA_VID_KEY_PREFIX: &str = "da/vid/";
 pub const DA_VERIFIED_KEY_PREFIX: &str = "da/verified/";
+/// App index entries from canonical blocks that are not final yet
+pub const DA_PENDING_VID_KEY_PREFIX: &str = "da/pending-vid/";
+/// Height of the last block whose app index entries were finalized
+pub const DA_FINALIZED_HEIGHT_KEY: &str = "da/indexer/finalized-height";
+/// Blobs included in a block, whose verified shares are removed with the block by the retention
+/// policy rather than as unincluded ones
+pub const DA_INCLUDED_KEY_PREFIX: &str = "da/included/";
+/// Highest app index whose blobs were removed by the retention policy, for each app
+pub const DA_PRUNED_INDEX_KEY_PREFIX: &str = "da/pruned-index/";
+/// Height of the last block whose blobs were pruned
+pub const DA_PRUNED_HEIGHT_KEY: &str = "da/indexer/pruned-height";
+/// Headers followed by light nodes
+pub const DA_LIGHT_HEADER_KEY_PREFIX: &str = "da/light/header/";
+/// Availability of the blobs referenced by the blocks followed by light nodes
+pub const DA_LIGHT_AVAILABILITY_KEY_PREFIX: &str = "da/light/availability/";
 
 pub fn key_bytes(prefix: &str, id: impl AsRef<[u8]>) -> Bytes {
     let mut buffer = BytesMut::new();
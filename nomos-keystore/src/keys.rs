@@ -0,0 +1,179 @@
+// std
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+// crates
+use cl::NullifierSecret;
+use nomos_libp2p::{ed25519, PeerId};
+use rand::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+// internal
+use crate::KeystoreError;
+
+/// Kind of the secrets a node is configured with
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum KeyType {
+    /// libp2p ed25519 key, identifying the node in the network, mix and DA swarms
+    Network,
+    /// x25519 key used to unwrap the mix messages addressed to the node
+    Mix,
+    /// BLS key signing the DA blobs attestations
+    DaVerifier,
+    /// Nullifier secret of the notes the node leads with
+    Leader,
+}
+
+impl KeyType {
+    pub const ALL: [KeyType; 4] = [
+        KeyType::Network,
+        KeyType::Mix,
+        KeyType::DaVerifier,
+        KeyType::Leader,
+    ];
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            KeyType::Network => "network",
+            KeyType::Mix => "mix",
+            KeyType::DaVerifier => "da-verifier",
+            KeyType::Leader => "leader",
+        }
+    }
+}
+
+impl Display for KeyType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for KeyType {
+    type Err = KeystoreError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|key_type| key_type.as_str() == s)
+            .ok_or_else(|| KeystoreError::UnknownKeyType(s.to_string()))
+    }
+}
+
+#[derive(Clone)]
+pub enum SecretKey {
+    Network(ed25519::SecretKey),
+    Mix(x25519_dalek::StaticSecret),
+    DaVerifier(blst::min_sig::SecretKey),
+    Leader(NullifierSecret),
+}
+
+impl SecretKey {
+    pub fn generate<R: RngCore + CryptoRng>(key_type: KeyType, mut rng: R) -> Self {
+        match key_type {
+            KeyType::Network => Self::Network(ed25519::SecretKey::generate()),
+            KeyType::Mix => Self::Mix(x25519_dalek::StaticSecret::random_from_rng(rng)),
+            KeyType::DaVerifier => {
+                let mut ikm = [0u8; 32];
+                rng.fill_bytes(&mut ikm);
+                Self::DaVerifier(
+                    blst::min_sig::SecretKey::key_gen(&ikm, &[])
+                        .expect("32 bytes of key material should be enough"),
+                )
+            }
+            KeyType::Leader => Self::Leader(NullifierSecret::random(rng)),
+        }
+    }
+
+    pub fn from_bytes(key_type: KeyType, bytes: &[u8]) -> Result<Self, KeystoreError> {
+        let invalid = |reason: String| KeystoreError::InvalidKey { key_type, reason };
+        match key_type {
+            KeyType::Network => {
+                let mut bytes = bytes.to_vec();
+                ed25519::SecretKey::try_from_bytes(&mut bytes)
+                    .map(Self::Network)
+                    .map_err(|e| invalid(e.to_string()))
+            }
+            KeyType::Mix => <[u8; 32]>::try_from(bytes)
+                .map(|bytes| Self::Mix(x25519_dalek::StaticSecret::from(bytes)))
+                .map_err(|_| invalid(format!("expected 32 bytes, got {}", bytes.len()))),
+            KeyType::DaVerifier => blst::min_sig::SecretKey::from_bytes(bytes)
+                .map(Self::DaVerifier)
+                .map_err(|e| invalid(format!("{e:?}"))),
+            KeyType::Leader => <[u8; 16]>::try_from(bytes)
+                .map(|bytes| Self::Leader(NullifierSecret::from_bytes(bytes)))
+                .map_err(|_| invalid(format!("expected 16 bytes, got {}", bytes.len()))),
+        }
+    }
+
+    pub fn key_type(&self) -> KeyType {
+        match self {
+            Self::Network(_) => KeyType::Network,
+            Self::Mix(_) => KeyType::Mix,
+            Self::DaVerifier(_) => KeyType::DaVerifier,
+            Self::Leader(_) => KeyType::Leader,
+        }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            Self::Network(sk) => sk.as_ref().to_vec(),
+            Self::Mix(sk) => sk.to_bytes().to_vec(),
+            Self::DaVerifier(sk) => sk.to_bytes().to_vec(),
+            Self::Leader(sk) => sk.0.to_vec(),
+        }
+    }
+
+    pub fn identity(&self) -> Identity {
+        let (public_key, peer_id) = match self {
+            Self::Network(sk) => {
+                let public_key = ed25519::Keypair::from(sk.clone()).public();
+                let peer_id = PeerId::from_public_key(&public_key.clone().into());
+                (public_key.to_bytes().to_vec(), Some(peer_id.to_string()))
+            }
+            Self::Mix(sk) => (x25519_dalek::PublicKey::from(sk).to_bytes().to_vec(), None),
+            Self::DaVerifier(sk) => (sk.sk_to_pk().to_bytes().to_vec(), None),
+            Self::Leader(sk) => (sk.commit().as_bytes().to_vec(), None),
+        };
+        Identity {
+            key_type: self.key_type(),
+            public_key: hex::encode(public_key),
+            peer_id,
+        }
+    }
+}
+
+/// Public information derived from a secret key, safe to share with other nodes
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Identity {
+    #[serde(rename = "type")]
+    pub key_type: KeyType,
+    /// Public key, or the nullifier commitment for leader secrets, hex encoded
+    pub public_key: String,
+    /// Set for network keys only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub peer_id: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn secret_keys_roundtrip_bytes() {
+        for key_type in KeyType::ALL {
+            let key = SecretKey::generate(key_type, rand::thread_rng());
+            let restored = SecretKey::from_bytes(key_type, &key.to_bytes()).unwrap();
+            assert_eq!(restored.identity(), key.identity());
+            assert_eq!(key_type.to_string().parse::<KeyType>().unwrap(), key_type);
+        }
+    }
+
+    #[test]
+    fn network_identity_has_peer_id() {
+        let mut bytes = [1u8; 32];
+        let sk = ed25519::SecretKey::try_from_bytes(&mut bytes).unwrap();
+        let expected = PeerId::from_public_key(&ed25519::Keypair::from(sk.clone()).public().into());
+        let identity = SecretKey::Network(sk).identity();
+        assert_eq!(identity.peer_id, Some(expected.to_string()));
+        assert!(SecretKey::from_bytes(KeyType::Leader, &[0; 32]).is_err());
+    }
+}
@@ -0,0 +1,333 @@
+pub mod keys;
+
+// std
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+// crates
+use argon2::Argon2;
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+// internal
+pub use keys::{Identity, KeyType, SecretKey};
+
+const VERSION: u32 = 1;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+
+#[derive(Debug, thiserror::Error)]
+pub enum KeystoreError {
+    #[error("keystore io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("invalid keystore file: {0}")]
+    Format(#[from] serde_json::Error),
+    #[error("invalid hex: {0}")]
+    Hex(#[from] hex::FromHexError),
+    #[error("unsupported keystore version {0}")]
+    UnsupportedVersion(u32),
+    #[error("keystore {0} already exists")]
+    AlreadyExists(PathBuf),
+    #[error("key {0} not found in the keystore")]
+    KeyNotFound(String),
+    #[error("key {0} already exists in the keystore")]
+    KeyExists(String),
+    #[error("the keystore is encrypted, a passphrase is required")]
+    MissingPassphrase,
+    #[error("wrong passphrase or corrupted key")]
+    WrongPassphrase,
+    #[error("invalid key nonce of {0} bytes, expected {NONCE_LEN}")]
+    InvalidNonce(usize),
+    #[error("could not derive the keystore key: {0}")]
+    Kdf(String),
+    #[error("unknown key type {0}")]
+    UnknownKeyType(String),
+    #[error("invalid {key_type} key: {reason}")]
+    InvalidKey { key_type: KeyType, reason: String },
+    #[error("key {name} is a {found} key, expected a {expected} one")]
+    WrongKeyType {
+        name: String,
+        expected: KeyType,
+        found: KeyType,
+    },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct KeystoreFile {
+    version: u32,
+    /// Salt of the passphrase derived key, set if the keys are encrypted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    salt: Option<String>,
+    keys: BTreeMap<String, StoredKey>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct StoredKey {
+    #[serde(flatten)]
+    identity: Identity,
+    /// Secret key, encrypted if the keystore is, hex encoded
+    secret: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    nonce: Option<String>,
+}
+
+/// JSON file holding named secret keys along with their public identities.
+///
+/// Secrets are encrypted with XChaCha20-Poly1305 under a key derived from a passphrase with
+/// Argon2 if one is given when the keystore is created, identities are always readable.
+pub struct Keystore {
+    path: PathBuf,
+    file: KeystoreFile,
+    cipher: Option<XChaCha20Poly1305>,
+}
+
+impl Keystore {
+    pub fn create(
+        path: impl Into<PathBuf>,
+        passphrase: Option<&str>,
+    ) -> Result<Self, KeystoreError> {
+        let path = path.into();
+        if path.exists() {
+            return Err(KeystoreError::AlreadyExists(path));
+        }
+        let (salt, cipher) = match passphrase {
+            Some(passphrase) => {
+                let mut salt = [0u8; SALT_LEN];
+                rand::thread_rng().fill_bytes(&mut salt);
+                (Some(hex::encode(salt)), Some(cipher(passphrase, &salt)?))
+            }
+            None => (None, None),
+        };
+        let keystore = Self {
+            path,
+            file: KeystoreFile {
+                version: VERSION,
+                salt,
+                keys: BTreeMap::new(),
+            },
+            cipher,
+        };
+        keystore.save()?;
+        Ok(keystore)
+    }
+
+    /// Opens an existing keystore, the passphrase is ignored if it is not encrypted
+    pub fn open(path: impl Into<PathBuf>, passphrase: Option<&str>) -> Result<Self, KeystoreError> {
+        let path = path.into();
+        let file: KeystoreFile = serde_json::from_slice(&fs::read(&path)?)?;
+        if file.version != VERSION {
+            return Err(KeystoreError::UnsupportedVersion(file.version));
+        }
+        let cipher = match (&file.salt, passphrase) {
+            (Some(salt), Some(passphrase)) => Some(cipher(passphrase, &hex::decode(salt)?)?),
+            (Some(_), None) => return Err(KeystoreError::MissingPassphrase),
+            (None, _) => None,
+        };
+        Ok(Self { path, file, cipher })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn is_encrypted(&self) -> bool {
+        self.file.salt.is_some()
+    }
+
+    pub fn identities(&self) -> impl Iterator<Item = (&str, &Identity)> {
+        self.file
+            .keys
+            .iter()
+            .map(|(name, key)| (name.as_str(), &key.identity))
+    }
+
+    /// Adds a key and writes the keystore to disk
+    pub fn insert(&mut self, name: &str, key: &SecretKey) -> Result<Identity, KeystoreError> {
+        if self.file.keys.contains_key(name) {
+            return Err(KeystoreError::KeyExists(name.to_string()));
+        }
+        let identity = key.identity();
+        let bytes = key.to_bytes();
+        let (secret, nonce) = match &self.cipher {
+            Some(cipher) => {
+                let mut nonce = [0u8; NONCE_LEN];
+                rand::thread_rng().fill_bytes(&mut nonce);
+                let secret = cipher
+                    .encrypt(XNonce::from_slice(&nonce), bytes.as_slice())
+                    .expect("encryption into a vec should not fail");
+                (secret, Some(hex::encode(nonce)))
+            }
+            None => (bytes, None),
+        };
+        self.file.keys.insert(
+            name.to_string(),
+            StoredKey {
+                identity: identity.clone(),
+                secret: hex::encode(secret),
+                nonce,
+            },
+        );
+        self.save()?;
+        Ok(identity)
+    }
+
+    pub fn get(&self, name: &str) -> Result<SecretKey, KeystoreError> {
+        let key = self
+            .file
+            .keys
+            .get(name)
+            .ok_or_else(|| KeystoreError::KeyNotFound(name.to_string()))?;
+        let secret = hex::decode(&key.secret)?;
+        let bytes = match (&self.cipher, &key.nonce) {
+            (Some(cipher), Some(nonce)) => {
+                let nonce = hex::decode(nonce)?;
+                if nonce.len() != NONCE_LEN {
+                    return Err(KeystoreError::InvalidNonce(nonce.len()));
+                }
+                cipher
+                    .decrypt(XNonce::from_slice(&nonce), secret.as_slice())
+                    .map_err(|_| KeystoreError::WrongPassphrase)?
+            }
+            (None, None) => secret,
+            _ => return Err(KeystoreError::WrongPassphrase),
+        };
+        let secret_key = SecretKey::from_bytes(key.identity.key_type, &bytes)?;
+        // a wrong passphrase can't decrypt, but the identity could have been tampered with
+        if secret_key.identity() != key.identity {
+            return Err(KeystoreError::WrongPassphrase);
+        }
+        Ok(secret_key)
+    }
+
+    /// Same as [`Keystore::get`], checking that the key is of the expected type
+    pub fn get_typed(&self, name: &str, expected: KeyType) -> Result<SecretKey, KeystoreError> {
+        let key = self.get(name)?;
+        if key.key_type() != expected {
+            return Err(KeystoreError::WrongKeyType {
+                name: name.to_string(),
+                expected,
+                found: key.key_type(),
+            });
+        }
+        Ok(key)
+    }
+
+    fn save(&self) -> Result<(), KeystoreError> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // written aside first so that a failure doesn't leave a truncated keystore
+        let tmp = self.path.with_extension("tmp");
+        // a leftover tmp file may have been created with other permissions
+        match fs::remove_file(&tmp) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e.into()),
+            _ => {}
+        }
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        // never readable by others, not even while it is written
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        let mut file = options.open(&tmp)?;
+        file.write_all(&serde_json::to_vec_pretty(&self.file)?)?;
+        file.sync_all()?;
+        fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+}
+
+fn cipher(passphrase: &str, salt: &[u8]) -> Result<XChaCha20Poly1305, KeystoreError> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| KeystoreError::Kdf(e.to_string()))?;
+    Ok(XChaCha20Poly1305::new(&key.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encrypted_keys_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("keys.json");
+        let mut keystore = Keystore::create(&path, Some("hunter2")).unwrap();
+        let mut identities = Vec::new();
+        for key_type in KeyType::ALL {
+            let key = SecretKey::generate(key_type, rand::thread_rng());
+            identities.push(keystore.insert(&key_type.to_string(), &key).unwrap());
+        }
+
+        let keystore = Keystore::open(&path, Some("hunter2")).unwrap();
+        assert!(keystore.is_encrypted());
+        for (key_type, identity) in KeyType::ALL.into_iter().zip(identities) {
+            let key = keystore.get_typed(&key_type.to_string(), key_type).unwrap();
+            assert_eq!(key.identity(), identity);
+        }
+        assert!(matches!(
+            keystore.get_typed("mix", KeyType::Network),
+            Err(KeystoreError::WrongKeyType { .. })
+        ));
+        // secrets are not stored in clear
+        let contents = fs::read_to_string(&path).unwrap();
+        let mix = keystore.get("mix").unwrap();
+        assert!(!contents.contains(&hex::encode(mix.to_bytes())));
+    }
+
+    #[test]
+    fn wrong_passphrase_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("keys.json");
+        let mut keystore = Keystore::create(&path, Some("hunter2")).unwrap();
+        let key = SecretKey::generate(KeyType::Network, rand::thread_rng());
+        keystore.insert("network", &key).unwrap();
+
+        assert!(matches!(
+            Keystore::open(&path, None),
+            Err(KeystoreError::MissingPassphrase)
+        ));
+        let keystore = Keystore::open(&path, Some("hunter3")).unwrap();
+        assert!(matches!(
+            keystore.get("network"),
+            Err(KeystoreError::WrongPassphrase)
+        ));
+    }
+
+    #[test]
+    fn truncated_nonce_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("keys.json");
+        let mut keystore = Keystore::create(&path, Some("hunter2")).unwrap();
+        let key = SecretKey::generate(KeyType::Network, rand::thread_rng());
+        keystore.insert("network", &key).unwrap();
+
+        let mut file: serde_json::Value =
+            serde_json::from_slice(&fs::read(&path).unwrap()).unwrap();
+        file["keys"]["network"]["nonce"] = "00".into();
+        fs::write(&path, serde_json::to_vec(&file).unwrap()).unwrap();
+
+        let keystore = Keystore::open(&path, Some("hunter2")).unwrap();
+        assert!(matches!(
+            keystore.get("network"),
+            Err(KeystoreError::InvalidNonce(1))
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn keystore_is_only_readable_by_owner() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("keys.json");
+        let mut keystore = Keystore::create(&path, None).unwrap();
+        let key = SecretKey::generate(KeyType::Network, rand::thread_rng());
+        keystore.insert("network", &key).unwrap();
+
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
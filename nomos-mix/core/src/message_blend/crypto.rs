@@ -9,14 +9,17 @@ pub struct CryptographicProcessor<R, M>
 where
     M: MixMessage,
 {
-    settings: CryptographicProcessorSettings<M::PrivateKey>,
+    private_key: M::PrivateKey,
+    num_mix_layers: usize,
     membership: Membership<M>,
     rng: R,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CryptographicProcessorSettings<K> {
-    pub private_key: K,
+    /// Can be left out when the node reads it from a keystore
+    #[serde(default)]
+    pub private_key: Option<K>,
     pub num_mix_layers: usize,
 }
 
@@ -32,7 +35,10 @@ where
         rng: R,
     ) -> Self {
         Self {
-            settings,
+            private_key: settings
+                .private_key
+                .expect("Mix private key should be set in the settings or read from the keystore"),
+            num_mix_layers: settings.num_mix_layers,
             membership,
             rng,
         }
@@ -42,7 +48,7 @@ where
         // TODO: Use the actual Sphinx encoding instead of mock.
         let public_keys = self
             .membership
-            .choose_remote_nodes(&mut self.rng, self.settings.num_mix_layers)
+            .choose_remote_nodes(&mut self.rng, self.num_mix_layers)
             .iter()
             .map(|node| node.public_key.clone())
             .collect::<Vec<_>>();
@@ -54,6 +60,6 @@ where
         &self,
         message: &[u8],
     ) -> Result<(Vec<u8>, bool), nomos_mix_message::Error> {
-        M::unwrap_message(message, &self.settings.private_key)
+        M::unwrap_message(message, &self.private_key)
     }
 }
@@ -7,7 +7,7 @@ use nomos_mempool::{
     tx::service::openapi::Status, MempoolMetrics, MempoolMsg, TxMempoolService,
 };
 use serde::{Deserialize, Serialize};
-use tokio::sync::oneshot;
+use tokio::sync::{broadcast, oneshot};
 
 type ClMempoolService<T> = TxMempoolService<
     MempoolNetworkAdapter<T, <T as Transaction>::Hash>,
@@ -69,3 +69,30 @@ where
 
     Ok(receiver.await?)
 }
+
+pub async fn cl_mempool_subscribe<T>(
+    handle: &overwatch_rs::overwatch::handle::OverwatchHandle,
+) -> Result<broadcast::Receiver<T>, super::DynError>
+where
+    T: Transaction
+        + Clone
+        + Debug
+        + Hash
+        + Serialize
+        + for<'de> Deserialize<'de>
+        + Send
+        + Sync
+        + 'static,
+    <T as nomos_core::tx::Transaction>::Hash: std::cmp::Ord + Debug + Send + Sync + 'static,
+{
+    let relay = handle.relay::<ClMempoolService<T>>().connect().await?;
+    let (sender, receiver) = oneshot::channel();
+    relay
+        .send(MempoolMsg::Subscribe {
+            reply_channel: sender,
+        })
+        .await
+        .map_err(|(e, _)| e)?;
+
+    Ok(receiver.await?)
+}
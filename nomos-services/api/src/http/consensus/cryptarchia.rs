@@ -3,13 +3,13 @@ use std::{fmt::Debug, hash::Hash};
 use overwatch_rs::overwatch::handle::OverwatchHandle;
 use rand::{RngCore, SeedableRng};
 use serde::{de::DeserializeOwned, Serialize};
-use tokio::sync::oneshot;
+use tokio::sync::{broadcast, oneshot};
 
 use crate::http::DynError;
 use cryptarchia_consensus::{
     mix::adapters::libp2p::LibP2pAdapter as MixAdapter,
     network::adapters::libp2p::LibP2pAdapter as ConsensusNetworkAdapter, ConsensusMsg,
-    CryptarchiaConsensus, CryptarchiaInfo,
+    CryptarchiaConsensus, CryptarchiaInfo, TipChange,
 };
 use kzgrs_backend::dispersal::BlobInfo;
 use nomos_core::{
@@ -162,3 +162,56 @@ where
 
     Ok(receiver.await?)
 }
+
+pub async fn cryptarchia_tip_subscribe<
+    Tx,
+    SS,
+    SamplingBackend,
+    SamplingNetworkAdapter,
+    SamplingRng,
+    SamplingStorage,
+    const SIZE: usize,
+>(
+    handle: &OverwatchHandle,
+) -> Result<broadcast::Receiver<TipChange>, DynError>
+where
+    Tx: Transaction
+        + Clone
+        + Debug
+        + Eq
+        + Hash
+        + Serialize
+        + DeserializeOwned
+        + Send
+        + Sync
+        + 'static,
+    <Tx as Transaction>::Hash: std::cmp::Ord + Debug + Send + Sync + 'static,
+    SS: StorageSerde + Send + Sync + 'static,
+    SamplingRng: SeedableRng + RngCore,
+    SamplingBackend: DaSamplingServiceBackend<SamplingRng, BlobId = BlobId> + Send,
+    SamplingBackend::Settings: Clone,
+    SamplingBackend::Blob: Debug + 'static,
+    SamplingBackend::BlobId: Debug + 'static,
+    SamplingNetworkAdapter: nomos_da_sampling::network::NetworkAdapter,
+    SamplingStorage: nomos_da_sampling::storage::DaStorageAdapter,
+{
+    let relay = handle
+        .relay::<Cryptarchia<
+            Tx,
+            SS,
+            SamplingBackend,
+            SamplingNetworkAdapter,
+            SamplingRng,
+            SamplingStorage,
+            SIZE,
+        >>()
+        .connect()
+        .await?;
+    let (sender, receiver) = oneshot::channel();
+    relay
+        .send(ConsensusMsg::TipSubscribe { sender })
+        .await
+        .map_err(|(e, _)| e)?;
+
+    Ok(receiver.await?)
+}
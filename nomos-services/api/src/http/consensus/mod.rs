@@ -1,2 +1,2 @@
 mod cryptarchia;
-pub use cryptarchia::{cryptarchia_headers, cryptarchia_info};
+pub use cryptarchia::{cryptarchia_headers, cryptarchia_info, cryptarchia_tip_subscribe};
@@ -10,12 +10,15 @@ use nomos_da_dispersal::adapters::network::DispersalNetworkAdapter;
 use nomos_da_dispersal::backend::DispersalBackend;
 use nomos_da_dispersal::{DaDispersalMsg, DispersalService};
 use nomos_da_indexer::storage::adapters::rocksdb::RocksAdapter as IndexerStorageAdapter;
-use nomos_da_indexer::DaMsg;
 use nomos_da_indexer::{
     consensus::adapters::cryptarchia::CryptarchiaConsensusAdapter, DataIndexerService,
 };
+use nomos_da_indexer::{DaMsg, IndexView, PrunedRange};
+use nomos_da_light::{BlockAvailability, DaLightMsg, DaLightService};
 use nomos_da_network_core::SubnetworkId;
+use nomos_da_network_service::backends::NetworkBackend as DaNetworkBackend;
 use nomos_da_sampling::backend::DaSamplingServiceBackend;
+use nomos_da_sampling::{DaSamplingService, DaSamplingServiceMsg, SamplingOutcome};
 use nomos_da_verifier::backend::VerifierBackend;
 use nomos_da_verifier::network::adapters::validator::Libp2pAdapter;
 use nomos_da_verifier::storage::adapters::rocksdb::RocksAdapter as VerifierStorageAdapter;
@@ -34,8 +37,8 @@ use serde::Serialize;
 use std::error::Error;
 use std::fmt::Debug;
 use std::hash::Hash;
-use subnetworks_assignations::MembershipHandler;
-use tokio::sync::oneshot;
+use subnetworks_assignations::{MembershipCreator, MembershipHandler};
+use tokio::sync::{broadcast, oneshot};
 
 pub type DaIndexer<
     Tx,
@@ -78,6 +81,25 @@ pub type DaVerifier<Attestation, Blob, Membership, VerifierBackend, StorageSeria
 pub type DaDispersal<Backend, NetworkAdapter, MempoolAdapter, Membership, Metadata> =
     DispersalService<Backend, NetworkAdapter, MempoolAdapter, Membership, Metadata>;
 
+pub type DaLight<
+    Tx,
+    C,
+    SS,
+    SamplingBackend,
+    SamplingNetworkAdapter,
+    SamplingRng,
+    SamplingStorage,
+    DaNetwork,
+> = DaLightService<
+    cryptarchia_consensus::network::adapters::libp2p::LibP2pAdapter<Tx, C>,
+    SamplingBackend,
+    SamplingNetworkAdapter,
+    SamplingRng,
+    SamplingStorage,
+    RocksBackend<SS>,
+    DaNetwork,
+>;
+
 pub async fn add_blob<A, B, M, VB, SS>(
     handle: &OverwatchHandle,
     blob: B,
@@ -87,7 +109,8 @@ where
     B: Blob + Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
     <B as Blob>::BlobId: AsRef<[u8]> + Send + Sync + 'static,
     <B as Blob>::ColumnIndex: AsRef<[u8]> + Send + Sync + 'static,
-    M: MembershipHandler<NetworkId = SubnetworkId, Id = PeerId>
+    M: MembershipCreator
+        + MembershipHandler<NetworkId = SubnetworkId, Id = PeerId>
         + Clone
         + Debug
         + Send
@@ -128,7 +151,8 @@ pub async fn get_range<
     handle: &OverwatchHandle,
     app_id: <V as metadata::Metadata>::AppId,
     range: Range<<V as metadata::Metadata>::Index>,
-) -> Result<Vec<(<V as metadata::Metadata>::Index, Vec<Bytes>)>, DynError>
+    view: IndexView,
+) -> Result<Result<Vec<(<V as metadata::Metadata>::Index, Vec<Bytes>)>, PrunedRange>, DynError>
 where
     Tx: Transaction
         + Eq
@@ -194,6 +218,7 @@ where
         .send(DaMsg::GetRange {
             app_id,
             range,
+            view,
             reply_channel: sender,
         })
         .await
@@ -206,7 +231,7 @@ pub async fn disperse_data<Backend, NetworkAdapter, MempoolAdapter, Membership,
     handle: &OverwatchHandle,
     data: Vec<u8>,
     metadata: Metadata,
-) -> Result<(), DynError>
+) -> Result<BlobId, DynError>
 where
     Membership: MembershipHandler<NetworkId = SubnetworkId, Id = PeerId>
         + Clone
@@ -241,3 +266,107 @@ where
 
     receiver.await?
 }
+
+pub async fn light_availability<
+    Tx,
+    C,
+    SS,
+    SamplingBackend,
+    SamplingNetworkAdapter,
+    SamplingRng,
+    SamplingStorage,
+    DaNetwork,
+>(
+    handle: &OverwatchHandle,
+    id: HeaderId,
+) -> Result<Option<BlockAvailability>, DynError>
+where
+    Tx: Transaction
+        + Eq
+        + Clone
+        + Debug
+        + Hash
+        + Serialize
+        + DeserializeOwned
+        + Send
+        + Sync
+        + 'static,
+    C: DispersedBlobInfo<BlobId = [u8; 32]>
+        + Clone
+        + Eq
+        + Hash
+        + Serialize
+        + DeserializeOwned
+        + Send
+        + Sync
+        + 'static,
+    SS: StorageSerde + Send + Sync + 'static,
+    SamplingRng: SeedableRng + RngCore,
+    SamplingBackend: DaSamplingServiceBackend<SamplingRng, BlobId = BlobId> + Send,
+    SamplingBackend::Settings: Clone,
+    SamplingBackend::Blob: Debug + 'static,
+    SamplingNetworkAdapter: nomos_da_sampling::network::NetworkAdapter,
+    SamplingStorage: nomos_da_sampling::storage::DaStorageAdapter,
+    DaNetwork: DaNetworkBackend + Send + 'static,
+{
+    let relay = handle
+        .relay::<DaLight<
+            Tx,
+            C,
+            SS,
+            SamplingBackend,
+            SamplingNetworkAdapter,
+            SamplingRng,
+            SamplingStorage,
+            DaNetwork,
+        >>()
+        .connect()
+        .await?;
+    let (sender, receiver) = oneshot::channel();
+    relay
+        .send(DaLightMsg::GetAvailability {
+            id,
+            reply_channel: sender,
+        })
+        .await
+        .map_err(|(e, _)| e)?;
+
+    Ok(receiver.await?)
+}
+
+pub async fn sampling_subscribe<
+    SamplingBackend,
+    SamplingNetworkAdapter,
+    SamplingRng,
+    SamplingStorage,
+>(
+    handle: &OverwatchHandle,
+) -> Result<broadcast::Receiver<SamplingOutcome<SamplingBackend::BlobId>>, DynError>
+where
+    SamplingRng: SeedableRng + RngCore,
+    SamplingBackend: DaSamplingServiceBackend<SamplingRng> + Send,
+    SamplingBackend::Settings: Clone,
+    SamplingBackend::Blob: Debug + 'static,
+    SamplingBackend::BlobId: Debug + 'static,
+    SamplingNetworkAdapter: nomos_da_sampling::network::NetworkAdapter,
+    SamplingStorage: nomos_da_sampling::storage::DaStorageAdapter,
+{
+    let relay = handle
+        .relay::<DaSamplingService<
+            SamplingBackend,
+            SamplingNetworkAdapter,
+            SamplingRng,
+            SamplingStorage,
+        >>()
+        .connect()
+        .await?;
+    let (sender, receiver) = oneshot::channel();
+    relay
+        .send(DaSamplingServiceMsg::Subscribe {
+            reply_channel: sender,
+        })
+        .await
+        .map_err(|(e, _)| e)?;
+
+    Ok(receiver.await?)
+}
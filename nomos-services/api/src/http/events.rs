@@ -0,0 +1,141 @@
+// std
+use core::hash::Hash;
+// crates
+use cryptarchia_consensus::TipChange;
+use futures::{stream, Stream, StreamExt};
+use kzgrs_backend::dispersal::BlobInfo;
+use nomos_core::block::Block;
+use nomos_storage::backends::StorageSerde;
+use overwatch_rs::overwatch::handle::OverwatchHandle;
+use serde::{de::DeserializeOwned, Serialize};
+use tokio::sync::broadcast;
+use tokio_stream::wrappers::{errors::BroadcastStreamRecvError, BroadcastStream};
+// internal
+use super::{storage, DynError};
+
+/// Event pushed to stream subscribers
+#[derive(Debug, Clone)]
+pub enum StreamEvent<T> {
+    Item(T),
+    /// The subscriber was too slow and `skipped` events were dropped.
+    /// It should reconnect, resuming from the last height it processed.
+    Lagged {
+        skipped: u64,
+    },
+}
+
+/// Stream the items of a broadcast channel, reporting lagging instead of failing
+pub fn live_stream<T>(receiver: broadcast::Receiver<T>) -> impl Stream<Item = StreamEvent<T>>
+where
+    T: Clone + Send + 'static,
+{
+    BroadcastStream::new(receiver).map(|item| match item {
+        Ok(item) => StreamEvent::Item(item),
+        Err(BroadcastStreamRecvError::Lagged(skipped)) => StreamEvent::Lagged { skipped },
+    })
+}
+
+struct ChainCursor {
+    handle: OverwatchHandle,
+    tips: BroadcastStream<TipChange>,
+    next_height: u64,
+}
+
+/// Stream canonical blocks starting at `from_height`.
+/// Blocks already in storage are sent first, then new ones as the tip moves. After a reorg
+/// the blocks of the adopted fork are sent again from the fork point on.
+/// `tips` must be subscribed before calling this so no tip change is missed while catching up.
+pub fn canonical_blocks_stream<S, Tx>(
+    handle: OverwatchHandle,
+    tips: broadcast::Receiver<TipChange>,
+    from_height: u64,
+) -> impl Stream<Item = Result<StreamEvent<(u64, Block<Tx, BlobInfo>)>, DynError>>
+where
+    S: StorageSerde + Send + Sync + 'static,
+    Tx: Serialize + DeserializeOwned + Clone + Eq + Hash + Send + 'static,
+{
+    let cursor = ChainCursor {
+        handle,
+        tips: BroadcastStream::new(tips),
+        // genesis has no block content stored
+        next_height: from_height.max(1),
+    };
+    stream::unfold(Some(cursor), |cursor| async move {
+        let mut cursor = cursor?;
+        loop {
+            match storage::block_by_height_req::<S, Tx>(&cursor.handle, cursor.next_height).await {
+                Ok(Some(block)) => {
+                    let height = cursor.next_height;
+                    cursor.next_height += 1;
+                    return Some((Ok(StreamEvent::Item((height, block))), Some(cursor)));
+                }
+                Ok(None) => {}
+                // report the error and close the stream
+                Err(e) => return Some((Err(e), None)),
+            }
+            // nothing new in storage, wait for the tip to move
+            match cursor.tips.next().await? {
+                Ok(TipChange { fork_height, .. }) => {
+                    cursor.next_height = cursor.next_height.min(fork_height + 1);
+                }
+                Err(BroadcastStreamRecvError::Lagged(skipped)) => {
+                    return Some((Ok(StreamEvent::Lagged { skipped }), Some(cursor)));
+                }
+            }
+        }
+    })
+}
+
+/// Stream canonical tip changes.
+/// If `from_height` is set, the canonical chain from that height up to the current tip is
+/// replayed from storage first, as a sequence of tip changes without reverted blocks.
+/// `tips` must be subscribed before calling this so no tip change is missed while replaying.
+pub fn tips_stream<S, Tx>(
+    handle: OverwatchHandle,
+    tips: broadcast::Receiver<TipChange>,
+    from_height: Option<u64>,
+) -> impl Stream<Item = Result<StreamEvent<TipChange>, DynError>>
+where
+    S: StorageSerde + Send + Sync + 'static,
+    Tx: Serialize + DeserializeOwned + Clone + Eq + Hash + Send + 'static,
+{
+    let replay = from_height.is_some();
+    let cursor = ChainCursor {
+        handle,
+        tips: BroadcastStream::new(tips),
+        next_height: from_height.unwrap_or_default().max(1),
+    };
+    stream::unfold(Some((cursor, replay)), |state| async move {
+        let (mut cursor, mut replay) = state?;
+        if replay {
+            match storage::block_by_height_req::<S, Tx>(&cursor.handle, cursor.next_height).await {
+                Ok(Some(block)) => {
+                    let height = cursor.next_height;
+                    cursor.next_height += 1;
+                    let change = TipChange {
+                        tip: block.header().id(),
+                        height,
+                        slot: block.header().slot(),
+                        fork_height: height - 1,
+                        reverted: Vec::new(),
+                    };
+                    return Some((Ok(StreamEvent::Item(change)), Some((cursor, replay))));
+                }
+                Ok(None) => replay = false,
+                Err(e) => return Some((Err(e), None)),
+            }
+        }
+        loop {
+            match cursor.tips.next().await? {
+                // already replayed from storage
+                Ok(change) if change.height < cursor.next_height && change.reverted.is_empty() => {
+                    continue
+                }
+                Ok(change) => return Some((Ok(StreamEvent::Item(change)), Some((cursor, replay)))),
+                Err(BroadcastStreamRecvError::Lagged(skipped)) => {
+                    return Some((Ok(StreamEvent::Lagged { skipped }), Some((cursor, replay))));
+                }
+            }
+        }
+    })
+}
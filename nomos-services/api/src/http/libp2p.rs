@@ -1,13 +1,13 @@
 use nomos_network::{
-    backends::libp2p::{Command, Libp2p, Libp2pInfo},
+    backends::libp2p::{Command, Libp2pBackend, Libp2pInfo},
     NetworkMsg, NetworkService,
 };
 use tokio::sync::oneshot;
 
-pub async fn libp2p_info(
+pub async fn libp2p_info<B: Libp2pBackend>(
     handle: &overwatch_rs::overwatch::handle::OverwatchHandle,
 ) -> Result<Libp2pInfo, overwatch_rs::DynError> {
-    let relay = handle.relay::<NetworkService<Libp2p>>().connect().await?;
+    let relay = handle.relay::<NetworkService<B>>().connect().await?;
     let (sender, receiver) = oneshot::channel();
 
     relay
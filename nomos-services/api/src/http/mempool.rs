@@ -4,12 +4,12 @@ use nomos_da_sampling::{
     backend::DaSamplingServiceBackend, network::NetworkAdapter as DaSamplingNetworkAdapter,
 };
 use nomos_mempool::{
-    backend::mockpool::MockPool, network::NetworkAdapter, DaMempoolService, MempoolMsg,
-    TxMempoolService,
+    backend::mockpool::MockPool, network::NetworkAdapter, DaMempoolService, MempoolMetrics,
+    MempoolMsg, TxMempoolService,
 };
 use nomos_network::backends::NetworkBackend;
 use rand::{RngCore, SeedableRng};
-use tokio::sync::oneshot;
+use tokio::sync::{broadcast, oneshot};
 
 pub async fn add_tx<N, A, Item, Key>(
     handle: &overwatch_rs::overwatch::handle::OverwatchHandle,
@@ -102,3 +102,101 @@ where
         Err(e) => Err(e.into()),
     }
 }
+
+pub async fn da_mempool_subscribe<
+    N,
+    A,
+    Item,
+    Key,
+    SamplingBackend,
+    SamplingAdapter,
+    SamplingRng,
+    SamplingStorage,
+>(
+    handle: &overwatch_rs::overwatch::handle::OverwatchHandle,
+) -> Result<broadcast::Receiver<Item>, super::DynError>
+where
+    N: NetworkBackend,
+    A: NetworkAdapter<Backend = N, Key = Key> + Send + Sync + 'static,
+    A::Payload: DispersedBlobInfo + Into<Item> + Debug,
+    A::Settings: Send + Sync,
+    Item: Clone + Debug + Send + Sync + 'static + Hash,
+    Key: Clone + Debug + Ord + Hash + 'static,
+    SamplingBackend: DaSamplingServiceBackend<SamplingRng, BlobId = Key> + Send,
+    SamplingBackend::BlobId: Debug,
+    SamplingBackend::Blob: Debug + 'static,
+    SamplingBackend::Settings: Clone,
+    SamplingAdapter: DaSamplingNetworkAdapter + Send,
+    SamplingRng: SeedableRng + RngCore + Send,
+    SamplingStorage: nomos_da_sampling::storage::DaStorageAdapter,
+{
+    let relay = handle
+        .relay::<DaMempoolService<
+            A,
+            MockPool<HeaderId, Item, Key>,
+            SamplingBackend,
+            SamplingAdapter,
+            SamplingRng,
+            SamplingStorage,
+        >>()
+        .connect()
+        .await?;
+    let (sender, receiver) = oneshot::channel();
+    relay
+        .send(MempoolMsg::Subscribe {
+            reply_channel: sender,
+        })
+        .await
+        .map_err(|(e, _)| e)?;
+
+    Ok(receiver.await?)
+}
+
+pub async fn da_mempool_metrics<
+    N,
+    A,
+    Item,
+    Key,
+    SamplingBackend,
+    SamplingAdapter,
+    SamplingRng,
+    SamplingStorage,
+>(
+    handle: &overwatch_rs::overwatch::handle::OverwatchHandle,
+) -> Result<MempoolMetrics, super::DynError>
+where
+    N: NetworkBackend,
+    A: NetworkAdapter<Backend = N, Key = Key> + Send + Sync + 'static,
+    A::Payload: DispersedBlobInfo + Into<Item> + Debug,
+    A::Settings: Send + Sync,
+    Item: Clone + Debug + Send + Sync + 'static + Hash,
+    Key: Clone + Debug + Ord + Hash + 'static,
+    SamplingBackend: DaSamplingServiceBackend<SamplingRng, BlobId = Key> + Send,
+    SamplingBackend::BlobId: Debug,
+    SamplingBackend::Blob: Debug + 'static,
+    SamplingBackend::Settings: Clone,
+    SamplingAdapter: DaSamplingNetworkAdapter + Send,
+    SamplingRng: SeedableRng + RngCore + Send,
+    SamplingStorage: nomos_da_sampling::storage::DaStorageAdapter,
+{
+    let relay = handle
+        .relay::<DaMempoolService<
+            A,
+            MockPool<HeaderId, Item, Key>,
+            SamplingBackend,
+            SamplingAdapter,
+            SamplingRng,
+            SamplingStorage,
+        >>()
+        .connect()
+        .await?;
+    let (sender, receiver) = oneshot::channel();
+    relay
+        .send(MempoolMsg::Metrics {
+            reply_channel: sender,
+        })
+        .await
+        .map_err(|(e, _)| e)?;
+
+    Ok(receiver.await?)
+}
@@ -2,6 +2,8 @@ pub type DynError = Box<dyn std::error::Error + Send + Sync + 'static>;
 pub mod cl;
 pub mod consensus;
 pub mod da;
+pub mod events;
 pub mod libp2p;
 pub mod mempool;
 pub mod storage;
+pub mod tracing;
@@ -1,24 +1,137 @@
+use bytes::Bytes;
+use cryptarchia_consensus::history::{
+    height_key, key_bytes, InclusionLocation, CHAIN_BLOB_KEY_PREFIX, CHAIN_TX_KEY_PREFIX,
+};
+use kzgrs_backend::dispersal::BlobInfo;
 use nomos_core::block::Block;
 use nomos_core::header::HeaderId;
 use nomos_storage::{
     backends::{rocksdb::RocksBackend, StorageSerde},
-    StorageMsg, StorageService,
+    StorageMsg, StorageReplyReceiver, StorageService,
 };
+use overwatch_rs::services::relay::OutboundRelay;
+use serde::{de::DeserializeOwned, Serialize};
 
-pub async fn block_req<S, Tx>(
+// Limit the number of blocks returned by a single range request
+pub const BLOCKS_RANGE_LIMIT: usize = 100;
+
+type StorageRelay<S> = OutboundRelay<StorageMsg<RocksBackend<S>>>;
+
+async fn storage_relay<S>(
     handle: &overwatch_rs::overwatch::handle::OverwatchHandle,
-    id: HeaderId,
-) -> Result<Option<Block<Tx, full_replication::BlobInfo>>, super::DynError>
+) -> Result<StorageRelay<S>, super::DynError>
 where
-    Tx: serde::Serialize + serde::de::DeserializeOwned + Clone + Eq + core::hash::Hash,
     S: StorageSerde + Send + Sync + 'static,
 {
-    let relay = handle
+    Ok(handle
         .relay::<StorageService<RocksBackend<S>>>()
         .connect()
-        .await?;
-    let (msg, receiver) = StorageMsg::new_load_message(id);
+        .await?)
+}
+
+async fn load<S, K, V>(relay: &StorageRelay<S>, key: K) -> Result<Option<V>, super::DynError>
+where
+    S: StorageSerde + Send + Sync + 'static,
+    K: Serialize,
+    V: DeserializeOwned,
+{
+    let (msg, receiver) = StorageMsg::new_load_message(key);
     relay.send(msg).await.map_err(|(e, _)| e)?;
 
     Ok(receiver.recv().await?)
 }
+
+// Index keys are stored raw, without going through the storage serializer
+async fn load_raw<S, V>(relay: &StorageRelay<S>, key: Bytes) -> Result<Option<V>, super::DynError>
+where
+    S: StorageSerde + Send + Sync + 'static,
+    V: DeserializeOwned,
+{
+    let (reply_channel, receiver) = tokio::sync::oneshot::channel();
+    relay
+        .send(StorageMsg::Load { key, reply_channel })
+        .await
+        .map_err(|(e, _)| e)?;
+
+    Ok(StorageReplyReceiver::<_, RocksBackend<S>>::new(receiver)
+        .recv()
+        .await?)
+}
+
+pub async fn block_req<S, Tx>(
+    handle: &overwatch_rs::overwatch::handle::OverwatchHandle,
+    id: HeaderId,
+) -> Result<Option<Block<Tx, BlobInfo>>, super::DynError>
+where
+    Tx: Serialize + DeserializeOwned + Clone + Eq + core::hash::Hash,
+    S: StorageSerde + Send + Sync + 'static,
+{
+    let relay = storage_relay::<S>(handle).await?;
+    load::<S, _, _>(&relay, id).await
+}
+
+pub async fn block_by_height_req<S, Tx>(
+    handle: &overwatch_rs::overwatch::handle::OverwatchHandle,
+    height: u64,
+) -> Result<Option<Block<Tx, BlobInfo>>, super::DynError>
+where
+    Tx: Serialize + DeserializeOwned + Clone + Eq + core::hash::Hash,
+    S: StorageSerde + Send + Sync + 'static,
+{
+    let relay = storage_relay::<S>(handle).await?;
+    let Some(id) = load_raw::<S, HeaderId>(&relay, height_key(height)).await? else {
+        return Ok(None);
+    };
+    load::<S, _, _>(&relay, id).await
+}
+
+/// Fetch the canonical blocks starting at height `from`, up to `limit` of them
+/// (capped to [`BLOCKS_RANGE_LIMIT`]). The range stops early at the current tip.
+pub async fn blocks_range_req<S, Tx>(
+    handle: &overwatch_rs::overwatch::handle::OverwatchHandle,
+    from: u64,
+    limit: usize,
+) -> Result<Vec<Block<Tx, BlobInfo>>, super::DynError>
+where
+    Tx: Serialize + DeserializeOwned + Clone + Eq + core::hash::Hash,
+    S: StorageSerde + Send + Sync + 'static,
+{
+    let relay = storage_relay::<S>(handle).await?;
+    let mut blocks = Vec::new();
+    for height in (from..).take(limit.min(BLOCKS_RANGE_LIMIT)) {
+        let Some(id) = load_raw::<S, HeaderId>(&relay, height_key(height)).await? else {
+            break;
+        };
+        // genesis has no block content stored
+        if let Some(block) = load::<S, _, _>(&relay, id).await? {
+            blocks.push(block);
+        }
+    }
+    Ok(blocks)
+}
+
+pub async fn tx_location_req<S, TxHash>(
+    handle: &overwatch_rs::overwatch::handle::OverwatchHandle,
+    hash: TxHash,
+) -> Result<Option<InclusionLocation>, super::DynError>
+where
+    TxHash: Serialize,
+    S: StorageSerde + Send + Sync + 'static,
+{
+    let relay = storage_relay::<S>(handle).await?;
+    let key = key_bytes(CHAIN_TX_KEY_PREFIX, S::serialize(hash));
+    load_raw::<S, _>(&relay, key).await
+}
+
+pub async fn blob_location_req<S, BlobId>(
+    handle: &overwatch_rs::overwatch::handle::OverwatchHandle,
+    blob_id: BlobId,
+) -> Result<Option<InclusionLocation>, super::DynError>
+where
+    BlobId: Serialize,
+    S: StorageSerde + Send + Sync + 'static,
+{
+    let relay = storage_relay::<S>(handle).await?;
+    let key = key_bytes(CHAIN_BLOB_KEY_PREFIX, S::serialize(blob_id));
+    load_raw::<S, _>(&relay, key).await
+}
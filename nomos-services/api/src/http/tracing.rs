@@ -0,0 +1,51 @@
+use nomos_tracing_service::{FilterStatus, FilterUpdate, Tracing, TracingMsg};
+use overwatch_rs::overwatch::handle::OverwatchHandle;
+use tokio::sync::oneshot;
+
+use super::DynError;
+
+pub async fn filter(handle: &OverwatchHandle) -> Result<Option<FilterStatus>, DynError> {
+    let relay = handle.relay::<Tracing>().connect().await?;
+    let (sender, receiver) = oneshot::channel();
+
+    relay
+        .send(TracingMsg::GetFilter {
+            reply_channel: sender,
+        })
+        .await
+        .map_err(|(e, _)| e)?;
+
+    Ok(receiver.await?)
+}
+
+pub async fn set_filter(
+    handle: &OverwatchHandle,
+    update: FilterUpdate,
+) -> Result<FilterStatus, DynError> {
+    let relay = handle.relay::<Tracing>().connect().await?;
+    let (sender, receiver) = oneshot::channel();
+
+    relay
+        .send(TracingMsg::SetFilter {
+            update,
+            reply_channel: sender,
+        })
+        .await
+        .map_err(|(e, _)| e)?;
+
+    receiver.await?
+}
+
+pub async fn reset_filter(handle: &OverwatchHandle) -> Result<FilterStatus, DynError> {
+    let relay = handle.relay::<Tracing>().connect().await?;
+    let (sender, receiver) = oneshot::channel();
+
+    relay
+        .send(TracingMsg::ResetFilter {
+            reply_channel: sender,
+        })
+        .await
+        .map_err(|(e, _)| e)?;
+
+    receiver.await?
+}
@@ -0,0 +1,310 @@
+// std
+use std::collections::HashMap;
+// crates
+use bytes::{Bytes, BytesMut};
+use cryptarchia_engine::Branches;
+use nomos_core::header::HeaderId;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+// internal
+
+pub const CHAIN_HEIGHT_KEY_PREFIX: &str = "chain/height/";
+pub const CHAIN_TX_KEY_PREFIX: &str = "chain/tx/";
+pub const CHAIN_BLOB_KEY_PREFIX: &str = "chain/blob/";
+
+pub fn key_bytes(prefix: &str, id: impl AsRef<[u8]>) -> Bytes {
+    let mut buffer = BytesMut::new();
+
+    buffer.extend_from_slice(prefix.as_bytes());
+    buffer.extend_from_slice(id.as_ref());
+
+    buffer.freeze()
+}
+
+pub fn height_key(height: u64) -> Bytes {
+    key_bytes(CHAIN_HEIGHT_KEY_PREFIX, height.to_be_bytes())
+}
+
+/// Where a transaction or a blob was included in the canonical chain.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct InclusionLocation {
+    pub block: HeaderId,
+    pub height: u64,
+    /// Position of the item within the block transactions or blobs.
+    pub index: u64,
+}
+
+/// Serialized keys of the content included in a block.
+#[derive(Debug, Clone, Default)]
+pub struct BlockContents {
+    pub txs: Vec<Bytes>,
+    pub blobs: Vec<Bytes>,
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum IndexError {
+    #[error("Block {0:?} is not known to consensus")]
+    UnknownBlock(HeaderId),
+}
+
+/// Storage operations needed to move the persisted index to a new canonical chain.
+/// Removals must be applied before insertions, as a reorg can include the same content
+/// in both the abandoned and the adopted branch.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct IndexUpdate {
+    /// Height of the last block shared by the previous and the new canonical chain.
+    pub fork_height: u64,
+    /// Blocks that left the canonical chain, from the old tip down to the fork point.
+    pub reverted: Vec<HeaderId>,
+    pub remove: Vec<Bytes>,
+    pub heights: Vec<(Bytes, HeaderId)>,
+    pub locations: Vec<(Bytes, InclusionLocation)>,
+}
+
+impl IndexUpdate {
+    pub fn is_empty(&self) -> bool {
+        self.remove.is_empty() && self.heights.is_empty() && self.locations.is_empty()
+    }
+}
+
+/// Canonical chain index kept by consensus.
+/// It maps heights to block ids and transaction hashes and blob ids to the block that
+/// included them, following the local tip across reorgs.
+pub struct ChainIndex {
+    // canonical chain, indexed by height
+    canonical: Vec<HeaderId>,
+    // content of the blocks that can still be reverted, pruned once they are final
+    contents: HashMap<HeaderId, BlockContents>,
+}
+
+impl ChainIndex {
+    pub fn new(genesis: HeaderId) -> Self {
+        Self::from_canonical(genesis, Vec::new())
+    }
+
+    /// Resume an index persisted by a previous run, from the ids of its blocks after genesis
+    /// in height order. The content of the blocks that may still be reverted has to be
+    /// recorded again with [`ChainIndex::record_block`].
+    pub fn from_canonical(genesis: HeaderId, chain: Vec<HeaderId>) -> Self {
+        let mut canonical = vec![genesis];
+        canonical.extend(chain);
+        Self {
+            canonical,
+            contents: HashMap::new(),
+        }
+    }
+
+    pub fn height(&self) -> u64 {
+        self.canonical.len() as u64 - 1
+    }
+
+    pub fn tip(&self) -> HeaderId {
+        *self
+            .canonical
+            .last()
+            .expect("canonical chain always contains genesis")
+    }
+
+    pub fn get(&self, height: u64) -> Option<HeaderId> {
+        self.canonical.get(height as usize).copied()
+    }
+
+    /// Remember the content of a block so it can be indexed once it becomes canonical.
+    pub fn record_block(&mut self, id: HeaderId, contents: BlockContents) {
+        self.contents.insert(id, contents);
+    }
+
+    /// Move the canonical chain to `tip` and return the storage changes to persist it.
+    /// Fails without touching the index if `tip` or one of its ancestors down to the
+    /// canonical chain is not in `branches`.
+    pub fn update(
+        &mut self,
+        branches: &Branches<HeaderId>,
+        tip: HeaderId,
+    ) -> Result<IndexUpdate, IndexError> {
+        let mut update = IndexUpdate {
+            fork_height: self.height(),
+            ..Default::default()
+        };
+        if tip == self.tip() {
+            return Ok(update);
+        }
+
+        let mut cur = branches.get(&tip).ok_or(IndexError::UnknownBlock(tip))?;
+        // a tip already in the index is one catching up with the chain persisted before a
+        // restart, which stays canonical until a different block is adopted at some height
+        if self.get(cur.length()) == Some(tip) {
+            return Ok(update);
+        }
+
+        // walk back from the new tip until we hit a block already in the canonical chain
+        let mut adopted = Vec::new();
+        let fork_height = loop {
+            let height = cur.length();
+            if self.get(height) == Some(cur.id()) {
+                break height;
+            }
+            adopted.push((height, cur.id()));
+            cur = branches
+                .get(&cur.parent())
+                .ok_or(IndexError::UnknownBlock(cur.parent()))?;
+        };
+        update.fork_height = fork_height;
+
+        for height in (fork_height + 1..self.canonical.len() as u64).rev() {
+            let id = self.canonical[height as usize];
+            update.reverted.push(id);
+            update.remove.push(height_key(height));
+            if let Some(contents) = self.contents.get(&id) {
+                update.remove.extend(contents.txs.iter().cloned());
+                update.remove.extend(contents.blobs.iter().cloned());
+            } else {
+                tracing::warn!(
+                    "reverting block {id:?} at height {height} whose content was already pruned"
+                );
+            }
+        }
+        self.canonical.truncate(fork_height as usize + 1);
+
+        for (height, id) in adopted.into_iter().rev() {
+            self.canonical.push(id);
+            update.heights.push((height_key(height), id));
+            if let Some(contents) = self.contents.get(&id) {
+                for keys in [&contents.txs, &contents.blobs] {
+                    update
+                        .locations
+                        .extend(keys.iter().enumerate().map(|(index, key)| {
+                            (
+                                key.clone(),
+                                InclusionLocation {
+                                    block: id,
+                                    height,
+                                    index: index as u64,
+                                },
+                            )
+                        }));
+                }
+            }
+        }
+
+        Ok(update)
+    }
+
+    /// Forget the content of the blocks at or below `final_height`, which can't be reverted
+    /// anymore, and of the blocks no longer known to consensus
+    pub fn prune(&mut self, branches: &Branches<HeaderId>, final_height: u64) {
+        self.contents.retain(|id, _| {
+            branches
+                .get(id)
+                .is_some_and(|branch| branch.length() > final_height)
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cryptarchia_engine::{Config, Cryptarchia};
+
+    fn config() -> Config {
+        Config {
+            security_param: 1,
+            active_slot_coeff: 1.0,
+        }
+    }
+
+    fn id(n: u8) -> HeaderId {
+        [n; 32].into()
+    }
+
+    fn contents(n: u8) -> BlockContents {
+        BlockContents {
+            txs: vec![key_bytes(CHAIN_TX_KEY_PREFIX, [n])],
+            blobs: vec![],
+        }
+    }
+
+    #[test]
+    fn follows_reorgs() {
+        let mut engine = Cryptarchia::from_genesis(id(0), config());
+        let mut index = ChainIndex::new(id(0));
+
+        engine = engine.receive_block(id(1), id(0), 1.into()).unwrap();
+        index.record_block(id(1), contents(1));
+        let update = index.update(engine.branches(), id(1)).unwrap();
+        assert_eq!(update.heights, vec![(height_key(1), id(1))]);
+        assert_eq!(update.locations.len(), 1);
+        assert!(update.remove.is_empty());
+
+        // competing fork that becomes longer
+        engine = engine.receive_block(id(2), id(0), 2.into()).unwrap();
+        engine = engine.receive_block(id(3), id(2), 3.into()).unwrap();
+        index.record_block(id(2), contents(2));
+        index.record_block(id(3), contents(3));
+        let update = index.update(engine.branches(), id(3)).unwrap();
+        assert_eq!(update.fork_height, 0);
+        assert_eq!(update.reverted, vec![id(1)]);
+        assert_eq!(
+            update.remove,
+            vec![height_key(1), key_bytes(CHAIN_TX_KEY_PREFIX, [1])]
+        );
+        assert_eq!(
+            update.heights,
+            vec![(height_key(1), id(2)), (height_key(2), id(3))]
+        );
+        assert_eq!(index.get(1), Some(id(2)));
+        assert_eq!(index.get(2), Some(id(3)));
+        assert_eq!(index.tip(), id(3));
+
+        assert!(index.update(engine.branches(), id(3)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn prunes_final_contents() {
+        let mut engine = Cryptarchia::from_genesis(id(0), config());
+        let mut index = ChainIndex::new(id(0));
+        for n in 1..=3 {
+            engine = engine
+                .receive_block(id(n), id(n - 1), (n as u64).into())
+                .unwrap();
+            index.record_block(id(n), contents(n));
+            index.update(engine.branches(), id(n)).unwrap();
+        }
+        index.prune(engine.branches(), 2);
+        assert_eq!(index.contents.len(), 1);
+        assert!(index.contents.contains_key(&id(3)));
+    }
+
+    #[test]
+    fn resumes_from_persisted_chain() {
+        let mut engine = Cryptarchia::from_genesis(id(0), config());
+        let mut index = ChainIndex::from_canonical(id(0), vec![id(1), id(2)]);
+        assert_eq!(index.height(), 2);
+
+        // catching up along the persisted chain leaves it untouched
+        engine = engine.receive_block(id(1), id(0), 1.into()).unwrap();
+        assert!(index.update(engine.branches(), id(1)).unwrap().is_empty());
+        assert_eq!(index.tip(), id(2));
+
+        // a different block at height 2 replaces the persisted one
+        engine = engine.receive_block(id(3), id(1), 3.into()).unwrap();
+        index.record_block(id(3), contents(3));
+        let update = index.update(engine.branches(), id(3)).unwrap();
+        assert_eq!(update.fork_height, 1);
+        assert_eq!(update.reverted, vec![id(2)]);
+        assert_eq!(update.heights, vec![(height_key(2), id(3))]);
+        assert_eq!(index.tip(), id(3));
+    }
+
+    #[test]
+    fn rejects_unknown_tip() {
+        let engine = Cryptarchia::from_genesis(id(0), config());
+        let mut index = ChainIndex::from_canonical(id(0), vec![id(1)]);
+        assert_eq!(
+            index.update(engine.branches(), id(2)),
+            Err(IndexError::UnknownBlock(id(2)))
+        );
+        assert_eq!(index.tip(), id(1));
+    }
+}
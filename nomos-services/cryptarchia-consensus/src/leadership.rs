@@ -24,8 +24,9 @@ pub struct Leader {
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct LeaderConfig {
     pub notes: Vec<NoteWitness>,
-    // this is common to every note
-    pub nf_sk: NullifierSecret,
+    // this is common to every note, can be left out when the node reads it from a keystore
+    #[serde(default)]
+    pub nf_sk: Option<NullifierSecret>,
 }
 
 impl Leader {
@@ -36,7 +37,9 @@ impl Leader {
     ) -> Self {
         Leader {
             notes: HashMap::from([(genesis, notes)]),
-            nf_sk,
+            nf_sk: nf_sk.expect(
+                "Leader secret key should be set in the settings or read from the keystore",
+            ),
             config,
         }
     }
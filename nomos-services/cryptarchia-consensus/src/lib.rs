@@ -1,12 +1,16 @@
+pub mod history;
 mod leadership;
 mod messages;
+mod metrics;
 pub mod mix;
 pub mod network;
 mod time;
 
+use bytes::Bytes;
 use core::fmt::Debug;
 use cryptarchia_engine::Slot;
 use futures::StreamExt;
+use history::{BlockContents, ChainIndex, IndexUpdate, CHAIN_BLOB_KEY_PREFIX, CHAIN_TX_KEY_PREFIX};
 pub use leadership::LeaderConfig;
 use network::NetworkAdapter;
 use nomos_core::da::blob::{
@@ -26,7 +30,11 @@ use nomos_mempool::{
     TxMempoolService,
 };
 use nomos_network::NetworkService;
-use nomos_storage::{backends::StorageBackend, StorageMsg, StorageService};
+use nomos_service_status::StatusUpdater;
+use nomos_storage::{
+    backends::{StorageBackend, StorageSerde},
+    StorageMsg, StorageService,
+};
 use overwatch_rs::services::life_cycle::LifecycleMessage;
 use overwatch_rs::services::relay::{OutboundRelay, Relay, RelayMessage};
 use overwatch_rs::services::{
@@ -54,6 +62,8 @@ type SamplingRelay<BlobId> = OutboundRelay<DaSamplingServiceMsg<BlobId>>;
 // Limit the number of blocks returned by GetHeaders
 const HEADERS_LIMIT: usize = 512;
 const CRYPTARCHIA_ID: ServiceId = "Cryptarchia";
+/// How long to wait for the mix service before sending blocks through it
+const MIX_READY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
 
 #[derive(Debug, Clone, Error)]
 pub enum Error {
@@ -190,7 +200,9 @@ pub struct CryptarchiaConsensus<
         DaSamplingService<SamplingBackend, SamplingNetworkAdapter, SamplingRng, SamplingStorage>,
     >,
     block_subscription_sender: broadcast::Sender<Block<ClPool::Item, DaPool::Item>>,
+    tip_subscription_sender: broadcast::Sender<TipChange>,
     storage_relay: Relay<StorageService<Storage>>,
+    status: StatusUpdater,
 }
 
 impl<
@@ -324,7 +336,7 @@ where
         + Send
         + Sync
         + 'static,
-    ClPool::Key: Debug + Send + Sync,
+    ClPool::Key: Debug + Serialize + Send + Sync,
     ClPoolAdapter:
         MempoolAdapter<Payload = ClPool::Item, Key = ClPool::Key> + Send + Sync + 'static,
     DaPoolAdapter: MempoolAdapter<Key = DaPool::Key> + Send + Sync + 'static,
@@ -338,7 +350,7 @@ where
     SamplingBackend: DaSamplingServiceBackend<SamplingRng> + Send,
     SamplingBackend::Settings: Clone,
     SamplingBackend::Blob: Debug + Send + 'static,
-    SamplingBackend::BlobId: Debug + Ord + Send + Sync + 'static,
+    SamplingBackend::BlobId: Debug + Ord + Serialize + Send + Sync + 'static,
 
     SamplingNetworkAdapter: nomos_da_sampling::network::NetworkAdapter,
     SamplingStorage: nomos_da_sampling::storage::DaStorageAdapter,
@@ -351,6 +363,8 @@ where
         let storage_relay = service_state.overwatch_handle.relay();
         let sampling_relay = service_state.overwatch_handle.relay();
         let (block_subscription_sender, _) = broadcast::channel(16);
+        let (tip_subscription_sender, _) = broadcast::channel(16);
+        let status = nomos_service_status::updater::<Self>(&service_state.overwatch_handle);
         Ok(Self {
             service_state,
             network_relay,
@@ -358,8 +372,10 @@ where
             cl_mempool_relay,
             da_mempool_relay,
             block_subscription_sender,
+            tip_subscription_sender,
             storage_relay,
             sampling_relay,
+            status,
         })
     }
 
@@ -429,15 +445,32 @@ where
         let blob_selector = BS::new(blob_selector_settings);
 
         let mut incoming_blocks = network_adapter.blocks_stream().await?;
+        let mut chain_index = Self::load_chain_index(
+            &storage_relay,
+            genesis_id,
+            config.consensus_config.security_param,
+        )
+        .await;
         let mut leader = leadership::Leader::new(genesis_id, leader_config, config);
         let timer = time::Timer::new(time);
 
         let mut slot_timer = IntervalStream::new(timer.slot_interval());
 
+        // give the mix service the time to set up its network before we start sending messages
+        if let Err(e) = nomos_service_status::watcher::<
+            nomos_mix_service::MixService<MixAdapter::Backend, MixAdapter::Network>,
+        >(&self.service_state.overwatch_handle)
+        .wait_ready_timeout(MIX_READY_TIMEOUT)
+        .await
+        {
+            error!("mix service is not available: {e}");
+        }
         let mix_adapter = MixAdapter::new(mix_adapter_settings, mix_relay).await;
 
         let mut lifecycle_stream = self.service_state.lifecycle_handle.message_stream();
+        let metrics = metrics::Metrics::new();
 
+        self.status.ready();
         async {
             loop {
                 tokio::select! {
@@ -445,14 +478,17 @@ where
                         cryptarchia = Self::process_block(
                             cryptarchia,
                             &mut leader,
+                            &mut chain_index,
                             block,
                             storage_relay.clone(),
                             cl_mempool_relay.clone(),
                             da_mempool_relay.clone(),
                             sampling_relay.clone(),
-                            &mut self.block_subscription_sender
+                            &mut self.block_subscription_sender,
+                            &self.tip_subscription_sender,
                         )
                         .await;
+                        metrics.record_chain(&cryptarchia);
                     }
 
                     _ = slot_timer.next() => {
@@ -460,6 +496,7 @@ where
                         let parent = cryptarchia.tip();
                         let note_tree = cryptarchia.tip_state().lead_commitments();
                         tracing::debug!("ticking for slot {}", u64::from(slot));
+                        metrics.record_slot(&cryptarchia, slot);
 
                         let Some(epoch_state) = cryptarchia.epoch_state_for_slot(slot) else {
                             tracing::error!("trying to propose a block for slot {} but epoch state is not available", u64::from(slot));
@@ -467,6 +504,7 @@ where
                         };
                         if let Some(proof) = leader.build_proof_for(note_tree, epoch_state, slot, parent).await {
                             tracing::debug!("proposing block...");
+                            metrics.record_leader_win();
                             // TODO: spawn as a separate task?
                             let block = Self::propose_block(
                                 parent,
@@ -486,10 +524,11 @@ where
                     }
 
                     Some(msg) = self.service_state.inbound_relay.next() => {
-                        Self::process_message(&cryptarchia, &self.block_subscription_sender, msg);
+                        Self::process_message(&cryptarchia, &self.block_subscription_sender, &self.tip_subscription_sender, msg);
                     }
                     Some(msg) = lifecycle_stream.next() => {
                         if Self::should_stop_service(msg).await {
+                            self.status.stopped("shutdown");
                             break;
                         }
                     }
@@ -563,7 +602,7 @@ where
     DaPool::Settings: Send + Sync + 'static,
     TxS: TxSelect<Tx = ClPool::Item> + Clone + Send + Sync + 'static,
     BS: BlobSelect<BlobId = DaPool::Item> + Clone + Send + Sync + 'static,
-    ClPool::Key: Debug + Send + Sync,
+    ClPool::Key: Debug + Serialize + Send + Sync,
     ClPoolAdapter:
         MempoolAdapter<Payload = ClPool::Item, Key = ClPool::Key> + Send + Sync + 'static,
     DaPoolAdapter: MempoolAdapter<Key = DaPool::Key> + Send + Sync + 'static,
@@ -573,7 +612,7 @@ where
     SamplingBackend: DaSamplingServiceBackend<SamplingRng> + Send,
     SamplingBackend::Settings: Clone,
     SamplingBackend::Blob: Debug + 'static,
-    SamplingBackend::BlobId: Debug + Ord + Send + Sync + 'static,
+    SamplingBackend::BlobId: Debug + Ord + Serialize + Send + Sync + 'static,
     SamplingNetworkAdapter: nomos_da_sampling::network::NetworkAdapter,
     SamplingStorage: nomos_da_sampling::storage::DaStorageAdapter,
 {
@@ -595,6 +634,7 @@ where
     fn process_message(
         cryptarchia: &Cryptarchia,
         block_channel: &broadcast::Sender<Block<ClPool::Item, DaPool::Item>>,
+        tip_channel: &broadcast::Sender<TipChange>,
         msg: ConsensusMsg<Block<ClPool::Item, DaPool::Item>>,
     ) {
         match msg {
@@ -612,6 +652,7 @@ where
                         .get(&cryptarchia.tip())
                         .expect("tip branch not available")
                         .length(),
+                    security_param: cryptarchia.ledger.config().consensus_config.security_param,
                 };
                 tx.send(info).unwrap_or_else(|e| {
                     tracing::error!("Could not send consensus info through channel: {:?}", e)
@@ -622,6 +663,17 @@ where
                     tracing::error!("Could not subscribe to block subscription channel")
                 });
             }
+            ConsensusMsg::TipSubscribe { sender } => {
+                sender.send(tip_channel.subscribe()).unwrap_or_else(|_| {
+                    tracing::error!("Could not subscribe to tip subscription channel")
+                });
+            }
+            ConsensusMsg::TipLedgerState { tx } => {
+                tx.send(cryptarchia.tip_state().clone())
+                    .unwrap_or_else(|_| {
+                        tracing::error!("Could not send tip ledger state through channel")
+                    });
+            }
             ConsensusMsg::GetHeaders { from, to, tx } => {
                 // default to tip block if not present
                 let from = from.unwrap_or(cryptarchia.tip());
@@ -647,6 +699,79 @@ where
         }
     }
 
+    fn block_contents(block: &Block<ClPool::Item, DaPool::Item>) -> BlockContents {
+        BlockContents {
+            txs: block
+                .transactions()
+                .map(|tx| {
+                    history::key_bytes(
+                        CHAIN_TX_KEY_PREFIX,
+                        Storage::SerdeOperator::serialize(tx.hash()),
+                    )
+                })
+                .collect(),
+            blobs: block
+                .blobs()
+                .map(|blob| {
+                    history::key_bytes(
+                        CHAIN_BLOB_KEY_PREFIX,
+                        Storage::SerdeOperator::serialize(blob.blob_id()),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Resume the chain index persisted by a previous run, with the content of the blocks
+    /// that may still be reverted
+    async fn load_chain_index(
+        storage_relay: &OutboundRelay<StorageMsg<Storage>>,
+        genesis_id: HeaderId,
+        security_param: u32,
+    ) -> ChainIndex {
+        let (reply_channel, receiver) = oneshot::channel();
+        let prefix = Bytes::from_static(history::CHAIN_HEIGHT_KEY_PREFIX.as_bytes());
+        if let Err((e, _)) = storage_relay
+            .send(StorageMsg::LoadPrefix {
+                prefix,
+                reply_channel,
+            })
+            .await
+        {
+            error!("Could not load the chain index: {e}");
+            return ChainIndex::new(genesis_id);
+        }
+        // height keys are big endian, so they are loaded in height order
+        let stored = receiver.await.unwrap_or_default();
+        let stored_len = stored.len();
+        // the index is positional, so it can't go past a height that can't be read back
+        let chain: Vec<HeaderId> = stored
+            .into_iter()
+            .map_while(|bytes| Storage::SerdeOperator::deserialize(bytes).ok())
+            .collect();
+        if chain.len() < stored_len {
+            error!(
+                "Could not decode the block at height {}, cutting the chain index there",
+                chain.len() + 1
+            );
+        }
+        let final_height = (chain.len() as u64).saturating_sub(security_param.into()) as usize;
+        let mut chain_index = ChainIndex::from_canonical(genesis_id, chain.clone());
+        for id in &chain[final_height..] {
+            let (msg, receiver) = <StorageMsg<Storage>>::new_load_message(*id);
+            if let Err((e, _)) = storage_relay.send(msg).await {
+                error!("Could not load block {id:?}: {e}");
+                continue;
+            }
+            match receiver.recv::<Block<ClPool::Item, DaPool::Item>>().await {
+                Ok(Some(block)) => chain_index.record_block(*id, Self::block_contents(&block)),
+                _ => error!("Block {id:?} of the chain index is missing from storage"),
+            }
+        }
+        tracing::info!("resumed chain index at height {}", chain_index.height());
+        chain_index
+    }
+
     #[allow(clippy::type_complexity, clippy::too_many_arguments)]
     #[instrument(
         level = "debug",
@@ -656,12 +781,16 @@ where
             cl_mempool_relay,
             da_mempool_relay,
             sampling_relay,
-            leader
-        )
+            leader,
+            chain_index,
+            tip_broadcaster
+        ),
+        fields(header_id = ?block.header().id(), slot = ?block.header().slot())
     )]
     async fn process_block(
         mut cryptarchia: Cryptarchia,
         leader: &mut leadership::Leader,
+        chain_index: &mut ChainIndex,
         block: Block<ClPool::Item, DaPool::Item>,
         storage_relay: OutboundRelay<StorageMsg<Storage>>,
         cl_mempool_relay: OutboundRelay<
@@ -672,6 +801,7 @@ where
         >,
         sampling_relay: SamplingRelay<DaPool::Key>,
         block_broadcaster: &mut broadcast::Sender<Block<ClPool::Item, DaPool::Item>>,
+        tip_broadcaster: &broadcast::Sender<TipChange>,
     ) -> Cryptarchia {
         tracing::debug!("received proposal {:?}", block);
 
@@ -722,6 +852,34 @@ where
                     tracing::error!("Could not send block to storage: {e}");
                 }
 
+                // update the canonical chain index
+                chain_index.record_block(id, Self::block_contents(&block));
+                match chain_index.update(new_state.consensus.branches(), new_state.tip()) {
+                    Ok(update) => {
+                        let tip_height = chain_index.height();
+                        let security_param =
+                            new_state.ledger.config().consensus_config.security_param;
+                        chain_index.prune(
+                            new_state.consensus.branches(),
+                            tip_height.saturating_sub(security_param.into()),
+                        );
+                        let tip_change = (!update.heights.is_empty()).then(|| TipChange {
+                            tip: new_state.tip(),
+                            height: tip_height,
+                            slot: new_state.tip_state().slot(),
+                            fork_height: update.fork_height,
+                            reverted: update.reverted.clone(),
+                        });
+                        persist_index_update(&storage_relay, update).await;
+                        // notify only once the index is persisted so subscribers can read it back
+                        if let Some(tip_change) = tip_change {
+                            // no subscribers is not an error
+                            let _ = tip_broadcaster.send(tip_change);
+                        }
+                    }
+                    Err(e) => error!("Could not update the chain index: {e}"),
+                }
+
                 if let Err(e) = block_broadcaster.send(block) {
                     tracing::error!("Could not notify block to services {e}");
                 }
@@ -819,6 +977,13 @@ pub enum ConsensusMsg<Block> {
         to: Option<HeaderId>,
         tx: Sender<Vec<HeaderId>>,
     },
+    TipSubscribe {
+        sender: oneshot::Sender<broadcast::Receiver<TipChange>>,
+    },
+    /// Ledger state of the canonical tip, with the epoch nonce, stake distribution and spent notes
+    TipLedgerState {
+        tx: Sender<LedgerState>,
+    },
 }
 
 impl<Block: 'static> RelayMessage for ConsensusMsg<Block> {}
@@ -830,6 +995,22 @@ pub struct CryptarchiaInfo {
     pub tip: HeaderId,
     pub slot: Slot,
     pub height: u64,
+    /// Depth `k` after which blocks are final
+    pub security_param: u32,
+}
+
+/// Change of the local canonical tip.
+/// A non-empty `reverted` list means the node switched to a different fork.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TipChange {
+    pub tip: HeaderId,
+    pub height: u64,
+    pub slot: Slot,
+    /// Height of the last block shared by the previous and the new canonical chain.
+    pub fork_height: u64,
+    /// Blocks that left the canonical chain, from the old tip down to the fork point.
+    pub reverted: Vec<HeaderId>,
 }
 
 async fn get_mempool_contents<Payload, Item, Key>(
@@ -874,6 +1055,43 @@ async fn mark_blob_in_block<BlobId: Debug>(
     }
 }
 
+async fn persist_index_update<Storage: StorageBackend>(
+    storage_relay: &OutboundRelay<StorageMsg<Storage>>,
+    update: IndexUpdate,
+) {
+    let IndexUpdate {
+        remove,
+        heights,
+        locations,
+        ..
+    } = update;
+    for key in remove {
+        let (reply_channel, receiver) = oneshot::channel();
+        if let Err((e, _)) = storage_relay
+            .send(StorageMsg::Remove { key, reply_channel })
+            .await
+        {
+            tracing::error!("Could not remove chain index entry: {e}");
+            continue;
+        }
+        // wait for removals to be applied so they are not reordered with the stores below
+        let _ = receiver.await;
+    }
+    let stores = heights
+        .into_iter()
+        .map(|(key, id)| (key, Storage::SerdeOperator::serialize(id)))
+        .chain(
+            locations
+                .into_iter()
+                .map(|(key, location)| (key, Storage::SerdeOperator::serialize(location))),
+        );
+    for (key, value) in stores {
+        if let Err((e, _)) = storage_relay.send(StorageMsg::Store { key, value }).await {
+            tracing::error!("Could not store chain index entry: {e}");
+        }
+    }
+}
+
 async fn get_sampled_blobs<BlobId>(
     sampling_relay: SamplingRelay<BlobId>,
 ) -> Result<BTreeSet<BlobId>, DynError> {
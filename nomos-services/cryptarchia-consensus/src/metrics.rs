@@ -0,0 +1,59 @@
+// std
+// crates
+use cryptarchia_engine::Slot;
+use opentelemetry::{
+    global,
+    metrics::{Counter, Gauge},
+};
+// internal
+use crate::Cryptarchia;
+
+pub(crate) struct Metrics {
+    chain_height: Gauge<u64>,
+    forks: Gauge<u64>,
+    slot_lag: Gauge<u64>,
+    leader_wins: Counter<u64>,
+}
+
+impl Metrics {
+    pub(crate) fn new() -> Self {
+        let meter = global::meter("cryptarchia");
+        Self {
+            chain_height: meter
+                .u64_gauge("cryptarchia_chain_height")
+                .with_description("Height of the local chain tip")
+                .init(),
+            forks: meter
+                .u64_gauge("cryptarchia_forks")
+                .with_description("Number of known branches, including the canonical one")
+                .init(),
+            slot_lag: meter
+                .u64_gauge("cryptarchia_slot_lag")
+                .with_description("Slots elapsed since the slot of the local chain tip")
+                .init(),
+            leader_wins: meter
+                .u64_counter("cryptarchia_leader_wins")
+                .with_description("Slots for which this node was elected leader")
+                .init(),
+        }
+    }
+
+    pub(crate) fn record_chain(&self, cryptarchia: &Cryptarchia) {
+        let branches = cryptarchia.consensus.branches();
+        if let Some(tip) = branches.get(&cryptarchia.tip()) {
+            self.chain_height.record(tip.length(), &[]);
+        }
+        self.forks.record(branches.branches().len() as u64, &[]);
+    }
+
+    pub(crate) fn record_slot(&self, cryptarchia: &Cryptarchia, current: Slot) {
+        if let Some(tip) = cryptarchia.consensus.branches().get(&cryptarchia.tip()) {
+            let lag = u64::from(current).saturating_sub(u64::from(tip.slot()));
+            self.slot_lag.record(lag, &[]);
+        }
+    }
+
+    pub(crate) fn record_leader_win(&self) {
+        self.leader_wins.add(1, &[]);
+    }
+}
@@ -12,7 +12,7 @@ use crate::{
 };
 use nomos_core::{block::Block, wire};
 use nomos_network::{
-    backends::libp2p::{Command, Event, EventKind, Libp2p},
+    backends::libp2p::{Command, Event, EventKind, Libp2p, Libp2pBackend},
     NetworkMsg, NetworkService,
 };
 use overwatch_rs::services::{relay::OutboundRelay, ServiceData};
@@ -20,28 +20,44 @@ use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
 
 type Relay<T> = OutboundRelay<<NetworkService<T> as ServiceData>::Message>;
 
-#[derive(Clone)]
-pub struct LibP2pAdapter<Tx, BlobCert>
+pub struct LibP2pAdapter<Tx, BlobCert, B = Libp2p>
 where
     Tx: Clone + Eq + Hash,
     BlobCert: Clone + Eq + Hash,
+    B: Libp2pBackend,
 {
-    network_relay: OutboundRelay<<NetworkService<Libp2p> as ServiceData>::Message>,
+    network_relay: Relay<B>,
     _phantom_tx: PhantomData<Tx>,
     _blob_cert: PhantomData<BlobCert>,
 }
 
+impl<Tx, BlobCert, B> Clone for LibP2pAdapter<Tx, BlobCert, B>
+where
+    Tx: Clone + Eq + Hash,
+    BlobCert: Clone + Eq + Hash,
+    B: Libp2pBackend,
+{
+    fn clone(&self) -> Self {
+        Self {
+            network_relay: self.network_relay.clone(),
+            _phantom_tx: PhantomData,
+            _blob_cert: PhantomData,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LibP2pAdapterSettings {
     pub topic: String,
 }
 
-impl<Tx, BlobCert> LibP2pAdapter<Tx, BlobCert>
+impl<Tx, BlobCert, B> LibP2pAdapter<Tx, BlobCert, B>
 where
     Tx: Clone + Eq + Hash + Serialize,
     BlobCert: Clone + Eq + Hash + Serialize,
+    B: Libp2pBackend,
 {
-    async fn subscribe(relay: &Relay<Libp2p>, topic: &str) {
+    async fn subscribe(relay: &Relay<B>, topic: &str) {
         if let Err((e, _)) = relay
             .send(NetworkMsg::Process(Command::Subscribe(topic.into())))
             .await
@@ -52,17 +68,18 @@ where
 }
 
 #[async_trait::async_trait]
-impl<Tx, BlobCert> NetworkAdapter for LibP2pAdapter<Tx, BlobCert>
+impl<Tx, BlobCert, B> NetworkAdapter for LibP2pAdapter<Tx, BlobCert, B>
 where
     Tx: Serialize + DeserializeOwned + Clone + Eq + Hash + Send + Sync + 'static,
     BlobCert: Serialize + DeserializeOwned + Clone + Eq + Hash + Send + Sync + 'static,
+    B: Libp2pBackend,
 {
-    type Backend = Libp2p;
+    type Backend = B;
     type Settings = LibP2pAdapterSettings;
     type Tx = Tx;
     type BlobCertificate = BlobCert;
 
-    async fn new(settings: Self::Settings, network_relay: Relay<Libp2p>) -> Self {
+    async fn new(settings: Self::Settings, network_relay: Relay<B>) -> Self {
         let relay = network_relay.clone();
         Self::subscribe(&relay, settings.topic.as_str()).await;
         tracing::debug!("Starting up...");
@@ -14,12 +14,13 @@ use overwatch_rs::services::ServiceData;
 use overwatch_rs::DynError;
 use std::fmt::Debug;
 use std::pin::Pin;
-use subnetworks_assignations::MembershipHandler;
+use subnetworks_assignations::{MembershipCreator, MembershipHandler};
 use tokio::sync::oneshot;
 
 pub struct Libp2pNetworkAdapter<Membership>
 where
-    Membership: MembershipHandler<NetworkId = SubnetworkId, Id = PeerId>
+    Membership: MembershipCreator
+        + MembershipHandler<NetworkId = SubnetworkId, Id = PeerId>
         + Clone
         + Debug
         + Send
@@ -32,7 +33,8 @@ where
 #[async_trait::async_trait]
 impl<Membership> DispersalNetworkAdapter for Libp2pNetworkAdapter<Membership>
 where
-    Membership: MembershipHandler<NetworkId = SubnetworkId, Id = PeerId>
+    Membership: MembershipCreator
+        + MembershipHandler<NetworkId = SubnetworkId, Id = PeerId>
         + Clone
         + Debug
         + Send
@@ -109,6 +109,8 @@ where
             &encoder_settings.global_params_path,
         )
         .expect("Global encoder params should be available");
+        kzgrs_backend::common::rows_domain(encoder_settings.num_columns, &global_params)
+            .expect("Number of columns should be supported by the global parameters");
         let encoder = Self::Encoder::new(DaEncoderParams::new(
             encoder_settings.num_columns,
             encoder_settings.with_cache,
@@ -128,7 +130,9 @@ where
     ) -> Result<(Self::BlobId, <Self::Encoder as DaEncoder>::EncodedData), DynError> {
         let encoder = Arc::clone(&self.encoder);
         // this is a REALLY heavy task, so we should try not to block the thread here
-        let heavy_task = tokio::task::spawn_blocking(move || encoder.encode(&data));
+        let span = tracing::Span::current();
+        let heavy_task =
+            tokio::task::spawn_blocking(move || span.in_scope(|| encoder.encode(&data)));
         let encoded_data = heavy_task.await??;
         let blob_id = build_blob_id(
             &encoded_data.aggregated_column_commitment,
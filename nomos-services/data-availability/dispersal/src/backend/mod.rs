@@ -3,6 +3,7 @@ use std::time::Duration;
 
 use nomos_core::da::{blob::metadata, DaDispersal, DaEncoder};
 use overwatch_rs::DynError;
+use tracing::{field, Instrument};
 
 pub mod kzgrs;
 
@@ -14,7 +15,7 @@ pub trait DispersalBackend {
     type NetworkAdapter: DispersalNetworkAdapter;
     type MempoolAdapter: DaMempoolAdapter;
     type Metadata: metadata::Metadata + Send;
-    type BlobId: Send;
+    type BlobId: AsRef<[u8]> + Clone + Send;
 
     fn init(
         config: Self::Settings,
@@ -36,16 +37,26 @@ pub trait DispersalBackend {
         metadata: Self::Metadata,
     ) -> Result<(), DynError>;
 
+    /// Encode and disperse `data`, returning the id of the blob it was dispersed as
     async fn process_dispersal(
         &self,
         data: Vec<u8>,
         metadata: Self::Metadata,
-    ) -> Result<(), DynError> {
-        let (blob_id, encoded_data) = self.encode(data).await?;
-        self.disperse(encoded_data).await?;
+    ) -> Result<Self::BlobId, DynError> {
+        let encode_span = tracing::info_span!("encode", size = data.len(), blob_id = field::Empty);
+        let (blob_id, encoded_data) = self.encode(data).instrument(encode_span.clone()).await?;
+        let hex_blob_id = hex::encode(&blob_id);
+        encode_span.record("blob_id", field::display(&hex_blob_id));
+        self.disperse(encoded_data)
+            .instrument(tracing::info_span!("disperse", blob_id = %hex_blob_id))
+            .await?;
         // let disperse and replication happen before pushing to mempool
-        tokio::time::sleep(Duration::from_secs(1)).await;
-        self.publish_to_mempool(blob_id, metadata).await?;
-        Ok(())
+        tokio::time::sleep(Duration::from_secs(1))
+            .instrument(tracing::info_span!("await_replication", blob_id = %hex_blob_id))
+            .await;
+        self.publish_to_mempool(blob_id.clone(), metadata)
+            .instrument(tracing::info_span!("publish_to_mempool", blob_id = %hex_blob_id))
+            .await?;
+        Ok(blob_id)
     }
 }
@@ -1,7 +1,9 @@
 // std
 use std::fmt::Debug;
 use std::marker::PhantomData;
+use std::time::Instant;
 // crates
+use opentelemetry::global;
 use serde::{Deserialize, Serialize};
 use tokio::sync::oneshot;
 use tracing::log::error;
@@ -9,7 +11,7 @@ use tracing::log::error;
 use crate::adapters::mempool::DaMempoolAdapter;
 use crate::adapters::network::DispersalNetworkAdapter;
 use crate::backend::DispersalBackend;
-use nomos_core::da::blob::metadata;
+use nomos_core::da::{blob::metadata, BlobId};
 use nomos_da_network_core::{PeerId, SubnetworkId};
 use overwatch_rs::services::handle::ServiceStateHandle;
 use overwatch_rs::services::relay::{Relay, RelayMessage};
@@ -28,7 +30,7 @@ pub enum DaDispersalMsg<Metadata> {
     Disperse {
         data: Vec<u8>,
         metadata: Metadata,
-        reply_channel: oneshot::Sender<Result<(), DynError>>,
+        reply_channel: oneshot::Sender<Result<BlobId, DynError>>,
     },
 }
 
@@ -95,6 +97,7 @@ where
             NetworkAdapter = NetworkAdapter,
             MempoolAdapter = MempoolAdapter,
             Metadata = Metadata,
+            BlobId = BlobId,
         > + Send
         + Sync,
     Backend::Settings: Clone + Send + Sync,
@@ -129,6 +132,11 @@ where
         let mempool_adapter = MempoolAdapter::new(mempool_relay);
         let backend = Backend::init(backend_settings, network_adapter, mempool_adapter);
         let mut inbound_relay = service_state.inbound_relay;
+        let dispersal_latency = global::meter("da-dispersal")
+            .f64_histogram("da_dispersal_latency")
+            .with_description("Time taken to encode and disperse data")
+            .with_unit("s")
+            .init();
         while let Some(dispersal_msg) = inbound_relay.recv().await {
             match dispersal_msg {
                 DaDispersalMsg::Disperse {
@@ -136,9 +144,12 @@ where
                     metadata,
                     reply_channel,
                 } => {
-                    if let Err(Err(e)) =
-                        reply_channel.send(backend.process_dispersal(data, metadata).await)
-                    {
+                    let start = Instant::now();
+                    let result = backend.process_dispersal(data, metadata).await;
+                    if result.is_ok() {
+                        dispersal_latency.record(start.elapsed().as_secs_f64(), &[]);
+                    }
+                    if let Err(Err(e)) = reply_channel.send(result) {
                         error!("Error forwarding dispersal response: {e}");
                     }
                 }
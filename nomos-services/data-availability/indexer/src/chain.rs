@@ -0,0 +1,87 @@
+// std
+use std::collections::BTreeMap;
+use std::hash::Hash;
+// crates
+use cryptarchia_consensus::history::height_key;
+use nomos_core::block::Block;
+use nomos_core::header::HeaderId;
+use nomos_storage::{backends::StorageBackend, StorageMsg, StorageReplyReceiver};
+use overwatch_rs::{services::relay::OutboundRelay, DynError};
+use serde::de::DeserializeOwned;
+// internal
+
+/// Canonical chain persisted by consensus, and how far the indexer followed it
+pub(crate) struct CanonicalChain<Backend: StorageBackend> {
+    storage_relay: OutboundRelay<StorageMsg<Backend>>,
+    security_param: u64,
+    /// Height up to which app index entries were added, pending or final
+    pub(crate) indexed_height: u64,
+    /// Blocks whose app index entries are pending, by height
+    pub(crate) pending_blocks: BTreeMap<u64, HeaderId>,
+    /// Height up to which app index entries are final
+    pub(crate) finalized_height: u64,
+    /// Height up to which blobs were removed by the retention policy
+    pub(crate) pruned_height: u64,
+}
+
+impl<Backend: StorageBackend> CanonicalChain<Backend> {
+    pub(crate) fn new(
+        storage_relay: OutboundRelay<StorageMsg<Backend>>,
+        security_param: u32,
+        finalized_height: u64,
+        pruned_height: u64,
+    ) -> Self {
+        Self {
+            storage_relay,
+            security_param: security_param.into(),
+            // entries above the finalized height are added again on the first tip change
+            indexed_height: finalized_height,
+            pending_blocks: BTreeMap::new(),
+            finalized_height,
+            pruned_height,
+        }
+    }
+
+    /// Height of the last final block when the canonical tip is at `tip_height`.
+    /// Blocks at least `k` deep can't be reverted anymore.
+    pub(crate) fn final_height(&self, tip_height: u64) -> u64 {
+        tip_height.saturating_sub(self.security_param)
+    }
+
+    pub(crate) async fn block<Tx, C>(&self, id: HeaderId) -> Result<Option<Block<Tx, C>>, DynError>
+    where
+        Tx: Clone + Eq + Hash + DeserializeOwned,
+        C: Clone + Eq + Hash + DeserializeOwned,
+    {
+        let (msg, receiver) = StorageMsg::new_load_message(id);
+        self.storage_relay.send(msg).await.map_err(|(e, _)| e)?;
+        Ok(receiver.recv().await?)
+    }
+
+    /// Canonical block at `height`, if consensus has indexed it already
+    pub(crate) async fn block_at<Tx, C>(
+        &self,
+        height: u64,
+    ) -> Result<Option<Block<Tx, C>>, DynError>
+    where
+        Tx: Clone + Eq + Hash + DeserializeOwned,
+        C: Clone + Eq + Hash + DeserializeOwned,
+    {
+        // height keys are stored raw, without going through the storage serializer
+        let (reply_channel, receiver) = tokio::sync::oneshot::channel();
+        self.storage_relay
+            .send(StorageMsg::Load {
+                key: height_key(height),
+                reply_channel,
+            })
+            .await
+            .map_err(|(e, _)| e)?;
+        let id = StorageReplyReceiver::<_, Backend>::new(receiver)
+            .recv::<HeaderId>()
+            .await?;
+        match id {
+            Some(id) => self.block(id).await,
+            None => Ok(None),
+        }
+    }
+}
@@ -3,10 +3,13 @@ use futures::Stream;
 use nomos_core::block::Block;
 use overwatch_rs::services::relay::OutboundRelay;
 use tokio::sync::oneshot;
-use tokio_stream::{wrappers::BroadcastStream, StreamExt};
-use tracing::debug;
+use tokio_stream::{
+    wrappers::{errors::BroadcastStreamRecvError, BroadcastStream},
+    StreamExt,
+};
+use tracing::{debug, warn};
 
-use crate::consensus::ConsensusAdapter;
+use crate::consensus::{ConsensusAdapter, TipEvent};
 
 pub struct CryptarchiaConsensusAdapter<Tx, C>
 where
@@ -55,4 +58,41 @@ where
             }),
         )
     }
+
+    async fn security_param(&self) -> u32 {
+        let (tx, receiver) = oneshot::channel();
+
+        self.consensus_relay
+            .send(ConsensusMsg::Info { tx })
+            .await
+            .expect("Failed to send Info message");
+
+        receiver
+            .await
+            .expect("Failed to receive consensus info")
+            .security_param
+    }
+
+    async fn tip_stream(&self) -> Box<dyn Stream<Item = TipEvent> + Unpin + Send> {
+        let (sender, receiver) = oneshot::channel();
+
+        self.consensus_relay
+            .send(ConsensusMsg::TipSubscribe { sender })
+            .await
+            .expect("Failed to send TipSubscribe message");
+
+        let broadcast_receiver = receiver
+            .await
+            .expect("Failed to receive broadcast receiver");
+
+        Box::new(
+            BroadcastStream::new(broadcast_receiver).map(|result| match result {
+                Ok(tip) => TipEvent::Changed(tip),
+                Err(BroadcastStreamRecvError::Lagged(skipped)) => {
+                    warn!("Indexer lagged behind consensus, skipped {skipped} tip changes");
+                    TipEvent::Lagged { skipped }
+                }
+            }),
+        )
+    }
 }
@@ -1,10 +1,18 @@
 pub mod adapters;
 
-use cryptarchia_consensus::ConsensusMsg;
+use cryptarchia_consensus::{ConsensusMsg, TipChange};
 use futures::Stream;
 use nomos_core::block::Block;
 use overwatch_rs::services::relay::OutboundRelay;
 
+pub enum TipEvent {
+    Changed(TipChange),
+    /// Tip changes were missed, so the blocks they reverted are unknown
+    Lagged {
+        skipped: u64,
+    },
+}
+
 #[async_trait::async_trait]
 pub trait ConsensusAdapter {
     type Tx: Clone + Eq + std::hash::Hash;
@@ -16,4 +24,10 @@ pub trait ConsensusAdapter {
     async fn block_stream(
         &self,
     ) -> Box<dyn Stream<Item = Block<Self::Tx, Self::Cert>> + Unpin + Send>;
+
+    /// Depth `k` after which blocks are final
+    async fn security_param(&self) -> u32;
+
+    /// Changes of the canonical tip, including reorgs
+    async fn tip_stream(&self) -> Box<dyn Stream<Item = TipEvent> + Unpin + Send>;
 }
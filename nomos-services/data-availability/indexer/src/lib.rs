@@ -1,15 +1,17 @@
+mod chain;
 pub mod consensus;
+pub mod retention;
 pub mod storage;
 
 use std::fmt::{Debug, Formatter};
 use std::hash::Hash;
 use std::ops::Range;
 
-use consensus::ConsensusAdapter;
+use chain::CanonicalChain;
+use consensus::{ConsensusAdapter, TipEvent};
 use cryptarchia_consensus::network::NetworkAdapter;
-use cryptarchia_consensus::CryptarchiaConsensus;
+use cryptarchia_consensus::{CryptarchiaConsensus, TipChange};
 use futures::StreamExt;
-use nomos_core::block::Block;
 use nomos_core::da::blob::{info::DispersedBlobInfo, metadata::Metadata, BlobSelect};
 use nomos_core::header::HeaderId;
 use nomos_core::tx::{Transaction, TxSelect};
@@ -24,6 +26,7 @@ use overwatch_rs::services::state::{NoOperator, NoState};
 use overwatch_rs::services::{ServiceCore, ServiceData, ServiceId};
 use overwatch_rs::DynError;
 use rand::{RngCore, SeedableRng};
+use retention::RetentionSettings;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use storage::DaStorageAdapter;
@@ -125,6 +128,17 @@ pub struct DataIndexerService<
     >,
 }
 
+/// Set of app index entries to read from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IndexView {
+    /// Entries from all the canonical blocks, including those that can still be reverted
+    #[default]
+    Pending,
+    /// Only entries from blocks at least `k` deep in the canonical chain
+    Final,
+}
+
 pub enum DaMsg<B, V: Metadata> {
     AddIndex {
         info: V,
@@ -132,10 +146,30 @@ pub enum DaMsg<B, V: Metadata> {
     GetRange {
         app_id: <V as Metadata>::AppId,
         range: Range<<V as Metadata>::Index>,
-        reply_channel: Sender<Vec<(<V as Metadata>::Index, Vec<B>)>>,
+        view: IndexView,
+        reply_channel: Sender<Result<Vec<(<V as Metadata>::Index, Vec<B>)>, PrunedRange>>,
     },
 }
 
+/// Some of the requested blobs were removed by the retention policy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrunedRange {
+    /// Number of indexes in the range whose blobs were pruned
+    pub pruned: usize,
+}
+
+impl std::fmt::Display for PrunedRange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Blobs at {} indexes of the range were pruned",
+            self.pruned
+        )
+    }
+}
+
+impl std::error::Error for PrunedRange {}
+
 impl<B: 'static, V: Metadata + 'static> Debug for DaMsg<B, V> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -265,11 +299,12 @@ where
     DaPool: MemPool<BlockId = HeaderId>,
     DaPoolAdapter: MempoolAdapter<Key = DaPool::Key>,
     DaPoolAdapter::Payload: DispersedBlobInfo + Into<DaPool::Item> + Debug,
-    ClPool::Item: Clone + Eq + Hash + Debug + 'static,
+    ClPool::Item: DeserializeOwned + Clone + Eq + Hash + Debug + 'static,
     ClPool::Key: Debug + 'static,
-    DaPool::Item: Metadata + Clone + Eq + Hash + Debug + 'static,
+    DaPool::Item: Metadata + DeserializeOwned + Clone + Eq + Hash + Debug + 'static,
     DaPool::Key: Debug + 'static,
-    <DaPool::Item as Metadata>::Index: Send + Sync,
+    <DaPool::Item as Metadata>::AppId: Clone,
+    <DaPool::Item as Metadata>::Index: Clone + Send + Sync,
     A::Backend: 'static,
     TxS: TxSelect<Tx = ClPool::Item>,
     BS: BlobSelect<BlobId = DaPool::Item>,
@@ -283,12 +318,100 @@ where
     SamplingNetworkAdapter: nomos_da_sampling::network::NetworkAdapter,
     SamplingStorage: nomos_da_sampling::storage::DaStorageAdapter,
 {
-    async fn handle_new_block(
+    async fn handle_tip_change(
+        storage_adapter: &DaStorage,
+        chain: &mut CanonicalChain<DaStorage::Backend>,
+        tip: TipChange,
+    ) -> Result<(), DynError> {
+        // drop the entries of blocks that left the canonical chain
+        for id in &tip.reverted {
+            if let Some(block) = chain.block::<ClPool::Item, DaPool::Item>(*id).await? {
+                for info in block.blobs() {
+                    storage_adapter.remove_pending_index(info).await?;
+                }
+            }
+        }
+
+        // add the entries of blocks that joined it
+        let from = chain.indexed_height.min(tip.fork_height) + 1;
+        chain.pending_blocks.split_off(&from);
+        for height in from..=tip.height {
+            if let Some(block) = chain.block_at::<ClPool::Item, DaPool::Item>(height).await? {
+                for info in block.blobs() {
+                    storage_adapter.add_pending_index(info).await?;
+                }
+                chain.pending_blocks.insert(height, block.header().id());
+            }
+            chain.indexed_height = height;
+        }
+
+        // finalize the entries of blocks that became `k` deep
+        let final_height = chain.final_height(tip.height);
+        for height in chain.finalized_height + 1..=final_height {
+            if let Some(block) = chain.block_at::<ClPool::Item, DaPool::Item>(height).await? {
+                for info in block.blobs() {
+                    storage_adapter.finalize_index(info).await?;
+                }
+            }
+            storage_adapter.store_finalized_height(height).await?;
+            chain.finalized_height = height;
+        }
+        chain.pending_blocks = chain
+            .pending_blocks
+            .split_off(&(chain.finalized_height + 1));
+        Ok(())
+    }
+
+    /// After missing tip changes, drop the pending entries of the blocks that left the
+    /// canonical chain in the meantime, found by comparing them with the chain in storage.
+    /// Entries from the first reverted height on are added again on the next tip change.
+    async fn resync(
         storage_adapter: &DaStorage,
-        block: Block<ClPool::Item, DaPool::Item>,
+        chain: &mut CanonicalChain<DaStorage::Backend>,
     ) -> Result<(), DynError> {
-        for info in block.blobs() {
-            storage_adapter.add_index(info).await?;
+        let mut reverted = Vec::new();
+        for (height, id) in &chain.pending_blocks {
+            let canonical = chain
+                .block_at::<ClPool::Item, DaPool::Item>(*height)
+                .await?;
+            if canonical.map(|block| block.header().id()) != Some(*id) {
+                reverted.push((*height, *id));
+            }
+        }
+        let Some((first_height, _)) = reverted.first().copied() else {
+            return Ok(());
+        };
+        for (_, id) in reverted {
+            if let Some(block) = chain.block::<ClPool::Item, DaPool::Item>(id).await? {
+                for info in block.blobs() {
+                    storage_adapter.remove_pending_index(info).await?;
+                }
+            }
+        }
+        chain.indexed_height = chain.indexed_height.min(first_height - 1);
+        Ok(())
+    }
+
+    async fn prune(
+        storage_adapter: &DaStorage,
+        chain: &mut CanonicalChain<DaStorage::Backend>,
+        retention: &RetentionSettings,
+        tip_slot: u64,
+    ) -> Result<(), DynError> {
+        // only final blocks are pruned, the others could still be reverted and indexed again
+        while chain.pruned_height < chain.finalized_height {
+            let height = chain.pruned_height + 1;
+            let Some(block) = chain.block_at::<ClPool::Item, DaPool::Item>(height).await? else {
+                break;
+            };
+            if !retention.is_expired(u64::from(block.header().slot()), tip_slot) {
+                break;
+            }
+            for info in block.blobs() {
+                storage_adapter.prune(info).await?;
+            }
+            storage_adapter.store_pruned_height(height).await?;
+            chain.pruned_height = height;
         }
         Ok(())
     }
@@ -302,10 +425,20 @@ where
             DaMsg::GetRange {
                 app_id,
                 range,
+                view,
                 reply_channel,
             } => {
-                let stream = storage_adapter.get_range_stream(app_id, range).await;
-                let results = stream.collect::<Vec<_>>().await;
+                let pruned = storage_adapter
+                    .pruned_indexes(app_id.clone(), range.clone())
+                    .await?;
+                let results = if pruned.is_empty() {
+                    let stream = storage_adapter.get_range_stream(app_id, range, view).await;
+                    Ok(stream.collect::<Vec<_>>().await)
+                } else {
+                    Err(PrunedRange {
+                        pruned: pruned.len(),
+                    })
+                };
 
                 reply_channel
                     .send(results)
@@ -399,8 +532,8 @@ where
         + Send
         + Sync
         + 'static,
-    <DaPool::Item as Metadata>::AppId: Send + Sync,
-    <DaPool::Item as Metadata>::Index: Send + Sync,
+    <DaPool::Item as Metadata>::AppId: Clone + Send + Sync,
+    <DaPool::Item as Metadata>::Index: Clone + Send + Sync,
 
     A::Backend: 'static,
     TxS: TxSelect<Tx = ClPool::Item>,
@@ -443,20 +576,55 @@ where
             .await
             .expect("Relay connection with StorageService should succeed");
 
+        let IndexerSettings { storage, retention } =
+            service_state.settings_reader.get_updated_settings();
         let consensus_adapter = Consensus::new(consensus_relay).await;
-        let mut consensus_blocks = consensus_adapter.block_stream().await;
-        let storage_adapter = DaStorage::new(
-            service_state.settings_reader.get_updated_settings().storage,
+        let security_param = consensus_adapter.security_param().await;
+        let mut tip_changes = consensus_adapter.tip_stream().await;
+        let storage_adapter = DaStorage::new(storage, storage_relay.clone()).await;
+        let finalized_height = storage_adapter
+            .finalized_height()
+            .await
+            .expect("Finalized height should be readable from storage");
+        let pruned_height = storage_adapter
+            .pruned_height()
+            .await
+            .expect("Pruned height should be readable from storage");
+        let mut chain = CanonicalChain::new(
             storage_relay,
-        )
-        .await;
+            security_param,
+            finalized_height,
+            pruned_height,
+        );
+        let mut tip_slot = 0;
+        let mut prune_interval = tokio::time::interval(retention.prune_interval);
 
         let mut lifecycle_stream = service_state.lifecycle_handle.message_stream();
         loop {
             tokio::select! {
-                Some(block) = consensus_blocks.next() => {
-                    if let Err(e) = Self::handle_new_block(&storage_adapter, block).await {
-                        tracing::debug!("Failed to add  a new received block: {e:?}");
+                Some(tip) = tip_changes.next() => match tip {
+                    TipEvent::Changed(tip) => {
+                        tip_slot = u64::from(tip.slot);
+                        if let Err(e) = Self::handle_tip_change(&storage_adapter, &mut chain, tip).await {
+                            tracing::debug!("Failed to index the new canonical tip: {e:?}");
+                        }
+                    }
+                    TipEvent::Lagged { .. } => {
+                        if let Err(e) = Self::resync(&storage_adapter, &mut chain).await {
+                            tracing::debug!("Failed to resync the index with the canonical chain: {e:?}");
+                        }
+                    }
+                },
+                _ = prune_interval.tick(), if retention.prunes() => {
+                    if let Err(e) = Self::prune(&storage_adapter, &mut chain, &retention, tip_slot).await {
+                        tracing::debug!("Failed to prune expired blobs: {e:?}");
+                    }
+                    if let Some(window) = retention.unincluded_window {
+                        match storage_adapter.prune_unincluded(window).await {
+                            Ok(0) => {}
+                            Ok(pruned) => tracing::debug!("Pruned {pruned} expired unincluded blobs"),
+                            Err(e) => tracing::debug!("Failed to prune expired unincluded blobs: {e:?}"),
+                        }
                     }
                 }
                 Some(msg) = service_state.inbound_relay.recv() => {
@@ -478,4 +646,6 @@ where
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IndexerSettings<S> {
     pub storage: S,
+    #[serde(default)]
+    pub retention: RetentionSettings,
 }
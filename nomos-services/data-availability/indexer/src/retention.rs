@@ -0,0 +1,107 @@
+// std
+use std::time::Duration;
+// crates
+use serde::{Deserialize, Serialize};
+// internal
+
+/// How long blobs are kept after the inclusion of their info in a block
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RetentionWindow {
+    Slots(u64),
+    Epochs {
+        epochs: u64,
+        /// Slots in an epoch, as in the ledger config
+        epoch_length: u64,
+    },
+}
+
+impl RetentionWindow {
+    pub fn slots(&self) -> u64 {
+        match self {
+            Self::Slots(slots) => *slots,
+            Self::Epochs {
+                epochs,
+                epoch_length,
+            } => epochs.saturating_mul(*epoch_length),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetentionSettings {
+    /// Blobs are kept forever if not set
+    pub window: Option<RetentionWindow>,
+    /// Keep all the blobs regardless of the window, for archival nodes
+    pub pin: bool,
+    /// Time between two pruning rounds
+    pub prune_interval: Duration,
+    /// How long the verified shares of blobs that no block included are kept, forever if not set
+    #[serde(default)]
+    pub unincluded_window: Option<Duration>,
+}
+
+impl RetentionSettings {
+    /// Slots after which blobs are pruned, `None` if they are never pruned
+    pub fn window_slots(&self) -> Option<u64> {
+        if self.pin {
+            return None;
+        }
+        self.window.as_ref().map(RetentionWindow::slots)
+    }
+
+    /// Whether pruning rounds have anything to remove
+    pub fn prunes(&self) -> bool {
+        self.window_slots().is_some() || self.unincluded_window.is_some()
+    }
+
+    /// Whether a blob included at `inclusion_slot` expired when the canonical tip is at `tip_slot`
+    pub fn is_expired(&self, inclusion_slot: u64, tip_slot: u64) -> bool {
+        self.window_slots()
+            .is_some_and(|window| tip_slot.saturating_sub(inclusion_slot) > window)
+    }
+}
+
+impl Default for RetentionSettings {
+    fn default() -> Self {
+        Self {
+            window: None,
+            pin: false,
+            prune_interval: Duration::from_secs(60),
+            unincluded_window: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expiration() {
+        let settings = RetentionSettings {
+            window: Some(RetentionWindow::Epochs {
+                epochs: 2,
+                epoch_length: 10,
+            }),
+            ..Default::default()
+        };
+        assert!(!settings.is_expired(5, 25));
+        assert!(settings.is_expired(5, 26));
+
+        let pinned = RetentionSettings {
+            pin: true,
+            ..settings
+        };
+        assert!(!pinned.is_expired(5, 1000));
+        assert!(!RetentionSettings::default().is_expired(5, 1000));
+
+        // unincluded blobs expire even on archival nodes
+        assert!(!RetentionSettings::default().prunes());
+        assert!(RetentionSettings {
+            unincluded_window: Some(Duration::from_secs(60)),
+            ..pinned
+        }
+        .prunes());
+    }
+}
@@ -1,5 +1,6 @@
 // std
 use std::path::PathBuf;
+use std::time::Duration;
 use std::{marker::PhantomData, ops::Range};
 // crates
 use bytes::Bytes;
@@ -9,8 +10,11 @@ use nomos_core::da::blob::{
     metadata::{Metadata, Next},
 };
 use nomos_core::da::BlobId;
-use nomos_da_storage::fs::load_blobs;
-use nomos_da_storage::rocksdb::{key_bytes, DA_VERIFIED_KEY_PREFIX, DA_VID_KEY_PREFIX};
+use nomos_da_storage::fs::{blobs_older_than, load_blobs, remove_blobs};
+use nomos_da_storage::rocksdb::{
+    key_bytes, DA_FINALIZED_HEIGHT_KEY, DA_INCLUDED_KEY_PREFIX, DA_PENDING_VID_KEY_PREFIX,
+    DA_PRUNED_HEIGHT_KEY, DA_PRUNED_INDEX_KEY_PREFIX, DA_VERIFIED_KEY_PREFIX, DA_VID_KEY_PREFIX,
+};
 use nomos_storage::{
     backends::{rocksdb::RocksBackend, StorageSerde},
     StorageMsg, StorageService,
@@ -20,8 +24,10 @@ use overwatch_rs::{
     DynError,
 };
 use serde::{Deserialize, Serialize};
+use tokio::sync::oneshot;
 // internal
 use crate::storage::DaStorageAdapter;
+use crate::IndexView;
 
 pub struct RocksAdapter<S, B>
 where
@@ -58,46 +64,125 @@ where
     }
 
     async fn add_index(&self, info: &Self::Info) -> Result<(), DynError> {
-        let (app_id, idx) = info.metadata();
-
-        // Check if Info in a block is something that the node've seen before.
-        let attested_key = key_bytes(DA_VERIFIED_KEY_PREFIX, info.blob_id());
-        let (reply_tx, reply_rx) = tokio::sync::oneshot::channel();
-
-        self.storage_relay
-            .send(StorageMsg::LoadPrefix {
-                prefix: attested_key,
-                reply_channel: reply_tx,
-            })
+        // If node haven't attested this info, there is nothing to index.
+        if !self.is_attested(info).await? {
+            return Ok(());
+        }
+        self.mark_included(info).await?;
+        // We are only persisting the id part of Info, the metadata can be derived from the key.
+        self.store(vid_key(DA_VID_KEY_PREFIX, info), blob_id_bytes(info))
             .await
-            .expect("Failed to send load request to storage relay");
+    }
 
-        // If node haven't attested this info, return early.
-        if reply_rx.await?.is_empty() {
+    async fn add_pending_index(&self, info: &Self::Info) -> Result<(), DynError> {
+        if !self.is_attested(info).await? {
             return Ok(());
         }
+        self.mark_included(info).await?;
+        self.store(
+            vid_key(DA_PENDING_VID_KEY_PREFIX, info),
+            blob_id_bytes(info),
+        )
+        .await
+    }
 
-        let vid_key = key_bytes(
-            DA_VID_KEY_PREFIX,
-            [app_id.clone().as_ref(), idx.as_ref()].concat(),
-        );
+    async fn remove_pending_index(&self, info: &Self::Info) -> Result<(), DynError> {
+        if self.remove_pending_entry(info).await? {
+            // expired as an unincluded blob unless it's indexed again
+            self.remove(key_bytes(DA_INCLUDED_KEY_PREFIX, info.blob_id()))
+                .await?;
+        }
+        Ok(())
+    }
 
-        // We are only persisting the id part of Info, the metadata can be derived from the key.
-        let value = Bytes::from(info.blob_id().to_vec());
+    async fn finalize_index(&self, info: &Self::Info) -> Result<(), DynError> {
+        self.add_index(info).await?;
+        self.remove_pending_entry(info).await?;
+        Ok(())
+    }
 
-        self.storage_relay
-            .send(StorageMsg::Store {
-                key: vid_key,
-                value,
-            })
-            .await
-            .map_err(|(e, _)| e.into())
+    async fn finalized_height(&self) -> Result<u64, DynError> {
+        self.load_height(DA_FINALIZED_HEIGHT_KEY).await
+    }
+
+    async fn store_finalized_height(&self, height: u64) -> Result<(), DynError> {
+        self.store_height(DA_FINALIZED_HEIGHT_KEY, height).await
+    }
+
+    async fn prune(&self, info: &Self::Info) -> Result<(), DynError> {
+        let blob_id = info.blob_id();
+        self.remove_shares(&blob_id).await?;
+        self.remove(key_bytes(DA_INCLUDED_KEY_PREFIX, blob_id))
+            .await?;
+
+        let key = vid_key(DA_VID_KEY_PREFIX, info);
+        if self.load(key.clone()).await? == Some(blob_id_bytes(info)) {
+            self.remove(key).await?;
+        }
+
+        let (app_id, index) = info.metadata();
+        let pruned_index_key = key_bytes(DA_PRUNED_INDEX_KEY_PREFIX, app_id.as_ref());
+        match self.load(pruned_index_key.clone()).await? {
+            Some(pruned_index) if pruned_index.as_ref() >= index.as_ref() => Ok(()),
+            _ => {
+                self.store(pruned_index_key, Bytes::copy_from_slice(index.as_ref()))
+                    .await
+            }
+        }
+    }
+
+    async fn prune_unincluded(&self, age: Duration) -> Result<usize, DynError> {
+        let blob_ids = blobs_older_than(self.settings.blob_storage_directory.clone(), age).await?;
+        let mut pruned = 0;
+        for blob_id in blob_ids {
+            if self
+                .load(key_bytes(DA_INCLUDED_KEY_PREFIX, blob_id))
+                .await?
+                .is_some()
+            {
+                continue;
+            }
+            self.remove_shares(&blob_id).await?;
+            pruned += 1;
+        }
+        Ok(pruned)
+    }
+
+    async fn pruned_height(&self) -> Result<u64, DynError> {
+        self.load_height(DA_PRUNED_HEIGHT_KEY).await
+    }
+
+    async fn store_pruned_height(&self, height: u64) -> Result<(), DynError> {
+        self.store_height(DA_PRUNED_HEIGHT_KEY, height).await
+    }
+
+    async fn pruned_indexes(
+        &self,
+        app_id: <Self::Info as Metadata>::AppId,
+        index_range: Range<<Self::Info as Metadata>::Index>,
+    ) -> Result<Vec<<Self::Info as Metadata>::Index>, DynError> {
+        let Some(pruned_index) = self
+            .load(key_bytes(DA_PRUNED_INDEX_KEY_PREFIX, app_id.as_ref()))
+            .await?
+        else {
+            return Ok(Vec::new());
+        };
+        let mut pruned = Vec::new();
+        // same inclusive stepping as `get_range_stream`, indexes are big endian so they compare
+        // as their bytes
+        let mut current_index = index_range.start.clone();
+        while current_index <= index_range.end && current_index.as_ref() <= pruned_index.as_ref() {
+            pruned.push(current_index.clone());
+            current_index = current_index.next();
+        }
+        Ok(pruned)
     }
 
     async fn get_range_stream(
         &self,
         app_id: <Self::Info as Metadata>::AppId,
         index_range: Range<<Self::Info as Metadata>::Index>,
+        view: IndexView,
     ) -> Box<dyn Stream<Item = (<Self::Info as Metadata>::Index, Vec<Bytes>)> + Unpin + Send> {
         let futures = FuturesUnordered::new();
 
@@ -112,22 +197,27 @@ where
             let app_id = app_id.clone();
             let settings = self.settings.clone();
 
-            let (reply_tx, reply_rx) = tokio::sync::oneshot::channel();
-            let key = key_bytes(
-                DA_VID_KEY_PREFIX,
-                [app_id.as_ref(), current_index.as_ref()].concat(),
-            );
-
-            self.storage_relay
-                .send(StorageMsg::Load {
-                    key,
-                    reply_channel: reply_tx,
-                })
-                .await
-                .expect("Failed to send load request to storage relay");
+            let key = [app_id.as_ref(), current_index.as_ref()].concat();
+            let pending_rx = match view {
+                IndexView::Pending => Some(
+                    self.send_load(key_bytes(DA_PENDING_VID_KEY_PREFIX, &key))
+                        .await,
+                ),
+                IndexView::Final => None,
+            };
+            let final_rx = self.send_load(key_bytes(DA_VID_KEY_PREFIX, &key)).await;
 
             futures.push(async move {
-                match reply_rx.await {
+                // pending entries, if requested, shadow the final ones
+                let pending = match pending_rx {
+                    Some(pending_rx) => pending_rx.await,
+                    None => Ok(None),
+                };
+                let id = match pending {
+                    Ok(None) => final_rx.await,
+                    pending => pending,
+                };
+                match id {
                     Ok(Some(id)) => (idx, load_blobs(settings.blob_storage_directory, &id).await),
                     Ok(None) => (idx, Vec::new()),
                     Err(_) => {
@@ -144,6 +234,127 @@ where
     }
 }
 
+impl<S, B> RocksAdapter<S, B>
+where
+    S: StorageSerde + Send + Sync + 'static,
+    B: DispersedBlobInfo<BlobId = BlobId> + Metadata + Send + Sync,
+{
+    // Check if Info in a block is something that the node've seen before.
+    async fn is_attested(&self, info: &B) -> Result<bool, DynError> {
+        let (reply_tx, reply_rx) = tokio::sync::oneshot::channel();
+        self.storage_relay
+            .send(StorageMsg::LoadPrefix {
+                prefix: key_bytes(DA_VERIFIED_KEY_PREFIX, info.blob_id()),
+                reply_channel: reply_tx,
+            })
+            .await
+            .map_err(|(e, _)| e)?;
+        Ok(!reply_rx.await?.is_empty())
+    }
+
+    /// Remove the pending app index entry of `info`, returning whether there was one
+    async fn remove_pending_entry(&self, info: &B) -> Result<bool, DynError>
+    where
+        B::AppId: AsRef<[u8]>,
+        B::Index: AsRef<[u8]>,
+    {
+        let key = vid_key(DA_PENDING_VID_KEY_PREFIX, info);
+        // the entry might have been replaced by a blob included in the adopted branch
+        if self.load(key.clone()).await? != Some(blob_id_bytes(info)) {
+            return Ok(false);
+        }
+        self.remove(key).await?;
+        Ok(true)
+    }
+
+    async fn mark_included(&self, info: &B) -> Result<(), DynError> {
+        self.store(
+            key_bytes(DA_INCLUDED_KEY_PREFIX, info.blob_id()),
+            Bytes::new(),
+        )
+        .await
+    }
+
+    /// Remove the column files of `blob_id` and their verified marks
+    async fn remove_shares(&self, blob_id: &BlobId) -> Result<(), DynError> {
+        let columns = remove_blobs(self.settings.blob_storage_directory.clone(), blob_id).await?;
+        for column_idx in columns {
+            self.remove(key_bytes(
+                DA_VERIFIED_KEY_PREFIX,
+                [blob_id.as_slice(), column_idx.as_slice()].concat(),
+            ))
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn send_load(&self, key: Bytes) -> oneshot::Receiver<Option<Bytes>> {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        self.storage_relay
+            .send(StorageMsg::Load {
+                key,
+                reply_channel: reply_tx,
+            })
+            .await
+            .expect("Failed to send load request to storage relay");
+        reply_rx
+    }
+
+    async fn load(&self, key: Bytes) -> Result<Option<Bytes>, DynError> {
+        Ok(self.send_load(key).await.await?)
+    }
+
+    async fn store(&self, key: Bytes, value: Bytes) -> Result<(), DynError> {
+        self.storage_relay
+            .send(StorageMsg::Store { key, value })
+            .await
+            .map_err(|(e, _)| e.into())
+    }
+
+    async fn load_height(&self, key: &'static str) -> Result<u64, DynError> {
+        let Some(height) = self.load(Bytes::from(key)).await? else {
+            return Ok(0);
+        };
+        let height = height
+            .as_ref()
+            .try_into()
+            .map_err(|_| format!("Invalid height in storage at {key}"))?;
+        Ok(u64::from_be_bytes(height))
+    }
+
+    async fn store_height(&self, key: &'static str, height: u64) -> Result<(), DynError> {
+        self.store(
+            Bytes::from(key),
+            Bytes::copy_from_slice(&height.to_be_bytes()),
+        )
+        .await
+    }
+
+    async fn remove(&self, key: Bytes) -> Result<(), DynError> {
+        let (reply_channel, reply_rx) = oneshot::channel();
+        self.storage_relay
+            .send(StorageMsg::Remove { key, reply_channel })
+            .await
+            .map_err(|(e, _)| e)?;
+        reply_rx.await?;
+        Ok(())
+    }
+}
+
+fn vid_key<B>(prefix: &str, info: &B) -> Bytes
+where
+    B: Metadata,
+    B::AppId: AsRef<[u8]>,
+    B::Index: AsRef<[u8]>,
+{
+    let (app_id, idx) = info.metadata();
+    key_bytes(prefix, [app_id.as_ref(), idx.as_ref()].concat())
+}
+
+fn blob_id_bytes<B: DispersedBlobInfo<BlobId = BlobId>>(info: &B) -> Bytes {
+    Bytes::copy_from_slice(&info.blob_id())
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RocksAdapterSettings {
     pub blob_storage_directory: PathBuf,
@@ -1,6 +1,7 @@
 pub mod adapters;
 
 use std::ops::Range;
+use std::time::Duration;
 
 use futures::Stream;
 use nomos_core::da::blob::{info::DispersedBlobInfo, metadata::Metadata};
@@ -10,6 +11,8 @@ use overwatch_rs::{
     DynError,
 };
 
+use crate::IndexView;
+
 #[async_trait::async_trait]
 pub trait DaStorageAdapter {
     type Backend: StorageBackend + Send + Sync + 'static;
@@ -23,10 +26,35 @@ pub trait DaStorageAdapter {
         storage_relay: OutboundRelay<<StorageService<Self::Backend> as ServiceData>::Message>,
     ) -> Self;
 
+    /// Add a final app index entry for `vid`
     async fn add_index(&self, vid: &Self::Info) -> Result<(), DynError>;
+    /// Add an app index entry for `vid`, included in a canonical block that is not final yet
+    async fn add_pending_index(&self, vid: &Self::Info) -> Result<(), DynError>;
+    /// Remove the pending app index entry of `vid`, whose block left the canonical chain
+    async fn remove_pending_index(&self, vid: &Self::Info) -> Result<(), DynError>;
+    /// Turn the pending app index entry of `vid` into a final one
+    async fn finalize_index(&self, vid: &Self::Info) -> Result<(), DynError>;
+    /// Height of the last block whose entries were finalized, 0 if none
+    async fn finalized_height(&self) -> Result<u64, DynError>;
+    async fn store_finalized_height(&self, height: u64) -> Result<(), DynError>;
+    /// Remove the blobs and the final app index entry of `vid`
+    async fn prune(&self, vid: &Self::Info) -> Result<(), DynError>;
+    /// Remove the verified shares stored for longer than `age` of blobs no block included,
+    /// returning the number of blobs removed
+    async fn prune_unincluded(&self, age: Duration) -> Result<usize, DynError>;
+    /// Height of the last block whose blobs were pruned, 0 if none
+    async fn pruned_height(&self) -> Result<u64, DynError>;
+    async fn store_pruned_height(&self, height: u64) -> Result<(), DynError>;
+    /// Indexes in `range` whose blobs were pruned
+    async fn pruned_indexes(
+        &self,
+        app_id: <Self::Info as Metadata>::AppId,
+        range: Range<<Self::Info as Metadata>::Index>,
+    ) -> Result<Vec<<Self::Info as Metadata>::Index>, DynError>;
     async fn get_range_stream(
         &self,
         app_id: <Self::Info as Metadata>::AppId,
         range: Range<<Self::Info as Metadata>::Index>,
+        view: IndexView,
     ) -> Box<dyn Stream<Item = (<Self::Info as Metadata>::Index, Vec<Self::Blob>)> + Unpin + Send>;
 }
@@ -0,0 +1,70 @@
+// std
+// crates
+use nomos_core::header::{Header, HeaderId};
+use nomos_ledger::{leader_proof::LeaderProof, LedgerState};
+use thiserror::Error;
+// internal
+
+#[derive(Debug, Clone, Error)]
+pub enum Error {
+    #[error("Ledger error: {0}")]
+    Ledger(#[from] nomos_ledger::LedgerError<HeaderId>),
+    #[error("Consensus error: {0}")]
+    Consensus(#[from] cryptarchia_engine::Error<HeaderId>),
+}
+
+/// Chain of the followed headers, validated the same way consensus does so that the epoch
+/// nonce and stake distribution match the ones of the DA network members
+pub struct Chain {
+    ledger: nomos_ledger::Ledger<HeaderId>,
+    consensus: cryptarchia_engine::Cryptarchia<HeaderId>,
+}
+
+impl Chain {
+    pub fn from_genesis(
+        genesis_id: HeaderId,
+        genesis_state: LedgerState,
+        config: nomos_ledger::Config,
+    ) -> Self {
+        Self {
+            consensus: <cryptarchia_engine::Cryptarchia<_>>::from_genesis(
+                genesis_id,
+                config.consensus_config.clone(),
+            ),
+            ledger: <nomos_ledger::Ledger<_>>::from_genesis(genesis_id, genesis_state, config),
+        }
+    }
+
+    pub fn contains(&self, id: &HeaderId) -> bool {
+        self.ledger.state(id).is_some()
+    }
+
+    /// Ledger state of the canonical tip
+    pub fn tip_state(&self) -> &LedgerState {
+        self.ledger
+            .state(&self.consensus.tip())
+            .expect("tip state not available")
+    }
+
+    /// Apply a header whose parent is known, with a later slot and a valid leader proof
+    pub fn try_apply_header(&self, header: &Header) -> Result<Self, Error> {
+        let id = header.id();
+        let parent = header.parent();
+        let slot = header.slot();
+        let ledger = self.ledger.try_update(
+            id,
+            parent,
+            slot,
+            header.leader_proof(),
+            header.orphaned_proofs().iter().map(|imported_header| {
+                (
+                    imported_header.id(),
+                    imported_header.leader_proof().to_orphan_proof(),
+                )
+            }),
+        )?;
+        let consensus = self.consensus.receive_block(id, parent, slot)?;
+
+        Ok(Self { ledger, consensus })
+    }
+}
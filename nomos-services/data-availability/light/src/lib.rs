@@ -0,0 +1,485 @@
+mod chain;
+mod tracker;
+
+// std
+use std::fmt::Debug;
+use std::time::{Duration, Instant};
+// crates
+use cryptarchia_consensus::network::NetworkAdapter;
+use futures::StreamExt;
+use nomos_core::block::Block;
+use nomos_core::da::blob::info::DispersedBlobInfo;
+use nomos_core::da::BlobId;
+use nomos_core::header::HeaderId;
+use nomos_da_membership::{update_epoch_membership, DaMembershipSettings};
+use nomos_da_network_service::backends::NetworkBackend as DaNetworkBackend;
+use nomos_da_network_service::{DaNetworkMsg, NetworkService as DaNetworkService};
+use nomos_da_sampling::backend::DaSamplingServiceBackend;
+use nomos_da_sampling::{DaSamplingService, DaSamplingServiceMsg, SamplingOutcome};
+use nomos_da_storage::rocksdb::{
+    key_bytes, DA_LIGHT_AVAILABILITY_KEY_PREFIX, DA_LIGHT_HEADER_KEY_PREFIX,
+};
+use nomos_ledger::LedgerState;
+use nomos_network::NetworkService;
+use nomos_storage::backends::{StorageBackend, StorageSerde};
+use nomos_storage::{StorageMsg, StorageService};
+use overwatch_rs::services::handle::ServiceStateHandle;
+use overwatch_rs::services::life_cycle::LifecycleMessage;
+use overwatch_rs::services::relay::{OutboundRelay, Relay, RelayMessage};
+use overwatch_rs::services::state::{NoOperator, NoState};
+use overwatch_rs::services::{ServiceCore, ServiceData, ServiceId};
+use overwatch_rs::DynError;
+use rand::{RngCore, SeedableRng};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::oneshot;
+use tracing::{error, info, span, warn, Instrument, Level};
+// internal
+use chain::Chain;
+use tracker::AvailabilityTracker;
+pub use tracker::{Availability, BlockAvailability};
+
+const DA_LIGHT_TAG: ServiceId = "DA-Light";
+
+type SamplingRelay = OutboundRelay<DaSamplingServiceMsg<BlobId>>;
+type DaNetworkRelay<Backend> = OutboundRelay<DaNetworkMsg<Backend>>;
+
+#[derive(Debug)]
+pub enum DaLightMsg {
+    GetAvailability {
+        id: HeaderId,
+        reply_channel: oneshot::Sender<Option<BlockAvailability>>,
+    },
+}
+
+impl RelayMessage for DaLightMsg {}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DaLightServiceSettings<NetworkSettings> {
+    pub network_adapter_settings: NetworkSettings,
+    /// Time the blobs of a block have to be sampled before the block is reported unavailable
+    pub sampling_timeout: Duration,
+    /// Ledger configuration and genesis state of the followed chain, the same as the ones of
+    /// consensus
+    pub ledger_config: nomos_ledger::Config,
+    pub genesis_state: LedgerState,
+    /// Stake declarations of the DA peers, the same as the ones of the DA network members
+    #[serde(default)]
+    pub da_membership: DaMembershipSettings,
+}
+
+/// Light client service following the blocks gossiped by consensus and sampling the blobs they
+/// reference. Only headers and sampling results are persisted.
+///
+/// Headers are validated against the ledger before their blobs are sampled, and the DA
+/// membership is assigned again on every epoch of the followed tip, the same way DA network
+/// members do.
+pub struct DaLightService<
+    A,
+    SamplingBackend,
+    SamplingNetworkAdapter,
+    SamplingRng,
+    SamplingStorage,
+    Storage,
+    DaNetwork,
+> where
+    A: NetworkAdapter,
+    SamplingRng: SeedableRng + RngCore,
+    SamplingBackend: DaSamplingServiceBackend<SamplingRng, BlobId = BlobId> + Send,
+    SamplingBackend::Settings: Clone,
+    SamplingBackend::Blob: Debug + 'static,
+    SamplingNetworkAdapter: nomos_da_sampling::network::NetworkAdapter,
+    SamplingStorage: nomos_da_sampling::storage::DaStorageAdapter,
+    Storage: StorageBackend + Send + Sync + 'static,
+    DaNetwork: DaNetworkBackend + Send + 'static,
+{
+    service_state: ServiceStateHandle<Self>,
+    network_relay: Relay<NetworkService<A::Backend>>,
+    sampling_relay: Relay<
+        DaSamplingService<SamplingBackend, SamplingNetworkAdapter, SamplingRng, SamplingStorage>,
+    >,
+    storage_relay: Relay<StorageService<Storage>>,
+    da_network_relay: Relay<DaNetworkService<DaNetwork>>,
+}
+
+impl<
+        A,
+        SamplingBackend,
+        SamplingNetworkAdapter,
+        SamplingRng,
+        SamplingStorage,
+        Storage,
+        DaNetwork,
+    > ServiceData
+    for DaLightService<
+        A,
+        SamplingBackend,
+        SamplingNetworkAdapter,
+        SamplingRng,
+        SamplingStorage,
+        Storage,
+        DaNetwork,
+    >
+where
+    A: NetworkAdapter,
+    SamplingRng: SeedableRng + RngCore,
+    SamplingBackend: DaSamplingServiceBackend<SamplingRng, BlobId = BlobId> + Send,
+    SamplingBackend::Settings: Clone,
+    SamplingBackend::Blob: Debug + 'static,
+    SamplingNetworkAdapter: nomos_da_sampling::network::NetworkAdapter,
+    SamplingStorage: nomos_da_sampling::storage::DaStorageAdapter,
+    Storage: StorageBackend + Send + Sync + 'static,
+    DaNetwork: DaNetworkBackend + Send + 'static,
+{
+    const SERVICE_ID: ServiceId = DA_LIGHT_TAG;
+    type Settings = DaLightServiceSettings<A::Settings>;
+    type State = NoState<Self::Settings>;
+    type StateOperator = NoOperator<Self::State>;
+    type Message = DaLightMsg;
+}
+
+impl<
+        A,
+        SamplingBackend,
+        SamplingNetworkAdapter,
+        SamplingRng,
+        SamplingStorage,
+        Storage,
+        DaNetwork,
+    >
+    DaLightService<
+        A,
+        SamplingBackend,
+        SamplingNetworkAdapter,
+        SamplingRng,
+        SamplingStorage,
+        Storage,
+        DaNetwork,
+    >
+where
+    A: NetworkAdapter + Send + Sync + 'static,
+    A::BlobCertificate: DispersedBlobInfo<BlobId = BlobId>,
+    SamplingRng: SeedableRng + RngCore,
+    SamplingBackend: DaSamplingServiceBackend<SamplingRng, BlobId = BlobId> + Send,
+    SamplingBackend::Settings: Clone,
+    SamplingBackend::Blob: Debug + 'static,
+    SamplingNetworkAdapter: nomos_da_sampling::network::NetworkAdapter,
+    SamplingStorage: nomos_da_sampling::storage::DaStorageAdapter,
+    Storage: StorageBackend + Send + Sync + 'static,
+    DaNetwork: DaNetworkBackend + Send + 'static,
+{
+    async fn should_stop_service(message: LifecycleMessage) -> bool {
+        match message {
+            LifecycleMessage::Shutdown(sender) => {
+                if sender.send(()).is_err() {
+                    error!(
+                        "Error sending successful shutdown signal from service {}",
+                        Self::SERVICE_ID
+                    );
+                }
+                true
+            }
+            LifecycleMessage::Kill => true,
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn handle_block(
+        block: Block<A::Tx, A::BlobCertificate>,
+        chain: &mut Chain,
+        epoch: &mut Option<u32>,
+        da_membership: &DaMembershipSettings,
+        tracker: &mut AvailabilityTracker,
+        sampling_relay: &SamplingRelay,
+        storage_relay: &OutboundRelay<StorageMsg<Storage>>,
+        da_network_relay: &DaNetworkRelay<DaNetwork>,
+    ) -> Result<(), DynError> {
+        let header = block.header();
+        let id = header.id();
+        if chain.contains(&id) {
+            // blocks are gossiped again by every peer
+            return Ok(());
+        }
+        // only blocks linked to the followed chain are reported
+        *chain = chain.try_apply_header(header)?;
+        store(
+            storage_relay,
+            key_bytes(DA_LIGHT_HEADER_KEY_PREFIX, <[u8; 32]>::from(id)),
+            Storage::SerdeOperator::serialize(header),
+        )
+        .await?;
+        // blobs of a new epoch are sampled from the subnetworks assigned for it
+        update_epoch_membership(da_network_relay, da_membership, chain.tip_state(), epoch).await;
+
+        let blobs: Vec<BlobId> = block.blobs().map(DispersedBlobInfo::blob_id).collect();
+        let availability = tracker.track(id, blobs.clone(), Instant::now());
+        store_availability(storage_relay, id, &availability).await?;
+        if availability.status == Availability::Available {
+            info!("Block {id} is available");
+        }
+        for blob_id in blobs {
+            sampling_relay
+                .send(DaSamplingServiceMsg::TriggerSampling { blob_id })
+                .await
+                .map_err(|(e, _)| e)?;
+        }
+        Ok(())
+    }
+
+    async fn handle_sampling_outcome(
+        outcome: SamplingOutcome<BlobId>,
+        tracker: &mut AvailabilityTracker,
+        sampling_relay: &SamplingRelay,
+        storage_relay: &OutboundRelay<StorageMsg<Storage>>,
+    ) -> Result<(), DynError> {
+        let (blob_id, resolved) = match outcome {
+            SamplingOutcome::Success { blob_id } => (blob_id, tracker.on_success(&blob_id)),
+            SamplingOutcome::Failure { blob_id } => (blob_id, tracker.on_failure(&blob_id)),
+        };
+        // the sampling service keeps validated blobs until they are marked as included
+        if !tracker.is_awaited(&blob_id) {
+            sampling_relay
+                .send(DaSamplingServiceMsg::MarkInBlock {
+                    blobs_id: vec![blob_id],
+                })
+                .await
+                .map_err(|(e, _)| e)?;
+        }
+        Self::report(resolved, storage_relay).await
+    }
+
+    async fn report(
+        resolved: Vec<(HeaderId, BlockAvailability)>,
+        storage_relay: &OutboundRelay<StorageMsg<Storage>>,
+    ) -> Result<(), DynError> {
+        for (id, availability) in resolved {
+            match &availability.status {
+                Availability::Unavailable { missing } => {
+                    warn!("Block {id} is unavailable, missing {} blobs", missing.len())
+                }
+                _ => info!("Block {id} is available"),
+            }
+            store_availability(storage_relay, id, &availability).await?;
+        }
+        Ok(())
+    }
+
+    async fn handle_service_message(
+        msg: DaLightMsg,
+        storage_relay: &OutboundRelay<StorageMsg<Storage>>,
+    ) {
+        match msg {
+            DaLightMsg::GetAvailability { id, reply_channel } => {
+                let availability = match load(
+                    storage_relay,
+                    key_bytes(DA_LIGHT_AVAILABILITY_KEY_PREFIX, <[u8; 32]>::from(id)),
+                )
+                .await
+                {
+                    Ok(availability) => availability
+                        .map(Storage::SerdeOperator::deserialize)
+                        .transpose()
+                        .unwrap_or_else(|_| {
+                            error!("Error deserializing availability of block {id}");
+                            None
+                        }),
+                    Err(e) => {
+                        error!("Error loading availability of block {id}: {e}");
+                        None
+                    }
+                };
+                if reply_channel.send(availability).is_err() {
+                    error!("Error replying availability request");
+                }
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<
+        A,
+        SamplingBackend,
+        SamplingNetworkAdapter,
+        SamplingRng,
+        SamplingStorage,
+        Storage,
+        DaNetwork,
+    > ServiceCore
+    for DaLightService<
+        A,
+        SamplingBackend,
+        SamplingNetworkAdapter,
+        SamplingRng,
+        SamplingStorage,
+        Storage,
+        DaNetwork,
+    >
+where
+    A: NetworkAdapter + Send + Sync + 'static,
+    A::Settings: Send + Sync,
+    A::Tx: Send + Sync,
+    A::BlobCertificate: DispersedBlobInfo<BlobId = BlobId> + Send + Sync,
+    SamplingRng: SeedableRng + RngCore,
+    SamplingBackend: DaSamplingServiceBackend<SamplingRng, BlobId = BlobId> + Send,
+    SamplingBackend::Settings: Clone,
+    SamplingBackend::Blob: Debug + 'static,
+    SamplingNetworkAdapter: nomos_da_sampling::network::NetworkAdapter,
+    SamplingStorage: nomos_da_sampling::storage::DaStorageAdapter,
+    Storage: StorageBackend + Send + Sync + 'static,
+    DaNetwork: DaNetworkBackend + Send + 'static,
+    DaNetwork::State: Send + Sync,
+{
+    fn init(service_state: ServiceStateHandle<Self>) -> Result<Self, DynError> {
+        let network_relay = service_state.overwatch_handle.relay();
+        let sampling_relay = service_state.overwatch_handle.relay();
+        let storage_relay = service_state.overwatch_handle.relay();
+        let da_network_relay = service_state.overwatch_handle.relay();
+        Ok(Self {
+            service_state,
+            network_relay,
+            sampling_relay,
+            storage_relay,
+            da_network_relay,
+        })
+    }
+
+    async fn run(self) -> Result<(), DynError> {
+        let Self {
+            mut service_state,
+            network_relay,
+            sampling_relay,
+            storage_relay,
+            da_network_relay,
+        } = self;
+        let DaLightServiceSettings {
+            network_adapter_settings,
+            sampling_timeout,
+            ledger_config,
+            genesis_state,
+            da_membership,
+        } = service_state.settings_reader.get_updated_settings();
+
+        let network_relay = network_relay.connect().await?;
+        let sampling_relay: SamplingRelay = sampling_relay.connect().await?;
+        let storage_relay = storage_relay.connect().await?;
+        let da_network_relay: DaNetworkRelay<DaNetwork> = da_network_relay.connect().await?;
+
+        let mut chain = Chain::from_genesis(HeaderId::from([0; 32]), genesis_state, ledger_config);
+        // the genesis epoch is assigned from the genesis nonce, as DA network members do
+        let mut epoch = None;
+        update_epoch_membership(
+            &da_network_relay,
+            &da_membership,
+            chain.tip_state(),
+            &mut epoch,
+        )
+        .await;
+
+        // subscribe before any sampling is triggered so that no outcome is missed
+        let (reply_channel, receiver) = oneshot::channel();
+        sampling_relay
+            .send(DaSamplingServiceMsg::Subscribe { reply_channel })
+            .await
+            .map_err(|(e, _)| e)?;
+        let mut outcomes = receiver.await?;
+
+        let adapter = A::new(network_adapter_settings, network_relay).await;
+        let mut blocks = adapter.blocks_stream().await?;
+        let mut tracker = AvailabilityTracker::new(sampling_timeout);
+        let mut expiration_tick = tokio::time::interval(sampling_timeout / 4);
+        let mut lifecycle_stream = service_state.lifecycle_handle.message_stream();
+
+        async {
+            loop {
+                tokio::select! {
+                    Some(block) = blocks.next() => {
+                        if let Err(e) = Self::handle_block(
+                            block,
+                            &mut chain,
+                            &mut epoch,
+                            &da_membership,
+                            &mut tracker,
+                            &sampling_relay,
+                            &storage_relay,
+                            &da_network_relay,
+                        ).await {
+                            warn!("Block not followed: {e}");
+                        }
+                    }
+                    outcome = outcomes.recv() => {
+                        match outcome {
+                            Ok(outcome) => {
+                                if let Err(e) = Self::handle_sampling_outcome(outcome, &mut tracker, &sampling_relay, &storage_relay).await {
+                                    error!("Error handling sampling outcome: {e}");
+                                }
+                            }
+                            // missed blobs are reported unavailable once their block expires
+                            Err(RecvError::Lagged(missed)) => {
+                                warn!("Missed {missed} sampling outcomes");
+                            }
+                            Err(RecvError::Closed) => {
+                                error!("Sampling outcomes channel closed");
+                                break;
+                            }
+                        }
+                    }
+                    _ = expiration_tick.tick() => {
+                        let expired = tracker.expire(Instant::now());
+                        if let Err(e) = Self::report(expired, &storage_relay).await {
+                            error!("Error reporting expired blocks: {e}");
+                        }
+                    }
+                    Some(msg) = service_state.inbound_relay.recv() => {
+                        Self::handle_service_message(msg, &storage_relay).await;
+                    }
+                    Some(msg) = lifecycle_stream.next() => {
+                        if Self::should_stop_service(msg).await {
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+        .instrument(span!(Level::TRACE, DA_LIGHT_TAG))
+        .await;
+
+        Ok(())
+    }
+}
+
+async fn store_availability<Storage: StorageBackend>(
+    storage_relay: &OutboundRelay<StorageMsg<Storage>>,
+    id: HeaderId,
+    availability: &BlockAvailability,
+) -> Result<(), DynError> {
+    store(
+        storage_relay,
+        key_bytes(DA_LIGHT_AVAILABILITY_KEY_PREFIX, <[u8; 32]>::from(id)),
+        Storage::SerdeOperator::serialize(availability),
+    )
+    .await
+}
+
+async fn load<Storage: StorageBackend>(
+    storage_relay: &OutboundRelay<StorageMsg<Storage>>,
+    key: bytes::Bytes,
+) -> Result<Option<bytes::Bytes>, DynError> {
+    let (reply_channel, receiver) = oneshot::channel();
+    storage_relay
+        .send(StorageMsg::Load { key, reply_channel })
+        .await
+        .map_err(|(e, _)| e)?;
+    Ok(receiver.await?)
+}
+
+async fn store<Storage: StorageBackend>(
+    storage_relay: &OutboundRelay<StorageMsg<Storage>>,
+    key: bytes::Bytes,
+    value: bytes::Bytes,
+) -> Result<(), DynError> {
+    storage_relay
+        .send(StorageMsg::Store { key, value })
+        .await
+        .map_err(|(e, _)| e.into())
+}
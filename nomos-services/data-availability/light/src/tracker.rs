@@ -0,0 +1,199 @@
+// std
+use std::collections::{BTreeSet, HashMap};
+use std::time::{Duration, Instant};
+// crates
+use nomos_core::da::BlobId;
+use nomos_core::header::HeaderId;
+use serde::{Deserialize, Serialize};
+// internal
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Availability {
+    /// Some blobs of the block are still being sampled
+    Pending,
+    /// All the blobs referenced by the block were sampled successfully
+    Available,
+    /// Sampling failed or timed out for some blobs of the block
+    Unavailable { missing: Vec<BlobId> },
+}
+
+/// Sampling result of the blobs referenced by a block
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockAvailability {
+    pub blobs: Vec<BlobId>,
+    pub status: Availability,
+}
+
+struct PendingBlock {
+    blobs: Vec<BlobId>,
+    missing: BTreeSet<BlobId>,
+    deadline: Instant,
+}
+
+impl PendingBlock {
+    fn resolve(self, status: Availability) -> BlockAvailability {
+        BlockAvailability {
+            blobs: self.blobs,
+            status,
+        }
+    }
+}
+
+/// Blocks whose blobs are being sampled, until they all succeed, one of them fails or the
+/// sampling timeout elapses
+pub(crate) struct AvailabilityTracker {
+    timeout: Duration,
+    pending: HashMap<HeaderId, PendingBlock>,
+}
+
+impl AvailabilityTracker {
+    pub(crate) fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            pending: HashMap::new(),
+        }
+    }
+
+    /// Start tracking a block, blocks without blobs are available right away
+    pub(crate) fn track(
+        &mut self,
+        id: HeaderId,
+        blobs: Vec<BlobId>,
+        now: Instant,
+    ) -> BlockAvailability {
+        if blobs.is_empty() {
+            return BlockAvailability {
+                blobs,
+                status: Availability::Available,
+            };
+        }
+        self.pending.insert(
+            id,
+            PendingBlock {
+                missing: blobs.iter().copied().collect(),
+                blobs: blobs.clone(),
+                deadline: now + self.timeout,
+            },
+        );
+        BlockAvailability {
+            blobs,
+            status: Availability::Pending,
+        }
+    }
+
+    /// Whether some pending block still waits for `blob_id` to be sampled
+    pub(crate) fn is_awaited(&self, blob_id: &BlobId) -> bool {
+        self.pending
+            .values()
+            .any(|block| block.missing.contains(blob_id))
+    }
+
+    /// Blocks that became available once `blob_id` was sampled
+    pub(crate) fn on_success(&mut self, blob_id: &BlobId) -> Vec<(HeaderId, BlockAvailability)> {
+        for block in self.pending.values_mut() {
+            block.missing.remove(blob_id);
+        }
+        self.resolve_where(
+            |block| block.missing.is_empty(),
+            |_| Availability::Available,
+        )
+    }
+
+    /// Blocks that became unavailable because sampling `blob_id` failed
+    pub(crate) fn on_failure(&mut self, blob_id: &BlobId) -> Vec<(HeaderId, BlockAvailability)> {
+        self.resolve_where(
+            |block| block.missing.contains(blob_id),
+            |block| Availability::Unavailable {
+                missing: block.missing.iter().copied().collect(),
+            },
+        )
+    }
+
+    /// Blocks whose blobs were not all sampled before their deadline
+    pub(crate) fn expire(&mut self, now: Instant) -> Vec<(HeaderId, BlockAvailability)> {
+        self.resolve_where(
+            |block| block.deadline <= now,
+            |block| Availability::Unavailable {
+                missing: block.missing.iter().copied().collect(),
+            },
+        )
+    }
+
+    fn resolve_where(
+        &mut self,
+        resolved: impl Fn(&PendingBlock) -> bool,
+        status: impl Fn(&PendingBlock) -> Availability,
+    ) -> Vec<(HeaderId, BlockAvailability)> {
+        let ids: Vec<HeaderId> = self
+            .pending
+            .iter()
+            .filter(|(_, block)| resolved(block))
+            .map(|(id, _)| *id)
+            .collect();
+        ids.into_iter()
+            .filter_map(|id| self.pending.remove(&id).map(|block| (id, block)))
+            .map(|(id, block)| {
+                let status = status(&block);
+                (id, block.resolve(status))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn availability() {
+        let mut tracker = AvailabilityTracker::new(Duration::from_secs(10));
+        let now = Instant::now();
+        let (available, unavailable, expired, empty) = (
+            HeaderId::from([1; 32]),
+            HeaderId::from([2; 32]),
+            HeaderId::from([3; 32]),
+            HeaderId::from([4; 32]),
+        );
+
+        assert_eq!(
+            tracker.track(empty, vec![], now).status,
+            Availability::Available
+        );
+        assert_eq!(
+            tracker.track(available, vec![[1; 32], [2; 32]], now).status,
+            Availability::Pending
+        );
+        tracker.track(unavailable, vec![[2; 32], [3; 32]], now);
+        tracker.track(expired, vec![[4; 32]], now + Duration::from_secs(5));
+
+        assert!(tracker.on_success(&[1; 32]).is_empty());
+        let resolved = tracker.on_success(&[2; 32]);
+        assert_eq!(resolved.len(), 1);
+        assert_eq!(resolved[0].0, available);
+        assert_eq!(resolved[0].1.status, Availability::Available);
+        assert!(tracker.is_awaited(&[3; 32]));
+
+        let resolved = tracker.on_failure(&[3; 32]);
+        assert_eq!(resolved.len(), 1);
+        assert_eq!(resolved[0].0, unavailable);
+        assert_eq!(
+            resolved[0].1.status,
+            Availability::Unavailable {
+                missing: vec![[3; 32]]
+            }
+        );
+        assert!(!tracker.is_awaited(&[3; 32]));
+
+        assert!(tracker.expire(now + Duration::from_secs(10)).is_empty());
+        let resolved = tracker.expire(now + Duration::from_secs(15));
+        assert_eq!(resolved.len(), 1);
+        assert_eq!(resolved[0].0, expired);
+        assert_eq!(
+            resolved[0].1.status,
+            Availability::Unavailable {
+                missing: vec![[4; 32]]
+            }
+        );
+    }
+}
@@ -0,0 +1,363 @@
+// std
+use std::collections::{HashMap, HashSet};
+// crates
+use cl::{
+    balance::Value, NoteCommitment, NoteWitness, Nullifier, NullifierCommitment, NullifierSecret,
+};
+use cryptarchia_consensus::{ConsensusMsg, TipChange};
+use futures::StreamExt;
+use nomos_da_network_service::backends::NetworkBackend;
+use nomos_da_network_service::{DaNetworkMsg, NetworkService as DaNetworkService};
+use nomos_ledger::{LedgerState, NoteTree};
+use nomos_libp2p::PeerId;
+use nomos_service_status::StatusUpdater;
+use overwatch_rs::services::handle::ServiceStateHandle;
+use overwatch_rs::services::life_cycle::LifecycleMessage;
+use overwatch_rs::services::relay::{NoMessage, OutboundRelay, Relay};
+use overwatch_rs::services::state::{NoOperator, NoState};
+use overwatch_rs::services::{ServiceCore, ServiceData, ServiceId};
+use overwatch_rs::DynError;
+use serde::{Deserialize, Serialize};
+use tokio::sync::oneshot;
+use tokio_stream::wrappers::{errors::BroadcastStreamRecvError, BroadcastStream};
+use tracing::{debug, error, info, warn};
+// internal
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct DaMembershipSettings {
+    /// Note holding the stake of each DA peer. Only peers whose note is part of the epoch stake
+    /// distribution, isn't spent at the tip and holds at least `min_stake` are assigned to
+    /// subnetworks.
+    /// Without declarations the members from the DA network configuration are kept.
+    #[serde(default)]
+    pub stake_declarations: HashMap<PeerId, StakeDeclaration>,
+    /// Value a declared note must hold for its peer to be a member
+    #[serde(default)]
+    pub min_stake: Value,
+}
+
+/// Note a DA peer stakes with, opened so that its value can be checked against its commitment.
+/// Leading with a note spends it, so a peer that proposes blocks has to declare the evolved note.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StakeDeclaration {
+    pub note: NoteWitness,
+    pub nf_pk: NullifierCommitment,
+    /// Nullifier revealed when the note is spent
+    pub nullifier: Nullifier,
+}
+
+impl StakeDeclaration {
+    pub fn new(note: NoteWitness, nf_sk: NullifierSecret) -> Self {
+        let nf_pk = nf_sk.commit();
+        Self {
+            note,
+            nf_pk,
+            nullifier: Nullifier::new(nf_sk, note.commit(nf_pk)),
+        }
+    }
+
+    pub fn commitment(&self) -> NoteCommitment {
+        self.note.commit(self.nf_pk)
+    }
+}
+
+/// Follows the canonical tip and assigns the DA network subnetworks again whenever it enters a
+/// new epoch, from the epoch nonce and stake distribution.
+pub struct DaMembershipService<Backend, Consensus>
+where
+    Backend: NetworkBackend + Send + 'static,
+    Consensus: ServiceData,
+{
+    service_state: ServiceStateHandle<Self>,
+    consensus_relay: Relay<Consensus>,
+    da_network_relay: Relay<DaNetworkService<Backend>>,
+    status: StatusUpdater,
+}
+
+impl<Backend, Consensus> ServiceData for DaMembershipService<Backend, Consensus>
+where
+    Backend: NetworkBackend + Send + 'static,
+    Consensus: ServiceData,
+{
+    const SERVICE_ID: ServiceId = "DaMembership";
+    type Settings = DaMembershipSettings;
+    type State = NoState<Self::Settings>;
+    type StateOperator = NoOperator<Self::State>;
+    type Message = NoMessage;
+}
+
+#[async_trait::async_trait]
+impl<Backend, Consensus, Block> ServiceCore for DaMembershipService<Backend, Consensus>
+where
+    Backend: NetworkBackend + Send + 'static,
+    Backend::State: Send + Sync,
+    Consensus: ServiceData<Message = ConsensusMsg<Block>> + Send + 'static,
+    Block: Send + 'static,
+{
+    fn init(service_state: ServiceStateHandle<Self>) -> Result<Self, DynError> {
+        let consensus_relay = service_state.overwatch_handle.relay();
+        let da_network_relay = service_state.overwatch_handle.relay();
+        let status = nomos_service_status::updater::<Self>(&service_state.overwatch_handle);
+        Ok(Self {
+            service_state,
+            consensus_relay,
+            da_network_relay,
+            status,
+        })
+    }
+
+    async fn run(self) -> Result<(), DynError> {
+        let Self {
+            service_state,
+            consensus_relay,
+            da_network_relay,
+            status,
+        } = self;
+        let consensus_relay: OutboundRelay<_> = consensus_relay
+            .connect()
+            .await
+            .expect("Relay connection with CryptarchiaConsensus should succeed");
+        let da_network_relay: OutboundRelay<_> = da_network_relay
+            .connect()
+            .await
+            .expect("Relay connection with DaNetworkService should succeed");
+        let settings = service_state.settings_reader.get_updated_settings();
+
+        let mut tips = Self::tip_stream(&consensus_relay).await?;
+        // a node restarted in the middle of an epoch starts from the epoch of its tip
+        let mut epoch = None;
+        Self::follow_tip_epoch(&consensus_relay, &da_network_relay, &settings, &mut epoch).await;
+
+        let mut lifecycle_stream = service_state.lifecycle_handle.message_stream();
+        status.ready();
+        loop {
+            tokio::select! {
+                Some(tip) = tips.next() => {
+                    if let Err(BroadcastStreamRecvError::Lagged(skipped)) = tip {
+                        // the epoch is checked against the current tip either way
+                        warn!("DA membership lagged behind consensus, skipped {skipped} tip changes");
+                    }
+                    Self::follow_tip_epoch(
+                        &consensus_relay,
+                        &da_network_relay,
+                        &settings,
+                        &mut epoch,
+                    )
+                    .await;
+                }
+                Some(msg) = lifecycle_stream.next() => {
+                    if Self::should_stop_service(msg).await {
+                        status.stopped("shutdown");
+                        break;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<Backend, Consensus, Block> DaMembershipService<Backend, Consensus>
+where
+    Backend: NetworkBackend + Send + 'static,
+    Backend::State: Send + Sync,
+    Consensus: ServiceData<Message = ConsensusMsg<Block>> + Send + 'static,
+    Block: Send + 'static,
+{
+    async fn tip_stream(
+        consensus_relay: &OutboundRelay<ConsensusMsg<Block>>,
+    ) -> Result<BroadcastStream<TipChange>, DynError> {
+        let (sender, receiver) = oneshot::channel();
+        consensus_relay
+            .send(ConsensusMsg::TipSubscribe { sender })
+            .await
+            .map_err(|(e, _)| Box::new(e) as DynError)?;
+        let tips = receiver.await.map_err(|e| Box::new(e) as DynError)?;
+        Ok(BroadcastStream::new(tips))
+    }
+
+    /// Update the DA network membership if the canonical tip entered a different epoch
+    async fn follow_tip_epoch(
+        consensus_relay: &OutboundRelay<ConsensusMsg<Block>>,
+        da_network_relay: &OutboundRelay<DaNetworkMsg<Backend>>,
+        settings: &DaMembershipSettings,
+        current_epoch: &mut Option<u32>,
+    ) {
+        let (tx, receiver) = oneshot::channel();
+        if let Err((e, _)) = consensus_relay
+            .send(ConsensusMsg::TipLedgerState { tx })
+            .await
+        {
+            error!("Could not request the tip ledger state: {e}");
+            return;
+        }
+        let tip_state: LedgerState = match receiver.await {
+            Ok(tip_state) => tip_state,
+            Err(e) => {
+                error!("Could not receive the tip ledger state: {e}");
+                return;
+            }
+        };
+        update_epoch_membership(da_network_relay, settings, &tip_state, current_epoch).await;
+    }
+
+    async fn should_stop_service(msg: LifecycleMessage) -> bool {
+        match msg {
+            LifecycleMessage::Shutdown(sender) => {
+                if sender.send(()).is_err() {
+                    error!(
+                        "Error sending successful shutdown signal from service {}",
+                        Self::SERVICE_ID
+                    );
+                }
+                true
+            }
+            LifecycleMessage::Kill => true,
+        }
+    }
+}
+
+/// Assign the DA network subnetworks for the epoch of `tip_state` unless `current_epoch` already
+/// is that epoch. Nodes following the same chain get the same assignations, whether they are
+/// part of the DA network or only sample from it.
+pub async fn update_epoch_membership<Backend>(
+    da_network_relay: &OutboundRelay<DaNetworkMsg<Backend>>,
+    settings: &DaMembershipSettings,
+    tip_state: &LedgerState,
+    current_epoch: &mut Option<u32>,
+) where
+    Backend: NetworkBackend + Send + 'static,
+{
+    let epoch_state = tip_state.epoch_state();
+    let epoch = u32::from(epoch_state.epoch());
+    if *current_epoch == Some(epoch) {
+        return;
+    }
+
+    let members = staking_members(settings, epoch_state.stake_distribution(), |nullifier| {
+        tip_state.is_nullified(nullifier)
+    });
+    match &members {
+        Some(members) => info!(
+            "DA membership for epoch {epoch} has {} members",
+            members.len()
+        ),
+        None => debug!("DA membership for epoch {epoch} keeps the configured members"),
+    }
+    if let Err((e, _)) = da_network_relay
+        .send(DaNetworkMsg::UpdateMembership {
+            epoch,
+            seed: *epoch_state.nonce(),
+            members,
+        })
+        .await
+    {
+        error!("Could not update the DA membership for epoch {epoch}: {e}");
+        return;
+    }
+    *current_epoch = Some(epoch);
+}
+
+/// Declared peers whose note is part of the epoch stake distribution, holds at least the minimum
+/// stake and isn't spent.
+/// `None` if no declared peer holds stake, ie: in the genesis epoch which has no stake snapshot
+/// yet, in which case the configured members are kept.
+fn staking_members(
+    settings: &DaMembershipSettings,
+    stake_distribution: &NoteTree,
+    is_spent: impl Fn(&Nullifier) -> bool,
+) -> Option<HashSet<PeerId>> {
+    let stake: HashSet<&NoteCommitment> = stake_distribution.commitments().iter().collect();
+    let members: HashSet<PeerId> = settings
+        .stake_declarations
+        .iter()
+        .filter(|(_, declaration)| {
+            declaration.note.value >= settings.min_stake
+                && !is_spent(&declaration.nullifier)
+                && stake.contains(&declaration.commitment())
+        })
+        .map(|(peer_id, _)| *peer_id)
+        .collect();
+    (!members.is_empty()).then_some(members)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nomos_libp2p::identity::Keypair;
+
+    fn random_peer_id() -> PeerId {
+        PeerId::from_public_key(&Keypair::generate_ed25519().public())
+    }
+
+    fn declaration(value: u64, n: u8) -> StakeDeclaration {
+        StakeDeclaration::new(
+            NoteWitness::basic(value, [0; 32], &mut rand::thread_rng()),
+            NullifierSecret([n; 16]),
+        )
+    }
+
+    fn settings(
+        declarations: &[(PeerId, StakeDeclaration)],
+        min_stake: Value,
+    ) -> DaMembershipSettings {
+        DaMembershipSettings {
+            stake_declarations: declarations.iter().copied().collect(),
+            min_stake,
+        }
+    }
+
+    #[test]
+    fn test_members_hold_unspent_stake_in_the_epoch() {
+        let (staking, spent, unstaked, small) = (
+            random_peer_id(),
+            random_peer_id(),
+            random_peer_id(),
+            random_peer_id(),
+        );
+        let declarations = [
+            (staking, declaration(10, 1)),
+            (spent, declaration(10, 2)),
+            (unstaked, declaration(10, 3)),
+            (small, declaration(1, 4)),
+        ];
+        // the note of `unstaked` isn't part of the stake snapshot
+        let stake: NoteTree = [0, 1, 3]
+            .into_iter()
+            .map(|i| declarations[i].1.commitment())
+            .collect();
+        let spent_nullifier = declarations[1].1.nullifier;
+        let is_spent = |nullifier: &Nullifier| *nullifier == spent_nullifier;
+
+        assert_eq!(
+            staking_members(&settings(&declarations, 5), &stake, is_spent),
+            Some(HashSet::from([staking]))
+        );
+        assert_eq!(
+            staking_members(&settings(&declarations, 0), &stake, is_spent),
+            Some(HashSet::from([staking, small]))
+        );
+    }
+
+    #[test]
+    fn test_configured_members_are_kept_without_stake() {
+        let declarations = [(random_peer_id(), declaration(10, 1))];
+        let stake: NoteTree = [declarations[0].1.commitment()].into_iter().collect();
+        let unspent = |_: &Nullifier| false;
+
+        assert_eq!(staking_members(&settings(&[], 0), &stake, unspent), None);
+        assert_eq!(
+            staking_members(&settings(&declarations, 0), &NoteTree::default(), unspent),
+            None
+        );
+        let other_stake: NoteTree = [NoteCommitment([2; 32])].into_iter().collect();
+        assert_eq!(
+            staking_members(&settings(&declarations, 0), &other_stake, unspent),
+            None
+        );
+        assert_eq!(
+            staking_members(&settings(&declarations, 0), &stake, |_| true),
+            None
+        );
+    }
+}
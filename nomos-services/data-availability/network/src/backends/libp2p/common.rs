@@ -5,9 +5,10 @@ use libp2p::swarm::NetworkBehaviour;
 use libp2p::Swarm;
 use log::error;
 use nomos_core::da::BlobId;
+use nomos_da_network_core::protocols::replication::behaviour::ReplicationConfig;
 use nomos_da_network_core::protocols::sampling;
 use nomos_da_network_core::protocols::sampling::behaviour::{
-    BehaviourSampleReq, BehaviourSampleRes, SamplingError,
+    BehaviourSampleReq, BehaviourSampleRes, SamplingConfig, SamplingError,
 };
 use nomos_da_network_core::swarm::validator::ValidatorEventsStream;
 use nomos_da_network_core::SubnetworkId;
@@ -32,6 +33,12 @@ pub struct DaNetworkBackendSettings<Membership> {
     pub membership: Membership,
     pub addresses: HashMap<PeerId, Multiaddr>,
     pub listening_address: Multiaddr,
+    /// Serving of samples to peers outside of the membership
+    #[serde(default)]
+    pub sampling: SamplingConfig,
+    /// Limits of the blob replication between subnetwork peers
+    #[serde(default)]
+    pub replication: ReplicationConfig,
 }
 
 /// Sampling events coming from da network
@@ -21,10 +21,10 @@ use overwatch_rs::services::state::NoState;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use std::fmt::Debug;
-use std::marker::PhantomData;
 use std::pin::Pin;
 use std::time::Duration;
-use subnetworks_assignations::MembershipHandler;
+use subnetworks_assignations::epochs::Epoch;
+use subnetworks_assignations::{MembershipCreator, MembershipHandler};
 use tokio::sync::broadcast;
 use tokio::sync::mpsc::UnboundedSender;
 use tokio::task::JoinHandle;
@@ -82,14 +82,19 @@ where
     sampling_broadcast_receiver: broadcast::Receiver<SamplingEvent>,
     verifying_broadcast_receiver: broadcast::Receiver<DaBlob>,
     dispersal_broadcast_receiver: broadcast::Receiver<DispersalExecutorEvent>,
-    dispersal_blobs_sender: UnboundedSender<(Membership::NetworkId, DaBlob)>,
-    _membership: PhantomData<Membership>,
+    dispersal_blobs_sender: UnboundedSender<(Membership::NetworkId, Epoch, DaBlob)>,
+    /// Membership from configuration, its members are assigned again at every epoch
+    membership: Membership,
+    /// Latest epoch, blobs requested to be dispersed are stamped with it
+    epoch: Epoch,
+    membership_update_sender: UnboundedSender<(Epoch, Membership)>,
 }
 
 #[async_trait::async_trait]
 impl<Membership> NetworkBackend for DaNetworkExecutorBackend<Membership>
 where
-    Membership: MembershipHandler<NetworkId = SubnetworkId, Id = PeerId>
+    Membership: MembershipCreator
+        + MembershipHandler<NetworkId = SubnetworkId, Id = PeerId>
         + Clone
         + Debug
         + Send
@@ -115,6 +120,8 @@ where
                 .clone()
                 .into_iter()
                 .collect(),
+            config.validator_settings.sampling.clone(),
+            config.validator_settings.replication.clone(),
         );
         let address = config.validator_settings.listening_address.clone();
         // put swarm to listen at the specified configuration address
@@ -141,6 +148,8 @@ where
         );
 
         let sampling_request_channel = executor_swarm.sample_request_channel();
+        let membership_update_sender = executor_swarm.membership_update_channel();
+        let membership = config.validator_settings.membership.clone();
 
         let dispersal_blobs_sender = executor_swarm.dispersal_blobs_channel();
         let executor_open_stream_sender = executor_swarm.dispersal_open_stream_sender();
@@ -201,7 +210,9 @@ where
             verifying_broadcast_receiver,
             dispersal_broadcast_receiver,
             dispersal_blobs_sender,
-            _membership: Default::default(),
+            membership,
+            epoch: 0,
+            membership_update_sender,
         }
     }
 
@@ -229,13 +240,31 @@ where
                 subnetwork_id,
                 da_blob,
             } => {
-                if let Err(e) = self.dispersal_blobs_sender.send((subnetwork_id, *da_blob)) {
+                if let Err(e) =
+                    self.dispersal_blobs_sender
+                        .send((subnetwork_id, self.epoch, *da_blob))
+                {
                     error!("Could not send internal blob to underlying dispersal behaviour: {e}");
                 }
             }
         }
     }
 
+    async fn update_membership(
+        &mut self,
+        epoch: Epoch,
+        seed: [u8; 32],
+        members: Option<HashSet<PeerId>>,
+    ) {
+        // staking members are shuffled into subnetworks with the epoch randomness
+        let members = members.unwrap_or_else(|| self.membership.members());
+        let membership = self.membership.update(members, seed);
+        self.epoch = epoch;
+        if let Err(e) = self.membership_update_sender.send((epoch, membership)) {
+            error!("Error updating DA membership for epoch {epoch}: {e}");
+        }
+    }
+
     async fn subscribe(
         &mut self,
         event: Self::EventKind,
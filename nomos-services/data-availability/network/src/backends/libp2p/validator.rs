@@ -7,16 +7,18 @@ use futures::future::{AbortHandle, Abortable, Aborted};
 use futures::{Stream, StreamExt};
 use kzgrs_backend::common::blob::DaBlob;
 use libp2p::PeerId;
+use log::error;
 use nomos_core::da::BlobId;
 use nomos_da_network_core::swarm::validator::ValidatorSwarm;
 use nomos_da_network_core::SubnetworkId;
 use nomos_libp2p::ed25519;
 use overwatch_rs::overwatch::handle::OverwatchHandle;
 use overwatch_rs::services::state::NoState;
+use std::collections::HashSet;
 use std::fmt::Debug;
-use std::marker::PhantomData;
 use std::pin::Pin;
-use subnetworks_assignations::MembershipHandler;
+use subnetworks_assignations::epochs::Epoch;
+use subnetworks_assignations::{MembershipCreator, MembershipHandler};
 use tokio::sync::broadcast;
 use tokio::sync::mpsc::UnboundedSender;
 use tokio::task::JoinHandle;
@@ -57,13 +59,16 @@ pub struct DaNetworkValidatorBackend<Membership> {
     sampling_request_channel: UnboundedSender<(SubnetworkId, BlobId)>,
     sampling_broadcast_receiver: broadcast::Receiver<SamplingEvent>,
     verifying_broadcast_receiver: broadcast::Receiver<DaBlob>,
-    _membership: PhantomData<Membership>,
+    /// Membership from configuration, its members are assigned again at every epoch
+    membership: Membership,
+    membership_update_sender: UnboundedSender<(Epoch, Membership)>,
 }
 
 #[async_trait::async_trait]
 impl<Membership> NetworkBackend for DaNetworkValidatorBackend<Membership>
 where
-    Membership: MembershipHandler<NetworkId = SubnetworkId, Id = PeerId>
+    Membership: MembershipCreator
+        + MembershipHandler<NetworkId = SubnetworkId, Id = PeerId>
         + Clone
         + Debug
         + Send
@@ -83,6 +88,8 @@ where
             keypair,
             config.membership.clone(),
             config.addresses.clone().into_iter().collect(),
+            config.sampling.clone(),
+            config.replication.clone(),
         );
         let address = config.listening_address;
         // put swarm to listen at the specified configuration address
@@ -103,6 +110,8 @@ where
         );
 
         let sampling_request_channel = validator_swarm.sample_request_channel();
+        let membership_update_sender = validator_swarm.membership_update_channel();
+        let membership = config.membership;
 
         let (task_abort_handle, abort_registration) = AbortHandle::new_pair();
         let task = (
@@ -134,7 +143,8 @@ where
             sampling_request_channel,
             sampling_broadcast_receiver,
             verifying_broadcast_receiver,
-            _membership: Default::default(),
+            membership,
+            membership_update_sender,
         }
     }
 
@@ -159,6 +169,20 @@ where
         }
     }
 
+    async fn update_membership(
+        &mut self,
+        epoch: Epoch,
+        seed: [u8; 32],
+        members: Option<HashSet<PeerId>>,
+    ) {
+        // staking members are shuffled into subnetworks with the epoch randomness
+        let members = members.unwrap_or_else(|| self.membership.members());
+        let membership = self.membership.update(members, seed);
+        if let Err(e) = self.membership_update_sender.send((epoch, membership)) {
+            error!("Error updating DA membership for epoch {epoch}: {e}");
+        }
+    }
+
     async fn subscribe(
         &mut self,
         event: Self::EventKind,
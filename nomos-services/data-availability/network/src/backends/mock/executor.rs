@@ -1,8 +1,11 @@
 use futures::{Stream, StreamExt};
 use kzgrs_backend::common::{blob::DaBlob, build_blob_id};
+use libp2p::PeerId;
 use overwatch_rs::{overwatch::handle::OverwatchHandle, services::state::NoState};
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::pin::Pin;
+use subnetworks_assignations::epochs::Epoch;
 use tokio::sync::{
     broadcast::{self},
     mpsc,
@@ -101,6 +104,14 @@ impl NetworkBackend for MockExecutorBackend {
         }
     }
 
+    async fn update_membership(
+        &mut self,
+        _epoch: Epoch,
+        _seed: [u8; 32],
+        _members: Option<HashSet<PeerId>>,
+    ) {
+    }
+
     async fn subscribe(
         &mut self,
         kind: Self::EventKind,
@@ -3,8 +3,11 @@ pub mod mock;
 
 use super::*;
 use futures::Stream;
+use libp2p::PeerId;
 use overwatch_rs::{overwatch::handle::OverwatchHandle, services::state::ServiceState};
+use std::collections::HashSet;
 use std::pin::Pin;
+use subnetworks_assignations::epochs::Epoch;
 
 #[async_trait::async_trait]
 pub trait NetworkBackend {
@@ -17,6 +20,14 @@ pub trait NetworkBackend {
     fn new(config: Self::Settings, overwatch_handle: OverwatchHandle) -> Self;
     fn shutdown(&mut self);
     async fn process(&self, msg: Self::Message);
+    /// Swap in the membership of a new epoch, `members` are assigned to subnetworks with the
+    /// epoch `seed`. Without `members` the ones from the configuration are assigned again.
+    async fn update_membership(
+        &mut self,
+        epoch: Epoch,
+        seed: [u8; 32],
+        members: Option<HashSet<PeerId>>,
+    );
     async fn subscribe(
         &mut self,
         event: Self::EventKind,
@@ -1,12 +1,15 @@
 pub mod backends;
 
 // std
+use std::collections::HashSet;
 use std::fmt::{self, Debug};
 use std::pin::Pin;
 // crates
 use async_trait::async_trait;
 use backends::NetworkBackend;
 use futures::{Stream, StreamExt};
+use libp2p::PeerId;
+use nomos_service_status::StatusUpdater;
 use overwatch_rs::services::life_cycle::LifecycleMessage;
 use overwatch_rs::services::{
     handle::ServiceStateHandle,
@@ -15,6 +18,7 @@ use overwatch_rs::services::{
     ServiceCore, ServiceData, ServiceId,
 };
 use serde::{Deserialize, Serialize};
+use subnetworks_assignations::epochs::Epoch;
 use tokio::sync::oneshot;
 use tracing::error;
 // internal
@@ -25,6 +29,13 @@ pub enum DaNetworkMsg<B: NetworkBackend> {
         kind: B::EventKind,
         sender: oneshot::Sender<Pin<Box<dyn Stream<Item = B::NetworkEvent> + Send>>>,
     },
+    /// A new epoch started, membership is assigned again using the epoch nonce as `seed`
+    UpdateMembership {
+        epoch: Epoch,
+        seed: [u8; 32],
+        /// Peers holding stake in the epoch, `None` keeps the configured members
+        members: Option<HashSet<PeerId>>,
+    },
 }
 
 impl<B: NetworkBackend> Debug for DaNetworkMsg<B> {
@@ -34,6 +45,9 @@ impl<B: NetworkBackend> Debug for DaNetworkMsg<B> {
             Self::Subscribe { kind, .. } => {
                 write!(fmt, "DaNetworkMsg::Subscribe{{ kind: {kind:?}}}")
             }
+            Self::UpdateMembership { epoch, .. } => {
+                write!(fmt, "DaNetworkMsg::UpdateMembership{{ epoch: {epoch}}}")
+            }
         }
     }
 }
@@ -54,6 +68,7 @@ impl<B: NetworkBackend> Debug for NetworkConfig<B> {
 pub struct NetworkService<B: NetworkBackend + Send + 'static> {
     backend: B,
     service_state: ServiceStateHandle<Self>,
+    status: StatusUpdater,
 }
 
 pub struct NetworkState<B: NetworkBackend> {
@@ -75,12 +90,14 @@ where
     B::State: Send + Sync,
 {
     fn init(service_state: ServiceStateHandle<Self>) -> Result<Self, overwatch_rs::DynError> {
+        let status = nomos_service_status::updater::<Self>(&service_state.overwatch_handle);
         Ok(Self {
             backend: <B as NetworkBackend>::new(
                 service_state.settings_reader.get_updated_settings().backend,
                 service_state.overwatch_handle.clone(),
             ),
             service_state,
+            status,
         })
     }
 
@@ -93,8 +110,10 @@ where
                     ..
                 },
             mut backend,
+            status,
         } = self;
         let mut lifecycle_stream = lifecycle_handle.message_stream();
+        status.ready();
         loop {
             tokio::select! {
                 Some(msg) = inbound_relay.recv() => {
@@ -102,6 +121,7 @@ where
                 }
                 Some(msg) = lifecycle_stream.next() => {
                     if Self::should_stop_service(msg).await {
+                        status.stopped("shutdown");
                         backend.shutdown();
                         break;
                     }
@@ -132,6 +152,11 @@ where
                         "client hung up before a subscription handle could be established"
                     )
                 }),
+            DaNetworkMsg::UpdateMembership {
+                epoch,
+                seed,
+                members,
+            } => backend.update_membership(epoch, seed, members).await,
         }
     }
 
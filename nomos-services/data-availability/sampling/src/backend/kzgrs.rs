@@ -14,7 +14,10 @@ use kzgrs_backend::common::blob::DaBlob;
 
 //
 // internal
-use crate::{backend::SamplingState, DaSamplingServiceBackend};
+use crate::{
+    backend::{SamplingRetry, SamplingState},
+    DaSamplingServiceBackend,
+};
 use nomos_core::da::BlobId;
 use nomos_da_network_core::SubnetworkId;
 
@@ -22,16 +25,48 @@ use nomos_da_network_core::SubnetworkId;
 pub struct SamplingContext {
     subnets: HashSet<SubnetworkId>,
     started: Instant,
+    /// Number of subnetworks selected for sampling
+    requested: usize,
+    /// Retries done so far for each subnetwork
+    retries: HashMap<SubnetworkId, u16>,
+    /// Subnetworks that failed after exhausting their retries
+    failed: HashSet<SubnetworkId>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct KzgrsSamplingBackendSettings {
     pub num_samples: u16,
+    /// Subnetworks out of `num_samples` that must be sampled successfully to validate a blob,
+    /// [`ALL_SAMPLES`] by default.
+    #[serde(default = "default_required_samples")]
+    pub required_samples: u16,
+    /// One subnetwork per column, so also the domain size of the sampled blobs
     pub num_subnets: u16,
+    /// Times a subnetwork is sampled again after a failure
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u16,
+    /// Wait before the first retry of a subnetwork, doubled on every following one
+    #[serde(default = "default_retry_backoff")]
+    pub retry_backoff: Duration,
     pub old_blobs_check_interval: Duration,
     pub blobs_validity_duration: Duration,
 }
 
+/// `required_samples` requiring every sampled subnetwork to succeed
+pub const ALL_SAMPLES: u16 = u16::MAX;
+
+fn default_required_samples() -> u16 {
+    ALL_SAMPLES
+}
+
+fn default_max_retries() -> u16 {
+    3
+}
+
+fn default_retry_backoff() -> Duration {
+    Duration::from_millis(500)
+}
+
 pub struct KzgrsSamplingBackend<R: Rng> {
     settings: KzgrsSamplingBackendSettings,
     validated_blobs: BTreeSet<BlobId>,
@@ -47,6 +82,11 @@ impl<R: Rng> KzgrsSamplingBackend<R> {
     }
 }
 
+/// Backoff before the `retry`-th retry, doubling from `base`
+fn retry_backoff(base: Duration, retry: u16) -> Duration {
+    base.saturating_mul(2u32.saturating_pow(u32::from(retry.saturating_sub(1))))
+}
+
 #[async_trait::async_trait]
 impl<R: Rng + Sync + Send> DaSamplingServiceBackend<R> for KzgrsSamplingBackend<R> {
     type Settings = KzgrsSamplingBackendSettings;
@@ -79,6 +119,17 @@ impl<R: Rng + Sync + Send> DaSamplingServiceBackend<R> for KzgrsSamplingBackend<
     }
 
     async fn handle_sampling_success(&mut self, blob_id: Self::BlobId, blob: Self::Blob) {
+        let required_samples = self.settings.required_samples as usize;
+        // a column outside of the domain can't come from the blob we are sampling
+        if blob.column_idx >= self.settings.num_subnets {
+            tracing::warn!(
+                "sampled column {} for blob id {} is outside of the {} columns domain",
+                blob.column_idx,
+                hex::encode(blob_id),
+                self.settings.num_subnets
+            );
+            return;
+        }
         if let Some(ctx) = self.pending_sampling_blobs.get_mut(&blob_id) {
             tracing::info!(
                 "subnet {} for blob id {} has been successfully sampled",
@@ -88,7 +139,7 @@ impl<R: Rng + Sync + Send> DaSamplingServiceBackend<R> for KzgrsSamplingBackend<
             ctx.subnets.insert(blob.column_idx as SubnetworkId);
 
             // sampling of this blob_id terminated successfully
-            if ctx.subnets.len() == self.settings.num_samples as usize {
+            if ctx.subnets.len() >= ctx.requested.min(required_samples) {
                 self.validated_blobs.insert(blob_id);
                 tracing::info!(
                     "blob_id {} has been successfully sampled",
@@ -100,10 +151,41 @@ impl<R: Rng + Sync + Send> DaSamplingServiceBackend<R> for KzgrsSamplingBackend<
         }
     }
 
-    async fn handle_sampling_error(&mut self, blob_id: Self::BlobId) {
-        // If it fails a single time we consider it failed.
-        // We may want to abstract the sampling policies somewhere else at some point if we
-        // need to get fancier than this
+    async fn handle_sampling_error(
+        &mut self,
+        blob_id: Self::BlobId,
+        subnetwork_id: SubnetworkId,
+    ) -> SamplingRetry {
+        let required_samples = self.settings.required_samples as usize;
+        let Some(ctx) = self.pending_sampling_blobs.get_mut(&blob_id) else {
+            return SamplingRetry::Wait;
+        };
+        if ctx.subnets.contains(&subnetwork_id) || ctx.failed.contains(&subnetwork_id) {
+            return SamplingRetry::Wait;
+        }
+        let retries = ctx.retries.entry(subnetwork_id).or_default();
+        if *retries < self.settings.max_retries {
+            *retries += 1;
+            return SamplingRetry::Retry {
+                subnetwork_id,
+                backoff: retry_backoff(self.settings.retry_backoff, *retries),
+            };
+        }
+        // the subnetwork is given up, fail once the rest can't reach the threshold anymore
+        ctx.failed.insert(subnetwork_id);
+        if ctx.requested.saturating_sub(ctx.failed.len()) >= ctx.requested.min(required_samples) {
+            return SamplingRetry::Wait;
+        }
+        tracing::info!(
+            "blob_id {} can't be sampled from enough subnets",
+            hex::encode(blob_id)
+        );
+        self.pending_sampling_blobs.remove(&blob_id);
+        self.validated_blobs.remove(&blob_id);
+        SamplingRetry::Failed
+    }
+
+    async fn abort_sampling(&mut self, blob_id: Self::BlobId) {
         self.pending_sampling_blobs.remove(&blob_id);
         self.validated_blobs.remove(&blob_id);
     }
@@ -122,6 +204,9 @@ impl<R: Rng + Sync + Send> DaSamplingServiceBackend<R> for KzgrsSamplingBackend<
         let ctx: SamplingContext = SamplingContext {
             subnets: HashSet::new(),
             started: Instant::now(),
+            requested: subnets.len(),
+            retries: HashMap::new(),
+            failed: HashSet::new(),
         };
         self.pending_sampling_blobs.insert(blob_id, ctx);
         SamplingState::Init(subnets)
@@ -135,7 +220,7 @@ impl<R: Rng + Sync + Send> DaSamplingServiceBackend<R> for KzgrsSamplingBackend<
 #[cfg(test)]
 mod test {
 
-    use std::collections::HashSet;
+    use std::collections::{HashMap, HashSet};
     use std::time::{Duration, Instant};
 
     use rand::prelude::*;
@@ -143,15 +228,27 @@ mod test {
 
     use crate::backend::kzgrs::{
         DaSamplingServiceBackend, KzgrsSamplingBackend, KzgrsSamplingBackendSettings,
-        SamplingContext, SamplingState,
+        SamplingContext, SamplingRetry, SamplingState,
     };
     use kzgrs_backend::common::{blob::DaBlob, Column};
     use nomos_core::da::BlobId;
 
     fn create_sampler(num_samples: usize, num_subnets: usize) -> KzgrsSamplingBackend<StdRng> {
+        create_sampler_with_policy(num_samples, num_samples, num_subnets, 0)
+    }
+
+    fn create_sampler_with_policy(
+        num_samples: usize,
+        required_samples: usize,
+        num_subnets: usize,
+        max_retries: u16,
+    ) -> KzgrsSamplingBackend<StdRng> {
         let settings = KzgrsSamplingBackendSettings {
             num_samples: num_samples as u16,
+            required_samples: required_samples as u16,
             num_subnets: num_subnets as u16,
+            max_retries,
+            retry_backoff: Duration::from_millis(100),
             old_blobs_check_interval: Duration::from_millis(20),
             blobs_validity_duration: Duration::from_millis(10),
         };
@@ -159,6 +256,18 @@ mod test {
         KzgrsSamplingBackend::new(settings, rng)
     }
 
+    fn blob_with_column(column_idx: u16) -> DaBlob {
+        DaBlob {
+            column_idx,
+            column: Column(vec![]),
+            column_commitment: Default::default(),
+            aggregated_column_commitment: Default::default(),
+            aggregated_column_proof: Default::default(),
+            rows_commitments: vec![],
+            rows_proofs: vec![],
+        }
+    }
+
     #[tokio::test]
     async fn test_init_sampling_subnet_range() {
         let number_of_subnets = 42;
@@ -204,7 +313,7 @@ mod test {
         let b1: BlobId = sampler.rng.gen();
         let b2: BlobId = sampler.rng.gen();
         let blob = DaBlob {
-            column_idx: 42,
+            column_idx: 0,
             column: Column(vec![]),
             column_commitment: Default::default(),
             aggregated_column_commitment: Default::default(),
@@ -246,9 +355,12 @@ mod test {
         _ = sampler.init_sampling(b1).await;
         _ = sampler.init_sampling(b2).await;
 
-        // handle ficticious error for b2
+        // handle ficticious error for b2, without retries
         // b2 should be gone, b1 still around
-        sampler.handle_sampling_error(b2).await;
+        assert_eq!(
+            sampler.handle_sampling_error(b2, 0).await,
+            SamplingRetry::Failed
+        );
         assert!(sampler.validated_blobs.is_empty());
         assert!(sampler.pending_sampling_blobs.len() == 1);
         assert!(sampler.pending_sampling_blobs.contains_key(&b1));
@@ -302,7 +414,7 @@ mod test {
         // handle_success for up to subnet size minus one subnet
         // should still not change anything
         // but subnets len is now subnet size minus one
-        // we already added subnet 42
+        // we already added subnet 0
         for i in 1..(subnet_num - 1) {
             let mut b = blob2.clone();
             b.column_idx = i as u16;
@@ -340,6 +452,24 @@ mod test {
         assert!(sampler.pending_sampling_blobs.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_sampling_success_outside_domain() {
+        let mut sampler = create_sampler(2, 4);
+        let blob_id: BlobId = sampler.rng.gen();
+        _ = sampler.init_sampling(blob_id).await;
+
+        // columns past the domain size don't count as sampled subnetworks
+        sampler
+            .handle_sampling_success(blob_id, blob_with_column(4))
+            .await;
+        assert!(sampler
+            .pending_sampling_blobs
+            .get(&blob_id)
+            .unwrap()
+            .subnets
+            .is_empty());
+    }
+
     #[tokio::test]
     async fn test_pruning() {
         let mut sampler = create_sampler(42, 42);
@@ -349,6 +479,9 @@ mod test {
         let ctx1 = SamplingContext {
             subnets: HashSet::new(),
             started: Instant::now(),
+            requested: 42,
+            retries: HashMap::new(),
+            failed: HashSet::new(),
         };
         let ctx2 = ctx1.clone();
         let ctx3 = ctx1.clone();
@@ -357,6 +490,9 @@ mod test {
         let ctx11 = SamplingContext {
             subnets: HashSet::new(),
             started: Instant::now() - Duration::from_secs(1),
+            requested: 42,
+            retries: HashMap::new(),
+            failed: HashSet::new(),
         };
         let ctx12 = ctx11.clone();
         let ctx13 = ctx11.clone();
@@ -399,4 +535,100 @@ mod test {
         sampler.prune();
         assert!(sampler.pending_sampling_blobs.is_empty());
     }
+
+    #[tokio::test]
+    async fn test_sampling_retries() {
+        let mut sampler = create_sampler_with_policy(2, 2, 2, 2);
+        let b1: BlobId = sampler.rng.gen();
+        let SamplingState::Init(subnets) = sampler.init_sampling(b1).await else {
+            panic!("unexpected return value")
+        };
+        let (first, second) = (subnets[0], subnets[1]);
+
+        // failures are retried with an exponential backoff
+        assert_eq!(
+            sampler.handle_sampling_error(b1, first).await,
+            SamplingRetry::Retry {
+                subnetwork_id: first,
+                backoff: Duration::from_millis(100)
+            }
+        );
+        assert_eq!(
+            sampler.handle_sampling_error(b1, first).await,
+            SamplingRetry::Retry {
+                subnetwork_id: first,
+                backoff: Duration::from_millis(200)
+            }
+        );
+        // retries are counted per subnetwork
+        assert_eq!(
+            sampler.handle_sampling_error(b1, second).await,
+            SamplingRetry::Retry {
+                subnetwork_id: second,
+                backoff: Duration::from_millis(100)
+            }
+        );
+        sampler
+            .handle_sampling_success(b1, blob_with_column(second as u16))
+            .await;
+        // late errors of sampled subnetworks are ignored
+        assert_eq!(
+            sampler.handle_sampling_error(b1, second).await,
+            SamplingRetry::Wait
+        );
+        assert!(sampler.pending_sampling_blobs.contains_key(&b1));
+
+        // once out of retries both subnetworks can't be sampled anymore
+        assert_eq!(
+            sampler.handle_sampling_error(b1, first).await,
+            SamplingRetry::Failed
+        );
+        assert!(sampler.pending_sampling_blobs.is_empty());
+        assert!(sampler.validated_blobs.is_empty());
+        // errors of blobs no longer sampled are ignored
+        assert_eq!(
+            sampler.handle_sampling_error(b1, first).await,
+            SamplingRetry::Wait
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sampling_threshold() {
+        // 2 out of 3 subnetworks are enough
+        let mut sampler = create_sampler_with_policy(3, 2, 3, 0);
+        let b1: BlobId = sampler.rng.gen();
+        let b2: BlobId = sampler.rng.gen();
+        let _ = sampler.init_sampling(b1).await;
+        let _ = sampler.init_sampling(b2).await;
+
+        // a single subnetwork can fail
+        assert_eq!(
+            sampler.handle_sampling_error(b1, 0).await,
+            SamplingRetry::Wait
+        );
+        sampler
+            .handle_sampling_success(b1, blob_with_column(1))
+            .await;
+        assert!(sampler.validated_blobs.is_empty());
+        sampler
+            .handle_sampling_success(b1, blob_with_column(2))
+            .await;
+        assert!(sampler.validated_blobs.contains(&b1));
+        assert!(!sampler.pending_sampling_blobs.contains_key(&b1));
+
+        // but not two of them
+        assert_eq!(
+            sampler.handle_sampling_error(b2, 0).await,
+            SamplingRetry::Wait
+        );
+        sampler
+            .handle_sampling_success(b2, blob_with_column(1))
+            .await;
+        assert_eq!(
+            sampler.handle_sampling_error(b2, 2).await,
+            SamplingRetry::Failed
+        );
+        assert!(!sampler.validated_blobs.contains(&b2));
+        assert!(sampler.pending_sampling_blobs.is_empty());
+    }
 }
@@ -2,6 +2,7 @@ pub mod kzgrs;
 
 // std
 use std::collections::BTreeSet;
+use std::time::Duration;
 // crates
 use rand::Rng;
 use tokio::time::Interval;
@@ -14,6 +15,21 @@ pub enum SamplingState {
     Terminated,
 }
 
+/// What to do after sampling a subnetwork failed
+#[derive(Debug, PartialEq, Eq)]
+pub enum SamplingRetry {
+    /// Sample the subnetwork again once `backoff` elapsed
+    Retry {
+        subnetwork_id: SubnetworkId,
+        backoff: Duration,
+    },
+    /// The blob can still be sampled successfully from the rest of subnetworks, or it isn't
+    /// being sampled anymore
+    Wait,
+    /// Not enough subnetworks can be sampled anymore, the blob sampling failed
+    Failed,
+}
+
 #[async_trait::async_trait]
 pub trait DaSamplingServiceBackend<R: Rng> {
     type Settings;
@@ -24,7 +40,12 @@ pub trait DaSamplingServiceBackend<R: Rng> {
     async fn get_validated_blobs(&self) -> BTreeSet<Self::BlobId>;
     async fn mark_completed(&mut self, blobs_ids: &[Self::BlobId]);
     async fn handle_sampling_success(&mut self, blob_id: Self::BlobId, blob: Self::Blob);
-    async fn handle_sampling_error(&mut self, blob_id: Self::BlobId);
+    async fn handle_sampling_error(
+        &mut self,
+        blob_id: Self::BlobId,
+        subnetwork_id: SubnetworkId,
+    ) -> SamplingRetry;
+    async fn abort_sampling(&mut self, blob_id: Self::BlobId);
     async fn init_sampling(&mut self, blob_id: Self::BlobId) -> SamplingState;
     fn prune_interval(&self) -> Interval;
     fn prune(&mut self);
@@ -6,12 +6,18 @@ pub mod storage;
 use std::collections::BTreeSet;
 use std::fmt::Debug;
 // crates
+use futures::future::BoxFuture;
+use futures::stream::FuturesUnordered;
+use futures::FutureExt;
 use kzgrs_backend::common::blob::DaBlob;
 use network::NetworkAdapter;
 use nomos_core::da::BlobId;
+use nomos_da_network_core::SubnetworkId;
 use nomos_da_network_service::backends::libp2p::common::SamplingEvent;
 use nomos_da_network_service::NetworkService;
+use nomos_service_status::StatusUpdater;
 use nomos_storage::StorageService;
+use opentelemetry::{global, metrics::Counter, KeyValue};
 use overwatch_rs::services::handle::ServiceStateHandle;
 use overwatch_rs::services::life_cycle::LifecycleMessage;
 use overwatch_rs::services::relay::{Relay, RelayMessage};
@@ -20,14 +26,19 @@ use overwatch_rs::services::{ServiceCore, ServiceData, ServiceId};
 use overwatch_rs::DynError;
 use rand::{RngCore, SeedableRng};
 use serde::{Deserialize, Serialize};
-use tokio::sync::oneshot;
+use tokio::sync::{broadcast, oneshot};
 use tokio_stream::StreamExt;
-use tracing::{error, span, Instrument, Level};
+use tracing::{error, field, info_span, span, Instrument, Level, Span};
 // internal
-use backend::{DaSamplingServiceBackend, SamplingState};
+use backend::{DaSamplingServiceBackend, SamplingRetry, SamplingState};
 use storage::DaStorageAdapter;
 
 const DA_SAMPLING_TAG: ServiceId = "DA-Sampling";
+// Slow subscribers lag behind and miss outcomes once this many are pending
+const SAMPLING_OUTCOMES_CAPACITY: usize = 1024;
+
+/// Subnetworks waiting for their backoff to be sampled again
+type PendingRetries = FuturesUnordered<BoxFuture<'static, (BlobId, SubnetworkId)>>;
 
 #[derive(Debug)]
 pub enum DaSamplingServiceMsg<BlobId> {
@@ -40,6 +51,62 @@ pub enum DaSamplingServiceMsg<BlobId> {
     MarkInBlock {
         blobs_id: Vec<BlobId>,
     },
+    Subscribe {
+        reply_channel: oneshot::Sender<broadcast::Receiver<SamplingOutcome<BlobId>>>,
+    },
+}
+
+/// Final result of sampling a blob
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SamplingOutcome<BlobId> {
+    Success { blob_id: BlobId },
+    Failure { blob_id: BlobId },
+}
+
+struct SamplingMetrics {
+    successes: Counter<u64>,
+    failures: Counter<u64>,
+}
+
+impl SamplingMetrics {
+    fn new() -> Self {
+        let meter = global::meter("da-sampling");
+        Self {
+            successes: meter
+                .u64_counter("da_sampling_successes")
+                .with_description("Columns successfully sampled")
+                .init(),
+            failures: meter
+                .u64_counter("da_sampling_failures")
+                .with_description("Failed sampling requests")
+                .init(),
+        }
+    }
+}
+
+/// Span of the handling of a sampling event, with the blob and peer it concerns when known
+fn sampling_event_span(event: &SamplingEvent) -> Span {
+    match event {
+        SamplingEvent::SamplingSuccess { blob_id, blob } => info_span!(
+            "sampling_success",
+            blob_id = %hex::encode(blob_id),
+            subnetwork_id = blob.column_idx
+        ),
+        SamplingEvent::SamplingError { error } => info_span!(
+            "sampling_error",
+            blob_id = error.blob_id().map(|blob_id| field::display(hex::encode(blob_id))),
+            peer_id = %error.peer_id()
+        ),
+        SamplingEvent::SamplingRequest {
+            blob_id,
+            column_idx,
+            ..
+        } => info_span!(
+            "sampling_request",
+            blob_id = %hex::encode(blob_id),
+            subnetwork_id = column_idx
+        ),
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -66,6 +133,8 @@ where
     storage_relay: Relay<StorageService<DaStorage::Backend>>,
     service_state: ServiceStateHandle<Self>,
     sampler: Backend,
+    outcomes: broadcast::Sender<SamplingOutcome<Backend::BlobId>>,
+    status: StatusUpdater,
 }
 
 impl<Backend, DaNetwork, SamplingRng, DaStorage>
@@ -97,20 +166,32 @@ where
         msg: <Self as ServiceData>::Message,
         network_adapter: &mut DaNetwork,
         sampler: &mut Backend,
+        outcomes: &broadcast::Sender<SamplingOutcome<BlobId>>,
+        status: &StatusUpdater,
     ) {
         match msg {
             DaSamplingServiceMsg::TriggerSampling { blob_id } => {
-                if let SamplingState::Init(sampling_subnets) = sampler.init_sampling(blob_id).await
-                {
-                    if let Err(e) = network_adapter
-                        .start_sampling(blob_id, &sampling_subnets)
-                        .await
+                async {
+                    if let SamplingState::Init(sampling_subnets) =
+                        sampler.init_sampling(blob_id).await
                     {
-                        // we can short circuit the failure from beginning
-                        sampler.handle_sampling_error(blob_id).await;
-                        error!("Error sampling for BlobId: {blob_id:?}: {e}");
+                        match network_adapter
+                            .start_sampling(blob_id, &sampling_subnets)
+                            .await
+                        {
+                            Ok(()) => status.ready(),
+                            Err(e) => {
+                                // we can short circuit the failure from beginning
+                                sampler.abort_sampling(blob_id).await;
+                                let _ = outcomes.send(SamplingOutcome::Failure { blob_id });
+                                status.degraded(format!("cannot start sampling: {e}"));
+                                error!("Error sampling for BlobId: {blob_id:?}: {e}");
+                            }
+                        }
                     }
                 }
+                .instrument(info_span!("trigger_sampling", blob_id = %hex::encode(blob_id)))
+                .await
             }
             DaSamplingServiceMsg::GetValidatedBlobs { reply_channel } => {
                 let validated_blobs = sampler.get_validated_blobs().await;
@@ -121,6 +202,11 @@ where
             DaSamplingServiceMsg::MarkInBlock { blobs_id } => {
                 sampler.mark_completed(&blobs_id).await;
             }
+            DaSamplingServiceMsg::Subscribe { reply_channel } => {
+                if let Err(_e) = reply_channel.send(outcomes.subscribe()) {
+                    error!("Error replying sampling subscription request");
+                }
+            }
         }
     }
 
@@ -128,14 +214,44 @@ where
         event: SamplingEvent,
         sampler: &mut Backend,
         storage_adapter: &DaStorage,
+        outcomes: &broadcast::Sender<SamplingOutcome<BlobId>>,
+        metrics: &SamplingMetrics,
+        retries: &mut PendingRetries,
     ) {
         match event {
             SamplingEvent::SamplingSuccess { blob_id, blob } => {
+                // columns are sampled from the subnetwork with the same index
+                metrics.successes.add(
+                    1,
+                    &[KeyValue::new("subnetwork", i64::from(blob.column_idx))],
+                );
                 sampler.handle_sampling_success(blob_id, *blob).await;
+                // only notify once the blob has been sampled from all the selected subnetworks
+                if outcomes.receiver_count() > 0
+                    && sampler.get_validated_blobs().await.contains(&blob_id)
+                {
+                    let _ = outcomes.send(SamplingOutcome::Success { blob_id });
+                }
             }
             SamplingEvent::SamplingError { error } => {
-                if let Some(blob_id) = error.blob_id() {
-                    sampler.handle_sampling_error(*blob_id).await;
+                match error.subnetwork_id() {
+                    Some(subnetwork_id) => metrics
+                        .failures
+                        .add(1, &[KeyValue::new("subnetwork", i64::from(*subnetwork_id))]),
+                    None => metrics.failures.add(1, &[]),
+                }
+                if let (Some(blob_id), Some(subnetwork_id)) =
+                    (error.blob_id(), error.subnetwork_id())
+                {
+                    tracing::debug!("Error sampling subnetwork {subnetwork_id}: {error}");
+                    Self::handle_subnetwork_failure(
+                        *blob_id,
+                        *subnetwork_id,
+                        sampler,
+                        outcomes,
+                        retries,
+                    )
+                    .await;
                     return;
                 }
                 error!("Error while sampling: {error}");
@@ -161,6 +277,49 @@ where
             }
         }
     }
+
+    async fn handle_subnetwork_failure(
+        blob_id: BlobId,
+        subnetwork_id: SubnetworkId,
+        sampler: &mut Backend,
+        outcomes: &broadcast::Sender<SamplingOutcome<BlobId>>,
+        retries: &mut PendingRetries,
+    ) {
+        match sampler.handle_sampling_error(blob_id, subnetwork_id).await {
+            SamplingRetry::Retry {
+                subnetwork_id,
+                backoff,
+            } => retries.push(
+                async move {
+                    tokio::time::sleep(backoff).await;
+                    (blob_id, subnetwork_id)
+                }
+                .boxed(),
+            ),
+            SamplingRetry::Wait => {}
+            SamplingRetry::Failed => {
+                let _ = outcomes.send(SamplingOutcome::Failure { blob_id });
+            }
+        }
+    }
+
+    async fn retry_sampling(
+        blob_id: BlobId,
+        subnetwork_id: SubnetworkId,
+        network_adapter: &mut DaNetwork,
+        sampler: &mut Backend,
+        outcomes: &broadcast::Sender<SamplingOutcome<BlobId>>,
+        retries: &mut PendingRetries,
+    ) {
+        if let Err(e) = network_adapter
+            .start_sampling(blob_id, &[subnetwork_id])
+            .await
+        {
+            error!("Error sampling again subnetwork {subnetwork_id} for BlobId: {blob_id:?}: {e}");
+            Self::handle_subnetwork_failure(blob_id, subnetwork_id, sampler, outcomes, retries)
+                .await;
+        }
+    }
 }
 
 impl<Backend, DaNetwork, SamplingRng, DaStorage> ServiceData
@@ -206,11 +365,14 @@ where
         let storage_relay = service_state.overwatch_handle.relay();
         let rng = SamplingRng::from_entropy();
 
+        let status = nomos_service_status::updater::<Self>(&service_state.overwatch_handle);
         Ok(Self {
             network_relay,
             storage_relay,
             service_state,
             sampler: Backend::new(sampling_settings, rng),
+            outcomes: broadcast::channel(SAMPLING_OUTCOMES_CAPACITY).0,
+            status,
         })
     }
 
@@ -224,6 +386,8 @@ where
             storage_relay,
             mut service_state,
             mut sampler,
+            outcomes,
+            status,
         } = self;
         let DaSamplingServiceSettings {
             storage_adapter_settings,
@@ -240,17 +404,25 @@ where
         let storage_adapter = DaStorage::new(storage_adapter_settings, storage_relay).await;
 
         let mut lifecycle_stream = service_state.lifecycle_handle.message_stream();
+        let metrics = SamplingMetrics::new();
+        let mut retries = PendingRetries::new();
+        status.ready();
         async {
             loop {
                 tokio::select! {
                     Some(service_message) = service_state.inbound_relay.recv() => {
-                        Self::handle_service_message(service_message, &mut network_adapter, &mut sampler).await;
+                        Self::handle_service_message(service_message, &mut network_adapter, &mut sampler, &outcomes, &status).await;
                     }
                     Some(sampling_message) = sampling_message_stream.next() => {
-                        Self::handle_sampling_message(sampling_message, &mut sampler, &storage_adapter).await;
+                        let span = sampling_event_span(&sampling_message);
+                        Self::handle_sampling_message(sampling_message, &mut sampler, &storage_adapter, &outcomes, &metrics, &mut retries).instrument(span).await;
+                    }
+                    Some((blob_id, subnetwork_id)) = retries.next() => {
+                        Self::retry_sampling(blob_id, subnetwork_id, &mut network_adapter, &mut sampler, &outcomes, &mut retries).await;
                     }
                     Some(msg) = lifecycle_stream.next() => {
                         if Self::should_stop_service(msg).await {
+                            status.stopped("shutdown");
                             break;
                         }
                     }
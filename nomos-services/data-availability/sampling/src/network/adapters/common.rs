@@ -2,7 +2,8 @@ macro_rules! adapter_for {
     ($DaNetworkBackend:ident, $DaNetworkMessage:ident, $DaEventKind:ident, $DaNetworkEvent:ident) => {
         pub struct Libp2pAdapter<Membership>
         where
-            Membership: MembershipHandler<NetworkId = SubnetworkId, Id = PeerId>
+            Membership: MembershipCreator
+                + MembershipHandler<NetworkId = SubnetworkId, Id = PeerId>
                 + Debug
                 + Clone
                 + Send
@@ -17,7 +18,8 @@ macro_rules! adapter_for {
         #[async_trait::async_trait]
         impl<Membership> NetworkAdapter for Libp2pAdapter<Membership>
         where
-            Membership: MembershipHandler<NetworkId = SubnetworkId, Id = PeerId>
+            Membership: MembershipCreator
+                + MembershipHandler<NetworkId = SubnetworkId, Id = PeerId>
                 + Debug
                 + Clone
                 + Send
@@ -21,7 +21,7 @@ use nomos_da_network_service::{DaNetworkMsg, NetworkService};
 use overwatch_rs::services::relay::OutboundRelay;
 use overwatch_rs::services::ServiceData;
 use overwatch_rs::DynError;
-use subnetworks_assignations::MembershipHandler;
+use subnetworks_assignations::{MembershipCreator, MembershipHandler};
 
 adapter_for!(
     DaNetworkExecutorBackend,
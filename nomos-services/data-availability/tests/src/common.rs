@@ -222,7 +222,7 @@ pub fn new_node(
                 persistent_transmission: Default::default(),
                 message_blend: MessageBlendSettings {
                     cryptographic_processor: CryptographicProcessorSettings {
-                        private_key: mix_config.private_key.to_bytes(),
+                        private_key: Some(mix_config.private_key.to_bytes()),
                         num_mix_layers: 1,
                     },
                     temporal_processor: TemporalSchedulerSettings {
@@ -245,6 +245,8 @@ pub fn new_node(
                     ),
                     addresses: da_network_settings.peer_addresses.into_iter().collect(),
                     listening_address: da_network_settings.listening_address,
+                    sampling: Default::default(),
+                    replication: Default::default(),
                 },
             },
             cl_mempool: TxMempoolSettings {
@@ -270,6 +272,7 @@ pub fn new_node(
                 storage: IndexerStorageSettings {
                     blob_storage_directory: blobs_dir.clone(),
                 },
+                retention: Default::default(),
             },
             cryptarchia: cryptarchia_consensus::CryptarchiaSettings {
                 transaction_selector_settings: (),
@@ -301,7 +304,10 @@ pub fn new_node(
                 // TODO: setup this properly!
                 sampling_settings: KzgrsSamplingBackendSettings {
                     num_samples: da_network_settings.num_samples,
+                    required_samples: da_network_settings.num_samples,
                     num_subnets: da_network_settings.num_subnets,
+                    max_retries: 3,
+                    retry_backoff: Duration::from_millis(500),
                     // Sampling service period can't be zero.
                     old_blobs_check_interval: Duration::from_secs(5),
                     blobs_validity_duration: Duration::from_secs(u64::MAX),
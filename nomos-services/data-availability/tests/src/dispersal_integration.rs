@@ -0,0 +1,328 @@
+// std
+use std::{path::PathBuf, str::FromStr, time::Duration};
+// crates
+use cl::{NoteWitness, NullifierSecret};
+use cryptarchia_consensus::{LeaderConfig, TimeConfig};
+use kzgrs_backend::common::blob::DaBlob;
+use kzgrs_backend::dispersal::{BlobInfo, Metadata};
+use libp2p::identity::{ed25519, PeerId};
+use nomos_core::{da::blob::info::DispersedBlobInfo, header::HeaderId, staking::NMO_UNIT};
+use nomos_da_dispersal::adapters::mempool::kzgrs::KzgrsMempoolAdapter;
+use nomos_da_dispersal::adapters::network::libp2p::Libp2pNetworkAdapter as DispersalNetworkAdapter;
+use nomos_da_dispersal::backend::kzgrs::{
+    DispersalKZGRSBackend, DispersalKZGRSBackendSettings, EncoderSettings,
+};
+use nomos_da_dispersal::{DaDispersalMsg, DispersalService, DispersalServiceSettings};
+use nomos_da_network_service::backends::libp2p::common::DaNetworkBackendSettings;
+use nomos_da_network_service::backends::libp2p::executor::{
+    DaNetworkExecutorBackend, DaNetworkExecutorBackendSettings,
+};
+use nomos_da_network_service::NetworkConfig as DaNetworkConfig;
+use nomos_da_network_service::NetworkService as DaNetworkService;
+use nomos_da_sampling::backend::kzgrs::{KzgrsSamplingBackend, KzgrsSamplingBackendSettings};
+use nomos_da_sampling::network::adapters::executor::Libp2pAdapter as SamplingLibp2pAdapter;
+use nomos_da_sampling::storage::adapters::rocksdb::RocksAdapter as SamplingStorageAdapter;
+use nomos_da_sampling::storage::adapters::rocksdb::RocksAdapterSettings as SamplingStorageSettings;
+use nomos_da_sampling::{DaSamplingService, DaSamplingServiceSettings};
+use nomos_da_verifier::backend::kzgrs::KzgrsDaVerifierSettings;
+use nomos_ledger::LedgerState;
+use nomos_libp2p::{Multiaddr, SwarmConfig};
+use nomos_mempool::backend::mockpool::MockPool;
+use nomos_mempool::da::service::DaMempoolService;
+use nomos_mempool::network::adapters::libp2p::Libp2pAdapter as MempoolNetworkAdapter;
+use nomos_mempool::network::adapters::libp2p::Settings as AdapterSettings;
+use nomos_mempool::DaMempoolSettings;
+use nomos_network::backends::libp2p::{Libp2p as NetworkBackend, Libp2pConfig};
+use nomos_network::{NetworkConfig, NetworkService};
+use nomos_node::Wire;
+use nomos_storage::backends::rocksdb::RocksBackend;
+use nomos_storage::StorageService;
+use nomos_tracing::tracing::memory::InMemorySpanExporter;
+use nomos_tracing_service::{LoggerLayer, Tracing, TracingLayer, TracingSettings};
+use overwatch_derive::*;
+use overwatch_rs::overwatch::{Overwatch, OverwatchRunner};
+use overwatch_rs::services::handle::ServiceHandle;
+use rand::{thread_rng, Rng};
+use subnetworks_assignations::versions::v1::FillFromNodeList;
+use tempfile::{NamedTempFile, TempDir};
+use time::OffsetDateTime;
+// internal
+use crate::common::*;
+use crate::rng::TestRng;
+
+type ExecutorDaSampling = DaSamplingService<
+    KzgrsSamplingBackend<TestRng>,
+    SamplingLibp2pAdapter<NomosDaMembership>,
+    TestRng,
+    SamplingStorageAdapter<DaBlob, Wire>,
+>;
+
+type ExecutorDaMempool = DaMempoolService<
+    MempoolNetworkAdapter<BlobInfo, <BlobInfo as DispersedBlobInfo>::BlobId>,
+    MockPool<HeaderId, BlobInfo, <BlobInfo as DispersedBlobInfo>::BlobId>,
+    KzgrsSamplingBackend<TestRng>,
+    SamplingLibp2pAdapter<NomosDaMembership>,
+    TestRng,
+    SamplingStorageAdapter<DaBlob, Wire>,
+>;
+
+type DispersalMempoolAdapter = KzgrsMempoolAdapter<
+    MempoolNetworkAdapter<BlobInfo, <BlobInfo as DispersedBlobInfo>::BlobId>,
+    MockPool<HeaderId, BlobInfo, <BlobInfo as DispersedBlobInfo>::BlobId>,
+    KzgrsSamplingBackend<TestRng>,
+    SamplingLibp2pAdapter<NomosDaMembership>,
+    TestRng,
+    SamplingStorageAdapter<DaBlob, Wire>,
+>;
+
+type DaDispersal = DispersalService<
+    DispersalKZGRSBackend<DispersalNetworkAdapter<NomosDaMembership>, DispersalMempoolAdapter>,
+    DispersalNetworkAdapter<NomosDaMembership>,
+    DispersalMempoolAdapter,
+    NomosDaMembership,
+    Metadata,
+>;
+
+#[derive(Services)]
+struct TestExecutor {
+    tracing: ServiceHandle<Tracing>,
+    network: ServiceHandle<NetworkService<NetworkBackend>>,
+    da_network: ServiceHandle<DaNetworkService<DaNetworkExecutorBackend<NomosDaMembership>>>,
+    da_sampling: ServiceHandle<ExecutorDaSampling>,
+    da_mempool: ServiceHandle<ExecutorDaMempool>,
+    storage: ServiceHandle<StorageService<RocksBackend<Wire>>>,
+    dispersal: ServiceHandle<DaDispersal>,
+}
+
+fn new_executor(
+    exporter: &InMemorySpanExporter,
+    swarm_config: &SwarmConfig,
+    db_path: PathBuf,
+    blobs_dir: &PathBuf,
+    initial_peers: Vec<Multiaddr>,
+    da_network_settings: TestDaNetworkSettings,
+) -> Overwatch {
+    OverwatchRunner::<TestExecutor>::run(
+        TestExecutorServiceSettings {
+            tracing: TracingSettings {
+                logger: LoggerLayer::None,
+                tracing: TracingLayer::InMemory(exporter.clone()),
+                ..Default::default()
+            },
+            network: NetworkConfig {
+                backend: Libp2pConfig {
+                    inner: swarm_config.clone(),
+                    initial_peers,
+                },
+            },
+            da_network: DaNetworkConfig {
+                backend: DaNetworkExecutorBackendSettings {
+                    validator_settings: DaNetworkBackendSettings {
+                        node_key: da_network_settings.node_key,
+                        membership: FillFromNodeList::new(
+                            &da_network_settings
+                                .peer_addresses
+                                .iter()
+                                .map(|(peer_id, _)| *peer_id)
+                                .collect::<Vec<PeerId>>(),
+                            da_network_settings.num_subnets.into(),
+                            da_network_settings.nodes_per_subnet.into(),
+                        ),
+                        addresses: da_network_settings.peer_addresses.into_iter().collect(),
+                        listening_address: da_network_settings.listening_address,
+                        sampling: Default::default(),
+                        replication: Default::default(),
+                    },
+                    num_subnets: da_network_settings.num_subnets,
+                },
+            },
+            da_sampling: DaSamplingServiceSettings {
+                sampling_settings: KzgrsSamplingBackendSettings {
+                    num_samples: da_network_settings.num_samples,
+                    required_samples: da_network_settings.num_samples,
+                    num_subnets: da_network_settings.num_subnets,
+                    max_retries: 3,
+                    retry_backoff: Duration::from_millis(500),
+                    old_blobs_check_interval: Duration::from_secs(5),
+                    blobs_validity_duration: Duration::from_secs(u64::MAX),
+                },
+                network_adapter_settings: (),
+                storage_adapter_settings: SamplingStorageSettings {
+                    blob_storage_directory: blobs_dir.clone(),
+                },
+            },
+            da_mempool: DaMempoolSettings {
+                backend: (),
+                network: AdapterSettings {
+                    topic: String::from(nomos_node::DA_TOPIC),
+                    id: <BlobInfo as DispersedBlobInfo>::blob_id,
+                },
+            },
+            storage: nomos_storage::backends::rocksdb::RocksBackendSettings {
+                db_path,
+                read_only: false,
+                column_family: Some("blocks".into()),
+            },
+            dispersal: DispersalServiceSettings {
+                backend: DispersalKZGRSBackendSettings {
+                    encoder_settings: EncoderSettings {
+                        num_columns: PARAMS.column_count(),
+                        with_cache: false,
+                        global_params_path: GLOBAL_PARAMS_PATH.into(),
+                    },
+                    dispersal_timeout: Duration::from_secs(20),
+                },
+            },
+        },
+        None,
+    )
+    .map_err(|e| eprintln!("Error encountered: {}", e))
+    .unwrap()
+}
+
+#[test]
+fn test_dispersal_spans() {
+    let exporter = InMemorySpanExporter::default();
+
+    let mut ids = vec![[0; 32]; 2];
+    for id in &mut ids {
+        thread_rng().fill(id);
+    }
+    let sks = ids
+        .iter()
+        .map(|&id| {
+            let mut sk = [0; 16];
+            sk.copy_from_slice(&id[0..16]);
+            NullifierSecret(sk)
+        })
+        .collect::<Vec<_>>();
+
+    let notes = (0..ids.len())
+        .map(|_| NoteWitness::basic(1, NMO_UNIT, &mut thread_rng()))
+        .collect::<Vec<_>>();
+
+    let commitments = notes.iter().zip(&sks).map(|(n, sk)| n.commit(sk.commit()));
+    let genesis_state = LedgerState::from_commitments(commitments, (ids.len() as u32).into());
+    let ledger_config = nomos_ledger::Config {
+        epoch_stake_distribution_stabilization: 3,
+        epoch_period_nonce_buffer: 3,
+        epoch_period_nonce_stabilization: 4,
+        consensus_config: cryptarchia_engine::Config {
+            security_param: 10,
+            active_slot_coeff: 0.9,
+        },
+    };
+    let time_config = TimeConfig {
+        slot_duration: Duration::from_secs(1),
+        chain_start_time: OffsetDateTime::now_utc(),
+    };
+
+    let swarm_configs = [7775, 7776, 7777].map(|port| SwarmConfig {
+        port,
+        ..Default::default()
+    });
+    let mix_configs = new_mix_configs(vec![
+        Multiaddr::from_str("/ip4/127.0.0.1/udp/7785/quic-v1").unwrap(),
+        Multiaddr::from_str("/ip4/127.0.0.1/udp/7786/quic-v1").unwrap(),
+    ]);
+
+    let blobs_dir = TempDir::new().unwrap().path().to_path_buf();
+
+    // the executor disperses to validators which only accept members, so it joins the membership
+    let da_peers: Vec<(ed25519::SecretKey, PeerId, Multiaddr)> = [8882, 8883, 8884]
+        .into_iter()
+        .map(|port| {
+            let (sk, peer_id) = generate_ed25519_sk_peerid();
+            let addr = Multiaddr::from_str(&format!("/ip4/127.0.0.1/udp/{port}/quic-v1")).unwrap();
+            (sk, peer_id, addr)
+        })
+        .collect();
+    let peer_addresses: Vec<_> = da_peers
+        .iter()
+        .map(|(_, peer_id, addr)| (*peer_id, addr.clone()))
+        .collect();
+    let da_network_settings = |i: usize| TestDaNetworkSettings {
+        peer_addresses: peer_addresses.clone(),
+        listening_address: da_peers[i].2.clone(),
+        num_subnets: 2,
+        num_samples: 1,
+        nodes_per_subnet: 2,
+        node_key: da_peers[i].0.clone(),
+    };
+
+    let _validators: Vec<Overwatch> = (0..2)
+        .map(|i| {
+            let (verifier_sk, _) = generate_blst_hex_keys();
+            new_node(
+                &LeaderConfig {
+                    notes: vec![notes[i].clone()],
+                    nf_sk: Some(sks[i]),
+                },
+                &ledger_config,
+                &genesis_state,
+                &time_config,
+                &swarm_configs[i],
+                &mix_configs[i],
+                NamedTempFile::new().unwrap().path().to_path_buf(),
+                &blobs_dir,
+                vec![node_address(&swarm_configs[1 - i])],
+                KzgrsDaVerifierSettings {
+                    sk: Some(verifier_sk),
+                    index: [0, 1].into(),
+                    global_params_path: GLOBAL_PARAMS_PATH.into(),
+                    domain_size: PARAMS.column_count(),
+                },
+                da_network_settings(i),
+            )
+        })
+        .collect();
+
+    let executor = new_executor(
+        &exporter,
+        &swarm_configs[2],
+        NamedTempFile::new().unwrap().path().to_path_buf(),
+        &blobs_dir,
+        vec![node_address(&swarm_configs[0])],
+        da_network_settings(2),
+    );
+
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    runtime.block_on(async {
+        // let the DA network connect to the validators
+        tokio::time::sleep(Duration::from_secs(2)).await;
+
+        let dispersal = executor
+            .handle()
+            .relay::<DaDispersal>()
+            .connect()
+            .await
+            .unwrap();
+        let (reply_channel, reply) = tokio::sync::oneshot::channel();
+        dispersal
+            .send(DaDispersalMsg::Disperse {
+                data: vec![1; 31],
+                metadata: Metadata::new([0; 32], 0u64.into()),
+                reply_channel,
+            })
+            .await
+            .unwrap();
+        reply.await.unwrap().expect("the blob should be dispersed");
+
+        // validators replicate the columns they receive
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while !exporter.span_names().iter().any(|name| name == "replicate") {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+        })
+        .await
+        .expect("the dispersed columns should be replicated");
+    });
+
+    let names = exporter.span_names();
+    for span in ["encode", "disperse", "replicate"] {
+        assert!(
+            names.iter().any(|name| name == span),
+            "missing {span} span in {names:?}"
+        );
+    }
+}
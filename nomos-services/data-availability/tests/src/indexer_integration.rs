@@ -116,7 +116,7 @@ fn test_indexer() {
     let node1 = new_node(
         &LeaderConfig {
             notes: vec![notes[0].clone()],
-            nf_sk: sks[0],
+            nf_sk: Some(sks[0]),
         },
         &ledger_config,
         &genesis_state,
@@ -127,9 +127,10 @@ fn test_indexer() {
         &blobs_dir,
         vec![node_address(&swarm_config2)],
         KzgrsDaVerifierSettings {
-            sk: node1_sk.clone(),
+            sk: Some(node1_sk.clone()),
             index: [0].into(),
             global_params_path: GLOBAL_PARAMS_PATH.into(),
+            domain_size: PARAMS.column_count(),
         },
         TestDaNetworkSettings {
             peer_addresses: peer_addresses.clone(),
@@ -144,7 +145,7 @@ fn test_indexer() {
     let node2 = new_node(
         &LeaderConfig {
             notes: vec![notes[1].clone()],
-            nf_sk: sks[1],
+            nf_sk: Some(sks[1]),
         },
         &ledger_config,
         &genesis_state,
@@ -155,9 +156,10 @@ fn test_indexer() {
         &blobs_dir,
         vec![node_address(&swarm_config1)],
         KzgrsDaVerifierSettings {
-            sk: node2_sk.clone(),
+            sk: Some(node2_sk.clone()),
             index: [1].into(),
             global_params_path: GLOBAL_PARAMS_PATH.into(),
+            domain_size: PARAMS.column_count(),
         },
         TestDaNetworkSettings {
             peer_addresses,
@@ -341,11 +343,13 @@ fn test_indexer() {
             .send(nomos_da_indexer::DaMsg::GetRange {
                 app_id,
                 range,
+                // the block is not `k` deep yet
+                view: nomos_da_indexer::IndexView::Pending,
                 reply_channel: indexer_tx,
             })
             .await
             .unwrap();
-        let mut app_id_blobs = indexer_rx.await.unwrap();
+        let mut app_id_blobs = indexer_rx.await.unwrap().unwrap();
 
         // Since we've only attested to blob_info at idx 0, the first
         // item should have "some" data, other indexes should be None.
@@ -2,6 +2,10 @@
 #[cfg(feature = "libp2p")]
 mod common;
 
+#[cfg(test)]
+#[cfg(feature = "libp2p")]
+mod dispersal_integration;
+
 #[cfg(test)]
 #[cfg(feature = "libp2p")]
 mod indexer_integration;
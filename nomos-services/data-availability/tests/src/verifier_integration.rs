@@ -97,7 +97,7 @@ fn test_verifier() {
     let node1 = new_node(
         &LeaderConfig {
             notes: vec![notes[0].clone()],
-            nf_sk: sks[0],
+            nf_sk: Some(sks[0]),
         },
         &ledger_config,
         &genesis_state,
@@ -108,9 +108,10 @@ fn test_verifier() {
         &blobs_dir,
         vec![node_address(&swarm_config2)],
         KzgrsDaVerifierSettings {
-            sk: node1_sk.clone(),
+            sk: Some(node1_sk.clone()),
             index: [0].into(),
             global_params_path: GLOBAL_PARAMS_PATH.into(),
+            domain_size: PARAMS.column_count(),
         },
         TestDaNetworkSettings {
             peer_addresses: peer_addresses.clone(),
@@ -125,7 +126,7 @@ fn test_verifier() {
     let node2 = new_node(
         &LeaderConfig {
             notes: vec![notes[1].clone()],
-            nf_sk: sks[1],
+            nf_sk: Some(sks[1]),
         },
         &ledger_config,
         &genesis_state,
@@ -136,9 +137,10 @@ fn test_verifier() {
         &blobs_dir,
         vec![node_address(&swarm_config1)],
         KzgrsDaVerifierSettings {
-            sk: node2_sk,
+            sk: Some(node2_sk),
             index: [1].into(),
             global_params_path: GLOBAL_PARAMS_PATH.into(),
+            domain_size: PARAMS.column_count(),
         },
         TestDaNetworkSettings {
             peer_addresses,
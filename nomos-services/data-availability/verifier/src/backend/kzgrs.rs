@@ -29,20 +29,38 @@ impl std::error::Error for KzgrsDaVerifierError {}
 
 pub struct KzgrsDaVerifier {
     verifier: NomosKzgrsVerifier,
+    domain_size: usize,
 }
 
 impl VerifierBackend for KzgrsDaVerifier {
     type Settings = KzgrsDaVerifierSettings;
 
     fn new(settings: Self::Settings) -> Self {
-        let bytes = hex::decode(settings.sk).expect("Secret key string should decode to bytes");
+        let sk = settings
+            .sk
+            .expect("Secret key should be set in the settings or read from the keystore");
+        let bytes = hex::decode(sk).expect("Secret key string should decode to bytes");
         let secret_key =
             SecretKey::from_bytes(&bytes).expect("Secret key should be reconstructed from bytes");
         let global_params = global_parameters_from_file(&settings.global_params_path)
             .expect("Global parameters has to be loaded from file");
 
         let verifier = NomosKzgrsVerifier::new(secret_key, settings.index, global_params);
-        Self { verifier }
+        verifier
+            .check_domain_size(settings.domain_size)
+            .expect("Domain size should be supported by the global parameters");
+        Self {
+            verifier,
+            domain_size: settings.domain_size,
+        }
+    }
+
+    fn verify_batch(&self, blobs: &[Self::DaBlob]) -> Result<(), Self::Error> {
+        // row and column proofs of all the blobs are checked with a single pairing
+        self.verifier
+            .verify_batch(blobs, self.domain_size)
+            .then_some(())
+            .ok_or(KzgrsDaVerifierError::VerificationError)
     }
 }
 
@@ -51,12 +69,8 @@ impl DaVerifier for KzgrsDaVerifier {
     type Error = KzgrsDaVerifierError;
 
     fn verify(&self, blob: &Self::DaBlob) -> Result<(), Self::Error> {
-        let blob = blob.clone();
-        // TODO: Prepare the domain depending the size, if fixed, so fixed domain, if not it needs
-        // to come with some metadata.
-        let domain_size = 2usize;
         self.verifier
-            .verify(&blob, domain_size)
+            .verify(blob, self.domain_size)
             .then_some(())
             .ok_or(KzgrsDaVerifierError::VerificationError)
     }
@@ -65,7 +79,11 @@ impl DaVerifier for KzgrsDaVerifier {
 // TODO: `sk` and `nodes_public_keys` need to be fetched from the params provider service.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct KzgrsDaVerifierSettings {
-    pub sk: String,
+    /// Can be left out when the node reads it from a keystore
+    #[serde(default)]
+    pub sk: Option<String>,
     pub index: HashSet<u32>,
     pub global_params_path: String,
+    /// Columns of the encoded blobs, same as the dispersal encoder `num_columns`
+    pub domain_size: usize,
 }
@@ -5,4 +5,9 @@ use nomos_core::da::DaVerifier;
 pub trait VerifierBackend: DaVerifier {
     type Settings;
     fn new(settings: Self::Settings) -> Self;
+
+    /// Verify `blobs` at once, failing if any of them is invalid
+    fn verify_batch(&self, blobs: &[Self::DaBlob]) -> Result<(), Self::Error> {
+        blobs.iter().try_for_each(|blob| self.verify(blob))
+    }
 }
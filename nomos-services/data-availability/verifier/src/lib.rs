@@ -6,8 +6,10 @@ pub mod storage;
 use std::error::Error;
 use std::fmt::{Debug, Formatter};
 // crates
+use futures::FutureExt;
 use nomos_core::da::blob::Blob;
 use nomos_da_network_service::NetworkService;
+use nomos_service_status::StatusUpdater;
 use nomos_storage::StorageService;
 use overwatch_rs::services::handle::ServiceStateHandle;
 use overwatch_rs::services::life_cycle::LifecycleMessage;
@@ -18,13 +20,16 @@ use overwatch_rs::DynError;
 use serde::{Deserialize, Serialize};
 use tokio::sync::oneshot::Sender;
 use tokio_stream::StreamExt;
-use tracing::{error, span, Instrument, Level};
+use tracing::{error, info_span, span, Instrument, Level, Span};
 // internal
 use backend::VerifierBackend;
 use network::NetworkAdapter;
 use storage::DaStorageAdapter;
 
 const DA_VERIFIER_TAG: ServiceId = "DA-Verifier";
+/// Most blobs received from the network verified at once
+const MAX_VERIFICATION_BATCH: usize = 64;
+
 pub enum DaVerifierMsg<B, A> {
     AddBlob {
         blob: B,
@@ -58,18 +63,25 @@ where
     service_state: ServiceStateHandle<Self>,
     storage_relay: Relay<StorageService<S::Backend>>,
     verifier: Backend,
+    status: StatusUpdater,
 }
 
 impl<Backend, N, S> DaVerifierService<Backend, N, S>
 where
     Backend: VerifierBackend + Send + 'static,
     Backend::DaBlob: Debug + Send,
+    <Backend::DaBlob as Blob>::BlobId: AsRef<[u8]>,
     Backend::Error: Error + Send + Sync,
     Backend::Settings: Clone,
     N: NetworkAdapter<Blob = Backend::DaBlob> + Send + 'static,
     N::Settings: Clone,
     S: DaStorageAdapter<Blob = Backend::DaBlob, Attestation = ()> + Send + 'static,
 {
+    /// Span of the handling of a single blob share
+    fn blob_span(blob: &Backend::DaBlob) -> Span {
+        info_span!("verify_share", blob_id = %hex::encode(blob.id()))
+    }
+
     async fn handle_new_blob(
         verifier: &Backend,
         storage_adapter: &S,
@@ -88,6 +100,52 @@ where
         }
     }
 
+    async fn handle_new_blobs(
+        verifier: &Backend,
+        storage_adapter: &S,
+        blobs: Vec<Backend::DaBlob>,
+    ) {
+        let mut new_blobs = Vec::with_capacity(blobs.len());
+        for blob in blobs {
+            match storage_adapter.get_blob(blob.id(), blob.column_idx()).await {
+                Ok(Some(_)) => {}
+                Ok(None) => new_blobs.push(blob),
+                Err(err) => error!("Error handling blob {blob:?} due to {err:?}"),
+            }
+        }
+        if new_blobs.is_empty() {
+            return;
+        }
+
+        let verified = if verifier.verify_batch(&new_blobs).is_ok() {
+            new_blobs
+        } else {
+            // a single invalid blob fails the whole batch, find out which ones are valid
+            new_blobs
+                .into_iter()
+                .filter(|blob| {
+                    let _span = Self::blob_span(blob).entered();
+                    match verifier.verify(blob) {
+                        Ok(()) => true,
+                        Err(err) => {
+                            error!("Error handling blob {blob:?} due to {err:?}");
+                            false
+                        }
+                    }
+                })
+                .collect()
+        };
+        for blob in verified {
+            if let Err(err) = storage_adapter
+                .add_blob(&blob, &())
+                .instrument(Self::blob_span(&blob))
+                .await
+            {
+                error!("Error handling blob {blob:?} due to {err:?}");
+            }
+        }
+    }
+
     async fn should_stop_service(message: LifecycleMessage) -> bool {
         match message {
             LifecycleMessage::Shutdown(sender) => {
@@ -127,6 +185,7 @@ where
     Backend: VerifierBackend + Send + Sync + 'static,
     Backend::Settings: Clone + Send + Sync + 'static,
     Backend::DaBlob: Debug + Send + Sync + 'static,
+    <Backend::DaBlob as Blob>::BlobId: AsRef<[u8]>,
     Backend::Error: Error + Send + Sync + 'static,
     N: NetworkAdapter<Blob = Backend::DaBlob> + Send + Sync + 'static,
     N::Settings: Clone + Send + Sync + 'static,
@@ -139,11 +198,13 @@ where
         } = service_state.settings_reader.get_updated_settings();
         let network_relay = service_state.overwatch_handle.relay();
         let storage_relay = service_state.overwatch_handle.relay();
+        let status = nomos_service_status::updater::<Self>(&service_state.overwatch_handle);
         Ok(Self {
             network_relay,
             storage_relay,
             service_state,
             verifier: Backend::new(verifier_settings),
+            status,
         })
     }
 
@@ -157,6 +218,7 @@ where
             storage_relay,
             mut service_state,
             verifier,
+            status,
         } = self;
 
         let DaVerifierServiceSettings {
@@ -173,17 +235,24 @@ where
         let storage_adapter = S::new(storage_adapter_settings, storage_relay).await;
 
         let mut lifecycle_stream = service_state.lifecycle_handle.message_stream();
+        status.ready();
         async {
             loop {
                 tokio::select! {
                     Some(blob) = blob_stream.next() => {
-                        if let Err(err) =  Self::handle_new_blob(&verifier,&storage_adapter, &blob).await {
-                            error!("Error handling blob {blob:?} due to {err:?}");
+                        // verify together with the blobs that arrived in the meantime
+                        let mut blobs = vec![blob];
+                        while blobs.len() < MAX_VERIFICATION_BATCH {
+                            match blob_stream.next().now_or_never() {
+                                Some(Some(blob)) => blobs.push(blob),
+                                _ => break,
+                            }
                         }
+                        Self::handle_new_blobs(&verifier, &storage_adapter, blobs).await;
                     }
                     Some(msg) = service_state.inbound_relay.recv() => {
                         let DaVerifierMsg::AddBlob { blob, reply_channel } = msg;
-                        match Self::handle_new_blob(&verifier, &storage_adapter, &blob).await {
+                        match Self::handle_new_blob(&verifier, &storage_adapter, &blob).instrument(Self::blob_span(&blob)).await {
                             Ok(attestation) => if let Err(err) = reply_channel.send(Some(attestation)) {
                                 error!("Error replying attestation {err:?}");
                             },
@@ -197,6 +266,7 @@ where
                     }
                     Some(msg) = lifecycle_stream.next() => {
                         if Self::should_stop_service(msg).await {
+                            status.stopped("shutdown");
                             break;
                         }
                     }
@@ -2,7 +2,8 @@ macro_rules! adapter_for {
     ($DaNetworkBackend:ident, $DaNetworksEventKind:ident, $DaNetworkEvent:ident) => {
         pub struct Libp2pAdapter<M>
         where
-            M: MembershipHandler<NetworkId = SubnetworkId, Id = PeerId>
+            M: MembershipCreator
+                + MembershipHandler<NetworkId = SubnetworkId, Id = PeerId>
                 + Clone
                 + Debug
                 + Send
@@ -17,7 +18,8 @@ macro_rules! adapter_for {
         #[async_trait::async_trait]
         impl<M> NetworkAdapter for Libp2pAdapter<M>
         where
-            M: MembershipHandler<NetworkId = SubnetworkId, Id = PeerId>
+            M: MembershipCreator
+                + MembershipHandler<NetworkId = SubnetworkId, Id = PeerId>
                 + Clone
                 + Debug
                 + Send
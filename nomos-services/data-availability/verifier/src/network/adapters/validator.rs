@@ -13,7 +13,7 @@ use nomos_da_network_service::backends::libp2p::validator::{
 use nomos_da_network_service::NetworkService;
 use overwatch_rs::services::relay::OutboundRelay;
 use overwatch_rs::services::ServiceData;
-use subnetworks_assignations::MembershipHandler;
+use subnetworks_assignations::{MembershipCreator, MembershipHandler};
 use tokio_stream::StreamExt;
 // internal
 use crate::network::NetworkAdapter;
@@ -14,13 +14,15 @@ use rand::{RngCore, SeedableRng};
 // internal
 use crate::backend::MemPool;
 use crate::network::NetworkAdapter;
-use crate::{MempoolMetrics, MempoolMsg};
+use crate::{MempoolMetrics, MempoolMsg, ADMITTED_ITEMS_CAPACITY};
 use nomos_core::da::blob::info::DispersedBlobInfo;
 use nomos_da_sampling::{
     backend::DaSamplingServiceBackend, network::NetworkAdapter as DaSamplingNetworkAdapter,
     DaSamplingService, DaSamplingServiceMsg,
 };
 use nomos_network::{NetworkMsg, NetworkService};
+use nomos_service_status::StatusUpdater;
+use opentelemetry::KeyValue;
 use overwatch_rs::services::life_cycle::LifecycleMessage;
 use overwatch_rs::services::{
     handle::ServiceStateHandle,
@@ -28,6 +30,7 @@ use overwatch_rs::services::{
     state::{NoOperator, NoState},
     ServiceCore, ServiceData, ServiceId,
 };
+use tokio::sync::broadcast;
 use tracing::error;
 
 pub struct DaMempoolService<N, P, DB, DN, R, SamplingStorage>
@@ -51,6 +54,8 @@ where
     network_relay: Relay<NetworkService<N::Backend>>,
     sampling_relay: Relay<DaSamplingService<DB, DN, R, SamplingStorage>>,
     pool: P,
+    admitted_items: broadcast::Sender<P::Item>,
+    status: StatusUpdater,
 }
 
 impl<N, P, DB, DN, R, DaStorage> ServiceData for DaMempoolService<N, P, DB, DN, R, DaStorage>
@@ -106,11 +111,14 @@ where
         let sampling_relay = service_state.overwatch_handle.relay();
         let settings = service_state.settings_reader.get_updated_settings();
 
+        let status = nomos_service_status::updater::<Self>(&service_state.overwatch_handle);
         Ok(Self {
             service_state,
             network_relay,
             sampling_relay,
             pool: P::new(settings.backend),
+            admitted_items: broadcast::channel(ADMITTED_ITEMS_CAPACITY).0,
+            status,
         })
     }
 
@@ -120,6 +128,8 @@ where
             network_relay,
             sampling_relay,
             mut pool,
+            admitted_items,
+            status,
             ..
         } = self;
 
@@ -142,20 +152,30 @@ where
         let mut network_items = adapter.payload_stream().await;
         let mut lifecycle_stream = service_state.lifecycle_handle.message_stream();
 
+        let pending_items = crate::pending_items_gauge();
+        let pool_label = [KeyValue::new("pool", "da")];
+
+        status.ready();
         loop {
             tokio::select! {
                 Some(msg) = service_state.inbound_relay.recv() => {
-                    Self::handle_mempool_message(msg, &mut pool, &mut network_relay, &mut service_state).await;
+                    Self::handle_mempool_message(msg, &mut pool, &mut network_relay, &mut service_state, &admitted_items).await;
+                    pending_items.record(pool.pending_item_count() as u64, &pool_label);
                 }
                 Some((key, item)) = network_items.next() => {
                     sampling_relay.send(DaSamplingServiceMsg::TriggerSampling{blob_id: key.clone()}).await.expect("Sampling trigger message needs to be sent");
-                    pool.add_item(key, item).unwrap_or_else(|e| {
-                        tracing::debug!("could not add item to the pool due to: {}", e)
-                    });
-                    tracing::info!(counter.da_mempool_pending_items = pool.pending_item_count());
+                    match pool.add_item(key, item.clone()) {
+                        Ok(_) => {
+                            // no subscribers is not an error
+                            let _ = admitted_items.send(item.into());
+                        }
+                        Err(e) => tracing::debug!("could not add item to the pool due to: {}", e),
+                    }
+                    pending_items.record(pool.pending_item_count() as u64, &pool_label);
                 }
                 Some(msg) = lifecycle_stream.next() =>  {
                     if Self::should_stop_service(msg).await {
+                        status.stopped("shutdown");
                         break;
                     }
                 }
@@ -203,6 +223,7 @@ where
         pool: &mut P,
         network_relay: &mut OutboundRelay<NetworkMsg<N::Backend>>,
         service_state: &mut ServiceStateHandle<Self>,
+        admitted_items: &broadcast::Sender<P::Item>,
     ) {
         match message {
             MempoolMsg::Add {
@@ -212,6 +233,7 @@ where
             } => {
                 match pool.add_item(key, item.clone()) {
                     Ok(_id) => {
+                        let _ = admitted_items.send(item.clone().into());
                         // Broadcast the item to the network
                         let net = network_relay.clone();
                         let settings = service_state.settings_reader.get_updated_settings().network;
@@ -269,6 +291,13 @@ where
                     .send(pool.status(&items))
                     .unwrap_or_else(|_| tracing::debug!("could not send back mempool status"));
             }
+            MempoolMsg::Subscribe { reply_channel } => {
+                reply_channel
+                    .send(admitted_items.subscribe())
+                    .unwrap_or_else(|_| {
+                        tracing::debug!("could not send back mempool subscription")
+                    });
+            }
         }
     }
 }
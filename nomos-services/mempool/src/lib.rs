@@ -5,13 +5,25 @@ pub mod tx;
 pub mod verify;
 
 use backend::Status;
+use opentelemetry::{global, metrics::Gauge};
 use overwatch_rs::services::relay::RelayMessage;
 use std::fmt::{Debug, Error, Formatter};
+use tokio::sync::broadcast;
 use tokio::sync::oneshot::Sender;
 
 pub use da::service::{DaMempoolService, DaMempoolSettings};
 pub use tx::service::{TxMempoolService, TxMempoolSettings};
 
+// Slow subscribers lag behind and miss items once this many are pending
+const ADMITTED_ITEMS_CAPACITY: usize = 1024;
+
+fn pending_items_gauge() -> Gauge<u64> {
+    global::meter("mempool")
+        .u64_gauge("mempool_pending_items")
+        .with_description("Items waiting in the pool to be included in a block")
+        .init()
+}
+
 pub enum MempoolMsg<BlockId, Payload, Item, Key> {
     Add {
         payload: Payload,
@@ -41,6 +53,10 @@ pub enum MempoolMsg<BlockId, Payload, Item, Key> {
         items: Vec<Key>,
         reply_channel: Sender<Vec<Status<BlockId>>>,
     },
+    /// Subscribe to items admitted into the pool, either from the network or local submissions
+    Subscribe {
+        reply_channel: Sender<broadcast::Receiver<Item>>,
+    },
 }
 
 impl<BlockId, Payload, Item, Key> Debug for MempoolMsg<BlockId, Payload, Item, Key>
@@ -69,6 +85,7 @@ where
             }
             Self::Metrics { .. } => write!(f, "MempoolMsg::Metrics"),
             Self::Status { items, .. } => write!(f, "MempoolMsg::Status{{items: {items:?}}}"),
+            Self::Subscribe { .. } => write!(f, "MempoolMsg::Subscribe"),
         }
     }
 }
@@ -6,23 +6,26 @@ use tokio_stream::StreamExt;
 // internal
 use crate::network::NetworkAdapter;
 use nomos_core::wire;
-use nomos_network::backends::libp2p::{Command, Event, EventKind, Libp2p, Message, TopicHash};
+use nomos_network::backends::libp2p::{
+    Command, Event, EventKind, Libp2p, Libp2pBackend, Message, TopicHash,
+};
 use nomos_network::{NetworkMsg, NetworkService};
 use overwatch_rs::services::relay::OutboundRelay;
 use overwatch_rs::services::ServiceData;
 
-pub struct Libp2pAdapter<Item, Key> {
-    network_relay: OutboundRelay<<NetworkService<Libp2p> as ServiceData>::Message>,
+pub struct Libp2pAdapter<Item, Key, B: Libp2pBackend = Libp2p> {
+    network_relay: OutboundRelay<<NetworkService<B> as ServiceData>::Message>,
     settings: Settings<Key, Item>,
 }
 
 #[async_trait::async_trait]
-impl<Item, Key> NetworkAdapter for Libp2pAdapter<Item, Key>
+impl<Item, Key, B> NetworkAdapter for Libp2pAdapter<Item, Key, B>
 where
     Item: DeserializeOwned + Serialize + Send + Sync + 'static + Clone,
     Key: Clone + Send + Sync + 'static,
+    B: Libp2pBackend,
 {
-    type Backend = Libp2p;
+    type Backend = B;
     type Settings = Settings<Key, Item>;
     type Payload = Item;
     type Key = Key;
@@ -12,8 +12,10 @@ use futures::StreamExt;
 // internal
 use crate::backend::MemPool;
 use crate::network::NetworkAdapter;
-use crate::{MempoolMetrics, MempoolMsg};
+use crate::{MempoolMetrics, MempoolMsg, ADMITTED_ITEMS_CAPACITY};
 use nomos_network::{NetworkMsg, NetworkService};
+use nomos_service_status::StatusUpdater;
+use opentelemetry::KeyValue;
 use overwatch_rs::services::life_cycle::LifecycleMessage;
 use overwatch_rs::services::{
     handle::ServiceStateHandle,
@@ -21,6 +23,7 @@ use overwatch_rs::services::{
     state::{NoOperator, NoState},
     ServiceCore, ServiceData, ServiceId,
 };
+use tokio::sync::broadcast;
 use tracing::error;
 
 pub struct TxMempoolService<N, P>
@@ -35,6 +38,8 @@ where
     service_state: ServiceStateHandle<Self>,
     network_relay: Relay<NetworkService<N::Backend>>,
     pool: P,
+    admitted_items: broadcast::Sender<P::Item>,
+    status: StatusUpdater,
 }
 
 impl<N, P> ServiceData for TxMempoolService<N, P>
@@ -73,10 +78,13 @@ where
         let network_relay = service_state.overwatch_handle.relay();
         let settings = service_state.settings_reader.get_updated_settings();
 
+        let status = nomos_service_status::updater::<Self>(&service_state.overwatch_handle);
         Ok(Self {
             service_state,
             network_relay,
             pool: P::new(settings.backend),
+            admitted_items: broadcast::channel(ADMITTED_ITEMS_CAPACITY).0,
+            status,
         })
     }
 
@@ -85,6 +93,8 @@ where
             mut service_state,
             network_relay,
             mut pool,
+            admitted_items,
+            status,
             ..
         } = self;
 
@@ -102,19 +112,29 @@ where
         let mut network_items = adapter.payload_stream().await;
         let mut lifecycle_stream = service_state.lifecycle_handle.message_stream();
 
+        let pending_items = crate::pending_items_gauge();
+        let pool_label = [KeyValue::new("pool", "cl")];
+
+        status.ready();
         loop {
             tokio::select! {
                 Some(msg) = service_state.inbound_relay.recv() => {
-                    Self::handle_mempool_message(msg, &mut pool, &mut network_relay, &mut service_state).await;
+                    Self::handle_mempool_message(msg, &mut pool, &mut network_relay, &mut service_state, &admitted_items).await;
+                    pending_items.record(pool.pending_item_count() as u64, &pool_label);
                 }
                 Some((key, item )) = network_items.next() => {
-                    pool.add_item(key, item).unwrap_or_else(|e| {
-                        tracing::debug!("could not add item to the pool due to: {}", e)
-                    });
-                    tracing::info!(counter.tx_mempool_pending_items = pool.pending_item_count());
+                    match pool.add_item(key, item.clone()) {
+                        Ok(_) => {
+                            // no subscribers is not an error
+                            let _ = admitted_items.send(item);
+                        }
+                        Err(e) => tracing::debug!("could not add item to the pool due to: {}", e),
+                    }
+                    pending_items.record(pool.pending_item_count() as u64, &pool_label);
                 }
                 Some(msg) = lifecycle_stream.next() =>  {
                     if Self::should_stop_service(msg).await {
+                        status.stopped("shutdown");
                         break;
                     }
                 }
@@ -154,6 +174,7 @@ where
         pool: &mut P,
         network_relay: &mut OutboundRelay<NetworkMsg<N::Backend>>,
         service_state: &mut ServiceStateHandle<Self>,
+        admitted_items: &broadcast::Sender<P::Item>,
     ) {
         match message {
             MempoolMsg::Add {
@@ -163,6 +184,7 @@ where
             } => {
                 match pool.add_item(key, item.clone()) {
                     Ok(_id) => {
+                        let _ = admitted_items.send(item.clone());
                         // Broadcast the item to the network
                         let net = network_relay.clone();
                         let settings = service_state.settings_reader.get_updated_settings().network;
@@ -220,6 +242,13 @@ where
                     .send(pool.status(&items))
                     .unwrap_or_else(|_| tracing::debug!("could not send back mempool status"));
             }
+            MempoolMsg::Subscribe { reply_channel } => {
+                reply_channel
+                    .send(admitted_items.subscribe())
+                    .unwrap_or_else(|_| {
+                        tracing::debug!("could not send back mempool subscription")
+                    });
+            }
         }
     }
 }
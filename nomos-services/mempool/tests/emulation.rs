@@ -0,0 +1,116 @@
+use std::time::Duration;
+
+use nomos_core::header::HeaderId;
+use nomos_libp2p::{ed25519, Multiaddr, Protocol};
+use nomos_network::{
+    backends::emulation::{Emulated, EmulatedNetwork, EmulationConfig, LinkConditions},
+    NetworkConfig, NetworkService,
+};
+use overwatch_derive::*;
+use overwatch_rs::{
+    overwatch::{Overwatch, OverwatchRunner},
+    services::handle::ServiceHandle,
+};
+
+use nomos_mempool::{
+    backend::mockpool::MockPool,
+    network::adapters::libp2p::{Libp2pAdapter, Settings as AdapterSettings},
+    MempoolMsg, TxMempoolService, TxMempoolSettings,
+};
+
+type Mempool =
+    TxMempoolService<Libp2pAdapter<String, String, Emulated>, MockPool<HeaderId, String, String>>;
+
+#[derive(Services)]
+struct EmulatedNode {
+    network: ServiceHandle<NetworkService<Emulated>>,
+    mempool: ServiceHandle<Mempool>,
+}
+
+fn address(i: u64) -> Multiaddr {
+    Multiaddr::empty().with(Protocol::Memory(i))
+}
+
+fn run_node(network: &EmulatedNetwork, i: u64, peers: &[u64]) -> Overwatch {
+    OverwatchRunner::<EmulatedNode>::run(
+        EmulatedNodeServiceSettings {
+            network: NetworkConfig {
+                backend: EmulationConfig {
+                    network: network.clone(),
+                    node_key: ed25519::SecretKey::generate(),
+                    listen_address: address(i),
+                    initial_peers: peers.iter().copied().map(address).collect(),
+                    duplicate_cache_time: Duration::from_secs(60),
+                },
+            },
+            mempool: TxMempoolSettings {
+                backend: (),
+                network: AdapterSettings {
+                    topic: "tx".to_string(),
+                    id: String::clone,
+                },
+            },
+        },
+        None,
+    )
+    .map_err(|e| eprintln!("Error encountered: {}", e))
+    .unwrap()
+}
+
+async fn view(app: &Overwatch) -> Vec<String> {
+    let mempool = app.handle().relay::<Mempool>().connect().await.unwrap();
+    let (reply_channel, items) = tokio::sync::oneshot::channel();
+    mempool
+        .send(MempoolMsg::View {
+            ancestor_hint: [0; 32].into(),
+            reply_channel,
+        })
+        .await
+        .unwrap();
+    items.await.unwrap().collect()
+}
+
+#[test]
+fn test_mempool_items_are_gossiped_through_the_emulated_network() {
+    let network = EmulatedNetwork::new(0);
+    network.set_default_link(LinkConditions {
+        latency: Duration::from_millis(50),
+        ..Default::default()
+    });
+    let node_a = run_node(&network, 0, &[]);
+    let node_b = run_node(&network, 1, &[0]);
+    let node_c = run_node(&network, 2, &[1]);
+
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    runtime.block_on(async {
+        // wait for the network services to start and dial their peers
+        while network.nodes().len() < 3 {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+
+        let mempool = node_a.handle().relay::<Mempool>().connect().await.unwrap();
+        let (reply_channel, added) = tokio::sync::oneshot::channel();
+        mempool
+            .send(MempoolMsg::Add {
+                payload: "tx".to_string(),
+                key: "tx".to_string(),
+                reply_channel,
+            })
+            .await
+            .unwrap();
+        added.await.unwrap().unwrap();
+
+        // relayed by b to c, which is not connected to a
+        for node in [&node_b, &node_c] {
+            tokio::time::timeout(Duration::from_secs(10), async {
+                while view(node).await.is_empty() {
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                }
+            })
+            .await
+            .expect("the item should reach every node");
+            assert_eq!(view(node).await, vec!["tx".to_string()]);
+        }
+    });
+}
@@ -16,6 +16,8 @@ use nomos_mix::persistent_transmission::{
 use nomos_mix::MixOutgoingMessage;
 use nomos_mix_message::mock::MockMixMessage;
 use nomos_network::NetworkService;
+use nomos_service_status::{StatusError, StatusUpdater};
+use opentelemetry::global;
 use overwatch_rs::services::{
     handle::ServiceStateHandle,
     life_cycle::LifecycleMessage,
@@ -32,6 +34,9 @@ use tokio::sync::mpsc;
 use tokio::time;
 use tokio_stream::wrappers::{IntervalStream, UnboundedReceiverStream};
 
+/// How long to wait for the network service before starting without it
+const NETWORK_READY_TIMEOUT: Duration = Duration::from_secs(10);
+
 /// A mix service that sends messages to the mix network
 /// and broadcasts fully unwrapped messages through the [`NetworkService`].
 ///
@@ -48,6 +53,7 @@ where
     service_state: ServiceStateHandle<Self>,
     network_relay: Relay<NetworkService<Network::Backend>>,
     membership: Membership<MockMixMessage>,
+    status: StatusUpdater,
 }
 
 impl<Backend, Network> ServiceData for MixService<Backend, Network>
@@ -76,6 +82,7 @@ where
     fn init(service_state: ServiceStateHandle<Self>) -> Result<Self, overwatch_rs::DynError> {
         let network_relay = service_state.overwatch_handle.relay();
         let mix_config = service_state.settings_reader.get_updated_settings();
+        let status = nomos_service_status::updater::<Self>(&service_state.overwatch_handle);
         Ok(Self {
             backend: <Backend as MixBackend>::new(
                 service_state.settings_reader.get_updated_settings().backend,
@@ -86,6 +93,7 @@ where
             service_state,
             network_relay,
             membership: mix_config.membership(),
+            status,
         })
     }
 
@@ -95,6 +103,7 @@ where
             mut backend,
             network_relay,
             membership,
+            status,
         } = self;
         let mix_config = service_state.settings_reader.get_updated_settings();
         let mut cryptographic_processor = CryptographicProcessor::new(
@@ -104,6 +113,17 @@ where
         );
         let network_relay = network_relay.connect().await?;
         let network_adapter = Network::new(network_relay);
+        // fully unwrapped messages are broadcast through the network service
+        match nomos_service_status::watcher::<NetworkService<Network::Backend>>(
+            &service_state.overwatch_handle,
+        )
+        .wait_ready_timeout(NETWORK_READY_TIMEOUT)
+        .await
+        {
+            Err(e @ StatusError::Stopped { .. }) => return Err(e.into()),
+            Err(e) => tracing::warn!("starting without a ready network: {e}"),
+            Ok(()) => {}
+        }
 
         // tier 1 persistent transmission
         let (persistent_sender, persistent_receiver) = mpsc::unbounded_channel();
@@ -142,10 +162,16 @@ where
             });
 
         let mut lifecycle_stream = service_state.lifecycle_handle.message_stream();
+        let emitted_messages = global::meter("mix")
+            .u64_counter("mix_emitted_messages")
+            .with_description("Messages emitted to the mix network, including cover traffic")
+            .init();
+        status.ready();
         loop {
             tokio::select! {
                 Some(msg) = persistent_transmission_messages.next() => {
                     backend.publish(msg).await;
+                    emitted_messages.add(1, &[]);
                 }
                 // Already processed blend messages
                 Some(msg) = blend_messages.next() => {
@@ -182,6 +208,7 @@ where
                 }
                 Some(msg) = lifecycle_stream.next() => {
                     if Self::should_stop_service(msg).await {
+                        status.stopped("shutdown");
                         break;
                     }
                 }
@@ -230,7 +257,11 @@ impl<BackendSettings> MixConfig<BackendSettings> {
     fn membership(&self) -> Membership<MockMixMessage> {
         // We use private key as a public key because the `MockMixMessage` doesn't differentiate between them.
         // TODO: Convert private key to public key properly once the real MixMessage is implemented.
-        let public_key = self.message_blend.cryptographic_processor.private_key;
+        let public_key = self
+            .message_blend
+            .cryptographic_processor
+            .private_key
+            .expect("Mix private key should be set in the settings or read from the keystore");
         Membership::new(self.membership.clone(), public_key)
     }
 }
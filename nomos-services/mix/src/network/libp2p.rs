@@ -1,5 +1,5 @@
 use nomos_network::{
-    backends::libp2p::{Command, Libp2p},
+    backends::libp2p::{Command, Libp2p, Libp2pBackend},
     NetworkMsg, NetworkService,
 };
 use overwatch_rs::services::{relay::OutboundRelay, ServiceData};
@@ -8,9 +8,16 @@ use serde::{Deserialize, Serialize};
 use super::NetworkAdapter;
 
 /// A network adapter for the network service that uses libp2p backend.
-#[derive(Clone)]
-pub struct Libp2pAdapter {
-    network_relay: OutboundRelay<<NetworkService<Libp2p> as ServiceData>::Message>,
+pub struct Libp2pAdapter<B: Libp2pBackend = Libp2p> {
+    network_relay: OutboundRelay<<NetworkService<B> as ServiceData>::Message>,
+}
+
+impl<B: Libp2pBackend> Clone for Libp2pAdapter<B> {
+    fn clone(&self) -> Self {
+        Self {
+            network_relay: self.network_relay.clone(),
+        }
+    }
 }
 
 /// Settings used to broadcast messages to the network service that uses libp2p backend.
@@ -20,8 +27,8 @@ pub struct Libp2pBroadcastSettings {
 }
 
 #[async_trait::async_trait]
-impl NetworkAdapter for Libp2pAdapter {
-    type Backend = Libp2p;
+impl<B: Libp2pBackend> NetworkAdapter for Libp2pAdapter<B> {
+    type Backend = B;
     type BroadcastSettings = Libp2pBroadcastSettings;
 
     fn new(
@@ -0,0 +1,560 @@
+//! In-memory backend connecting many nodes of a single process through an [`EmulatedNetwork`]
+//! with configurable link conditions.
+//!
+//! It is driven with the same [`Command`]s and emits the same [`Event`]s as the libp2p backend,
+//! so the services built on top of the latter run unmodified. Messages are flooded: every node
+//! relays the messages it receives for the first time to all of its other peers, whether it
+//! subscribed to their topic or not.
+//!
+//! Only the traffic of this network service is emulated: consensus blocks and the mempools,
+//! including the DA mempool of blob certificates. Dispersal, replication and sampling of DA blobs
+//! go through the separate DA network service, whose backends run their own libp2p swarms, so DA
+//! services still need real sockets.
+
+mod network;
+
+// std
+use std::collections::{HashSet, VecDeque};
+use std::io;
+use std::time::Duration;
+// crates
+use nomos_libp2p::libp2p::TransportError;
+use nomos_libp2p::{ed25519, gossipsub, identity, DialError, Multiaddr, PeerId, Swarm};
+use overwatch_rs::{overwatch::handle::OverwatchHandle, services::state::NoState};
+use tokio::sync::{broadcast, mpsc, oneshot};
+use tokio::time::Instant;
+// internal
+use super::libp2p::swarm::{SwarmHandler, MAX_RETRY};
+use super::libp2p::{Command, Dial, Event, EventKind, Libp2pInfo, Topic, BUFFER_SIZE};
+use super::NetworkBackend;
+use network::Delivery;
+pub use network::{EmulatedNetwork, LinkConditions};
+
+pub struct Emulated {
+    events_tx: broadcast::Sender<Event>,
+    commands_tx: mpsc::Sender<Command>,
+}
+
+#[derive(Clone, Debug)]
+pub struct EmulationConfig {
+    pub network: EmulatedNetwork,
+    pub node_key: ed25519::SecretKey,
+    /// Address the other nodes dial to reach this one, e.g. `/memory/1`
+    pub listen_address: Multiaddr,
+    pub initial_peers: Vec<Multiaddr>,
+    /// How long a message is remembered to not relay it again, as the gossipsub
+    /// `duplicate_cache_time`. Messages still in flight after that are delivered twice.
+    pub duplicate_cache_time: Duration,
+}
+
+impl EmulationConfig {
+    pub fn peer_id(&self) -> PeerId {
+        let keypair = identity::Keypair::from(ed25519::Keypair::from(self.node_key.clone()));
+        PeerId::from(keypair.public())
+    }
+}
+
+#[async_trait::async_trait]
+impl NetworkBackend for Emulated {
+    type Settings = EmulationConfig;
+    type State = NoState<EmulationConfig>;
+    type Message = Command;
+    type EventKind = EventKind;
+    type NetworkEvent = Event;
+
+    fn new(config: Self::Settings, overwatch_handle: OverwatchHandle) -> Self {
+        let (commands_tx, commands_rx) = mpsc::channel(BUFFER_SIZE);
+        let (events_tx, _) = broadcast::channel(BUFFER_SIZE);
+        let (inbox_tx, inbox_rx) = mpsc::unbounded_channel();
+
+        let peer_id = config.peer_id();
+        tracing::info!("emulated peer_id:{peer_id}");
+        // join the network right away, so that nodes created together can dial each other
+        config
+            .network
+            .register(peer_id, config.listen_address.clone(), inbox_tx.clone());
+
+        let node = EmulatedNode {
+            peer_id,
+            listen_address: config.listen_address,
+            network: config.network,
+            peers: HashSet::new(),
+            topics: HashSet::new(),
+            seen: SeenCache::new(config.duplicate_cache_time),
+            sequence_number: 0,
+            commands_tx: commands_tx.downgrade(),
+            commands_rx,
+            inbox_tx,
+            inbox_rx,
+            events_tx: events_tx.clone(),
+        };
+        overwatch_handle
+            .runtime()
+            .spawn(node.run(config.initial_peers));
+
+        Self {
+            events_tx,
+            commands_tx,
+        }
+    }
+
+    async fn process(&self, msg: Self::Message) {
+        if let Err(e) = self.commands_tx.send(msg).await {
+            tracing::error!("failed to send command to the emulated network: {e:?}");
+        }
+    }
+
+    async fn subscribe(
+        &mut self,
+        kind: Self::EventKind,
+    ) -> broadcast::Receiver<Self::NetworkEvent> {
+        match kind {
+            EventKind::Message => {
+                tracing::debug!("processed subscription to incoming messages");
+                self.events_tx.subscribe()
+            }
+        }
+    }
+}
+
+/// Task of a node, running until its backend is dropped
+struct EmulatedNode {
+    peer_id: PeerId,
+    listen_address: Multiaddr,
+    network: EmulatedNetwork,
+    peers: HashSet<PeerId>,
+    topics: HashSet<gossipsub::TopicHash>,
+    seen: SeenCache,
+    sequence_number: u64,
+    // weak so that the commands channel closes once the backend is dropped
+    commands_tx: mpsc::WeakSender<Command>,
+    commands_rx: mpsc::Receiver<Command>,
+    inbox_tx: mpsc::UnboundedSender<Delivery>,
+    inbox_rx: mpsc::UnboundedReceiver<Delivery>,
+    events_tx: broadcast::Sender<Event>,
+}
+
+impl EmulatedNode {
+    async fn run(mut self, initial_peers: Vec<Multiaddr>) {
+        for initial_peer in initial_peers {
+            let (tx, _) = oneshot::channel();
+            self.connect(Dial {
+                addr: initial_peer,
+                retry_count: 0,
+                result_sender: tx,
+            });
+        }
+
+        loop {
+            tokio::select! {
+                command = self.commands_rx.recv() => match command {
+                    Some(command) => self.handle_command(command),
+                    None => break,
+                },
+                Some(delivery) = self.inbox_rx.recv() => {
+                    self.handle_delivery(delivery);
+                }
+            }
+        }
+
+        for peer in &self.peers {
+            self.network.disconnect(self.peer_id, *peer);
+        }
+        self.network
+            .deregister(self.peer_id, &self.listen_address, &self.inbox_tx);
+    }
+
+    fn handle_command(&mut self, command: Command) {
+        match command {
+            Command::Connect(dial) => {
+                self.connect(dial);
+            }
+            Command::Broadcast { topic, message } => {
+                self.broadcast(topic, message, 0);
+            }
+            Command::Subscribe(topic) => {
+                tracing::debug!("subscribing to topic: {topic}");
+                self.topics.insert(Swarm::topic_hash(&topic));
+            }
+            Command::Unsubscribe(topic) => {
+                tracing::debug!("unsubscribing to topic: {topic}");
+                self.topics.remove(&Swarm::topic_hash(&topic));
+            }
+            Command::Info { reply } => {
+                let info = Libp2pInfo {
+                    listen_addresses: vec![self.listen_address.clone()],
+                    n_peers: self.peers.len(),
+                    n_connections: self.peers.len() as u32,
+                    n_pending_connections: 0,
+                };
+                if let Err(e) = reply.send(info) {
+                    tracing::warn!("failed to send network info: {e:?}");
+                }
+            }
+            Command::RetryBroadcast {
+                topic,
+                message,
+                retry_count,
+            } => {
+                self.broadcast(topic, message, retry_count);
+            }
+        }
+    }
+
+    fn handle_delivery(&mut self, delivery: Delivery) {
+        match delivery {
+            Delivery::Connected(peer_id) => {
+                tracing::debug!("connected to peer:{peer_id}");
+                self.peers.insert(peer_id);
+            }
+            Delivery::Disconnected(peer_id) => {
+                tracing::debug!("connection closed from peer: {peer_id}");
+                self.peers.remove(&peer_id);
+            }
+            Delivery::Message { from, message } => self.receive(from, message),
+        }
+    }
+
+    fn connect(&mut self, mut dial: Dial) {
+        tracing::debug!("Connecting to {}", dial.addr);
+        if let Some(peer_id) = self.network.connect(self.peer_id, &dial.addr) {
+            self.peers.insert(peer_id);
+            if let Err(e) = dial.result_sender.send(Ok(peer_id)) {
+                tracing::warn!("failed to send the Ok result of dialing: {e:?}");
+            }
+            return;
+        }
+
+        // retry like the libp2p backend does, as the node may not have joined the network yet
+        dial.retry_count += 1;
+        if dial.retry_count > MAX_RETRY {
+            tracing::debug!("Max retry({MAX_RETRY}) has been reached: {dial:?}");
+            let error = io::Error::new(io::ErrorKind::ConnectionRefused, "node unreachable");
+            let error = DialError::Transport(vec![(dial.addr, TransportError::Other(error))]);
+            if let Err(e) = dial.result_sender.send(Err(error)) {
+                tracing::warn!("failed to send the Err result of dialing: {e:?}");
+            }
+            return;
+        }
+        let wait = SwarmHandler::exp_backoff(dial.retry_count);
+        tracing::debug!("Retry dialing in {wait:?}: {dial:?}");
+        self.schedule(wait, Command::Connect(dial));
+    }
+
+    fn broadcast(&mut self, topic: Topic, message: Box<[u8]>, retry_count: usize) {
+        tracing::debug!("broadcasting message to topic: {topic}");
+        if self.peers.is_empty() {
+            if retry_count < MAX_RETRY {
+                let wait = SwarmHandler::exp_backoff(retry_count);
+                tracing::error!("failed to broadcast message to topic due to insufficient peers, trying again in {wait:?}");
+                self.schedule(
+                    wait,
+                    Command::RetryBroadcast {
+                        topic,
+                        message,
+                        retry_count: retry_count + 1,
+                    },
+                );
+            } else {
+                tracing::error!("failed to broadcast message to topic: {topic} no peers");
+            }
+            return;
+        }
+
+        self.sequence_number += 1;
+        self.seen
+            .insert((self.peer_id, self.sequence_number), Instant::now());
+        let topic_hash = Swarm::topic_hash(&topic);
+        let message = gossipsub::Message {
+            source: Some(self.peer_id),
+            data: message.into(),
+            sequence_number: Some(self.sequence_number),
+            topic: topic_hash.clone(),
+        };
+        for peer in &self.peers {
+            self.network.send(self.peer_id, *peer, message.clone());
+        }
+        // self-notification, as the libp2p backend does
+        if self.topics.contains(&topic_hash) {
+            self.emit(gossipsub::Message {
+                source: None,
+                sequence_number: None,
+                ..message
+            });
+        }
+    }
+
+    fn receive(&mut self, from: PeerId, message: gossipsub::Message) {
+        let (Some(source), Some(sequence_number)) = (message.source, message.sequence_number)
+        else {
+            return;
+        };
+        if !self.seen.insert((source, sequence_number), Instant::now()) {
+            return;
+        }
+        for peer in &self.peers {
+            if *peer != from && *peer != source {
+                self.network.send(self.peer_id, *peer, message.clone());
+            }
+        }
+        if self.topics.contains(&message.topic) {
+            self.emit(message);
+        }
+    }
+
+    fn emit(&self, message: gossipsub::Message) {
+        if let Err(e) = self.events_tx.send(Event::Message(message)) {
+            tracing::debug!("no subscriber for incoming message: {e:?}");
+        }
+    }
+
+    fn schedule(&self, wait: std::time::Duration, command: Command) {
+        let commands_tx = self.commands_tx.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(wait).await;
+            if let Some(commands_tx) = commands_tx.upgrade() {
+                commands_tx
+                    .send(command)
+                    .await
+                    .unwrap_or_else(|_| tracing::error!("could not schedule retry"));
+            }
+        });
+    }
+}
+
+/// Messages received within the last `ttl`, by source and sequence number
+struct SeenCache {
+    ttl: Duration,
+    ids: HashSet<(PeerId, u64)>,
+    // in the order they were received, so the expired ones are at the front
+    received: VecDeque<(Instant, (PeerId, u64))>,
+}
+
+impl SeenCache {
+    fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            ids: HashSet::new(),
+            received: VecDeque::new(),
+        }
+    }
+
+    /// Record a message, returns false if it was already seen
+    fn insert(&mut self, id: (PeerId, u64), now: Instant) -> bool {
+        while let Some((received_at, expired)) = self.received.front() {
+            if now.duration_since(*received_at) < self.ttl {
+                break;
+            }
+            self.ids.remove(expired);
+            self.received.pop_front();
+        }
+        if !self.ids.insert(id) {
+            return false;
+        }
+        self.received.push_back((now, id));
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use nomos_libp2p::Protocol;
+    use tokio::time::{timeout, Instant};
+
+    use super::*;
+
+    const TOPIC: &str = "topic";
+
+    fn address(i: u64) -> Multiaddr {
+        Multiaddr::empty().with(Protocol::Memory(i))
+    }
+
+    /// Start node `i`, subscribed to [`TOPIC`] and connected to `peers`
+    async fn node(
+        network: &EmulatedNetwork,
+        i: u64,
+        peers: &[u64],
+    ) -> (Emulated, PeerId, broadcast::Receiver<Event>) {
+        let config = EmulationConfig {
+            network: network.clone(),
+            node_key: ed25519::SecretKey::generate(),
+            listen_address: address(i),
+            initial_peers: peers.iter().copied().map(address).collect(),
+            duplicate_cache_time: Duration::from_secs(60),
+        };
+        let peer_id = config.peer_id();
+        let mut backend = Emulated::new(
+            config,
+            OverwatchHandle::new(tokio::runtime::Handle::current(), mpsc::channel(1).0),
+        );
+        backend.process(Command::Subscribe(TOPIC.into())).await;
+        let events = backend.subscribe(EventKind::Message).await;
+        (backend, peer_id, events)
+    }
+
+    /// Let the nodes process what they were sent so far
+    async fn settle() {
+        tokio::time::sleep(Duration::from_millis(1)).await;
+    }
+
+    async fn broadcast(backend: &Emulated, data: &[u8]) {
+        backend
+            .process(Command::Broadcast {
+                topic: TOPIC.into(),
+                message: data.into(),
+            })
+            .await;
+    }
+
+    async fn next_message(events: &mut broadcast::Receiver<Event>) -> Option<gossipsub::Message> {
+        match timeout(Duration::from_secs(10), events.recv()).await {
+            Ok(Ok(Event::Message(message))) => Some(message),
+            _ => None,
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn messages_are_relayed_to_subscribers() {
+        let network = EmulatedNetwork::new(0);
+        let (a, a_id, mut a_events) = node(&network, 0, &[]).await;
+        let (_b, _, mut b_events) = node(&network, 1, &[0]).await;
+        let (_c, _, mut c_events) = node(&network, 2, &[1]).await;
+        settle().await;
+
+        broadcast(&a, b"hello").await;
+        let own = next_message(&mut a_events).await.unwrap();
+        assert_eq!((own.source, own.data.as_slice()), (None, &b"hello"[..]));
+        for events in [&mut b_events, &mut c_events] {
+            let message = next_message(events).await.unwrap();
+            assert_eq!(message.source, Some(a_id));
+            assert_eq!(message.data, b"hello");
+            assert_eq!(message.topic, Swarm::topic_hash(TOPIC));
+        }
+        // every message is only delivered once
+        assert!(next_message(&mut c_events).await.is_none());
+
+        let (reply, info) = oneshot::channel();
+        a.process(Command::Info { reply }).await;
+        let info = info.await.unwrap();
+        assert_eq!(info.n_peers, 1);
+        assert_eq!(info.listen_addresses, vec![address(0)]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn links_delay_messages() {
+        let network = EmulatedNetwork::new(0);
+        let (a, a_id, _) = node(&network, 0, &[]).await;
+        let (_b, b_id, mut b_events) = node(&network, 1, &[0]).await;
+        let (_c, c_id, mut c_events) = node(&network, 2, &[0]).await;
+        settle().await;
+
+        let latency = Duration::from_millis(100);
+        network.set_link(
+            a_id,
+            b_id,
+            LinkConditions {
+                latency,
+                bandwidth: Some(1000),
+                ..Default::default()
+            },
+        );
+        let jitter = Duration::from_millis(50);
+        network.set_link(
+            a_id,
+            c_id,
+            LinkConditions {
+                latency,
+                jitter,
+                ..Default::default()
+            },
+        );
+
+        let start = Instant::now();
+        broadcast(&a, &[0; 500]).await;
+        broadcast(&a, &[1; 500]).await;
+        for _ in 0..2 {
+            next_message(&mut c_events).await.unwrap();
+            let elapsed = start.elapsed();
+            assert!(elapsed >= latency && elapsed <= latency + jitter);
+        }
+        // each message takes 500ms to transmit, and the second one waits for the first
+        for expected in [600, 1100] {
+            next_message(&mut b_events).await.unwrap();
+            let elapsed = start.elapsed();
+            let expected = Duration::from_millis(expected);
+            assert!(elapsed >= expected && elapsed < expected + Duration::from_millis(5));
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn partitions_drop_messages_until_healed() {
+        let network = EmulatedNetwork::new(0);
+        let (a, a_id, _) = node(&network, 0, &[]).await;
+        let (b, b_id, mut b_events) = node(&network, 1, &[0]).await;
+        settle().await;
+
+        network.partition(&[&[a_id], &[b_id]]);
+        broadcast(&a, b"lost").await;
+        assert!(next_message(&mut b_events).await.is_none());
+        let (result_sender, result) = oneshot::channel();
+        b.process(Command::Connect(Dial {
+            addr: address(0),
+            retry_count: 0,
+            result_sender,
+        }))
+        .await;
+        assert!(result.await.unwrap().is_err());
+
+        network.heal();
+        broadcast(&a, b"found").await;
+        assert_eq!(next_message(&mut b_events).await.unwrap().data, b"found");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn lossy_links_drop_messages() {
+        let network = EmulatedNetwork::new(0);
+        network.set_default_link(LinkConditions {
+            loss: 1.0,
+            ..Default::default()
+        });
+        let (a, _, _) = node(&network, 0, &[]).await;
+        let (_b, _, mut b_events) = node(&network, 1, &[0]).await;
+        settle().await;
+
+        broadcast(&a, b"lost").await;
+        assert!(next_message(&mut b_events).await.is_none());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn dropped_nodes_leave_the_network() {
+        let network = EmulatedNetwork::new(0);
+        let (a, _, _) = node(&network, 0, &[]).await;
+        let (b, _, _) = node(&network, 1, &[0]).await;
+        settle().await;
+        assert_eq!(network.nodes().len(), 2);
+
+        drop(b);
+        settle().await;
+        assert_eq!(network.nodes().len(), 1);
+        let (reply, info) = oneshot::channel();
+        a.process(Command::Info { reply }).await;
+        assert_eq!(info.await.unwrap().n_peers, 0);
+    }
+
+    #[test]
+    fn seen_messages_expire() {
+        let ttl = Duration::from_secs(60);
+        let mut seen = SeenCache::new(ttl);
+        let id = (PeerId::random(), 1);
+        let start = Instant::now();
+        assert!(seen.insert(id, start));
+        assert!(!seen.insert(id, start + ttl / 2));
+        assert!(seen.insert((PeerId::random(), 1), start + ttl / 2));
+
+        assert!(seen.insert(id, start + ttl));
+        assert_eq!(seen.ids.len(), 2);
+        assert_eq!(seen.received.len(), 2);
+    }
+}
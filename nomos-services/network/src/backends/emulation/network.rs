@@ -0,0 +1,232 @@
+// std
+use std::collections::HashMap;
+use std::fmt::{self, Debug};
+use std::sync::Arc;
+use std::time::Duration;
+// crates
+use nomos_libp2p::{gossipsub, Multiaddr, PeerId};
+use parking_lot::Mutex;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+// internal
+
+/// Conditions of the messages sent over a link
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LinkConditions {
+    /// Time it takes a message to cross the link once it's transmitted
+    pub latency: Duration,
+    /// Upper bound of an extra latency drawn uniformly for each message
+    pub jitter: Duration,
+    /// Bytes per second transmitted over the link, unlimited if `None`. Messages sent while the
+    /// link is busy wait for the previous ones to be transmitted.
+    pub bandwidth: Option<u64>,
+    /// Probability for a message to be lost, between 0 and 1
+    pub loss: f64,
+}
+
+/// What the emulated network hands to a node
+#[derive(Debug)]
+pub(crate) enum Delivery {
+    Connected(PeerId),
+    Disconnected(PeerId),
+    Message {
+        from: PeerId,
+        message: gossipsub::Message,
+    },
+}
+
+/// In-memory network shared by the nodes of a process.
+///
+/// Nodes join it when their backend is created and dial each other by listen address. Link
+/// conditions and partitions can be changed at any time from the outside, and apply to the
+/// messages sent from then on. Losses and jitter are drawn from the seed of the network.
+#[derive(Clone)]
+pub struct EmulatedNetwork {
+    inner: Arc<Mutex<Inner>>,
+}
+
+struct Inner {
+    rng: StdRng,
+    default_link: LinkConditions,
+    /// Conditions overriding the default ones, by sender and receiver
+    links: HashMap<(PeerId, PeerId), LinkConditions>,
+    /// Group of each partitioned node. Nodes only reach the ones of the same group, and the
+    /// nodes left out of every group form one more group.
+    groups: HashMap<PeerId, usize>,
+    addresses: HashMap<Multiaddr, PeerId>,
+    inboxes: HashMap<PeerId, mpsc::UnboundedSender<Delivery>>,
+    /// Time until which each link is busy transmitting, by sender and receiver
+    busy_until: HashMap<(PeerId, PeerId), Instant>,
+}
+
+impl Debug for EmulatedNetwork {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let inner = self.inner.lock();
+        f.debug_struct("EmulatedNetwork")
+            .field("nodes", &inner.addresses)
+            .field("default_link", &inner.default_link)
+            .field("links", &inner.links)
+            .field("groups", &inner.groups)
+            .finish()
+    }
+}
+
+impl EmulatedNetwork {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Inner {
+                rng: StdRng::seed_from_u64(seed),
+                default_link: LinkConditions::default(),
+                links: HashMap::new(),
+                groups: HashMap::new(),
+                addresses: HashMap::new(),
+                inboxes: HashMap::new(),
+                busy_until: HashMap::new(),
+            })),
+        }
+    }
+
+    /// Set the conditions of the links without conditions of their own
+    pub fn set_default_link(&self, conditions: LinkConditions) {
+        self.inner.lock().default_link = conditions;
+    }
+
+    /// Set the conditions of the link between `a` and `b`, in both directions
+    pub fn set_link(&self, a: PeerId, b: PeerId, conditions: LinkConditions) {
+        let mut inner = self.inner.lock();
+        inner.links.insert((a, b), conditions);
+        inner.links.insert((b, a), conditions);
+    }
+
+    pub fn clear_link(&self, a: PeerId, b: PeerId) {
+        let mut inner = self.inner.lock();
+        inner.links.remove(&(a, b));
+        inner.links.remove(&(b, a));
+    }
+
+    /// Split the network so that nodes only reach the ones of their group. Messages in flight
+    /// are still delivered.
+    pub fn partition(&self, groups: &[&[PeerId]]) {
+        self.inner.lock().groups = groups
+            .iter()
+            .enumerate()
+            .flat_map(|(group, peers)| peers.iter().map(move |peer| (*peer, group)))
+            .collect();
+    }
+
+    pub fn heal(&self) {
+        self.inner.lock().groups.clear();
+    }
+
+    /// Nodes currently in the network, by listen address
+    pub fn nodes(&self) -> HashMap<Multiaddr, PeerId> {
+        self.inner.lock().addresses.clone()
+    }
+
+    pub(crate) fn register(
+        &self,
+        peer_id: PeerId,
+        address: Multiaddr,
+        inbox: mpsc::UnboundedSender<Delivery>,
+    ) {
+        let mut inner = self.inner.lock();
+        inner.addresses.insert(address, peer_id);
+        inner.inboxes.insert(peer_id, inbox);
+    }
+
+    /// Remove a node, unless it was registered again by a newer instance in the meantime
+    pub(crate) fn deregister(
+        &self,
+        peer_id: PeerId,
+        address: &Multiaddr,
+        inbox: &mpsc::UnboundedSender<Delivery>,
+    ) {
+        let mut inner = self.inner.lock();
+        if inner
+            .inboxes
+            .get(&peer_id)
+            .is_some_and(|registered| registered.same_channel(inbox))
+        {
+            inner.inboxes.remove(&peer_id);
+            inner.addresses.remove(address);
+        }
+    }
+
+    /// Open a connection from `from` to the node listening on `address`, if it's reachable
+    pub(crate) fn connect(&self, from: PeerId, address: &Multiaddr) -> Option<PeerId> {
+        let inner = self.inner.lock();
+        let to = *inner.addresses.get(address)?;
+        if to == from || !inner.reachable(&from, &to) {
+            return None;
+        }
+        inner
+            .inboxes
+            .get(&to)?
+            .send(Delivery::Connected(from))
+            .ok()?;
+        Some(to)
+    }
+
+    pub(crate) fn disconnect(&self, from: PeerId, to: PeerId) {
+        if let Some(inbox) = self.inner.lock().inboxes.get(&to) {
+            // the other node may be shutting down too
+            let _ = inbox.send(Delivery::Disconnected(from));
+        }
+    }
+
+    /// Send a message over the link from `from` to `to`, subject to its conditions
+    pub(crate) fn send(&self, from: PeerId, to: PeerId, message: gossipsub::Message) {
+        let mut inner = self.inner.lock();
+        if !inner.reachable(&from, &to) {
+            tracing::trace!("dropping message from {from} to {to}: partitioned");
+            return;
+        }
+        let Some(inbox) = inner.inboxes.get(&to).cloned() else {
+            return;
+        };
+        let conditions = inner
+            .links
+            .get(&(from, to))
+            .copied()
+            .unwrap_or(inner.default_link);
+        if conditions.loss > 0.0 && inner.rng.gen_bool(conditions.loss.min(1.0)) {
+            tracing::trace!("dropping message from {from} to {to}: lost");
+            return;
+        }
+
+        let now = Instant::now();
+        let mut transmitted = now;
+        if let Some(bandwidth) = conditions.bandwidth {
+            let busy_until = inner.busy_until.entry((from, to)).or_insert(now);
+            let transmission =
+                Duration::from_secs_f64(message.data.len() as f64 / bandwidth.max(1) as f64);
+            *busy_until = (*busy_until).max(now) + transmission;
+            transmitted = *busy_until;
+        }
+        let jitter = if conditions.jitter.is_zero() {
+            Duration::ZERO
+        } else {
+            let max = conditions.jitter.as_nanos() as u64;
+            Duration::from_nanos(inner.rng.gen_range(0, max + 1))
+        };
+        drop(inner);
+
+        let delivery = Delivery::Message { from, message };
+        let at = transmitted + conditions.latency + jitter;
+        if at <= now {
+            let _ = inbox.send(delivery);
+        } else {
+            tokio::spawn(async move {
+                tokio::time::sleep_until(at).await;
+                let _ = inbox.send(delivery);
+            });
+        }
+    }
+}
+
+impl Inner {
+    fn reachable(&self, a: &PeerId, b: &PeerId) -> bool {
+        self.groups.get(a) == self.groups.get(b)
+    }
+}
@@ -30,7 +30,19 @@ pub enum Event {
     Message(Message),
 }
 
-const BUFFER_SIZE: usize = 64;
+pub(crate) const BUFFER_SIZE: usize = 64;
+
+/// Backends driven with the libp2p [`Command`]s and emitting libp2p [`Event`]s, on top of which
+/// the libp2p adapters of the services can run
+pub trait Libp2pBackend:
+    NetworkBackend<Message = Command, EventKind = EventKind, NetworkEvent = Event> + 'static
+{
+}
+
+impl<T> Libp2pBackend for T where
+    T: NetworkBackend<Message = Command, EventKind = EventKind, NetworkEvent = Event> + 'static
+{
+}
 
 #[async_trait::async_trait]
 impl NetworkBackend for Libp2p {
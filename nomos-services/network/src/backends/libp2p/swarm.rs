@@ -3,6 +3,7 @@ use std::{collections::HashMap, time::Duration};
 use nomos_libp2p::{
     gossipsub, libp2p::swarm::ConnectionId, BehaviourEvent, Multiaddr, PeerId, Swarm, SwarmEvent,
 };
+use opentelemetry::{global, metrics::Gauge, KeyValue};
 use tokio::sync::{broadcast, mpsc, oneshot};
 use tokio_stream::StreamExt;
 
@@ -19,6 +20,7 @@ pub struct SwarmHandler {
     pub commands_tx: mpsc::Sender<Command>,
     pub commands_rx: mpsc::Receiver<Command>,
     pub events_tx: broadcast::Sender<Event>,
+    connected_peers: Gauge<u64>,
 }
 
 macro_rules! log_error {
@@ -32,7 +34,7 @@ macro_rules! log_error {
 // TODO: make this configurable
 const BACKOFF: u64 = 5;
 // TODO: make this configurable
-const MAX_RETRY: usize = 3;
+pub(crate) const MAX_RETRY: usize = 3;
 
 impl SwarmHandler {
     pub fn new(
@@ -52,6 +54,10 @@ impl SwarmHandler {
             commands_tx,
             commands_rx,
             events_tx,
+            connected_peers: global::meter("libp2p")
+                .u64_gauge("libp2p_connected_peers")
+                .with_description("Peers with at least one open connection")
+                .init(),
         }
     }
 
@@ -95,6 +101,7 @@ impl SwarmHandler {
                 ..
             } => {
                 tracing::debug!("connected to peer:{peer_id}, connection_id:{connection_id:?}");
+                self.record_connected_peers();
                 if endpoint.is_dialer() {
                     self.complete_connect(connection_id, peer_id);
                 }
@@ -108,6 +115,7 @@ impl SwarmHandler {
                 tracing::debug!(
                     "connection closed from peer: {peer_id} {connection_id:?} due to {cause:?}"
                 );
+                self.record_connected_peers();
             }
             SwarmEvent::OutgoingConnectionError {
                 peer_id,
@@ -124,6 +132,12 @@ impl SwarmHandler {
         }
     }
 
+    fn record_connected_peers(&self) {
+        let peers = self.swarm.swarm().network_info().num_peers();
+        self.connected_peers
+            .record(peers as u64, &[KeyValue::new("network", "nomos")]);
+    }
+
     async fn handle_command(&mut self, command: Command) {
         match command {
             Command::Connect(dial) => {
@@ -252,7 +266,7 @@ impl SwarmHandler {
         }
     }
 
-    fn exp_backoff(retry: usize) -> Duration {
+    pub(crate) fn exp_backoff(retry: usize) -> Duration {
         std::time::Duration::from_secs(BACKOFF.pow(retry as u32))
     }
 }
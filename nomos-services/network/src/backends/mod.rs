@@ -5,6 +5,9 @@ use tokio::sync::broadcast::Receiver;
 #[cfg(feature = "libp2p")]
 pub mod libp2p;
 
+#[cfg(feature = "emulation")]
+pub mod emulation;
+
 #[cfg(feature = "mock")]
 pub mod mock;
 
@@ -10,6 +10,7 @@ use tokio::sync::broadcast;
 use tokio::sync::oneshot;
 // internal
 use backends::NetworkBackend;
+use nomos_service_status::StatusUpdater;
 use overwatch_rs::services::life_cycle::LifecycleMessage;
 use overwatch_rs::services::{
     handle::ServiceStateHandle,
@@ -55,6 +56,7 @@ impl<B: NetworkBackend> Debug for NetworkConfig<B> {
 pub struct NetworkService<B: NetworkBackend + 'static> {
     backend: B,
     service_state: ServiceStateHandle<Self>,
+    status: StatusUpdater,
 }
 
 pub struct NetworkState<B: NetworkBackend> {
@@ -76,12 +78,14 @@ where
     B::State: Send + Sync,
 {
     fn init(service_state: ServiceStateHandle<Self>) -> Result<Self, overwatch_rs::DynError> {
+        let status = nomos_service_status::updater::<Self>(&service_state.overwatch_handle);
         Ok(Self {
             backend: <B as NetworkBackend>::new(
                 service_state.settings_reader.get_updated_settings().backend,
                 service_state.overwatch_handle.clone(),
             ),
             service_state,
+            status,
         })
     }
 
@@ -94,8 +98,10 @@ where
                     ..
                 },
             mut backend,
+            status,
         } = self;
         let mut lifecycle_stream = lifecycle_handle.message_stream();
+        status.ready();
         loop {
             tokio::select! {
                 Some(msg) = inbound_relay.recv() => {
@@ -103,6 +109,7 @@ where
                 }
                 Some(msg) = lifecycle_stream.next() => {
                     if Self::should_stop_service(msg).await {
+                        status.stopped("shutdown");
                         break;
                     }
                 }
@@ -0,0 +1,439 @@
+// std
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Debug;
+use std::time::Duration;
+// crates
+use futures::StreamExt;
+use overwatch_rs::overwatch::handle::OverwatchHandle;
+use overwatch_rs::services::handle::ServiceStateHandle;
+use overwatch_rs::services::life_cycle::LifecycleMessage;
+use overwatch_rs::services::relay::RelayMessage;
+use overwatch_rs::services::state::{NoOperator, NoState};
+use overwatch_rs::services::{ServiceCore, ServiceData, ServiceId};
+use overwatch_rs::DynError;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{mpsc, oneshot, watch};
+// internal
+
+/// Lifecycle status of a service, ordered from best to worst
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    /// Up and running
+    Ready,
+    /// Running, but some of its functionality is impaired
+    Degraded,
+    /// Initialized but not serving requests yet
+    Starting,
+    /// Not running anymore
+    Stopped,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ServiceStatus {
+    pub status: Status,
+    pub reason: Option<String>,
+}
+
+impl ServiceStatus {
+    fn new(status: Status, reason: Option<String>) -> Self {
+        Self { status, reason }
+    }
+
+    /// Whether dependents can start using the service
+    pub fn is_ready(&self) -> bool {
+        matches!(self.status, Status::Ready | Status::Degraded)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum StatusError {
+    #[error("service {service} stopped: {reason}")]
+    Stopped { service: ServiceId, reason: String },
+    #[error("service {service} was not ready in time")]
+    Timeout { service: ServiceId },
+    #[error("status service is not available: {0}")]
+    Unavailable(String),
+}
+
+/// Statuses of the services of one Overwatch instance, keyed by service id
+#[derive(Default)]
+pub struct StatusRegistry {
+    services: HashMap<ServiceId, watch::Sender<ServiceStatus>>,
+}
+
+impl StatusRegistry {
+    fn sender(&mut self, service: ServiceId) -> &watch::Sender<ServiceStatus> {
+        self.services
+            .entry(service)
+            .or_insert_with(|| watch::channel(ServiceStatus::new(Status::Starting, None)).0)
+    }
+
+    pub fn update(&mut self, service: ServiceId, new: ServiceStatus) {
+        // avoid waking up watchers when nothing changed, and keep the reason a service
+        // stopped for when its updater is dropped afterwards
+        self.sender(service).send_if_modified(|current| {
+            if *current == new || current.status == Status::Stopped && new.status == Status::Stopped
+            {
+                return false;
+            }
+            *current = new;
+            true
+        });
+    }
+
+    pub fn subscribe(&mut self, service: ServiceId) -> watch::Receiver<ServiceStatus> {
+        self.sender(service).subscribe()
+    }
+
+    pub fn health(&self) -> HealthReport {
+        let services: BTreeMap<_, _> = self
+            .services
+            .iter()
+            .map(|(service, sender)| (service.to_string(), sender.borrow().clone()))
+            .collect();
+        let status = services
+            .values()
+            .map(|service| service.status)
+            .max()
+            .unwrap_or(Status::Starting);
+        HealthReport { status, services }
+    }
+}
+
+#[derive(Debug)]
+pub enum StatusMsg {
+    Update {
+        service: ServiceId,
+        status: ServiceStatus,
+    },
+    Watch {
+        service: ServiceId,
+        reply_channel: oneshot::Sender<watch::Receiver<ServiceStatus>>,
+    },
+    Health {
+        reply_channel: oneshot::Sender<HealthReport>,
+    },
+}
+
+impl RelayMessage for StatusMsg {}
+
+/// Keeps track of the status of the other services of the node
+pub struct StatusService {
+    service_state: ServiceStateHandle<Self>,
+    registry: StatusRegistry,
+}
+
+impl StatusService {
+    async fn should_stop_service(msg: LifecycleMessage) -> bool {
+        match msg {
+            LifecycleMessage::Shutdown(sender) => {
+                if sender.send(()).is_err() {
+                    tracing::error!(
+                        "Error sending successful shutdown signal from service {}",
+                        Self::SERVICE_ID
+                    );
+                }
+                true
+            }
+            LifecycleMessage::Kill => true,
+        }
+    }
+
+    fn handle_message(registry: &mut StatusRegistry, msg: StatusMsg) {
+        match msg {
+            StatusMsg::Update { service, status } => registry.update(service, status),
+            StatusMsg::Watch {
+                service,
+                reply_channel,
+            } => {
+                if reply_channel.send(registry.subscribe(service)).is_err() {
+                    tracing::debug!("status watcher of {service} went away");
+                }
+            }
+            StatusMsg::Health { reply_channel } => {
+                if reply_channel.send(registry.health()).is_err() {
+                    tracing::debug!("health report requester went away");
+                }
+            }
+        }
+    }
+}
+
+impl ServiceData for StatusService {
+    const SERVICE_ID: ServiceId = "Status";
+    type Settings = ();
+    type State = NoState<Self::Settings>;
+    type StateOperator = NoOperator<Self::State>;
+    type Message = StatusMsg;
+}
+
+#[async_trait::async_trait]
+impl ServiceCore for StatusService {
+    fn init(service_state: ServiceStateHandle<Self>) -> Result<Self, DynError> {
+        Ok(Self {
+            service_state,
+            registry: StatusRegistry::default(),
+        })
+    }
+
+    async fn run(self) -> Result<(), DynError> {
+        let Self {
+            mut service_state,
+            mut registry,
+        } = self;
+        let mut lifecycle_stream = service_state.lifecycle_handle.message_stream();
+        loop {
+            tokio::select! {
+                Some(msg) = service_state.inbound_relay.recv() => {
+                    Self::handle_message(&mut registry, msg);
+                }
+                Some(msg) = lifecycle_stream.next() => {
+                    if Self::should_stop_service(msg).await {
+                        break;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Handle used by a service to report its own status.
+/// The service is reported as stopped when the updater is dropped, so a service that
+/// exits or panics doesn't stay ready.
+pub struct StatusUpdater {
+    service: ServiceId,
+    sender: mpsc::UnboundedSender<ServiceStatus>,
+}
+
+impl StatusUpdater {
+    /// Register `service` as starting with the status service of `handle`.
+    /// Updates are forwarded in order from a background task, so they can be sent from
+    /// synchronous code. Nothing is reported if the node doesn't run a status service.
+    pub fn new(service: ServiceId, handle: &OverwatchHandle) -> Self {
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        let relay = handle.relay::<StatusService>();
+        handle.runtime().spawn(async move {
+            let relay = match relay.connect().await {
+                Ok(relay) => relay,
+                Err(e) => {
+                    tracing::debug!("not reporting the status of {service}: {e:?}");
+                    return;
+                }
+            };
+            while let Some(status) = receiver.recv().await {
+                if let Err((e, _)) = relay.send(StatusMsg::Update { service, status }).await {
+                    tracing::debug!("could not report the status of {service}: {e:?}");
+                    return;
+                }
+            }
+        });
+        let updater = Self { service, sender };
+        updater.update(Status::Starting, None);
+        updater
+    }
+
+    pub fn service(&self) -> ServiceId {
+        self.service
+    }
+
+    pub fn ready(&self) {
+        self.update(Status::Ready, None);
+    }
+
+    pub fn degraded(&self, reason: impl Into<String>) {
+        self.update(Status::Degraded, Some(reason.into()));
+    }
+
+    pub fn stopped(&self, reason: impl Into<String>) {
+        self.update(Status::Stopped, Some(reason.into()));
+    }
+
+    fn update(&self, status: Status, reason: Option<String>) {
+        // the forwarding task is gone only if there's no status service to report to
+        let _ = self.sender.send(ServiceStatus::new(status, reason));
+    }
+}
+
+impl Drop for StatusUpdater {
+    fn drop(&mut self) {
+        self.update(Status::Stopped, Some("service exited".to_string()));
+    }
+}
+
+/// Handle used to follow the status of a service
+pub struct StatusWatcher {
+    service: ServiceId,
+    handle: OverwatchHandle,
+    receiver: Option<watch::Receiver<ServiceStatus>>,
+}
+
+impl StatusWatcher {
+    pub fn new(service: ServiceId, handle: &OverwatchHandle) -> Self {
+        Self {
+            service,
+            handle: handle.clone(),
+            receiver: None,
+        }
+    }
+
+    async fn receiver(&mut self) -> Result<&mut watch::Receiver<ServiceStatus>, StatusError> {
+        if self.receiver.is_none() {
+            let (reply_channel, receiver) = oneshot::channel();
+            send(
+                &self.handle,
+                StatusMsg::Watch {
+                    service: self.service,
+                    reply_channel,
+                },
+            )
+            .await?;
+            let receiver = receiver
+                .await
+                .map_err(|e| StatusError::Unavailable(e.to_string()))?;
+            self.receiver = Some(receiver);
+        }
+        Ok(self.receiver.as_mut().expect("receiver was just set"))
+    }
+
+    pub async fn current(&mut self) -> Result<ServiceStatus, StatusError> {
+        Ok(self.receiver().await?.borrow().clone())
+    }
+
+    /// Wait until the service is ready or degraded.
+    /// Fails if the service stops before getting there.
+    pub async fn wait_ready(&mut self) -> Result<(), StatusError> {
+        let service = self.service;
+        let status = self
+            .receiver()
+            .await?
+            .wait_for(|status| status.is_ready() || status.status == Status::Stopped)
+            .await
+            .map_err(|e| StatusError::Unavailable(e.to_string()))?;
+        if status.status == Status::Stopped {
+            return Err(StatusError::Stopped {
+                service,
+                reason: status.reason.clone().unwrap_or_default(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Same as [`StatusWatcher::wait_ready`], giving up after `timeout`
+    pub async fn wait_ready_timeout(&mut self, timeout: Duration) -> Result<(), StatusError> {
+        let service = self.service;
+        tokio::time::timeout(timeout, self.wait_ready())
+            .await
+            .map_err(|_| StatusError::Timeout { service })?
+    }
+}
+
+async fn send(handle: &OverwatchHandle, msg: StatusMsg) -> Result<(), StatusError> {
+    let relay = handle
+        .relay::<StatusService>()
+        .connect()
+        .await
+        .map_err(|e| StatusError::Unavailable(format!("{e:?}")))?;
+    relay
+        .send(msg)
+        .await
+        .map_err(|(e, _)| StatusError::Unavailable(format!("{e:?}")))
+}
+
+/// Register the status of service `S`, to be called from its `init`
+pub fn updater<S: ServiceData>(handle: &OverwatchHandle) -> StatusUpdater {
+    StatusUpdater::new(S::SERVICE_ID, handle)
+}
+
+/// Follow the status of service `S`
+pub fn watcher<S: ServiceData>(handle: &OverwatchHandle) -> StatusWatcher {
+    StatusWatcher::new(S::SERVICE_ID, handle)
+}
+
+/// Aggregated status of all the services of a node
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct HealthReport {
+    /// Worst status among all services
+    pub status: Status,
+    pub services: BTreeMap<String, ServiceStatus>,
+}
+
+impl HealthReport {
+    pub fn is_ready(&self) -> bool {
+        matches!(self.status, Status::Ready | Status::Degraded)
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.status != Status::Stopped
+    }
+}
+
+/// Status of the services of the node running on `handle`
+pub async fn health(handle: &OverwatchHandle) -> Result<HealthReport, StatusError> {
+    let (reply_channel, receiver) = oneshot::channel();
+    send(handle, StatusMsg::Health { reply_channel }).await?;
+    receiver
+        .await
+        .map_err(|e| StatusError::Unavailable(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn waits_for_readiness() {
+        let mut registry = StatusRegistry::default();
+        let mut receiver = registry.subscribe("test-ready");
+        assert_eq!(receiver.borrow().status, Status::Starting);
+        assert!(tokio::time::timeout(
+            Duration::from_millis(10),
+            receiver.wait_for(ServiceStatus::is_ready)
+        )
+        .await
+        .is_err());
+
+        registry.update(
+            "test-ready",
+            ServiceStatus::new(Status::Degraded, Some("no peers".to_string())),
+        );
+        receiver.wait_for(ServiceStatus::is_ready).await.unwrap();
+        assert_eq!(receiver.borrow().reason.as_deref(), Some("no peers"));
+        assert_eq!(
+            registry.health().services["test-ready"].status,
+            Status::Degraded
+        );
+    }
+
+    #[test]
+    fn stopped_service_keeps_its_reason() {
+        let mut registry = StatusRegistry::default();
+        registry.update(
+            "test-stopped",
+            ServiceStatus::new(Status::Stopped, Some("disk full".to_string())),
+        );
+        registry.update(
+            "test-stopped",
+            ServiceStatus::new(Status::Stopped, Some("service exited".to_string())),
+        );
+        let health = registry.health();
+        assert!(!health.is_alive());
+        assert_eq!(
+            health.services["test-stopped"].reason.as_deref(),
+            Some("disk full")
+        );
+    }
+
+    #[test]
+    fn registries_are_independent() {
+        let mut a = StatusRegistry::default();
+        let b = StatusRegistry::default();
+        a.update("test-shared", ServiceStatus::new(Status::Ready, None));
+        assert!(a.health().is_ready());
+        assert!(!b.health().services.contains_key("test-shared"));
+    }
+}
@@ -13,6 +13,7 @@ use serde::Serialize;
 // internal
 use backends::StorageBackend;
 use backends::{StorageSerde, StorageTransaction};
+use nomos_service_status::StatusUpdater;
 use overwatch_rs::services::life_cycle::LifecycleMessage;
 use overwatch_rs::services::relay::RelayMessage;
 use overwatch_rs::services::state::{NoOperator, NoState};
@@ -173,6 +174,7 @@ enum StorageServiceError<Backend: StorageBackend> {
 pub struct StorageService<Backend: StorageBackend + Send + Sync + 'static> {
     backend: Backend,
     service_state: ServiceStateHandle<Self>,
+    status: StatusUpdater,
 }
 
 impl<Backend: StorageBackend + Send + Sync + 'static> StorageService<Backend> {
@@ -191,8 +193,12 @@ impl<Backend: StorageBackend + Send + Sync + 'static> StorageService<Backend> {
             LifecycleMessage::Kill => true,
         }
     }
-    async fn handle_storage_message(msg: StorageMsg<Backend>, backend: &mut Backend) {
-        if let Err(e) = match msg {
+    async fn handle_storage_message(
+        msg: StorageMsg<Backend>,
+        backend: &mut Backend,
+        status: &StatusUpdater,
+    ) {
+        let result = match msg {
             StorageMsg::Load { key, reply_channel } => {
                 Self::handle_load(backend, key, reply_channel).await
             }
@@ -208,9 +214,17 @@ impl<Backend: StorageBackend + Send + Sync + 'static> StorageService<Backend> {
                 transaction,
                 reply_channel,
             } => Self::handle_execute(backend, transaction, reply_channel).await,
-        } {
-            // TODO: add proper logging
-            println!("{e}");
+        };
+        match result {
+            // operations going through again mean the backend recovered
+            Ok(()) => status.ready(),
+            Err(e) => {
+                if let StorageServiceError::BackendError(source) = &e {
+                    status.degraded(format!("storage backend error: {source}"));
+                }
+                // TODO: add proper logging
+                println!("{e}");
+            }
         }
     }
     /// Handle load message
@@ -303,9 +317,11 @@ impl<Backend: StorageBackend + Send + Sync + 'static> StorageService<Backend> {
 #[async_trait]
 impl<Backend: StorageBackend + Send + Sync + 'static> ServiceCore for StorageService<Backend> {
     fn init(service_state: ServiceStateHandle<Self>) -> Result<Self, overwatch_rs::DynError> {
+        let status = nomos_service_status::updater::<Self>(&service_state.overwatch_handle);
         Ok(Self {
             backend: Backend::new(service_state.settings_reader.get_updated_settings())?,
             service_state,
+            status,
         })
     }
 
@@ -318,16 +334,19 @@ impl<Backend: StorageBackend + Send + Sync + 'static> ServiceCore for StorageSer
                     lifecycle_handle,
                     ..
                 },
+            status,
         } = self;
         let mut lifecycle_stream = lifecycle_handle.message_stream();
         let backend = &mut backend;
+        status.ready();
         loop {
             tokio::select! {
                 Some(msg) = inbound_relay.recv() => {
-                    Self::handle_storage_message(msg, backend).await;
+                    Self::handle_storage_message(msg, backend, &status).await;
                 }
                 Some(msg) = lifecycle_stream.next() => {
                     if Self::should_stop_service(msg).await {
+                        status.stopped("shutdown");
                         break;
                     }
                 }
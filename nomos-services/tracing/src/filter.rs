@@ -0,0 +1,197 @@
+// std
+use std::collections::HashMap;
+use std::time::Duration;
+// crates
+use nomos_tracing::filter::envfilter::{create_leveled_envfilter, EnvFilterConfig};
+use overwatch_rs::DynError;
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+use tracing::Level;
+use tracing_subscriber::{reload, EnvFilter, Registry};
+// internal
+use crate::serde_level;
+
+pub(crate) type FilterHandle = reload::Handle<EnvFilter, Registry>;
+
+/// Directives deciding which events reach the logging, tracing and metrics layers
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FilterDirectives {
+    /// Level of the events whose target is not matched by any of the `filters`
+    #[serde(with = "serde_level")]
+    pub level: Level,
+    /// Crate/module name to level directives, taking precedence over `level`
+    #[serde(default)]
+    pub filters: HashMap<String, String>,
+}
+
+impl FilterDirectives {
+    pub fn to_envfilter(&self) -> Result<EnvFilter, DynError> {
+        create_leveled_envfilter(
+            self.level,
+            EnvFilterConfig {
+                filters: self.filters.clone(),
+            },
+        )
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FilterUpdate {
+    #[serde(flatten)]
+    pub directives: FilterDirectives,
+    /// If set, the previous directives are restored once this time elapses, e.g. `"10m"`
+    #[serde(default, with = "humantime_serde")]
+    pub duration: Option<Duration>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FilterStatus {
+    pub directives: FilterDirectives,
+    /// Time left before the directives in use revert, if they were set for a limited time
+    #[serde(with = "humantime_serde")]
+    pub reverts_in: Option<Duration>,
+}
+
+/// Filter of the installed subscriber, which can be swapped at runtime.
+///
+/// Directives set without a duration replace the ones in use until the node restarts, while
+/// timed ones are reverted to the last untimed directives when they expire.
+pub(crate) struct ReloadableFilter {
+    handle: FilterHandle,
+    configured: FilterDirectives,
+    base: FilterDirectives,
+    active: FilterDirectives,
+    revert_at: Option<Instant>,
+}
+
+impl ReloadableFilter {
+    pub fn new(handle: FilterHandle, configured: FilterDirectives) -> Self {
+        Self {
+            handle,
+            base: configured.clone(),
+            active: configured.clone(),
+            configured,
+            revert_at: None,
+        }
+    }
+
+    pub fn status(&self, now: Instant) -> FilterStatus {
+        FilterStatus {
+            directives: self.active.clone(),
+            reverts_in: self.revert_at.map(|at| at.saturating_duration_since(now)),
+        }
+    }
+
+    pub fn revert_at(&self) -> Option<Instant> {
+        self.revert_at
+    }
+
+    pub fn update(&mut self, update: FilterUpdate, now: Instant) -> Result<FilterStatus, DynError> {
+        let FilterUpdate {
+            directives,
+            duration,
+        } = update;
+        self.apply(&directives)?;
+        if duration.is_none() {
+            self.base = directives.clone();
+        }
+        self.active = directives;
+        self.revert_at = duration.map(|duration| now + duration);
+        Ok(self.status(now))
+    }
+
+    /// Restores the directives from the service settings
+    pub fn reset(&mut self, now: Instant) -> Result<FilterStatus, DynError> {
+        self.base = self.configured.clone();
+        self.revert(now)
+    }
+
+    /// Drops the timed directives in use, if any
+    pub fn revert(&mut self, now: Instant) -> Result<FilterStatus, DynError> {
+        let base = self.base.clone();
+        self.apply(&base)?;
+        self.active = base;
+        self.revert_at = None;
+        Ok(self.status(now))
+    }
+
+    fn apply(&self, directives: &FilterDirectives) -> Result<(), DynError> {
+        let filter = directives.to_envfilter()?;
+        self.handle.reload(filter).map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    fn directives(level: Level, filters: &[(&str, &str)]) -> FilterDirectives {
+        FilterDirectives {
+            level,
+            filters: filters
+                .iter()
+                .map(|(target, level)| (target.to_string(), level.to_string()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn timed_update_reverts_to_last_untimed_directives() {
+        let configured = directives(Level::INFO, &[]);
+        let (layer, handle) = reload::Layer::new(configured.to_envfilter().unwrap());
+        let _subscriber = Registry::default().with(layer);
+        let mut filter = ReloadableFilter::new(handle, configured.clone());
+        let now = Instant::now();
+
+        let permanent = directives(Level::WARN, &[("nomos_da_sampling", "info")]);
+        filter
+            .update(
+                FilterUpdate {
+                    directives: permanent.clone(),
+                    duration: None,
+                },
+                now,
+            )
+            .unwrap();
+        assert_eq!(filter.revert_at(), None);
+
+        let debug = directives(Level::DEBUG, &[]);
+        let status = filter
+            .update(
+                FilterUpdate {
+                    directives: debug.clone(),
+                    duration: Some(Duration::from_secs(60)),
+                },
+                now,
+            )
+            .unwrap();
+        assert_eq!(status.directives, debug);
+        assert_eq!(status.reverts_in, Some(Duration::from_secs(60)));
+
+        let status = filter.revert(now).unwrap();
+        assert_eq!(status.directives, permanent);
+        assert_eq!(status.reverts_in, None);
+
+        let status = filter.reset(now).unwrap();
+        assert_eq!(status.directives, configured);
+    }
+
+    #[test]
+    fn invalid_directives_are_rejected() {
+        let configured = directives(Level::INFO, &[]);
+        let (layer, handle) = reload::Layer::new(configured.to_envfilter().unwrap());
+        let _subscriber = Registry::default().with(layer);
+        let mut filter = ReloadableFilter::new(handle, configured.clone());
+
+        let result = filter.update(
+            FilterUpdate {
+                directives: directives(Level::DEBUG, &[("nomos", "not-a-level")]),
+                duration: None,
+            },
+            Instant::now(),
+        );
+        assert!(result.is_err());
+        assert_eq!(filter.status(Instant::now()).directives, configured);
+    }
+}
@@ -1,35 +1,68 @@
+mod filter;
+
 // std
 use std::fmt::{Debug, Formatter};
 use std::io::Write;
 use std::sync::{Arc, Mutex};
 // crates
 use futures::StreamExt;
-use nomos_tracing::filter::envfilter::{create_envfilter_layer, EnvFilterConfig};
+use nomos_tracing::filter::envfilter::EnvFilterConfig;
 use nomos_tracing::logging::gelf::{create_gelf_layer, GelfConfig};
-use nomos_tracing::logging::local::{create_file_layer, create_writer_layer, FileConfig};
+use nomos_tracing::logging::local::{
+    create_file_layer, create_writer_layer, FileConfig, LogFormat,
+};
 use nomos_tracing::logging::loki::{create_loki_layer, LokiConfig};
 use nomos_tracing::metrics::otlp::{create_otlp_metrics_layer, OtlpMetricsConfig};
+use nomos_tracing::metrics::prometheus::{
+    create_prometheus_metrics_layer, PrometheusMetricsConfig,
+};
 use overwatch_rs::services::life_cycle::LifecycleMessage;
 use overwatch_rs::services::{
     handle::ServiceStateHandle,
-    relay::NoMessage,
+    relay::RelayMessage,
     state::{NoOperator, NoState},
     ServiceCore, ServiceData,
 };
+use overwatch_rs::DynError;
 use serde::{Deserialize, Serialize};
-use tracing::{error, Level};
+use tokio::sync::oneshot;
+use tokio::time::Instant;
+use tracing::{error, info, Level};
 use tracing_appender::non_blocking::WorkerGuard;
-use tracing_subscriber::filter::LevelFilter;
 use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::reload;
 use tracing_subscriber::util::SubscriberInitExt;
 // internal
+use filter::ReloadableFilter;
+pub use filter::{FilterDirectives, FilterStatus, FilterUpdate};
+use nomos_tracing::tracing::memory::{create_memory_tracing_layer, InMemorySpanExporter};
 use nomos_tracing::tracing::otlp::{create_otlp_tracing_layer, OtlpTracingConfig};
 
 pub struct Tracing {
     service_state: ServiceStateHandle<Self>,
     logger_guard: Option<WorkerGuard>,
+    filter: Option<ReloadableFilter>,
+}
+
+#[derive(Debug)]
+pub enum TracingMsg {
+    /// Directives currently filtering the events of the node
+    GetFilter {
+        reply_channel: oneshot::Sender<Option<FilterStatus>>,
+    },
+    /// Replaces the directives in use, for a limited time if the update has a duration
+    SetFilter {
+        update: FilterUpdate,
+        reply_channel: oneshot::Sender<Result<FilterStatus, DynError>>,
+    },
+    /// Restores the directives from the settings
+    ResetFilter {
+        reply_channel: oneshot::Sender<Result<FilterStatus, DynError>>,
+    },
 }
 
+impl RelayMessage for TracingMsg {}
+
 /// This is a wrapper around a writer to allow cloning which is
 /// required by contract by Overwatch for a configuration struct
 #[derive(Clone)]
@@ -85,6 +118,9 @@ pub enum LoggerLayer {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum TracingLayer {
     Otlp(OtlpTracingConfig),
+    /// Keep the spans in memory, for tests to assert on
+    #[serde(skip)]
+    InMemory(InMemorySpanExporter),
     None,
 }
 
@@ -97,6 +133,8 @@ pub enum FilterLayer {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum MetricsLayer {
     Otlp(OtlpMetricsConfig),
+    /// Serve metrics from the node `/metrics` endpoint
+    Prometheus(PrometheusMetricsConfig),
     None,
 }
 
@@ -108,6 +146,23 @@ pub struct TracingSettings {
     pub metrics: MetricsLayer,
     #[serde(with = "serde_level")]
     pub level: Level,
+    /// Format of the logs written by the `File`, `Stdout`, `Stderr` and `Writer` loggers
+    #[serde(default)]
+    pub format: LogFormat,
+}
+
+impl TracingSettings {
+    /// Directives the subscriber filter is installed with
+    pub fn filter_directives(&self) -> FilterDirectives {
+        let filters = match &self.filter {
+            FilterLayer::EnvFilter(EnvFilterConfig { filters }) => filters.clone(),
+            FilterLayer::None => Default::default(),
+        };
+        FilterDirectives {
+            level: self.level,
+            filters,
+        }
+    }
 }
 
 impl Default for TracingSettings {
@@ -118,6 +173,7 @@ impl Default for TracingSettings {
             filter: FilterLayer::None,
             metrics: MetricsLayer::None,
             level: Level::DEBUG,
+            format: LogFormat::Plain,
         }
     }
 }
@@ -130,6 +186,7 @@ impl TracingSettings {
         filter: FilterLayer,
         metrics: MetricsLayer,
         level: Level,
+        format: LogFormat,
     ) -> Self {
         Self {
             logger,
@@ -137,6 +194,7 @@ impl TracingSettings {
             filter,
             metrics,
             level,
+            format,
         }
     }
 }
@@ -146,7 +204,7 @@ impl ServiceData for Tracing {
     type Settings = TracingSettings;
     type State = NoState<Self::Settings>;
     type StateOperator = NoOperator<Self::State>;
-    type Message = NoMessage;
+    type Message = TracingMsg;
 }
 
 #[async_trait::async_trait]
@@ -158,6 +216,7 @@ impl ServiceCore for Tracing {
         static ONCE_INIT: Once = Once::new();
 
         let config = service_state.settings_reader.get_updated_settings();
+        let directives = config.filter_directives();
         let mut layers: Vec<Box<dyn tracing_subscriber::Layer<_> + Send + Sync>> = vec![];
 
         let (logger_layer, logger_guard): (
@@ -169,9 +228,9 @@ impl ServiceCore for Tracing {
                     create_gelf_layer(config, service_state.overwatch_handle.runtime())?;
                 (Box::new(gelf_layer), None)
             }
-            LoggerLayer::File(config) => {
-                let (layer, guard) = create_file_layer(config);
-                (Box::new(layer), Some(guard))
+            LoggerLayer::File(file_config) => {
+                let (layer, guard) = create_file_layer(file_config, config.format)?;
+                (layer, Some(guard))
             }
             LoggerLayer::Loki(config) => {
                 let loki_layer =
@@ -179,35 +238,44 @@ impl ServiceCore for Tracing {
                 (Box::new(loki_layer), None)
             }
             LoggerLayer::Stdout => {
-                let (layer, guard) = create_writer_layer(std::io::stdout());
-                (Box::new(layer), Some(guard))
+                let (layer, guard) = create_writer_layer(std::io::stdout(), config.format);
+                (layer, Some(guard))
             }
             LoggerLayer::Stderr => {
-                let (layer, guard) = create_writer_layer(std::io::stderr());
-                (Box::new(layer), Some(guard))
+                let (layer, guard) = create_writer_layer(std::io::stderr(), config.format);
+                (layer, Some(guard))
             }
             LoggerLayer::Writer(writer) => {
-                let (layer, guard) = create_writer_layer(writer);
-                (Box::new(layer), Some(guard))
+                let (layer, guard) = create_writer_layer(writer, config.format);
+                (layer, Some(guard))
             }
             LoggerLayer::None => (Box::new(tracing_subscriber::fmt::Layer::new()), None),
         };
 
         layers.push(logger_layer);
 
-        if let TracingLayer::Otlp(config) = config.tracing {
-            let tracing_layer = create_otlp_tracing_layer(config)?;
-            layers.push(Box::new(tracing_layer));
-        }
-
-        if let FilterLayer::EnvFilter(config) = config.filter {
-            let filter_layer = create_envfilter_layer(config)?;
-            layers.push(Box::new(filter_layer));
+        match config.tracing {
+            TracingLayer::Otlp(config) => {
+                let tracing_layer = create_otlp_tracing_layer(config)?;
+                layers.push(Box::new(tracing_layer));
+            }
+            TracingLayer::InMemory(exporter) => {
+                let tracing_layer = create_memory_tracing_layer(exporter);
+                layers.push(Box::new(tracing_layer));
+            }
+            TracingLayer::None => {}
         }
 
-        if let MetricsLayer::Otlp(config) = config.metrics {
-            let metrics_layer = create_otlp_metrics_layer(config)?;
-            layers.push(Box::new(metrics_layer));
+        match config.metrics {
+            MetricsLayer::Otlp(config) => {
+                let metrics_layer = create_otlp_metrics_layer(config)?;
+                layers.push(Box::new(metrics_layer));
+            }
+            MetricsLayer::Prometheus(config) => {
+                let metrics_layer = create_prometheus_metrics_layer(config)?;
+                layers.push(Box::new(metrics_layer));
+            }
+            MetricsLayer::None => {}
         }
 
         // If no layers are created, tracing subscriber is not required.
@@ -215,51 +283,70 @@ impl ServiceCore for Tracing {
             return Ok(Self {
                 service_state,
                 logger_guard: None,
+                filter: None,
             });
         }
 
+        // The filter sits behind a reload layer so its directives can be changed at runtime
+        let (filter_layer, filter_handle) = reload::Layer::new(directives.to_envfilter()?);
+
         #[cfg(test)]
         ONCE_INIT.call_once(move || {
             tracing_subscriber::registry()
-                .with(LevelFilter::from(config.level))
+                .with(filter_layer)
                 .with(layers)
                 .init();
         });
         #[cfg(not(test))]
         tracing_subscriber::registry()
-            .with(LevelFilter::from(config.level))
+            .with(filter_layer)
             .with(layers)
             .init();
 
         Ok(Self {
             service_state,
             logger_guard,
+            filter: Some(ReloadableFilter::new(filter_handle, directives)),
         })
     }
 
     async fn run(self) -> Result<(), overwatch_rs::DynError> {
         let Self {
-            service_state,
+            mut service_state,
             logger_guard,
+            mut filter,
         } = self;
-        // keep the handle alive without stressing the runtime
         let mut lifecycle_stream = service_state.lifecycle_handle.message_stream();
         loop {
-            if let Some(msg) = lifecycle_stream.next().await {
-                match msg {
-                    LifecycleMessage::Shutdown(sender) => {
-                        // flush pending logs before signaling message processing
-                        drop(logger_guard);
-                        if sender.send(()).is_err() {
-                            error!(
-                                "Error sending successful shutdown signal from service {}",
-                                Self::SERVICE_ID
-                            );
+            let revert_at = filter.as_ref().and_then(ReloadableFilter::revert_at);
+            tokio::select! {
+                Some(msg) = service_state.inbound_relay.recv() => {
+                    handle_message(filter.as_mut(), msg);
+                }
+                _ = wait_until(revert_at) => {
+                    if let Some(filter) = filter.as_mut() {
+                        match filter.revert(Instant::now()) {
+                            Ok(status) => info!("Timed log filter expired, restored {:?}", status.directives),
+                            Err(e) => error!("Error restoring log filter: {e}"),
                         }
-                        break;
                     }
-                    LifecycleMessage::Kill => {
-                        break;
+                }
+                Some(msg) = lifecycle_stream.next() => {
+                    match msg {
+                        LifecycleMessage::Shutdown(sender) => {
+                            // flush pending logs before signaling message processing
+                            drop(logger_guard);
+                            if sender.send(()).is_err() {
+                                error!(
+                                    "Error sending successful shutdown signal from service {}",
+                                    Self::SERVICE_ID
+                                );
+                            }
+                            break;
+                        }
+                        LifecycleMessage::Kill => {
+                            break;
+                        }
                     }
                 }
             }
@@ -268,7 +355,50 @@ impl ServiceCore for Tracing {
     }
 }
 
-mod serde_level {
+fn handle_message(filter: Option<&mut ReloadableFilter>, msg: TracingMsg) {
+    const NO_FILTER: &str = "No subscriber filter installed";
+    let now = Instant::now();
+    match msg {
+        TracingMsg::GetFilter { reply_channel } => {
+            if reply_channel
+                .send(filter.map(|filter| filter.status(now)))
+                .is_err()
+            {
+                error!("Could not send log filter through reply channel");
+            }
+        }
+        TracingMsg::SetFilter {
+            update,
+            reply_channel,
+        } => {
+            let result = filter.map_or_else(
+                || Err(NO_FILTER.into()),
+                |filter| filter.update(update, now),
+            );
+            if let Ok(status) = &result {
+                info!("Log filter set to {:?}", status);
+            }
+            if reply_channel.send(result).is_err() {
+                error!("Could not send log filter through reply channel");
+            }
+        }
+        TracingMsg::ResetFilter { reply_channel } => {
+            let result = filter.map_or_else(|| Err(NO_FILTER.into()), |filter| filter.reset(now));
+            if reply_channel.send(result).is_err() {
+                error!("Could not send log filter through reply channel");
+            }
+        }
+    }
+}
+
+async fn wait_until(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
+}
+
+pub(crate) mod serde_level {
     use super::Level;
     use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
 
@@ -3,6 +3,7 @@ use std::collections::HashMap;
 use std::error::Error;
 // crates
 use serde::{Deserialize, Serialize};
+use tracing::Level;
 use tracing_subscriber::EnvFilter;
 // internal
 
@@ -16,12 +17,28 @@ pub struct EnvFilterConfig {
 pub fn create_envfilter_layer(
     config: EnvFilterConfig,
 ) -> Result<EnvFilter, Box<dyn Error + Send + Sync>> {
-    let filter_string = config
+    EnvFilter::try_new(directives(config).join(",")).map_err(|e| e.into())
+}
+
+/// Filter letting through the events at `level` or above, unless a directive of the config
+/// matches their target.
+pub fn create_leveled_envfilter(
+    level: Level,
+    config: EnvFilterConfig,
+) -> Result<EnvFilter, Box<dyn Error + Send + Sync>> {
+    let mut filter_string = level.as_str().to_string();
+    for directive in directives(config) {
+        filter_string.push(',');
+        filter_string.push_str(&directive);
+    }
+
+    EnvFilter::try_new(filter_string).map_err(|e| e.into())
+}
+
+fn directives(config: EnvFilterConfig) -> Vec<String> {
+    config
         .filters
         .into_iter()
         .map(|(target, level)| format!("{}={}", target, level))
-        .collect::<Vec<_>>()
-        .join(",");
-
-    EnvFilter::try_new(filter_string).map_err(|e| e.into())
+        .collect()
 }
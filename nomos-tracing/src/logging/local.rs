@@ -1,33 +1,227 @@
 // std
-use std::{io::Write, path::PathBuf};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 // crates
 use serde::{Deserialize, Serialize};
+use time::{format_description::FormatItem, macros::format_description, OffsetDateTime};
+use tracing::Subscriber;
 use tracing_appender::non_blocking::WorkerGuard;
-use tracing_subscriber::fmt::{
-    format::{DefaultFields, Format},
-    Layer,
-};
+use tracing_subscriber::{registry::LookupSpan, Layer};
 // internal
 
-pub type FmtLayer<S> = Layer<S, DefaultFields, Format, tracing_appender::non_blocking::NonBlocking>;
+pub type BoxedLayer<S> = Box<dyn Layer<S> + Send + Sync>;
+
+/// Format of the logs written to files and standard streams
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LogFormat {
+    /// Human readable lines
+    #[default]
+    Plain,
+    /// One JSON object per line, along with the fields of the spans the event was emitted in
+    Json,
+}
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct FileConfig {
     pub directory: PathBuf,
     pub prefix: Option<PathBuf>,
+    #[serde(default)]
+    pub rotation: RotationConfig,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RotationPeriod {
+    Minutely,
+    #[default]
+    Hourly,
+    Daily,
+    Never,
+}
+
+impl RotationPeriod {
+    fn format(&self) -> Option<&'static [FormatItem<'static>]> {
+        match self {
+            Self::Minutely => Some(format_description!("[year]-[month]-[day]-[hour]-[minute]")),
+            Self::Hourly => Some(format_description!("[year]-[month]-[day]-[hour]")),
+            Self::Daily => Some(format_description!("[year]-[month]-[day]")),
+            Self::Never => None,
+        }
+    }
+
+    /// Suffix of the files written during the period `now` falls in
+    fn suffix(&self, now: OffsetDateTime) -> Option<String> {
+        self.format().map(|format| {
+            now.format(format)
+                .expect("log file suffix should be formattable")
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct RotationConfig {
+    /// A new file is started at the beginning of every period
+    #[serde(default)]
+    pub period: RotationPeriod,
+    /// Size in bytes after which a new file is started within the same period
+    #[serde(default)]
+    pub max_file_size: Option<u64>,
+    /// Number of log files kept in the directory, the oldest ones are removed on rotation
+    #[serde(default)]
+    pub max_files: Option<usize>,
+}
+
+/// Appender rotating the files it writes to over time and size.
+///
+/// Files are named `<prefix>.<period>` and `<prefix>.<period>.<n>` for the ones started when
+/// the previous one of the same period reached the maximum size.
+pub struct RollingFileWriter {
+    directory: PathBuf,
+    prefix: String,
+    rotation: RotationConfig,
+    period: Option<String>,
+    index: usize,
+    file: File,
+    path: PathBuf,
+    written: u64,
+}
+
+impl RollingFileWriter {
+    pub fn new(directory: PathBuf, prefix: String, rotation: RotationConfig) -> io::Result<Self> {
+        fs::create_dir_all(&directory)?;
+        let period = rotation.period.suffix(OffsetDateTime::now_utc());
+        let path = file_path(&directory, &prefix, period.as_deref(), 0);
+        let (file, written) = open(&path)?;
+        let writer = Self {
+            directory,
+            prefix,
+            rotation,
+            period,
+            index: 0,
+            file,
+            path,
+            written,
+        };
+        writer.prune()?;
+        Ok(writer)
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        self.path = file_path(
+            &self.directory,
+            &self.prefix,
+            self.period.as_deref(),
+            self.index,
+        );
+        (self.file, self.written) = open(&self.path)?;
+        self.prune()
+    }
+
+    /// Removes the oldest log files beyond the retention limit
+    fn prune(&self) -> io::Result<()> {
+        let Some(max_files) = self.rotation.max_files else {
+            return Ok(());
+        };
+        let mut files = fs::read_dir(&self.directory)?
+            .filter_map(Result::ok)
+            .filter_map(|entry| {
+                let order = file_order(&self.prefix, &entry.file_name().to_string_lossy())?;
+                Some((order, entry.path()))
+            })
+            .filter(|(_, path)| path != &self.path)
+            .collect::<Vec<_>>();
+        // keep a slot for the file being written
+        let keep = max_files.saturating_sub(1);
+        if files.len() <= keep {
+            return Ok(());
+        }
+        files.sort();
+        for (_, path) in &files[..files.len() - keep] {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
+
+impl Write for RollingFileWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let period = self.rotation.period.suffix(OffsetDateTime::now_utc());
+        if period != self.period {
+            self.period = period;
+            self.index = 0;
+            self.rotate()?;
+        }
+        if let Some(max_file_size) = self.rotation.max_file_size {
+            while self.written > 0 && self.written + buf.len() as u64 > max_file_size {
+                self.index += 1;
+                self.rotate()?;
+            }
+        }
+        let written = self.file.write(buf)?;
+        self.written += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+fn file_path(directory: &Path, prefix: &str, period: Option<&str>, index: usize) -> PathBuf {
+    let mut name = prefix.to_string();
+    if let Some(period) = period {
+        name.push('.');
+        name.push_str(period);
+    }
+    if index > 0 {
+        name.push_str(&format!(".{index}"));
+    }
+    directory.join(name)
+}
+
+/// Period and index of a log file named by [`file_path`], which order files from the oldest to
+/// the newest. `None` for files not written by this appender.
+fn file_order(prefix: &str, name: &str) -> Option<(Option<String>, usize)> {
+    let suffix = name.strip_prefix(prefix)?;
+    if suffix.is_empty() {
+        return Some((None, 0));
+    }
+    let parts = suffix.strip_prefix('.')?.split('.').collect::<Vec<_>>();
+    match parts.as_slice() {
+        [index] if index.chars().all(|c| c.is_ascii_digit()) => Some((None, index.parse().ok()?)),
+        [period] => Some((Some(period.to_string()), 0)),
+        [period, index] => Some((Some(period.to_string()), index.parse().ok()?)),
+        _ => None,
+    }
+}
+
+fn open(path: &Path) -> io::Result<(File, u64)> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    let len = file.metadata()?.len();
+    Ok((file, len))
 }
 
-pub fn create_file_layer<S>(config: FileConfig) -> (FmtLayer<S>, WorkerGuard) {
-    let file_appender = tracing_appender::rolling::hourly(
-        config.directory,
-        config.prefix.unwrap_or_else(|| PathBuf::from("nomos.log")),
-    );
+pub fn create_file_layer<S>(
+    config: FileConfig,
+    format: LogFormat,
+) -> io::Result<(BoxedLayer<S>, WorkerGuard)>
+where
+    S: Subscriber + for<'a> LookupSpan<'a> + 'static,
+{
+    let prefix = config
+        .prefix
+        .unwrap_or_else(|| PathBuf::from("nomos.log"))
+        .to_string_lossy()
+        .into_owned();
+    let file_appender = RollingFileWriter::new(config.directory, prefix, config.rotation)?;
 
-    create_writer_layer(file_appender)
+    Ok(create_writer_layer(file_appender, format))
 }
 
-pub fn create_writer_layer<S, W>(writer: W) -> (FmtLayer<S>, WorkerGuard)
+pub fn create_writer_layer<S, W>(writer: W, format: LogFormat) -> (BoxedLayer<S>, WorkerGuard)
 where
+    S: Subscriber + for<'a> LookupSpan<'a> + 'static,
     W: Write + Send + 'static,
 {
     let (non_blocking, guard) = tracing_appender::non_blocking(writer);
@@ -35,6 +229,129 @@ where
     let layer = tracing_subscriber::fmt::Layer::new()
         .with_level(true)
         .with_writer(non_blocking);
+    let layer: BoxedLayer<S> = match format {
+        LogFormat::Plain => Box::new(layer),
+        LogFormat::Json => Box::new(layer.json().with_current_span(true).with_span_list(true)),
+    };
 
     (layer, guard)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn emit_logs<W: Write + Send + 'static>(writer: W, format: LogFormat, count: usize) {
+        let (layer, guard) = create_writer_layer(writer, format);
+        let subscriber = tracing_subscriber::registry().with(layer);
+        tracing::subscriber::with_default(subscriber, || {
+            let block = tracing::info_span!("process_block", slot = 42, header_id = "0xcafe");
+            let _block = block.enter();
+            for i in 0..count {
+                let sampling = tracing::info_span!("sampling", blob_id = "0xbeef");
+                let _sampling = sampling.enter();
+                tracing::info!(peer_id = "12D3KooW", "sampled blob {i}");
+            }
+        });
+        // flushes pending logs
+        drop(guard);
+    }
+
+    fn parse_lines(logs: &str) -> Vec<serde_json::Value> {
+        logs.lines()
+            .map(|line| serde_json::from_str(line).expect("log line should be valid json"))
+            .collect()
+    }
+
+    #[test]
+    fn json_logs_carry_span_fields() {
+        let buffer = Buffer::default();
+        emit_logs(buffer.clone(), LogFormat::Json, 1);
+
+        let logs = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let lines = parse_lines(&logs);
+        assert_eq!(lines.len(), 1);
+        let line = &lines[0];
+        assert_eq!(line["level"], "INFO");
+        assert_eq!(line["fields"]["message"], "sampled blob 0");
+        assert_eq!(line["fields"]["peer_id"], "12D3KooW");
+        assert_eq!(line["span"]["name"], "sampling");
+        assert_eq!(line["span"]["blob_id"], "0xbeef");
+        let spans = line["spans"].as_array().unwrap();
+        assert_eq!(spans[0]["name"], "process_block");
+        assert_eq!(spans[0]["slot"], 42);
+        assert_eq!(spans[0]["header_id"], "0xcafe");
+    }
+
+    #[test]
+    fn orders_files_by_period_and_index() {
+        let mut names = vec![
+            "node.log.2024-01-02-00.10",
+            "node.log.2024-01-02-00.2",
+            "node.log.2024-01-02-00",
+            "node.log.2024-01-01-23.11",
+        ];
+        names.sort_by_key(|name| file_order("node.log", name));
+        assert_eq!(
+            names,
+            vec![
+                "node.log.2024-01-01-23.11",
+                "node.log.2024-01-02-00",
+                "node.log.2024-01-02-00.2",
+                "node.log.2024-01-02-00.10",
+            ]
+        );
+        assert!(file_order("node.log", "node.log.10") > file_order("node.log", "node.log.9"));
+        assert_eq!(file_order("node.log", "other.log"), None);
+    }
+
+    #[test]
+    fn rotates_on_size_and_keeps_max_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let writer = RollingFileWriter::new(
+            dir.path().to_owned(),
+            "node.log".to_string(),
+            RotationConfig {
+                period: RotationPeriod::Never,
+                max_file_size: Some(1024),
+                max_files: Some(3),
+            },
+        )
+        .unwrap();
+        emit_logs(writer, LogFormat::Json, 100);
+
+        let files = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect::<Vec<_>>();
+        assert_eq!(files.len(), 3);
+        let mut messages = Vec::new();
+        for file in files {
+            let logs = fs::read_to_string(&file).unwrap();
+            assert!(logs.len() <= 1024);
+            messages.extend(
+                parse_lines(&logs)
+                    .into_iter()
+                    .map(|line| line["fields"]["message"].as_str().unwrap().to_string()),
+            );
+        }
+        // the oldest files are the ones removed
+        assert!(messages.contains(&"sampled blob 99".to_string()));
+        assert!(!messages.contains(&"sampled blob 0".to_string()));
+    }
+}
@@ -0,0 +1,77 @@
+//! Pull based metrics, to be scraped from the node `/metrics` endpoint.
+//!
+//! Services record through the global OpenTelemetry meter, so the same metrics are available
+//! with either this layer or the OTLP one:
+//!
+//! | metric                             | type      | recorded by                          |
+//! |------------------------------------|-----------|--------------------------------------|
+//! | `cryptarchia_chain_height`         | gauge     | consensus, height of the local tip   |
+//! | `cryptarchia_forks`                | gauge     | consensus, number of known branches  |
+//! | `cryptarchia_slot_lag`             | gauge     | consensus, current slot - tip slot   |
+//! | `cryptarchia_leader_wins`          | counter   | consensus, slots won by this node    |
+//! | `mempool_pending_items`            | gauge     | mempools, by `pool` (`cl` or `da`)   |
+//! | `da_dispersal_latency`             | histogram | dispersal, seconds per dispersal     |
+//! | `da_sampling_successes`            | counter   | sampling, by `subnetwork`            |
+//! | `da_sampling_failures`             | counter   | sampling, by `subnetwork` if known   |
+//! | `mix_emitted_messages`             | counter   | mix, messages sent to the mix network|
+//! | `libp2p_connected_peers`           | gauge     | network and DA network, by `network` |
+//! | `tracing_dropped_spans`            | counter   | otlp tracing, by `reason`            |
+// std
+use std::error::Error;
+use std::sync::OnceLock;
+// crates
+use opentelemetry::{global, KeyValue};
+use opentelemetry_sdk::{metrics::SdkMeterProvider, Resource};
+use prometheus::{Encoder, Registry, TextEncoder};
+use serde::{Deserialize, Serialize};
+use tracing::Subscriber;
+use tracing_opentelemetry::MetricsLayer;
+use tracing_subscriber::registry::LookupSpan;
+// internal
+
+/// Content type of the encoded metrics
+pub const CONTENT_TYPE: &str = prometheus::TEXT_FORMAT;
+
+static REGISTRY: OnceLock<Registry> = OnceLock::new();
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PrometheusMetricsConfig {
+    pub host_identifier: String,
+}
+
+pub fn create_prometheus_metrics_layer<S>(
+    config: PrometheusMetricsConfig,
+) -> Result<MetricsLayer<S>, Box<dyn Error + Send + Sync>>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    let registry = REGISTRY.get_or_init(Registry::new).clone();
+    let exporter = opentelemetry_prometheus::exporter()
+        .with_registry(registry)
+        .build()?;
+
+    let resource = Resource::new(vec![KeyValue::new(
+        opentelemetry_semantic_conventions::resource::SERVICE_NAME,
+        config.host_identifier,
+    )]);
+    let meter_provider = SdkMeterProvider::builder()
+        .with_reader(exporter)
+        .with_resource(resource)
+        .build();
+
+    global::set_meter_provider(meter_provider.clone());
+    Ok(MetricsLayer::new(meter_provider))
+}
+
+/// Encode the current value of all metrics in the Prometheus text format.
+/// Returns `None` if the Prometheus layer was not set up.
+pub fn encode_metrics() -> Option<Result<String, Box<dyn Error + Send + Sync>>> {
+    let registry = REGISTRY.get()?;
+    let mut buffer = Vec::new();
+    Some(
+        TextEncoder::new()
+            .encode(&registry.gather(), &mut buffer)
+            .map_err(Into::into)
+            .and_then(|_| String::from_utf8(buffer).map_err(Into::into)),
+    )
+}
@@ -0,0 +1,60 @@
+// std
+use std::sync::{Arc, Mutex};
+// crates
+use futures::future::BoxFuture;
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_sdk::export::trace::{ExportResult, SpanData, SpanExporter};
+use opentelemetry_sdk::trace::{Tracer, TracerProvider};
+use tracing::Subscriber;
+use tracing_opentelemetry::OpenTelemetryLayer;
+use tracing_subscriber::registry::LookupSpan;
+// internal
+
+/// Exporter keeping the spans in memory, so tests can assert on the spans a scenario produced.
+///
+/// Clones share the same spans.
+#[derive(Clone, Debug, Default)]
+pub struct InMemorySpanExporter {
+    spans: Arc<Mutex<Vec<SpanData>>>,
+}
+
+impl InMemorySpanExporter {
+    pub fn spans(&self) -> Vec<SpanData> {
+        self.spans.lock().unwrap().clone()
+    }
+
+    pub fn span_names(&self) -> Vec<String> {
+        self.spans
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|span| span.name.to_string())
+            .collect()
+    }
+
+    pub fn clear(&self) {
+        self.spans.lock().unwrap().clear();
+    }
+}
+
+impl SpanExporter for InMemorySpanExporter {
+    fn export(&mut self, batch: Vec<SpanData>) -> BoxFuture<'static, ExportResult> {
+        self.spans.lock().unwrap().extend(batch);
+        Box::pin(std::future::ready(Ok(())))
+    }
+}
+
+/// Layer exporting every span to `exporter` as soon as it ends
+pub fn create_memory_tracing_layer<S>(
+    exporter: InMemorySpanExporter,
+) -> OpenTelemetryLayer<S, Tracer>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    let tracer_provider = TracerProvider::builder()
+        .with_simple_exporter(exporter)
+        .build();
+    let tracer = tracer_provider.tracer("NomosTracer");
+
+    OpenTelemetryLayer::new(tracer)
+}
@@ -1,9 +1,18 @@
 // std
+use std::collections::HashMap;
 use std::error::Error;
+use std::sync::{Once, OnceLock};
+use std::time::Duration;
 // crates
-use opentelemetry::{global, trace::TracerProvider as _};
+use opentelemetry::metrics::Counter;
+use opentelemetry::trace::{
+    Link, SamplingResult, SpanKind, TraceError, TraceId, TracerProvider as _,
+};
+use opentelemetry::{global, Context, KeyValue};
 use opentelemetry_otlp::WithExportConfig;
-use opentelemetry_sdk::trace::{BatchConfig, Sampler, Tracer};
+use opentelemetry_sdk::runtime::TrySendError;
+use opentelemetry_sdk::trace::{BatchConfigBuilder, Sampler, ShouldSample, Tracer};
+use opentelemetry_semantic_conventions::trace::CODE_NAMESPACE;
 use serde::{Deserialize, Serialize};
 use tracing::Subscriber;
 use tracing_opentelemetry::OpenTelemetryLayer;
@@ -14,7 +23,109 @@ use url::Url;
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct OtlpTracingConfig {
     pub endpoint: Url,
+    /// Ratio of the traces exported, decided when their root span starts
     pub sample_ratio: f64,
+    /// Ratios overriding `sample_ratio` for the traces whose root span is in a target, i.e. a
+    /// module path such as `nomos_da_sampling`. The longest matching target is used.
+    #[serde(default)]
+    pub target_sample_ratios: HashMap<String, f64>,
+    #[serde(default)]
+    pub batch: OtlpBatchConfig,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OtlpBatchConfig {
+    /// Spans waiting to be exported, the ones ending while the queue is full are dropped
+    pub max_queue_size: usize,
+    /// Spans sent to the collector in a single request
+    pub max_export_batch_size: usize,
+    /// Delay between two exports
+    #[serde(with = "humantime_serde")]
+    pub scheduled_delay: Duration,
+    /// Export requests in flight at the same time
+    pub max_concurrent_exports: usize,
+}
+
+impl Default for OtlpBatchConfig {
+    fn default() -> Self {
+        Self {
+            max_queue_size: 2048,
+            max_export_batch_size: 512,
+            scheduled_delay: Duration::from_secs(5),
+            max_concurrent_exports: 1,
+        }
+    }
+}
+
+/// Samples the root spans with the ratio of their target, falling back to the default one
+#[derive(Clone, Debug)]
+struct TargetRatioSampler {
+    default: Sampler,
+    // sorted from the most to the least specific target
+    targets: Vec<(String, Sampler)>,
+}
+
+impl TargetRatioSampler {
+    fn new(sample_ratio: f64, target_sample_ratios: HashMap<String, f64>) -> Self {
+        let mut targets = target_sample_ratios
+            .into_iter()
+            .map(|(target, ratio)| (target, Sampler::TraceIdRatioBased(ratio)))
+            .collect::<Vec<_>>();
+        targets.sort_by(|(a, _), (b, _)| b.len().cmp(&a.len()));
+        Self {
+            default: Sampler::TraceIdRatioBased(sample_ratio),
+            targets,
+        }
+    }
+
+    fn sampler(&self, module_path: Option<&str>) -> &Sampler {
+        module_path
+            .and_then(|path| {
+                self.targets.iter().find(|(target, _)| {
+                    path.strip_prefix(target.as_str())
+                        .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+                })
+            })
+            .map_or(&self.default, |(_, sampler)| sampler)
+    }
+}
+
+impl ShouldSample for TargetRatioSampler {
+    fn should_sample(
+        &self,
+        parent_context: Option<&Context>,
+        trace_id: TraceId,
+        name: &str,
+        span_kind: &SpanKind,
+        attributes: &[KeyValue],
+        links: &[Link],
+    ) -> SamplingResult {
+        // tracing spans carry the module path they were created in
+        let module_path = attributes
+            .iter()
+            .find(|attribute| attribute.key.as_str() == CODE_NAMESPACE)
+            .map(|attribute| attribute.value.as_str());
+        self.sampler(module_path.as_deref()).should_sample(
+            parent_context,
+            trace_id,
+            name,
+            span_kind,
+            attributes,
+            links,
+        )
+    }
+}
+
+/// Child spans follow the decision taken for the root of their trace
+pub(crate) fn create_sampler(
+    sample_ratio: f64,
+    target_sample_ratios: HashMap<String, f64>,
+) -> Sampler {
+    Sampler::ParentBased(Box::new(TargetRatioSampler::new(
+        sample_ratio,
+        target_sample_ratios,
+    )))
 }
 
 pub fn create_otlp_tracing_layer<S>(
@@ -23,15 +134,30 @@ pub fn create_otlp_tracing_layer<S>(
 where
     S: Subscriber + for<'span> LookupSpan<'span>,
 {
+    install_error_handler();
+
+    let OtlpTracingConfig {
+        endpoint,
+        sample_ratio,
+        target_sample_ratios,
+        batch,
+    } = config;
     let otel_exporter = opentelemetry_otlp::new_exporter()
         .tonic()
-        .with_endpoint(config.endpoint);
+        .with_endpoint(endpoint);
+    let batch_config = BatchConfigBuilder::default()
+        .with_max_queue_size(batch.max_queue_size)
+        .with_max_export_batch_size(batch.max_export_batch_size)
+        .with_scheduled_delay(batch.scheduled_delay)
+        .with_max_concurrent_exports(batch.max_concurrent_exports)
+        .build();
     let tracer_provider = opentelemetry_otlp::new_pipeline()
         .tracing()
-        .with_trace_config(opentelemetry_sdk::trace::Config::default().with_sampler(
-            Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(config.sample_ratio))),
-        ))
-        .with_batch_config(BatchConfig::default())
+        .with_trace_config(
+            opentelemetry_sdk::trace::Config::default()
+                .with_sampler(create_sampler(sample_ratio, target_sample_ratios)),
+        )
+        .with_batch_config(batch_config)
         .with_exporter(otel_exporter)
         .install_batch(opentelemetry_sdk::runtime::Tokio)?;
 
@@ -40,3 +166,107 @@ where
 
     Ok(OpenTelemetryLayer::new(tracer))
 }
+
+/// The batch processor reports dropped spans and failed exports through the global error
+/// handler only, so they are counted there.
+fn install_error_handler() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        if let Err(e) = global::set_error_handler(handle_error) {
+            eprintln!("Could not install the OpenTelemetry error handler: {e}");
+        }
+    });
+}
+
+fn handle_error(error: global::Error) {
+    static DROPS: OnceLock<Counter<u64>> = OnceLock::new();
+    let drops = || {
+        DROPS.get_or_init(|| {
+            global::meter("tracing")
+                .u64_counter("tracing_dropped_spans")
+                .with_description("Spans dropped from the export queue and failed exports")
+                .init()
+        })
+    };
+
+    match &error {
+        global::Error::Trace(TraceError::Other(e))
+            if matches!(
+                e.downcast_ref::<TrySendError>(),
+                Some(TrySendError::ChannelFull)
+            ) =>
+        {
+            // reported for every span, so not logged
+            drops().add(1, &[KeyValue::new("reason", "queue_full")]);
+            return;
+        }
+        global::Error::Trace(TraceError::ExportFailed(_) | TraceError::ExportTimedOut(_)) => {
+            drops().add(1, &[KeyValue::new("reason", "export_failed")]);
+        }
+        _ => {}
+    }
+    eprintln!("OpenTelemetry error occurred. {error}");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tracing::memory::InMemorySpanExporter;
+    use opentelemetry_sdk::trace::TracerProvider;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    mod noisy {
+        pub fn span() -> tracing::Span {
+            tracing::info_span!("noisy")
+        }
+    }
+
+    #[test]
+    fn target_ratio_applies_to_root_spans() {
+        let exporter = InMemorySpanExporter::default();
+        let sampler = create_sampler(
+            1.0,
+            [(format!("{}::noisy", module_path!()), 0.0)]
+                .into_iter()
+                .collect(),
+        );
+        let provider = TracerProvider::builder()
+            .with_config(opentelemetry_sdk::trace::Config::default().with_sampler(sampler))
+            .with_simple_exporter(exporter.clone())
+            .build();
+        let subscriber =
+            tracing_subscriber::registry().with(OpenTelemetryLayer::new(provider.tracer("test")));
+
+        tracing::subscriber::with_default(subscriber, || {
+            // dropped, the trace starts in the noisy target
+            noisy::span().in_scope(|| {});
+            // kept, the parent is sampled
+            tracing::info_span!("root").in_scope(|| noisy::span().in_scope(|| {}));
+        });
+
+        let mut names = exporter.span_names();
+        names.sort();
+        assert_eq!(names, vec!["noisy", "root"]);
+    }
+
+    #[test]
+    fn sampler_picks_most_specific_target() {
+        let sampler = TargetRatioSampler::new(
+            0.5,
+            [
+                ("nomos_da".to_string(), 0.1),
+                ("nomos_da::sampling".to_string(), 0.2),
+            ]
+            .into_iter()
+            .collect(),
+        );
+        let ratio = |path| match sampler.sampler(path) {
+            Sampler::TraceIdRatioBased(ratio) => *ratio,
+            _ => unreachable!(),
+        };
+        assert_eq!(ratio(Some("nomos_da::sampling::backend")), 0.2);
+        assert_eq!(ratio(Some("nomos_da::verifier")), 0.1);
+        assert_eq!(ratio(Some("nomos_da_network")), 0.5);
+        assert_eq!(ratio(None), 0.5);
+    }
+}
@@ -7,15 +7,18 @@ use std::{fs, process};
 // crates
 use axum::extract::State;
 use axum::Json;
-use axum::{http::StatusCode, response::IntoResponse, routing::post, Router};
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Router,
+};
 use cfgsync::config::Host;
-use cfgsync::repo::{ConfigRepo, RepoResponse};
+use cfgsync::repo::{AllocationState, ConfigRepo, NodeConfig, RepoResponse};
 use cfgsync::TracingParams;
 use clap::Parser;
 use reqwest::Url;
 use serde::{Deserialize, Serialize};
-use tests::nodes::executor::create_executor_config;
-use tests::nodes::validator::create_validator_config;
 use tests::topology::configs::consensus::ConsensusParams;
 use tests::topology::configs::da::DaParams;
 use tokio::sync::oneshot::channel;
@@ -32,6 +35,9 @@ struct CfgSyncConfig {
     port: u16,
     n_hosts: usize,
     timeout: u64,
+    /// File the allocated configs are kept in, so they survive restarts of the server
+    #[serde(default)]
+    state_path: Option<PathBuf>,
 
     // ConsensusConfig related parameters
     security_param: u32,
@@ -41,7 +47,10 @@ struct CfgSyncConfig {
     subnetwork_size: usize,
     dispersal_factor: usize,
     num_samples: u16,
+    required_samples: u16,
     num_subnets: u16,
+    sampling_max_retries: u16,
+    sampling_retry_backoff_millis: u64,
     old_blobs_check_interval_secs: u64,
     blobs_validity_duration_secs: u64,
     global_params_path: String,
@@ -73,10 +82,14 @@ impl CfgSyncConfig {
             subnetwork_size: self.subnetwork_size,
             dispersal_factor: self.dispersal_factor,
             num_samples: self.num_samples,
+            required_samples: self.required_samples,
             num_subnets: self.num_subnets,
+            sampling_max_retries: self.sampling_max_retries,
+            sampling_retry_backoff: Duration::from_millis(self.sampling_retry_backoff_millis),
             old_blobs_check_interval: Duration::from_secs(self.old_blobs_check_interval_secs),
             blobs_validity_duration: Duration::from_secs(self.blobs_validity_duration_secs),
             global_params_path: self.global_params_path.clone(),
+            retention: Default::default(),
         }
     }
 
@@ -100,20 +113,11 @@ async fn validator_config(
     Json(payload): Json<ClientIp>,
 ) -> impl IntoResponse {
     let ClientIp { ip, identifier } = payload;
-
-    let (reply_tx, reply_rx) = channel();
-    config_repo.register(Host::default_validator_from_ip(ip, identifier), reply_tx);
-
-    match reply_rx.await {
-        Ok(config_response) => match config_response {
-            RepoResponse::Config(config) => {
-                let config = create_validator_config(*config);
-                (StatusCode::OK, Json(config)).into_response()
-            }
-            RepoResponse::Timeout => (StatusCode::REQUEST_TIMEOUT).into_response(),
-        },
-        Err(_) => (StatusCode::INTERNAL_SERVER_ERROR, "Error receiving config").into_response(),
-    }
+    node_config(
+        &config_repo,
+        Host::default_validator_from_ip(ip, identifier),
+    )
+    .await
 }
 
 async fn executor_config(
@@ -121,22 +125,30 @@ async fn executor_config(
     Json(payload): Json<ClientIp>,
 ) -> impl IntoResponse {
     let ClientIp { ip, identifier } = payload;
+    node_config(&config_repo, Host::default_executor_from_ip(ip, identifier)).await
+}
 
+async fn node_config(config_repo: &ConfigRepo, host: Host) -> Response {
     let (reply_tx, reply_rx) = channel();
-    config_repo.register(Host::default_executor_from_ip(ip, identifier), reply_tx);
+    config_repo.register(host, reply_tx);
 
     match reply_rx.await {
         Ok(config_response) => match config_response {
-            RepoResponse::Config(config) => {
-                let config = create_executor_config(*config);
-                (StatusCode::OK, Json(config)).into_response()
-            }
+            RepoResponse::Config(config) => match *config {
+                NodeConfig::Validator(config) => (StatusCode::OK, Json(config)).into_response(),
+                NodeConfig::Executor(config) => (StatusCode::OK, Json(config)).into_response(),
+            },
             RepoResponse::Timeout => (StatusCode::REQUEST_TIMEOUT).into_response(),
+            RepoResponse::Conflict(reason) => (StatusCode::CONFLICT, reason).into_response(),
         },
         Err(_) => (StatusCode::INTERNAL_SERVER_ERROR, "Error receiving config").into_response(),
     }
 }
 
+async fn allocation(State(config_repo): State<Arc<ConfigRepo>>) -> impl IntoResponse {
+    Json(config_repo.allocations())
+}
+
 #[tokio::main]
 async fn main() {
     let cli = Args::parse();
@@ -148,6 +160,16 @@ async fn main() {
     let consensus_params = config.to_consensus_params();
     let da_params = config.to_da_params();
     let tracing_params = config.to_tracing_params();
+    let allocation_state = config
+        .state_path
+        .as_deref()
+        .map(AllocationState::load)
+        .transpose()
+        .unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            process::exit(1);
+        })
+        .unwrap_or_default();
 
     let config_repo = ConfigRepo::new(
         config.n_hosts,
@@ -155,10 +177,13 @@ async fn main() {
         da_params,
         tracing_params,
         Duration::from_secs(config.timeout),
+        allocation_state,
+        config.state_path,
     );
     let app = Router::new()
         .route("/validator", post(validator_config))
         .route("/executor", post(executor_config))
+        .route("/allocation", get(allocation))
         .with_state(config_repo.clone());
 
     println!("Server running on http://0.0.0.0:{}", config.port);
@@ -1,20 +1,33 @@
 // std
-use std::{collections::HashMap, net::Ipv4Addr, str::FromStr};
+use std::{
+    collections::{HashMap, HashSet},
+    net::Ipv4Addr,
+    path::PathBuf,
+    str::FromStr,
+};
 // crates
-use nomos_libp2p::{Multiaddr, PeerId};
+use cl::NullifierSecret;
+use cryptarchia_consensus::{LeaderConfig, TimeConfig};
+use nomos_da_membership::StakeDeclaration;
+use nomos_ledger::LedgerState;
+use nomos_libp2p::{ed25519, Multiaddr, PeerId, SwarmConfig};
 use nomos_mix::membership::Node;
 use nomos_mix_message::{mock::MockMixMessage, MixMessage};
+use nomos_mix_service::backends::libp2p::Libp2pMixBackendSettings;
+use nomos_node::NomosDaMembership;
 use nomos_tracing::{
-    logging::loki::LokiConfig, metrics::otlp::OtlpMetricsConfig, tracing::otlp::OtlpTracingConfig,
+    logging::local::LogFormat, logging::loki::LokiConfig, metrics::otlp::OtlpMetricsConfig,
+    tracing::otlp::OtlpTracingConfig,
 };
 use nomos_tracing_service::{FilterLayer, LoggerLayer, MetricsLayer, TracingSettings};
 use rand::{thread_rng, Rng};
+use serde::{Deserialize, Serialize};
 use tests::topology::configs::{
     api::GeneralApiConfig,
-    consensus::{create_consensus_configs, ConsensusParams},
-    da::{create_da_configs, DaParams},
-    mix::create_mix_configs,
-    network::create_network_configs,
+    consensus::{create_consensus_configs, ConsensusParams, GeneralConsensusConfig},
+    da::{create_da_configs, declare_stake, DaParams, GeneralDaConfig},
+    mix::{create_mix_configs, GeneralMixConfig},
+    network::{create_network_configs, GeneralNetworkConfig},
     tracing::GeneralTracingConfig,
     GeneralConfig,
 };
@@ -27,13 +40,14 @@ const DEFAULT_DA_NETWORK_PORT: u16 = 3300;
 const DEFAULT_MIX_PORT: u16 = 3400;
 const DEFAULT_API_PORT: u16 = 18080;
 
-#[derive(Eq, PartialEq, Hash, Clone)]
+#[derive(Eq, PartialEq, Hash, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum HostKind {
     Validator,
     Executor,
 }
 
-#[derive(Eq, PartialEq, Hash, Clone)]
+#[derive(Eq, PartialEq, Hash, Clone, Debug, Serialize, Deserialize)]
 pub struct Host {
     pub kind: HostKind,
     pub ip: Ipv4Addr,
@@ -79,7 +93,8 @@ pub fn create_node_configs(
     }
 
     let consensus_configs = create_consensus_configs(&ids, consensus_params);
-    let da_configs = create_da_configs(&ids, da_params);
+    let mut da_configs = create_da_configs(&ids, da_params);
+    declare_stake(&mut da_configs, &consensus_configs);
     let network_configs = create_network_configs(&ids, Default::default());
     let mix_configs = create_mix_configs(&ids);
     let api_configs = ids
@@ -148,6 +163,129 @@ pub fn create_node_configs(
     configured_hosts
 }
 
+/// Parts of the genesis configs that are the same for all the hosts, which the hosts admitted
+/// after genesis are configured with.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Topology {
+    pub ledger_config: nomos_ledger::Config,
+    pub genesis_state: LedgerState,
+    pub time: TimeConfig,
+    pub da_membership: NomosDaMembership,
+    pub da_addresses: HashMap<PeerId, Multiaddr>,
+    #[serde(default)]
+    pub da_stake_declarations: HashMap<PeerId, StakeDeclaration>,
+    pub mix_membership: Vec<Node<<MockMixMessage as MixMessage>::PublicKey>>,
+}
+
+impl Topology {
+    pub fn from_genesis_config(config: &GeneralConfig) -> Self {
+        Self {
+            ledger_config: config.consensus_config.ledger_config.clone(),
+            genesis_state: config.consensus_config.genesis_state.clone(),
+            time: config.consensus_config.time.clone(),
+            da_membership: config.da_config.membership.clone(),
+            da_addresses: config.da_config.addresses.clone(),
+            da_stake_declarations: config.da_config.stake_declarations.clone(),
+            mix_membership: config.mix_config.membership.clone(),
+        }
+    }
+}
+
+/// Config of a host joining after genesis. The genesis configs can't be changed anymore, so
+/// the host has no stake to lead with and is neither a DA nor a mix network member: it
+/// follows the chain, samples blobs and sends messages through the genesis hosts.
+pub fn create_joiner_config(
+    topology: &Topology,
+    da_params: DaParams,
+    tracing_params: TracingParams,
+    genesis_hosts: Vec<Host>,
+    host: &Host,
+    index: usize,
+) -> GeneralConfig {
+    let mut id = [0; 32];
+    thread_rng().fill(&mut id);
+    let node_key = ed25519::SecretKey::try_from_bytes(&mut id.clone())
+        .expect("Failed to generate secret key from bytes");
+    let peer_id =
+        PeerId::from_public_key(&ed25519::Keypair::from(node_key.clone()).public().into());
+
+    let mut nf_sk = [0; 16];
+    nf_sk.copy_from_slice(&id[0..16]);
+    let consensus_config = GeneralConsensusConfig {
+        leader_config: LeaderConfig {
+            notes: vec![],
+            nf_sk: Some(NullifierSecret(nf_sk)),
+        },
+        ledger_config: topology.ledger_config.clone(),
+        genesis_state: topology.genesis_state.clone(),
+        time: topology.time.clone(),
+    };
+
+    let da_config = GeneralDaConfig {
+        node_key: node_key.clone(),
+        peer_id,
+        membership: topology.da_membership.clone(),
+        addresses: topology.da_addresses.clone(),
+        listening_address: Multiaddr::from_str(&format!(
+            "/ip4/0.0.0.0/udp/{}/quic-v1",
+            host.da_network_port,
+        ))
+        .unwrap(),
+        blob_storage_directory: PathBuf::from(format!("/tmp/blob_storage_{index}")),
+        global_params_path: da_params.global_params_path,
+        verifier_sk: hex::encode(
+            blst::min_sig::SecretKey::key_gen(&id, &[])
+                .unwrap()
+                .to_bytes(),
+        ),
+        verifier_index: HashSet::new(),
+        num_samples: da_params.num_samples,
+        required_samples: da_params.required_samples,
+        num_subnets: da_params.num_subnets,
+        sampling_max_retries: da_params.sampling_max_retries,
+        sampling_retry_backoff: da_params.sampling_retry_backoff,
+        old_blobs_check_interval: da_params.old_blobs_check_interval,
+        blobs_validity_duration: da_params.blobs_validity_duration,
+        retention: da_params.retention,
+        stake_declarations: topology.da_stake_declarations.clone(),
+    };
+
+    let network_config = GeneralNetworkConfig {
+        swarm_config: SwarmConfig {
+            node_key: node_key.clone(),
+            host: Ipv4Addr::UNSPECIFIED,
+            port: host.network_port,
+            ..Default::default()
+        },
+        initial_peers: update_network_init_peers(genesis_hosts),
+    };
+
+    let mix_config = GeneralMixConfig {
+        backend: Libp2pMixBackendSettings {
+            listening_address: Multiaddr::from_str(&format!(
+                "/ip4/0.0.0.0/udp/{}/quic-v1",
+                host.mix_port
+            ))
+            .unwrap(),
+            node_key,
+            peering_degree: 1,
+        },
+        private_key: x25519_dalek::StaticSecret::random(),
+        membership: topology.mix_membership.clone(),
+    };
+
+    GeneralConfig {
+        consensus_config,
+        da_config,
+        network_config,
+        mix_config,
+        api_config: GeneralApiConfig {
+            address: format!("0.0.0.0:{DEFAULT_API_PORT}").parse().unwrap(),
+        },
+        tracing_config: tracing_config_for_grafana(tracing_params, host.identifier.clone()),
+    }
+}
+
 fn update_network_init_peers(hosts: Vec<Host>) -> Vec<Multiaddr> {
     hosts
         .iter()
@@ -200,6 +338,8 @@ fn tracing_config_for_grafana(params: TracingParams, identifier: String) -> Gene
             tracing: nomos_tracing_service::TracingLayer::Otlp(OtlpTracingConfig {
                 endpoint: params.tempo_endpoint,
                 sample_ratio: 1.0,
+                target_sample_ratios: Default::default(),
+                batch: Default::default(),
             }),
             filter: FilterLayer::None,
             metrics: MetricsLayer::Otlp(OtlpMetricsConfig {
@@ -209,12 +349,13 @@ fn tracing_config_for_grafana(params: TracingParams, identifier: String) -> Gene
                 host_identifier: identifier,
             }),
             level: Level::INFO,
+            format: LogFormat::Plain,
         },
     }
 }
 
 #[cfg(test)]
-mod cfgsync_tests {
+pub(crate) mod cfgsync_tests {
     use std::str::FromStr;
     use std::{net::Ipv4Addr, time::Duration};
 
@@ -224,7 +365,43 @@ mod cfgsync_tests {
 
     use crate::TracingParams;
 
-    use super::{create_node_configs, Host, HostKind};
+    use super::{create_joiner_config, create_node_configs, Host, HostKind, Topology};
+
+    pub fn consensus_params(n_participants: usize) -> ConsensusParams {
+        ConsensusParams {
+            n_participants,
+            security_param: 10,
+            active_slot_coeff: 0.9,
+        }
+    }
+
+    pub fn da_params() -> DaParams {
+        DaParams {
+            subnetwork_size: 2,
+            dispersal_factor: 1,
+            num_samples: 1,
+            required_samples: 1,
+            num_subnets: 2,
+            sampling_max_retries: 3,
+            sampling_retry_backoff: Duration::from_millis(500),
+            old_blobs_check_interval: Duration::from_secs(5),
+            blobs_validity_duration: Duration::from_secs(u64::MAX),
+            global_params_path: "".into(),
+            retention: Default::default(),
+        }
+    }
+
+    pub fn tracing_params() -> TracingParams {
+        TracingParams {
+            tempo_endpoint: "http://test.com".try_into().unwrap(),
+            loki_endpoint: "http://test.com".try_into().unwrap(),
+            metrics_endpoint: "http://test.com".try_into().unwrap(),
+        }
+    }
+
+    pub fn validator_host(i: u8) -> Host {
+        Host::default_validator_from_ip(Ipv4Addr::new(10, 1, 1, i), format!("validator-10.1.1.{i}"))
+    }
 
     #[test]
     fn basic_ip_list() {
@@ -239,28 +416,8 @@ mod cfgsync_tests {
             })
             .collect();
 
-        let configs = create_node_configs(
-            ConsensusParams {
-                n_participants: 10,
-                security_param: 10,
-                active_slot_coeff: 0.9,
-            },
-            DaParams {
-                subnetwork_size: 2,
-                dispersal_factor: 1,
-                num_samples: 1,
-                num_subnets: 2,
-                old_blobs_check_interval: Duration::from_secs(5),
-                blobs_validity_duration: Duration::from_secs(u64::MAX),
-                global_params_path: "".into(),
-            },
-            TracingParams {
-                tempo_endpoint: "http://test.com".try_into().unwrap(),
-                loki_endpoint: "http://test.com".try_into().unwrap(),
-                metrics_endpoint: "http://test.com".try_into().unwrap(),
-            },
-            hosts,
-        );
+        let configs =
+            create_node_configs(consensus_params(10), da_params(), tracing_params(), hosts);
 
         for (host, config) in configs.iter() {
             let network_port = config.network_config.swarm_config.port;
@@ -273,6 +430,43 @@ mod cfgsync_tests {
         }
     }
 
+    #[test]
+    fn joiner_follows_genesis_topology() {
+        let hosts: Vec<_> = (0..3).map(validator_host).collect();
+        let configs = create_node_configs(
+            consensus_params(3),
+            da_params(),
+            tracing_params(),
+            hosts.clone(),
+        );
+        let genesis = &configs[&hosts[0]];
+        let topology = Topology::from_genesis_config(genesis);
+
+        let joiner = validator_host(3);
+        let config = create_joiner_config(
+            &topology,
+            da_params(),
+            tracing_params(),
+            hosts.clone(),
+            &joiner,
+            3,
+        );
+
+        assert!(config.consensus_config.genesis_state == genesis.consensus_config.genesis_state);
+        assert!(config.consensus_config.leader_config.notes.is_empty());
+        assert_eq!(config.da_config.addresses, genesis.da_config.addresses);
+        assert!(!config
+            .da_config
+            .addresses
+            .contains_key(&config.da_config.peer_id));
+        assert!(config.da_config.verifier_index.is_empty());
+        assert_eq!(config.network_config.initial_peers.len(), hosts.len());
+        assert_eq!(
+            extract_port(&config.mix_config.backend.listening_address),
+            joiner.mix_port
+        );
+    }
+
     fn extract_port(multiaddr: &Multiaddr) -> u16 {
         multiaddr
             .iter()
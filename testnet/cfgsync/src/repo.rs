@@ -1,8 +1,16 @@
 // std
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 // crates
+use nomos_executor::config::Config as ExecutorConfig;
+use nomos_libp2p::PeerId;
+use nomos_node::Config as ValidatorConfig;
+use serde::{Deserialize, Serialize};
+use tests::nodes::executor::create_executor_config;
+use tests::nodes::validator::create_validator_config;
 use tests::topology::configs::consensus::ConsensusParams;
 use tests::topology::configs::da::DaParams;
 use tests::topology::configs::GeneralConfig;
@@ -10,16 +18,143 @@ use tokio::sync::oneshot::Sender;
 use tokio::time::timeout;
 // internal
 
-use crate::config::{create_node_configs, Host};
+use crate::config::{create_joiner_config, create_node_configs, Host, HostKind, Topology};
 use crate::TracingParams;
 
 pub enum RepoResponse {
-    Config(Box<GeneralConfig>),
+    Config(Box<NodeConfig>),
     Timeout,
+    /// The host identifier was already allocated to a host of another kind
+    Conflict(String),
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "config", rename_all = "snake_case")]
+pub enum NodeConfig {
+    Validator(Box<ValidatorConfig>),
+    Executor(Box<ExecutorConfig>),
+}
+
+impl NodeConfig {
+    fn new(kind: &HostKind, config: GeneralConfig) -> Self {
+        match kind {
+            HostKind::Validator => Self::Validator(Box::new(create_validator_config(config))),
+            HostKind::Executor => Self::Executor(Box::new(create_executor_config(config))),
+        }
+    }
+}
+
+/// Config served to a host, and returned again when it registers after a restart
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Allocation {
+    pub host: Host,
+    pub peer_id: PeerId,
+    /// Whether the host is part of the genesis topology, or was admitted afterwards
+    pub genesis: bool,
+    pub config: NodeConfig,
+}
+
+/// Public part of an allocation, as listed by the server
+#[derive(Serialize)]
+pub struct AllocationInfo {
+    #[serde(flatten)]
+    pub host: Host,
+    pub peer_id: PeerId,
+    pub genesis: bool,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct AllocationState {
+    /// Set once the genesis configs are generated
+    topology: Option<Topology>,
+    /// Allocations by host identifier
+    hosts: BTreeMap<String, Allocation>,
+}
+
+impl AllocationState {
+    /// Load the state saved by a previous run, or start from scratch if there is none
+    pub fn load(path: &Path) -> Result<Self, String> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read(path)
+            .map_err(|err| format!("Failed to read allocation state: {}", err))?;
+        serde_json::from_slice(&content)
+            .map_err(|err| format!("Failed to parse allocation state: {}", err))
+    }
+
+    fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|err| format!("Failed to create state directory: {}", err))?;
+        }
+        let content = serde_json::to_vec_pretty(self)
+            .map_err(|err| format!("Failed to serialize allocation state: {}", err))?;
+        // write then rename, so a crash never leaves a truncated state behind
+        let tmp_path = path.with_extension("tmp");
+        write_private(&tmp_path, &content)
+            .and_then(|_| std::fs::rename(&tmp_path, path))
+            .map_err(|err| format!("Failed to write allocation state: {}", err))
+    }
+
+    fn allocate(&mut self, host: Host, config: GeneralConfig, genesis: bool) -> NodeConfig {
+        let allocation = Allocation {
+            peer_id: config.da_config.peer_id,
+            genesis,
+            config: NodeConfig::new(&host.kind, config),
+            host,
+        };
+        let config = allocation.config.clone();
+        self.hosts
+            .insert(allocation.host.identifier.clone(), allocation);
+        config
+    }
+
+    fn genesis_hosts(&self) -> Vec<Host> {
+        self.hosts
+            .values()
+            .filter(|allocation| allocation.genesis)
+            .map(|allocation| allocation.host.clone())
+            .collect()
+    }
+
+    pub fn list(&self) -> Vec<AllocationInfo> {
+        self.hosts
+            .values()
+            .map(|allocation| AllocationInfo {
+                host: allocation.host.clone(),
+                peer_id: allocation.peer_id,
+                genesis: allocation.genesis,
+            })
+            .collect()
+    }
+}
+
+/// Write a file only its owner can read, the state holds the node keys
+fn write_private(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    // a leftover file keeps its permissions when opened
+    match std::fs::remove_file(path) {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err),
+        _ => {}
+    }
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options.open(path)?;
+    file.write_all(content)?;
+    file.sync_all()
+}
+
+struct RepoState {
+    /// Hosts waiting for the genesis configs, by identifier
+    waiting_hosts: HashMap<String, (Host, Sender<RepoResponse>)>,
+    allocation: AllocationState,
 }
 
 pub struct ConfigRepo {
-    waiting_hosts: Mutex<HashMap<Host, Sender<RepoResponse>>>,
+    state: Mutex<RepoState>,
+    state_path: Option<PathBuf>,
     n_hosts: usize,
     consensus_params: ConsensusParams,
     da_params: DaParams,
@@ -34,9 +169,15 @@ impl ConfigRepo {
         da_params: DaParams,
         tracing_params: TracingParams,
         timeout_duration: Duration,
+        allocation: AllocationState,
+        state_path: Option<PathBuf>,
     ) -> Arc<Self> {
         let repo = Arc::new(Self {
-            waiting_hosts: Mutex::new(HashMap::new()),
+            state: Mutex::new(RepoState {
+                waiting_hosts: HashMap::new(),
+                allocation,
+            }),
+            state_path,
             n_hosts,
             consensus_params,
             da_params,
@@ -53,41 +194,107 @@ impl ConfigRepo {
     }
 
     pub fn register(&self, host: Host, reply_tx: Sender<RepoResponse>) {
-        let mut waiting_hosts = self.waiting_hosts.lock().unwrap();
-        waiting_hosts.insert(host, reply_tx);
+        let mut state = self.state.lock().unwrap();
+
+        if let Some(allocation) = state.allocation.hosts.get(&host.identifier) {
+            let response = if allocation.host.kind == host.kind {
+                // restarted hosts get the same keys back
+                RepoResponse::Config(Box::new(allocation.config.clone()))
+            } else {
+                RepoResponse::Conflict(format!(
+                    "{} is allocated to a {:?} host",
+                    host.identifier, allocation.host.kind
+                ))
+            };
+            let _ = reply_tx.send(response);
+            return;
+        }
+
+        let Some(topology) = state.allocation.topology.clone() else {
+            if let Some((waiting, _)) = state.waiting_hosts.get(&host.identifier) {
+                if waiting.kind != host.kind {
+                    let _ = reply_tx.send(RepoResponse::Conflict(format!(
+                        "{} is registered by a {:?} host",
+                        host.identifier, waiting.kind
+                    )));
+                    return;
+                }
+            }
+            state
+                .waiting_hosts
+                .insert(host.identifier.clone(), (host, reply_tx));
+            return;
+        };
+
+        println!("Admitting {} after genesis", host.identifier);
+        let config = create_joiner_config(
+            &topology,
+            self.da_params.clone(),
+            self.tracing_params.clone(),
+            state.allocation.genesis_hosts(),
+            &host,
+            state.allocation.hosts.len(),
+        );
+        let config = state.allocation.allocate(host, config, false);
+        self.persist(&state.allocation);
+        let _ = reply_tx.send(RepoResponse::Config(Box::new(config)));
+    }
+
+    pub fn allocations(&self) -> Vec<AllocationInfo> {
+        self.state.lock().unwrap().allocation.list()
+    }
+
+    fn persist(&self, allocation: &AllocationState) {
+        if let Some(path) = &self.state_path {
+            if let Err(err) = allocation.save(path) {
+                eprintln!("{}", err);
+            }
+        }
     }
 
     async fn run(&self) {
+        if self.state.lock().unwrap().allocation.topology.is_some() {
+            println!("Genesis configs restored, admitting new hosts as they announce");
+            return;
+        }
+
         let timeout_duration = self.timeout_duration;
 
         match timeout(timeout_duration, self.wait_for_hosts()).await {
             Ok(_) => {
                 println!("All hosts have announced their IPs");
 
-                let mut waiting_hosts = self.waiting_hosts.lock().unwrap();
+                let mut state = self.state.lock().unwrap();
+                let RepoState {
+                    waiting_hosts,
+                    allocation,
+                } = &mut *state;
                 let hosts = waiting_hosts
-                    .iter()
+                    .values()
                     .map(|(host, _)| host)
                     .cloned()
                     .collect();
 
-                let configs = create_node_configs(
+                let mut configs = create_node_configs(
                     self.consensus_params.clone(),
                     self.da_params.clone(),
                     self.tracing_params.clone(),
                     hosts,
                 );
+                allocation.topology = configs.values().next().map(Topology::from_genesis_config);
 
-                for (host, sender) in waiting_hosts.drain() {
-                    let config = configs.get(&host).expect("host should have a config");
-                    let _ = sender.send(RepoResponse::Config(Box::new(config.to_owned())));
+                for (_, (host, sender)) in waiting_hosts.drain() {
+                    let config = configs.remove(&host).expect("host should have a config");
+                    let config = allocation.allocate(host, config, true);
+                    let _ = sender.send(RepoResponse::Config(Box::new(config)));
                 }
+                self.persist(allocation);
             }
             Err(_) => {
                 println!("Timeout: Not all hosts announced within the time limit");
 
-                let mut waiting_hosts = self.waiting_hosts.lock().unwrap();
-                for (_, sender) in waiting_hosts.drain() {
+                let mut state = self.state.lock().unwrap();
+                for (_, (_, sender)) in state.waiting_hosts.drain() {
                     let _ = sender.send(RepoResponse::Timeout);
                 }
             }
@@ -96,10 +303,119 @@ impl ConfigRepo {
 
     async fn wait_for_hosts(&self) {
         loop {
-            if self.waiting_hosts.lock().unwrap().len() >= self.n_hosts {
+            if self.state.lock().unwrap().waiting_hosts.len() >= self.n_hosts {
                 break;
             }
             tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tests::topology::configs::GeneralConfig;
+    use tokio::sync::oneshot;
+
+    use crate::config::cfgsync_tests::{
+        consensus_params, da_params, tracing_params, validator_host,
+    };
+    use crate::config::{create_joiner_config, create_node_configs, Host, HostKind, Topology};
+
+    use super::{AllocationState, ConfigRepo, NodeConfig, RepoResponse};
+
+    #[test]
+    fn allocation_survives_restarts() {
+        let hosts: Vec<_> = (0..2).map(validator_host).collect();
+        let mut configs = create_node_configs(
+            consensus_params(2),
+            da_params(),
+            tracing_params(),
+            hosts.clone(),
+        );
+        let topology = Topology::from_genesis_config(&configs[&hosts[0]]);
+
+        let mut state = AllocationState {
+            topology: Some(topology.clone()),
+            ..Default::default()
+        };
+        for host in &hosts {
+            let config: GeneralConfig = configs.remove(host).unwrap();
+            state.allocate(host.clone(), config, true);
+        }
+        let joiner = validator_host(2);
+        let config = create_joiner_config(
+            &topology,
+            da_params(),
+            tracing_params(),
+            state.genesis_hosts(),
+            &joiner,
+            2,
+        );
+        state.allocate(joiner.clone(), config, false);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state").join("allocation.json");
+        state.save(&path).unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        let restored = AllocationState::load(&path).unwrap();
+
+        assert!(restored.topology.is_some());
+        assert_eq!(restored.genesis_hosts().len(), hosts.len());
+        let (NodeConfig::Validator(before), NodeConfig::Validator(after)) = (
+            &state.hosts[&joiner.identifier].config,
+            &restored.hosts[&joiner.identifier].config,
+        ) else {
+            panic!("validators should be allocated validator configs");
+        };
+        assert_eq!(
+            serde_json::to_value(before).unwrap(),
+            serde_json::to_value(after).unwrap()
+        );
+        let listed = restored.list();
+        assert_eq!(listed.len(), 3);
+        assert!(!listed.iter().find(|a| a.host == joiner).unwrap().genesis);
+        assert!(AllocationState::load(&dir.path().join("missing.json"))
+            .unwrap()
+            .hosts
+            .is_empty());
+    }
+
+    #[test]
+    fn waiting_hosts_of_another_kind_conflict() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async {
+            let repo = ConfigRepo::new(
+                2,
+                consensus_params(2),
+                da_params(),
+                tracing_params(),
+                Duration::from_secs(60),
+                AllocationState::default(),
+                None,
+            );
+            let validator = validator_host(0);
+            let (validator_tx, _validator_rx) = oneshot::channel();
+            repo.register(validator.clone(), validator_tx);
+
+            let (executor_tx, executor_rx) = oneshot::channel();
+            repo.register(
+                Host {
+                    kind: HostKind::Executor,
+                    ..validator
+                },
+                executor_tx,
+            );
+            assert!(matches!(
+                executor_rx.await.unwrap(),
+                RepoResponse::Conflict(_)
+            ));
+        });
+    }
+}
@@ -2,8 +2,9 @@ pub mod nodes;
 pub mod topology;
 
 // std
+use std::collections::HashSet;
 use std::env;
-use std::net::TcpListener;
+use std::net::{TcpListener, UdpSocket};
 use std::ops::Mul;
 use std::sync::Mutex;
 use std::time::Duration;
@@ -11,9 +12,8 @@ use std::time::Duration;
 //crates
 use nomos_libp2p::{Multiaddr, PeerId, Swarm};
 use once_cell::sync::Lazy;
-use rand::{thread_rng, Rng};
 
-static NET_PORT: Lazy<Mutex<u16>> = Lazy::new(|| Mutex::new(thread_rng().gen_range(8000..10000)));
+static ALLOCATED_PORTS: Lazy<Mutex<HashSet<u16>>> = Lazy::new(Default::default);
 static IS_SLOW_TEST_ENV: Lazy<bool> =
     Lazy::new(|| env::var("SLOW_TEST_ENV").is_ok_and(|s| s == "true"));
 pub static GLOBAL_PARAMS_PATH: Lazy<String> = Lazy::new(|| {
@@ -27,13 +27,19 @@ pub static GLOBAL_PARAMS_PATH: Lazy<String> = Lazy::new(|| {
         .to_string()
 });
 
+/// Port free for both UDP and TCP, never returned twice by the same process. Ports are picked
+/// by the OS, so that test binaries running in parallel don't walk over the same range.
 pub fn get_available_port() -> u16 {
-    let mut port = NET_PORT.lock().unwrap();
-    *port += 1;
-    while TcpListener::bind(("127.0.0.1", *port)).is_err() {
-        *port += 1;
+    let mut allocated = ALLOCATED_PORTS.lock().unwrap();
+    loop {
+        let port = UdpSocket::bind(("127.0.0.1", 0))
+            .and_then(|socket| socket.local_addr())
+            .expect("Failed to bind a UDP socket")
+            .port();
+        if TcpListener::bind(("127.0.0.1", port)).is_ok() && allocated.insert(port) {
+            return port;
+        }
     }
-    *port
 }
 
 /// In slow test environments like Codecov, use 2x timeout.
@@ -0,0 +1,192 @@
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use node_http_client::NodeHttpClient;
+use nomos_da_light::{BlockAvailability, DaLightServiceSettings};
+use nomos_da_membership::DaMembershipSettings;
+use nomos_da_network_service::backends::libp2p::common::DaNetworkBackendSettings;
+use nomos_da_network_service::NetworkConfig as DaNetworkConfig;
+use nomos_da_sampling::storage::adapters::rocksdb::RocksAdapterSettings as SamplingStorageAdapterSettings;
+use nomos_da_sampling::{backend::kzgrs::KzgrsSamplingBackendSettings, DaSamplingServiceSettings};
+use nomos_light::{api::backend::AxumBackendSettings, api::paths, config::Config};
+use nomos_network::{backends::libp2p::Libp2pConfig, NetworkConfig};
+use nomos_node::{HeaderId, RocksBackendSettings};
+use reqwest::Url;
+
+use crate::adjust_timeout;
+use crate::nodes::LOGS_PREFIX;
+use crate::topology::configs::{
+    api::GeneralApiConfig, consensus::GeneralConsensusConfig, da::GeneralDaConfig,
+    network::GeneralNetworkConfig, tracing::GeneralTracingConfig,
+};
+
+use super::process::NodeProcess;
+use super::{create_tempdir, CLIENT};
+
+const BIN_PATH: &str = "../target/debug/nomos-light";
+/// Time the light node gives to the blobs of a block to be sampled
+const SAMPLING_TIMEOUT: Duration = Duration::from_secs(20);
+
+pub struct LightNode {
+    addr: SocketAddr,
+    process: NodeProcess,
+    config: Config,
+}
+
+impl LightNode {
+    pub async fn spawn(mut config: Config) -> Self {
+        let dir = create_tempdir().unwrap();
+
+        #[cfg(not(feature = "debug"))]
+        {
+            use nomos_tracing::logging::local::FileConfig;
+            use nomos_tracing_service::LoggerLayer;
+
+            // setup logging so that we can intercept it later in testing
+            config.tracing.logger = LoggerLayer::File(FileConfig {
+                directory: dir.path().to_owned(),
+                prefix: Some(LOGS_PREFIX.into()),
+                rotation: Default::default(),
+            });
+        }
+
+        config.storage.db_path = dir.path().join("db");
+        config
+            .da_sampling
+            .storage_adapter_settings
+            .blob_storage_directory = dir.path().to_owned();
+
+        let node = Self {
+            addr: config.http.backend_settings.address,
+            process: NodeProcess::spawn("nomos-light", BIN_PATH, dir, &config),
+            config,
+        };
+        node.wait_startup().await;
+
+        node
+    }
+
+    async fn wait_startup(&self) {
+        tokio::time::timeout(adjust_timeout(Duration::from_secs(10)), async {
+            self.wait_online().await
+        })
+        .await
+        .unwrap();
+    }
+
+    /// Process of the node, to pause or kill it
+    pub fn process(&self) -> &NodeProcess {
+        &self.process
+    }
+
+    /// Kill the node and start it again with the same config and data directory
+    pub async fn restart(&self) {
+        self.process.restart();
+        self.wait_startup().await;
+    }
+
+    pub fn url(&self) -> Url {
+        format!("http://{}", self.addr).parse().unwrap()
+    }
+
+    pub fn client(&self) -> NodeHttpClient {
+        NodeHttpClient::new(CLIENT.clone(), self.url())
+    }
+
+    async fn wait_online(&self) {
+        let client = self.client();
+        while client
+            .ready()
+            .await
+            .map_or(true, |report| !report.is_ready())
+        {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    }
+
+    /// Sampling result of the blobs referenced by the block, if the light node saw it
+    pub async fn get_availability(&self, id: HeaderId) -> Option<BlockAvailability> {
+        let client = self.client();
+        client
+            .json_response(
+                client
+                    .client()
+                    .post(client.url(paths::DA_LIGHT_AVAILABILITY))
+                    .json(&id),
+            )
+            .await
+            .unwrap()
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+}
+
+pub fn create_light_node_config(
+    consensus_config: GeneralConsensusConfig,
+    da_config: GeneralDaConfig,
+    network_config: GeneralNetworkConfig,
+    api_config: GeneralApiConfig,
+    tracing_config: GeneralTracingConfig,
+) -> Config {
+    Config {
+        network: NetworkConfig {
+            backend: Libp2pConfig {
+                inner: network_config.swarm_config,
+                initial_peers: network_config.initial_peers,
+            },
+        },
+        da_network: DaNetworkConfig {
+            backend: DaNetworkBackendSettings {
+                node_key: da_config.node_key,
+                membership: da_config.membership,
+                addresses: da_config.addresses,
+                listening_address: da_config.listening_address,
+                sampling: Default::default(),
+                replication: Default::default(),
+            },
+        },
+        da_sampling: DaSamplingServiceSettings {
+            sampling_settings: KzgrsSamplingBackendSettings {
+                num_samples: da_config.num_samples,
+                required_samples: da_config.required_samples,
+                num_subnets: da_config.num_subnets,
+                max_retries: da_config.sampling_max_retries,
+                retry_backoff: da_config.sampling_retry_backoff,
+                old_blobs_check_interval: da_config.old_blobs_check_interval,
+                blobs_validity_duration: da_config.blobs_validity_duration,
+            },
+            storage_adapter_settings: SamplingStorageAdapterSettings {
+                blob_storage_directory: "./".into(),
+            },
+            network_adapter_settings: (),
+        },
+        da_light: DaLightServiceSettings {
+            network_adapter_settings:
+                cryptarchia_consensus::network::adapters::libp2p::LibP2pAdapterSettings {
+                    topic: String::from(nomos_node::CONSENSUS_TOPIC),
+                },
+            sampling_timeout: SAMPLING_TIMEOUT,
+            ledger_config: consensus_config.ledger_config,
+            genesis_state: consensus_config.genesis_state,
+            da_membership: DaMembershipSettings {
+                stake_declarations: da_config.stake_declarations,
+                ..Default::default()
+            },
+        },
+        tracing: tracing_config.tracing_settings,
+        http: nomos_api::ApiServiceSettings {
+            backend_settings: AxumBackendSettings {
+                address: api_config.address,
+                cors_origins: vec![],
+                admin_token: None,
+            },
+        },
+        storage: RocksBackendSettings {
+            db_path: "./db".into(),
+            read_only: false,
+            column_family: Some("blocks".into()),
+        },
+    }
+}
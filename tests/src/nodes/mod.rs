@@ -1,11 +1,14 @@
 pub mod executor;
+pub mod light;
+pub mod process;
 pub mod validator;
 
 use std::ops::Range;
 
+use kzgrs_backend::dispersal::{Index, Metadata};
+use node_http_client::{IndexView, NodeHttpClient};
 use once_cell::sync::Lazy;
 use reqwest::Client;
-use serde::{Deserialize, Serialize};
 use tempfile::TempDir;
 
 const LOGS_PREFIX: &str = "__logs";
@@ -31,8 +34,21 @@ fn persist_tempdir(tempdir: &mut TempDir, label: &str) -> std::io::Result<()> {
     Ok(())
 }
 
-#[derive(Serialize, Deserialize)]
-struct GetRangeReq {
-    pub app_id: [u8; 32],
-    pub range: Range<[u8; 8]>,
+async fn get_indexer_range(
+    client: &NodeHttpClient,
+    app_id: [u8; 32],
+    range: Range<[u8; 8]>,
+) -> Vec<([u8; 8], Vec<Vec<u8>>)> {
+    let index = |bytes| Index::from(u64::from_be_bytes(bytes));
+    client
+        .get_range::<Metadata>(
+            app_id,
+            index(range.start)..index(range.end),
+            IndexView::Pending,
+        )
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|(index, blobs)| (index.to_u64().to_be_bytes(), blobs))
+        .collect()
 }
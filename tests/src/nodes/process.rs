@@ -0,0 +1,98 @@
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::Mutex;
+
+use nix::sys::signal::{kill, Signal};
+use nix::unistd::Pid;
+use serde::Serialize;
+use tempfile::TempDir;
+
+use super::persist_tempdir;
+
+const CONFIG_FILE: &str = "config.yaml";
+
+/// A node binary running from a directory holding its config and data. The directory outlives
+/// the process, so that the node can be killed and restarted with the same state.
+pub struct NodeProcess {
+    label: &'static str,
+    bin_path: PathBuf,
+    tempdir: TempDir,
+    child: Mutex<Option<Child>>,
+}
+
+impl Drop for NodeProcess {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            if let Err(e) = persist_tempdir(&mut self.tempdir, self.label) {
+                println!("failed to persist tempdir: {e}");
+            }
+        }
+
+        self.kill();
+    }
+}
+
+impl NodeProcess {
+    pub fn spawn<C: Serialize>(
+        label: &'static str,
+        bin_path: &str,
+        tempdir: TempDir,
+        config: &C,
+    ) -> Self {
+        let file = std::fs::File::create(tempdir.path().join(CONFIG_FILE)).unwrap();
+        serde_yaml::to_writer(file, config).unwrap();
+        let process = Self {
+            label,
+            bin_path: std::env::current_dir().unwrap().join(bin_path),
+            tempdir,
+            child: Mutex::new(None),
+        };
+        process.start();
+        process
+    }
+
+    pub fn dir(&self) -> &Path {
+        self.tempdir.path()
+    }
+
+    fn start(&self) {
+        let child = Command::new(&self.bin_path)
+            .arg(self.dir().join(CONFIG_FILE))
+            .current_dir(self.dir())
+            .stdout(Stdio::inherit())
+            .spawn()
+            .unwrap();
+        *self.child.lock().unwrap() = Some(child);
+    }
+
+    fn signal(&self, signal: Signal) {
+        let child = self.child.lock().unwrap();
+        let child = child.as_ref().expect("node process is not running");
+        kill(Pid::from_raw(child.id() as i32), signal).unwrap();
+    }
+
+    /// Stop the process without terminating it, as if the node hung
+    pub fn pause(&self) {
+        self.signal(Signal::SIGSTOP);
+    }
+
+    pub fn resume(&self) {
+        self.signal(Signal::SIGCONT);
+    }
+
+    /// Terminate the process abruptly, as in a crash. Its directory is kept.
+    pub fn kill(&self) {
+        if let Some(mut child) = self.child.lock().unwrap().take() {
+            if let Err(e) = child.kill() {
+                println!("failed to kill the child process: {e}");
+            }
+            let _ = child.wait();
+        }
+    }
+
+    /// Start the process again with the same config and data, killing it first if needed
+    pub fn restart(&self) {
+        self.kill();
+        self.start();
+    }
+}
@@ -1,12 +1,14 @@
+use std::net::SocketAddr;
 use std::ops::Range;
-use std::process::{Command, Stdio};
 use std::time::Duration;
-use std::{net::SocketAddr, process::Child};
 
 use cryptarchia_consensus::{CryptarchiaInfo, CryptarchiaSettings};
+use node_http_client::NodeHttpClient;
 use nomos_core::block::Block;
 use nomos_da_indexer::storage::adapters::rocksdb::RocksAdapterSettings as IndexerStorageAdapterSettings;
 use nomos_da_indexer::IndexerSettings;
+use nomos_da_membership::DaMembershipSettings;
+use nomos_da_network_core::protocols::sampling::behaviour::SamplingConfig;
 use nomos_da_network_service::backends::libp2p::common::DaNetworkBackendSettings;
 use nomos_da_network_service::NetworkConfig as DaNetworkConfig;
 use nomos_da_sampling::storage::adapters::rocksdb::RocksAdapterSettings as SamplingStorageAdapterSettings;
@@ -18,19 +20,16 @@ use nomos_mix::message_blend::{
     CryptographicProcessorSettings, MessageBlendSettings, TemporalSchedulerSettings,
 };
 use nomos_network::{backends::libp2p::Libp2pConfig, NetworkConfig};
-use nomos_node::api::paths::{
-    CL_METRICS, CRYPTARCHIA_HEADERS, CRYPTARCHIA_INFO, DA_GET_RANGE, STORAGE_BLOCK,
-};
 use nomos_node::{api::backend::AxumBackendSettings, Config, RocksBackendSettings};
 use nomos_node::{BlobInfo, HeaderId, Tx};
 use reqwest::Url;
-use tempfile::NamedTempFile;
 
 use crate::adjust_timeout;
 use crate::nodes::LOGS_PREFIX;
 use crate::topology::configs::GeneralConfig;
 
-use super::{create_tempdir, persist_tempdir, GetRangeReq, CLIENT};
+use super::process::NodeProcess;
+use super::{create_tempdir, get_indexer_range, CLIENT};
 
 const BIN_PATH: &str = "../target/debug/nomos-node";
 
@@ -41,30 +40,13 @@ pub enum Pool {
 
 pub struct Validator {
     addr: SocketAddr,
-    tempdir: tempfile::TempDir,
-    child: Child,
+    process: NodeProcess,
     config: Config,
 }
 
-impl Drop for Validator {
-    fn drop(&mut self) {
-        if std::thread::panicking() {
-            if let Err(e) = persist_tempdir(&mut self.tempdir, "nomos-node") {
-                println!("failed to persist tempdir: {e}");
-            }
-        }
-
-        if let Err(e) = self.child.kill() {
-            println!("failed to kill the child process: {e}");
-        }
-    }
-}
-
 impl Validator {
     pub async fn spawn(mut config: Config) -> Self {
         let dir = create_tempdir().unwrap();
-        let mut file = NamedTempFile::new().unwrap();
-        let config_path = file.path().to_owned();
 
         #[cfg(not(feature = "debug"))]
         {
@@ -75,6 +57,7 @@ impl Validator {
             config.tracing.logger = LoggerLayer::File(FileConfig {
                 directory: dir.path().to_owned(),
                 prefix: Some(LOGS_PREFIX.into()),
+                rotation: Default::default(),
             });
         }
 
@@ -89,78 +72,62 @@ impl Validator {
             .blob_storage_directory = dir.path().to_owned();
         config.da_indexer.storage.blob_storage_directory = dir.path().to_owned();
 
-        serde_yaml::to_writer(&mut file, &config).unwrap();
-        let child = Command::new(std::env::current_dir().unwrap().join(BIN_PATH))
-            .arg(&config_path)
-            .current_dir(dir.path())
-            .stdout(Stdio::inherit())
-            .spawn()
-            .unwrap();
         let node = Self {
             addr: config.http.backend_settings.address,
-            child,
-            tempdir: dir,
+            process: NodeProcess::spawn("nomos-node", BIN_PATH, dir, &config),
             config,
         };
+        node.wait_startup().await;
+
+        node
+    }
+
+    async fn wait_startup(&self) {
         tokio::time::timeout(adjust_timeout(Duration::from_secs(10)), async {
-            node.wait_online().await
+            self.wait_online().await
         })
         .await
         .unwrap();
+    }
 
-        node
+    /// Process of the node, to pause or kill it
+    pub fn process(&self) -> &NodeProcess {
+        &self.process
     }
 
-    async fn get(&self, path: &str) -> reqwest::Result<reqwest::Response> {
-        CLIENT
-            .get(format!("http://{}{}", self.addr, path))
-            .send()
-            .await
+    /// Kill the node and start it again with the same config and data directory
+    pub async fn restart(&self) {
+        self.process.restart();
+        self.wait_startup().await;
     }
 
     pub fn url(&self) -> Url {
         format!("http://{}", self.addr).parse().unwrap()
     }
 
+    pub fn client(&self) -> NodeHttpClient {
+        NodeHttpClient::new(CLIENT.clone(), self.url())
+    }
+
     async fn wait_online(&self) {
-        loop {
-            let res = self.get(CL_METRICS).await;
-            if res.is_ok() && res.unwrap().status().is_success() {
-                break;
-            }
+        let client = self.client();
+        while client
+            .ready()
+            .await
+            .map_or(true, |report| !report.is_ready())
+        {
             tokio::time::sleep(Duration::from_millis(100)).await;
         }
     }
 
     pub async fn get_block(&self, id: HeaderId) -> Option<Block<Tx, BlobInfo>> {
-        CLIENT
-            .post(format!("http://{}{}", self.addr, STORAGE_BLOCK))
-            .header("Content-Type", "application/json")
-            .body(serde_json::to_string(&id).unwrap())
-            .send()
-            .await
-            .unwrap()
-            .json::<Option<Block<Tx, BlobInfo>>>()
-            .await
-            .unwrap()
+        self.client().block(id).await.unwrap()
     }
 
     pub async fn get_mempoool_metrics(&self, pool: Pool) -> MempoolMetrics {
-        let discr = match pool {
-            Pool::Cl => "cl",
-            Pool::Da => "da",
-        };
-        let addr = format!("/{}/metrics", discr);
-        let res = self
-            .get(&addr)
-            .await
-            .unwrap()
-            .json::<serde_json::Value>()
-            .await
-            .unwrap();
-        MempoolMetrics {
-            pending_items: res["pending_items"].as_u64().unwrap() as usize,
-            last_item_timestamp: res["last_item_timestamp"].as_u64().unwrap(),
+        match pool {
+            Pool::Cl => self.client().cl_metrics().await.unwrap(),
+            Pool::Da => self.client().da_metrics().await.unwrap(),
         }
     }
 
@@ -169,26 +136,14 @@ impl Validator {
         app_id: [u8; 32],
         range: Range<[u8; 8]>,
     ) -> Vec<([u8; 8], Vec<Vec<u8>>)> {
-        CLIENT
-            .post(format!("http://{}{}", self.addr, DA_GET_RANGE))
-            .header("Content-Type", "application/json")
-            .body(serde_json::to_string(&GetRangeReq { app_id, range }).unwrap())
-            .send()
-            .await
-            .unwrap()
-            .json::<Vec<([u8; 8], Vec<Vec<u8>>)>>()
-            .await
-            .unwrap()
+        get_indexer_range(&self.client(), app_id, range).await
     }
 
     // not async so that we can use this in `Drop`
     pub fn get_logs_from_file(&self) -> String {
-        println!(
-            "fetching logs from dir {}...",
-            self.tempdir.path().display()
-        );
+        println!("fetching logs from dir {}...", self.process.dir().display());
         // std::thread::sleep(std::time::Duration::from_secs(50));
-        std::fs::read_dir(self.tempdir.path())
+        std::fs::read_dir(self.process.dir())
             .unwrap()
             .filter_map(|entry| {
                 let entry = entry.unwrap();
@@ -208,27 +163,11 @@ impl Validator {
     }
 
     pub async fn get_headers(&self, from: Option<HeaderId>, to: Option<HeaderId>) -> Vec<HeaderId> {
-        let mut req = CLIENT.get(format!("http://{}{}", self.addr, CRYPTARCHIA_HEADERS));
-
-        if let Some(from) = from {
-            req = req.query(&[("from", from)]);
-        }
-
-        if let Some(to) = to {
-            req = req.query(&[("to", to)]);
-        }
-
-        let res = req.send().await;
-
-        println!("res: {res:?}");
-
-        res.unwrap().json::<Vec<HeaderId>>().await.unwrap()
+        self.client().cryptarchia_headers(from, to).await.unwrap()
     }
 
     pub async fn consensus_info(&self) -> CryptarchiaInfo {
-        let res = self.get(CRYPTARCHIA_INFO).await;
-        println!("{:?}", res);
-        res.unwrap().json().await.unwrap()
+        self.client().cryptarchia_info().await.unwrap()
     }
 }
 
@@ -245,7 +184,7 @@ pub fn create_validator_config(config: GeneralConfig) -> Config {
             persistent_transmission: Default::default(),
             message_blend: MessageBlendSettings {
                 cryptographic_processor: CryptographicProcessorSettings {
-                    private_key: config.mix_config.private_key.to_bytes(),
+                    private_key: Some(config.mix_config.private_key.to_bytes()),
                     num_mix_layers: 1,
                 },
                 temporal_processor: TemporalSchedulerSettings {
@@ -279,18 +218,30 @@ pub fn create_validator_config(config: GeneralConfig) -> Config {
                 membership: config.da_config.membership,
                 addresses: config.da_config.addresses,
                 listening_address: config.da_config.listening_address,
+                // light nodes sample from validators without being members
+                sampling: SamplingConfig {
+                    serve_non_members: true,
+                    ..Default::default()
+                },
+                replication: Default::default(),
             },
         },
+        da_membership: DaMembershipSettings {
+            stake_declarations: config.da_config.stake_declarations,
+            ..Default::default()
+        },
         da_indexer: IndexerSettings {
             storage: IndexerStorageAdapterSettings {
                 blob_storage_directory: "./".into(),
             },
+            retention: config.da_config.retention,
         },
         da_verifier: DaVerifierServiceSettings {
             verifier_settings: KzgrsDaVerifierSettings {
-                sk: config.da_config.verifier_sk,
+                sk: Some(config.da_config.verifier_sk),
                 index: config.da_config.verifier_index,
                 global_params_path: config.da_config.global_params_path,
+                domain_size: config.da_config.num_subnets as usize,
             },
             network_adapter_settings: (),
             storage_adapter_settings: VerifierStorageAdapterSettings {
@@ -302,12 +253,16 @@ pub fn create_validator_config(config: GeneralConfig) -> Config {
             backend_settings: AxumBackendSettings {
                 address: config.api_config.address,
                 cors_origins: vec![],
+                admin_token: None,
             },
         },
         da_sampling: DaSamplingServiceSettings {
             sampling_settings: KzgrsSamplingBackendSettings {
                 num_samples: config.da_config.num_samples,
+                required_samples: config.da_config.required_samples,
                 num_subnets: config.da_config.num_subnets,
+                max_retries: config.da_config.sampling_max_retries,
+                retry_backoff: config.da_config.sampling_retry_backoff,
                 old_blobs_check_interval: config.da_config.old_blobs_check_interval,
                 blobs_validity_duration: config.da_config.blobs_validity_duration,
             },
@@ -321,5 +276,6 @@ pub fn create_validator_config(config: GeneralConfig) -> Config {
             read_only: false,
             column_family: Some("blocks".into()),
         },
+        keystore: None,
     }
 }
@@ -1,30 +1,25 @@
-use executor_http_client::ExecutorHttpClient;
-
 use kzgrs_backend::common::blob::DaBlob;
+use kzgrs_backend::dispersal::{Index, Metadata};
 use kzgrs_backend::reconstruction::reconstruct_without_missing_data;
+use node_http_client::Error;
 use nomos_core::wire;
-use reqwest::ClientBuilder;
-use reqwest::Url;
+use nomos_da_indexer::retention::{RetentionSettings, RetentionWindow};
+use nomos_da_indexer::IndexView;
+use nomos_da_light::Availability;
 use std::time::Duration;
 use tests::nodes::executor::Executor;
+use tests::nodes::validator::Validator;
 use tests::topology::Topology;
 use tests::topology::TopologyConfig;
 
 const APP_ID: &str = "fd3384e132ad02a56c78f45547ee40038dc79002b90d29ed90e08eee762ae715";
 
-async fn disseminate(executor: &Executor, data: &[u8]) {
-    let executor_config = executor.config();
-
-    let client = ClientBuilder::new()
-        .build()
-        .expect("Client from default settings should be able to build");
-
-    let backend_address = executor_config.http.backend_settings.address;
-    let exec_url = Url::parse(&format!("http://{}", backend_address)).unwrap();
-    let client = ExecutorHttpClient::new(client, exec_url);
+async fn disseminate(executor: &Executor, data: &[u8], index: u64) {
+    let client = executor.client();
 
     let app_id = hex::decode(APP_ID).unwrap();
-    let metadata = kzgrs_backend::dispersal::Metadata::new(app_id.try_into().unwrap(), 0u64.into());
+    let metadata =
+        kzgrs_backend::dispersal::Metadata::new(app_id.try_into().unwrap(), index.into());
     client.publish_blob(data.to_vec(), metadata).await.unwrap();
 }
 
@@ -37,7 +32,7 @@ async fn disseminate_and_retrieve() {
     let data = [1u8; 31];
 
     tokio::time::sleep(Duration::from_secs(15)).await;
-    disseminate(executor, &data).await;
+    disseminate(executor, &data, 0).await;
     tokio::time::sleep(Duration::from_secs(20)).await;
 
     let from = 0u64.to_be_bytes();
@@ -75,7 +70,6 @@ async fn disseminate_and_retrieve() {
     }
 }
 
-#[ignore = "todo: make work in parallel to other tests"]
 #[tokio::test]
 async fn disseminate_retrieve_reconstruct() {
     let topology = Topology::spawn(TopologyConfig::validator_and_executor()).await;
@@ -83,7 +77,7 @@ async fn disseminate_retrieve_reconstruct() {
     let data = [1u8; 31];
 
     tokio::time::sleep(Duration::from_secs(15)).await;
-    disseminate(executor, &data).await;
+    disseminate(executor, &data, 0).await;
     tokio::time::sleep(Duration::from_secs(20)).await;
 
     let from = 0u64.to_be_bytes();
@@ -103,6 +97,146 @@ async fn disseminate_retrieve_reconstruct() {
 
     // Reconstruction is performed from the one of the two blobs.
     let blobs = vec![executor_idx_0_blobs[0].clone()];
-    let reconstructed = reconstruct_without_missing_data(&blobs);
+    let domain_size = executor.config().da_verifier.verifier_settings.domain_size;
+    let reconstructed = reconstruct_without_missing_data(&blobs, domain_size);
     assert_eq!(reconstructed, data);
 }
+
+#[tokio::test]
+async fn light_node_reports_availability() {
+    let topology = Topology::spawn(TopologyConfig::validator_executor_and_light_node()).await;
+    let executor = &topology.executors()[0];
+    let validator = &topology.validators()[0];
+    let light_node = &topology.light_nodes()[0];
+    let data = [1u8; 31];
+
+    tokio::time::sleep(Duration::from_secs(15)).await;
+    disseminate(executor, &data, 0).await;
+    tokio::time::sleep(Duration::from_secs(20)).await;
+
+    let mut availabilities = Vec::new();
+    for id in validator.get_headers(None, None).await {
+        if let Some(availability) = light_node.get_availability(id).await {
+            availabilities.push(availability);
+        }
+    }
+
+    // the light node followed the chain and sampled the blob included in one of its blocks
+    assert!(!availabilities.is_empty());
+    assert!(availabilities
+        .iter()
+        .all(|availability| !matches!(availability.status, Availability::Unavailable { .. })));
+    assert!(availabilities.iter().any(|availability| {
+        availability.status == Availability::Available && !availability.blobs.is_empty()
+    }));
+}
+
+async fn wait_for_epoch(
+    validator: &Validator,
+    epoch_length: u64,
+    slot_duration: Duration,
+    epoch: u64,
+) {
+    let timeout = tests::adjust_timeout(slot_duration * (epoch * epoch_length * 2) as u32);
+    tokio::time::timeout(timeout, async {
+        while u64::from(validator.consensus_info().await.slot) < epoch * epoch_length {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+        }
+    })
+    .await
+    .unwrap_or_else(|_| panic!("consensus did not reach epoch {epoch}"));
+}
+
+async fn retrieve_and_reconstruct(executor: &Executor, index: u64) -> Vec<u8> {
+    let app_id = hex::decode(APP_ID).unwrap();
+    let from = index.to_be_bytes();
+    let to = (index + 1).to_be_bytes();
+    let blobs: Vec<_> = executor
+        .get_indexer_range(app_id.try_into().unwrap(), from..to)
+        .await
+        .into_iter()
+        .filter(|(i, _)| i == &from)
+        .flat_map(|(_, blobs)| blobs)
+        .map(|blob| wire::deserialize::<DaBlob>(&blob).unwrap())
+        .collect();
+    assert!(!blobs.is_empty(), "no blobs retrieved at index {index}");
+
+    let domain_size = executor.config().da_verifier.verifier_settings.domain_size;
+    reconstruct_without_missing_data(&blobs[..1], domain_size)
+}
+
+#[tokio::test]
+async fn disseminate_across_epochs() {
+    // short epochs so that subnetworks are assigned again a few times during the test
+    let topology =
+        Topology::spawn(TopologyConfig::validator_and_executor().with_security_param(2)).await;
+    let executor = &topology.executors()[0];
+    let validator = &topology.validators()[0];
+    let epoch_length = executor.config().cryptarchia.config.epoch_length();
+    let slot_duration = executor.config().cryptarchia.time.slot_duration;
+
+    let genesis_data = [1u8; 31];
+    tokio::time::sleep(Duration::from_secs(15)).await;
+    disseminate(executor, &genesis_data, 0).await;
+
+    // from epoch 2 on the members are assigned from the stake snapshot of the previous epoch
+    wait_for_epoch(validator, epoch_length, slot_duration, 2).await;
+    let data = [2u8; 31];
+    disseminate(executor, &data, 1).await;
+    tokio::time::sleep(slot_duration * 10).await;
+
+    // blobs dispersed in a previous epoch are still retrievable after the reassignment
+    assert_eq!(retrieve_and_reconstruct(executor, 0).await, genesis_data);
+    assert_eq!(retrieve_and_reconstruct(executor, 1).await, data);
+}
+
+/// Directories holding the shares of a blob in the blob storage of `executor`
+fn stored_blobs(executor: &Executor) -> Vec<String> {
+    std::fs::read_dir(executor.process().dir())
+        .unwrap()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .filter(|name| name.len() == 64 && hex::decode(name).is_ok())
+        .collect()
+}
+
+#[tokio::test]
+async fn expired_blobs_are_pruned() {
+    let retention = RetentionSettings {
+        window: Some(RetentionWindow::Slots(2)),
+        pin: false,
+        prune_interval: Duration::from_secs(1),
+        unincluded_window: Some(Duration::from_secs(20)),
+    };
+    let topology = Topology::spawn(
+        TopologyConfig::validator_and_executor()
+            .with_security_param(2)
+            .with_retention(retention),
+    )
+    .await;
+    let executor = &topology.executors()[0];
+    let slot_duration = executor.config().cryptarchia.time.slot_duration;
+
+    // shares of a blob that no block will include
+    let unincluded = executor.process().dir().join(hex::encode([9u8; 32]));
+    std::fs::create_dir_all(&unincluded).unwrap();
+    std::fs::write(unincluded.join(hex::encode(0u16.to_be_bytes())), [0u8; 32]).unwrap();
+
+    tokio::time::sleep(Duration::from_secs(15)).await;
+    disseminate(executor, &[1u8; 31], 0).await;
+    // the blob is included, becomes final and expires a few slots later
+    tokio::time::sleep(slot_duration * 20).await;
+
+    assert!(
+        stored_blobs(executor).is_empty(),
+        "blobs are still stored: {:?}",
+        stored_blobs(executor)
+    );
+    let app_id = hex::decode(APP_ID).unwrap().try_into().unwrap();
+    let range = executor
+        .client()
+        .get_range::<Metadata>(app_id, Index::from(0)..Index::from(1), IndexView::Final)
+        .await;
+    assert!(matches!(range, Err(Error::Pruned(_))), "{range:?}");
+}
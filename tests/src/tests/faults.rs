@@ -0,0 +1,89 @@
+use std::time::Duration;
+use tests::{
+    adjust_timeout,
+    nodes::validator::Validator,
+    topology::{Topology, TopologyConfig},
+};
+
+// blocks each side of a partition produces before checking that the chains diverged
+const PARTITION_BLOCKS: u64 = 3;
+
+async fn wait_for_height(node: &Validator, height: u64) {
+    while node.consensus_info().await.height < height {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+}
+
+#[tokio::test]
+async fn paused_node_resumes() {
+    let topology = Topology::spawn(TopologyConfig::two_validators()).await;
+    let validator = &topology.validators()[0];
+
+    validator.process().pause();
+    assert!(
+        tokio::time::timeout(Duration::from_secs(1), validator.consensus_info())
+            .await
+            .is_err(),
+        "paused node should not respond"
+    );
+
+    validator.process().resume();
+    tokio::time::timeout(
+        adjust_timeout(Duration::from_secs(10)),
+        validator.consensus_info(),
+    )
+    .await
+    .expect("resumed node should respond");
+}
+
+#[tokio::test]
+async fn killed_node_restarts_with_its_data() {
+    let topology = Topology::spawn(TopologyConfig::two_validators()).await;
+    let validator = &topology.validators()[0];
+    let db_path = validator.config().storage.db_path.clone();
+
+    validator.process().kill();
+    assert!(
+        validator.client().cryptarchia_info().await.is_err(),
+        "killed node should not respond"
+    );
+
+    validator.restart().await;
+    assert_eq!(validator.config().storage.db_path, db_path);
+    assert!(db_path.exists());
+    validator.consensus_info().await;
+}
+
+#[tokio::test]
+async fn partitioned_validators_fork() {
+    let topology = Topology::spawn(TopologyConfig::two_validators().with_fault_injection()).await;
+    let validators = topology.validators();
+    let (a, b) = (topology.validator_id(0), topology.validator_id(1));
+
+    topology.faults().partition(&[&[a], &[b]]);
+    let heights = [
+        validators[0].consensus_info().await.height,
+        validators[1].consensus_info().await.height,
+    ];
+    let config = validators[0].config();
+    // each side holds half of the stake, so it takes twice as long to grow its chain
+    let timeout = (2.0 * (PARTITION_BLOCKS + 1) as f64
+        / config.cryptarchia.config.consensus_config.active_slot_coeff
+        * config.cryptarchia.time.slot_duration.as_secs_f64()
+        * 3.0)
+        .ceil() as u64;
+    tokio::time::timeout(adjust_timeout(Duration::from_secs(timeout)), async {
+        for (validator, height) in validators.iter().zip(heights) {
+            wait_for_height(validator, height + PARTITION_BLOCKS).await;
+        }
+    })
+    .await
+    .expect("timed out waiting for both sides of the partition to produce blocks");
+
+    let tips = [
+        validators[0].consensus_info().await.tip,
+        validators[1].consensus_info().await.tip,
+    ];
+    assert!(validators[0].get_block(tips[1]).await.is_none());
+    assert!(validators[1].get_block(tips[0]).await.is_none());
+}
@@ -87,7 +87,7 @@ pub fn create_consensus_configs(
         .map(|(note, nf_sk)| GeneralConsensusConfig {
             leader_config: LeaderConfig {
                 notes: vec![note],
-                nf_sk,
+                nf_sk: Some(nf_sk),
             },
             ledger_config: ledger_config.clone(),
             genesis_state: genesis_state.clone(),
@@ -6,13 +6,17 @@ use std::{
     time::Duration,
 };
 
+use cryptarchia_consensus::LeaderConfig;
+use nomos_da_indexer::retention::RetentionSettings;
+use nomos_da_membership::StakeDeclaration;
 use nomos_libp2p::{ed25519, Multiaddr, PeerId};
 use nomos_node::NomosDaMembership;
 use once_cell::sync::Lazy;
-use subnetworks_assignations::MembershipHandler;
 
 use crate::{get_available_port, secret_key_to_peer_id};
 
+use super::consensus::GeneralConsensusConfig;
+
 pub static GLOBAL_PARAMS_PATH: Lazy<String> = Lazy::new(|| {
     let relative_path = "./kzgrs/kzgrs_test_params";
     let current_dir = env::current_dir().expect("Failed to get current directory");
@@ -29,10 +33,14 @@ pub struct DaParams {
     pub subnetwork_size: usize,
     pub dispersal_factor: usize,
     pub num_samples: u16,
+    pub required_samples: u16,
     pub num_subnets: u16,
+    pub sampling_max_retries: u16,
+    pub sampling_retry_backoff: Duration,
     pub old_blobs_check_interval: Duration,
     pub blobs_validity_duration: Duration,
     pub global_params_path: String,
+    pub retention: RetentionSettings,
 }
 
 impl Default for DaParams {
@@ -41,10 +49,14 @@ impl Default for DaParams {
             subnetwork_size: 2,
             dispersal_factor: 1,
             num_samples: 1,
+            required_samples: 1,
             num_subnets: 2,
+            sampling_max_retries: 3,
+            sampling_retry_backoff: Duration::from_millis(500),
             old_blobs_check_interval: Duration::from_secs(5),
             blobs_validity_duration: Duration::from_secs(u64::MAX),
             global_params_path: GLOBAL_PARAMS_PATH.to_string(),
+            retention: Default::default(),
         }
     }
 }
@@ -61,9 +73,15 @@ pub struct GeneralDaConfig {
     pub verifier_sk: String,
     pub verifier_index: HashSet<u32>,
     pub num_samples: u16,
+    pub required_samples: u16,
     pub num_subnets: u16,
+    pub sampling_max_retries: u16,
+    pub sampling_retry_backoff: Duration,
     pub old_blobs_check_interval: Duration,
     pub blobs_validity_duration: Duration,
+    pub retention: RetentionSettings,
+    /// Note holding the stake of each DA member, see [`declare_stake`]
+    pub stake_declarations: HashMap<PeerId, StakeDeclaration>,
 }
 
 pub fn create_da_configs(ids: &[[u8; 32]], da_params: DaParams) -> Vec<GeneralDaConfig> {
@@ -105,7 +123,9 @@ pub fn create_da_configs(ids: &[[u8; 32]], da_params: DaParams) -> Vec<GeneralDa
             let verifier_sk_bytes = verifier_sk.to_bytes();
             let peer_id = peer_ids[i];
 
-            let subnetwork_ids = membership.membership(&peer_id);
+            // subnetworks are assigned again every epoch, so the verifier accepts the columns of
+            // all of them
+            let subnetwork_ids = (0..da_params.num_subnets as u32).collect();
 
             GeneralDaConfig {
                 node_key: node_key.clone(),
@@ -118,14 +138,69 @@ pub fn create_da_configs(ids: &[[u8; 32]], da_params: DaParams) -> Vec<GeneralDa
                 verifier_sk: hex::encode(verifier_sk_bytes),
                 verifier_index: subnetwork_ids,
                 num_samples: da_params.num_samples,
+                required_samples: da_params.required_samples,
                 num_subnets: da_params.num_subnets,
+                sampling_max_retries: da_params.sampling_max_retries,
+                sampling_retry_backoff: da_params.sampling_retry_backoff,
                 old_blobs_check_interval: da_params.old_blobs_check_interval,
                 blobs_validity_duration: da_params.blobs_validity_duration,
+                retention: da_params.retention.clone(),
+                stake_declarations: HashMap::new(),
+            }
+        })
+        .collect()
+}
+
+/// Configs of light nodes sampling from the members of the network described by
+/// `member_config`, without being members themselves
+pub fn create_light_da_configs(
+    ids: &[[u8; 32]],
+    member_config: &GeneralDaConfig,
+) -> Vec<GeneralDaConfig> {
+    ids.iter()
+        .map(|id| {
+            let mut node_key_bytes = *id;
+            let node_key = ed25519::SecretKey::try_from_bytes(&mut node_key_bytes)
+                .expect("Failed to generate secret key from bytes");
+            let listening_address = Multiaddr::from_str(&format!(
+                "/ip4/127.0.0.1/udp/{}/quic-v1",
+                get_available_port(),
+            ))
+            .expect("Failed to create multiaddr");
+
+            GeneralDaConfig {
+                peer_id: secret_key_to_peer_id(node_key.clone()),
+                node_key,
+                listening_address,
+                verifier_index: HashSet::new(),
+                ..member_config.clone()
             }
         })
         .collect()
 }
 
+/// Declares the note each DA member leads with, so that members are assigned to subnetworks from
+/// the stake distribution of each epoch
+pub fn declare_stake(
+    da_configs: &mut [GeneralDaConfig],
+    consensus_configs: &[GeneralConsensusConfig],
+) {
+    let stake_declarations: HashMap<PeerId, StakeDeclaration> = da_configs
+        .iter()
+        .zip(consensus_configs)
+        .flat_map(|(da_config, consensus_config)| {
+            let LeaderConfig { notes, nf_sk } = &consensus_config.leader_config;
+            let nf_sk = nf_sk.expect("the consensus configs set the leader secret key");
+            notes
+                .iter()
+                .map(move |note| (da_config.peer_id, StakeDeclaration::new(*note, nf_sk)))
+        })
+        .collect();
+    for da_config in da_configs {
+        da_config.stake_declarations = stake_declarations.clone();
+    }
+}
+
 fn build_da_peer_list(
     peer_ids: &[PeerId],
     listening_addresses: &[Multiaddr],
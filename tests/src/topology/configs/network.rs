@@ -51,6 +51,30 @@ pub fn create_network_configs(
         .collect()
 }
 
+/// Configs of nodes joining the network through `entry_config`, outside of any layout
+pub fn create_light_network_configs(
+    ids: &[[u8; 32]],
+    entry_config: &GeneralNetworkConfig,
+) -> Vec<GeneralNetworkConfig> {
+    let entry_addr = node_address_from_port(entry_config.swarm_config.port);
+    ids.iter()
+        .map(|id| {
+            let mut node_key_bytes = *id;
+            let node_key = ed25519::SecretKey::try_from_bytes(&mut node_key_bytes)
+                .expect("Failed to generate secret key from bytes");
+
+            GeneralNetworkConfig {
+                swarm_config: SwarmConfig {
+                    node_key,
+                    port: get_available_port(),
+                    ..Default::default()
+                },
+                initial_peers: vec![entry_addr.clone()],
+            }
+        })
+        .collect()
+}
+
 fn initial_peers_by_network_layout(
     mut swarm_configs: Vec<SwarmConfig>,
     network_params: NetworkParams,
@@ -1,5 +1,6 @@
 use nomos_tracing::{
-    logging::loki::LokiConfig, metrics::otlp::OtlpMetricsConfig, tracing::otlp::OtlpTracingConfig,
+    logging::local::LogFormat, logging::loki::LokiConfig, metrics::otlp::OtlpMetricsConfig,
+    tracing::otlp::OtlpTracingConfig,
 };
 use nomos_tracing_service::{
     FilterLayer, LoggerLayer, MetricsLayer, TracingLayer, TracingSettings,
@@ -24,6 +25,8 @@ impl GeneralTracingConfig {
                 tracing: TracingLayer::Otlp(OtlpTracingConfig {
                     endpoint: "http://localhost:4317".try_into().unwrap(),
                     sample_ratio: 0.1,
+                    target_sample_ratios: Default::default(),
+                    batch: Default::default(),
                 }),
                 filter: FilterLayer::EnvFilter(nomos_tracing::filter::envfilter::EnvFilterConfig {
                     // Allow events only from modules that matches the regex, if it matches - use
@@ -41,6 +44,7 @@ impl GeneralTracingConfig {
                     host_identifier,
                 }),
                 level: Level::DEBUG,
+                format: LogFormat::Plain,
             },
         }
     }
@@ -0,0 +1,336 @@
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use nomos_libp2p::{Multiaddr, Protocol};
+use rand::{thread_rng, Rng};
+use tokio::net::UdpSocket;
+use tokio::task::{JoinHandle, JoinSet};
+
+/// Index of a node in the topology: validators first, then executors, then light nodes
+pub type NodeId = usize;
+
+const MAX_DATAGRAM_SIZE: usize = 65535;
+
+/// Conditions of the packets sent between two nodes, in both directions
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LinkConditions {
+    /// Delay added to every packet
+    pub latency: Duration,
+    /// Probability of every packet to be dropped, between 0 and 1
+    pub loss: f64,
+}
+
+#[derive(Default)]
+struct FaultState {
+    /// Group of each partitioned node, nodes of different groups can't reach each other
+    partition: HashMap<NodeId, usize>,
+    links: HashMap<(NodeId, NodeId), LinkConditions>,
+}
+
+impl FaultState {
+    /// `None` if packets between the nodes are dropped
+    fn conditions(&self, a: NodeId, b: NodeId) -> Option<LinkConditions> {
+        let group = |node| self.partition.get(&node).copied();
+        if group(a) != group(b) {
+            return None;
+        }
+        Some(self.links.get(&link(a, b)).copied().unwrap_or_default())
+    }
+}
+
+fn link(a: NodeId, b: NodeId) -> (NodeId, NodeId) {
+    (a.min(b), a.max(b))
+}
+
+/// Faults of the network between the nodes of a topology.
+///
+/// Each node reaches the others through UDP proxies, one per peer and per service (network,
+/// DA network and mix), which drop or delay the packets according to the current faults.
+pub struct NetworkFaults {
+    state: Arc<Mutex<FaultState>>,
+    /// Proxies by source node and target address
+    proxies: HashMap<(NodeId, SocketAddr), u16>,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl Drop for NetworkFaults {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+impl NetworkFaults {
+    pub fn new() -> Self {
+        Self {
+            state: Default::default(),
+            proxies: HashMap::new(),
+            tasks: Vec::new(),
+        }
+    }
+
+    /// Split the nodes into groups that can't reach each other. Nodes not in any of the groups
+    /// form a group of their own.
+    pub fn partition(&self, groups: &[&[NodeId]]) {
+        let mut state = self.state.lock().unwrap();
+        state.partition = groups
+            .iter()
+            .enumerate()
+            .flat_map(|(group, nodes)| nodes.iter().map(move |node| (*node, group + 1)))
+            .collect();
+    }
+
+    pub fn set_link(&self, a: NodeId, b: NodeId, conditions: LinkConditions) {
+        self.state
+            .lock()
+            .unwrap()
+            .links
+            .insert(link(a, b), conditions);
+    }
+
+    /// Remove the partition and the conditions of all the links
+    pub fn heal(&self) {
+        *self.state.lock().unwrap() = FaultState::default();
+    }
+
+    /// Port on which `from` reaches `target`, which belongs to `to`
+    pub fn proxy(&mut self, from: NodeId, to: NodeId, target: SocketAddr) -> u16 {
+        if let Some(port) = self.proxies.get(&(from, target)) {
+            return *port;
+        }
+        let socket = std::net::UdpSocket::bind((Ipv4Addr::LOCALHOST, 0))
+            .expect("Failed to bind a proxy socket");
+        socket.set_nonblocking(true).unwrap();
+        let port = socket.local_addr().unwrap().port();
+        let socket = UdpSocket::from_std(socket).unwrap();
+        self.tasks.push(tokio::spawn(run_proxy(
+            socket,
+            target,
+            (from, to),
+            self.state.clone(),
+        )));
+        self.proxies.insert((from, target), port);
+        port
+    }
+
+    /// Make `from` reach the other nodes through proxies, `owners` giving the node listening on
+    /// each port. Addresses of `from` itself or of unknown ports are left as they are.
+    pub fn route(
+        &mut self,
+        from: NodeId,
+        owners: &HashMap<u16, NodeId>,
+        addr: &Multiaddr,
+    ) -> Multiaddr {
+        let (Some(ip), Some(port)) = (
+            addr.iter().find_map(|protocol| match protocol {
+                Protocol::Ip4(ip) => Some(ip),
+                _ => None,
+            }),
+            udp_port(addr),
+        ) else {
+            return addr.clone();
+        };
+        let Some(&to) = owners.get(&port).filter(|to| **to != from) else {
+            return addr.clone();
+        };
+        let proxy_port = self.proxy(from, to, SocketAddr::from((ip, port)));
+        addr.iter()
+            .map(|protocol| match protocol {
+                Protocol::Ip4(_) => Protocol::Ip4(Ipv4Addr::LOCALHOST),
+                Protocol::Udp(_) => Protocol::Udp(proxy_port),
+                protocol => protocol,
+            })
+            .collect()
+    }
+}
+
+/// Port of a QUIC address
+pub fn udp_port(addr: &Multiaddr) -> Option<u16> {
+    addr.iter().find_map(|protocol| match protocol {
+        Protocol::Udp(port) => Some(port),
+        _ => None,
+    })
+}
+
+impl Default for NetworkFaults {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Relay the packets of every client of `listener` to `target`, through a socket per client so
+/// that the replies can be sent back to it
+async fn run_proxy(
+    listener: UdpSocket,
+    target: SocketAddr,
+    (from, to): (NodeId, NodeId),
+    state: Arc<Mutex<FaultState>>,
+) {
+    let listener = Arc::new(listener);
+    let mut upstreams: HashMap<SocketAddr, Arc<UdpSocket>> = HashMap::new();
+    // replies are relayed by tasks dropped with the proxy
+    let mut replies = JoinSet::new();
+    let mut buffer = vec![0; MAX_DATAGRAM_SIZE];
+    loop {
+        let Ok((len, client)) = listener.recv_from(&mut buffer).await else {
+            continue;
+        };
+        let upstream = match upstreams.get(&client) {
+            Some(upstream) => upstream.clone(),
+            None => {
+                let upstream = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+                upstream.connect(target).await.unwrap();
+                let upstream = Arc::new(upstream);
+                replies.spawn(relay_replies(
+                    upstream.clone(),
+                    listener.clone(),
+                    client,
+                    (from, to),
+                    state.clone(),
+                ));
+                upstreams.insert(client, upstream.clone());
+                upstream
+            }
+        };
+        let conditions = state.lock().unwrap().conditions(from, to);
+        deliver(upstream, None, buffer[..len].to_vec(), conditions);
+    }
+}
+
+async fn relay_replies(
+    upstream: Arc<UdpSocket>,
+    listener: Arc<UdpSocket>,
+    client: SocketAddr,
+    (from, to): (NodeId, NodeId),
+    state: Arc<Mutex<FaultState>>,
+) {
+    let mut buffer = vec![0; MAX_DATAGRAM_SIZE];
+    loop {
+        let Ok(len) = upstream.recv(&mut buffer).await else {
+            continue;
+        };
+        let conditions = state.lock().unwrap().conditions(from, to);
+        deliver(
+            listener.clone(),
+            Some(client),
+            buffer[..len].to_vec(),
+            conditions,
+        );
+    }
+}
+
+/// Send a packet according to the link conditions, to the connected address if `to` is `None`
+fn deliver(
+    socket: Arc<UdpSocket>,
+    to: Option<SocketAddr>,
+    packet: Vec<u8>,
+    conditions: Option<LinkConditions>,
+) {
+    let Some(conditions) = conditions else {
+        return;
+    };
+    if conditions.loss > 0.0 && thread_rng().gen_bool(conditions.loss.min(1.0)) {
+        return;
+    }
+    tokio::spawn(async move {
+        if !conditions.latency.is_zero() {
+            tokio::time::sleep(conditions.latency).await;
+        }
+        let _ = match to {
+            Some(to) => socket.send_to(&packet, to).await,
+            None => socket.send(&packet).await,
+        };
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::*;
+
+    async fn echo_server() -> (SocketAddr, JoinHandle<()>) {
+        let socket = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let addr = socket.local_addr().unwrap();
+        let task = tokio::spawn(async move {
+            let mut buffer = [0; 64];
+            while let Ok((len, from)) = socket.recv_from(&mut buffer).await {
+                let _ = socket.send_to(&buffer[..len], from).await;
+            }
+        });
+        (addr, task)
+    }
+
+    async fn echo(client: &UdpSocket, port: u16) -> Option<Duration> {
+        let start = Instant::now();
+        client
+            .send_to(b"ping", (Ipv4Addr::LOCALHOST, port))
+            .await
+            .unwrap();
+        let mut buffer = [0; 64];
+        tokio::time::timeout(Duration::from_millis(500), client.recv(&mut buffer))
+            .await
+            .ok()
+            .map(|_| start.elapsed())
+    }
+
+    #[tokio::test]
+    async fn proxies_apply_faults() {
+        let (target, server) = echo_server().await;
+        let mut faults = NetworkFaults::new();
+        let port = faults.proxy(0, 1, target);
+        assert_eq!(faults.proxy(0, 1, target), port);
+        let client = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+
+        assert!(echo(&client, port).await.is_some());
+
+        faults.partition(&[&[0], &[1]]);
+        assert!(echo(&client, port).await.is_none());
+        // nodes outside of the groups are together
+        faults.partition(&[&[2]]);
+        assert!(echo(&client, port).await.is_some());
+
+        let latency = Duration::from_millis(100);
+        faults.set_link(1, 0, LinkConditions { latency, loss: 0.0 });
+        // the latency applies in both directions
+        assert!(echo(&client, port).await.unwrap() >= latency * 2);
+
+        faults.set_link(
+            0,
+            1,
+            LinkConditions {
+                latency: Duration::ZERO,
+                loss: 1.0,
+            },
+        );
+        assert!(echo(&client, port).await.is_none());
+
+        faults.heal();
+        assert!(echo(&client, port).await.unwrap() < latency);
+        server.abort();
+    }
+
+    #[test]
+    fn routes_addresses_of_other_nodes() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let _guard = runtime.enter();
+        let mut faults = NetworkFaults::new();
+        let owners = HashMap::from([(4000, 0), (4001, 1)]);
+
+        let own: Multiaddr = "/ip4/127.0.0.1/udp/4000/quic-v1".parse().unwrap();
+        assert_eq!(faults.route(0, &owners, &own), own);
+
+        let peer: Multiaddr = "/ip4/127.0.0.1/udp/4001/quic-v1".parse().unwrap();
+        let routed = faults.route(0, &owners, &peer);
+        let port = faults.proxies[&(0, "127.0.0.1:4001".parse().unwrap())];
+        assert_eq!(
+            routed,
+            format!("/ip4/127.0.0.1/udp/{port}/quic-v1")
+                .parse::<Multiaddr>()
+                .unwrap()
+        );
+    }
+}
@@ -1,16 +1,23 @@
 pub mod configs;
+pub mod faults;
+
+use std::collections::HashMap;
 
 use configs::{
-    da::{create_da_configs, DaParams},
-    network::{create_network_configs, NetworkParams},
+    da::{create_da_configs, create_light_da_configs, declare_stake, DaParams},
+    network::{create_light_network_configs, create_network_configs, NetworkParams},
     tracing::create_tracing_configs,
     GeneralConfig,
 };
+use faults::{udp_port, NetworkFaults, NodeId};
+use nomos_da_indexer::retention::RetentionSettings;
+use nomos_libp2p::{Multiaddr, PeerId};
 use rand::{thread_rng, Rng};
 
 use crate::{
     nodes::{
         executor::{create_executor_config, Executor},
+        light::{create_light_node_config, LightNode},
         validator::{create_validator_config, Validator},
     },
     topology::configs::{
@@ -23,9 +30,11 @@ use crate::{
 pub struct TopologyConfig {
     n_validators: usize,
     n_executors: usize,
+    n_light_nodes: usize,
     consensus_params: ConsensusParams,
     da_params: DaParams,
     network_params: NetworkParams,
+    fault_injection: bool,
 }
 
 impl TopologyConfig {
@@ -33,9 +42,11 @@ impl TopologyConfig {
         TopologyConfig {
             n_validators: 2,
             n_executors: 0,
+            n_light_nodes: 0,
             consensus_params: ConsensusParams::default_for_participants(2),
             da_params: Default::default(),
             network_params: Default::default(),
+            fault_injection: false,
         }
     }
 
@@ -43,6 +54,7 @@ impl TopologyConfig {
         TopologyConfig {
             n_validators: 1,
             n_executors: 1,
+            n_light_nodes: 0,
             consensus_params: ConsensusParams::default_for_participants(2),
             da_params: DaParams {
                 dispersal_factor: 2,
@@ -51,6 +63,35 @@ impl TopologyConfig {
                 ..Default::default()
             },
             network_params: Default::default(),
+            fault_injection: false,
+        }
+    }
+
+    pub fn validator_executor_and_light_node() -> TopologyConfig {
+        TopologyConfig {
+            n_light_nodes: 1,
+            ..Self::validator_and_executor()
+        }
+    }
+
+    /// Set the consensus security parameter, a small one makes for short epochs
+    pub fn with_security_param(mut self, security_param: u32) -> TopologyConfig {
+        self.consensus_params.security_param = security_param;
+        self
+    }
+
+    /// Set how long the nodes keep the blobs they store
+    pub fn with_retention(mut self, retention: RetentionSettings) -> TopologyConfig {
+        self.da_params.retention = retention;
+        self
+    }
+
+    /// Connect the nodes through proxies, so that faults can be injected in the network between
+    /// them with [`Topology::faults`]
+    pub fn with_fault_injection(self) -> TopologyConfig {
+        TopologyConfig {
+            fault_injection: true,
+            ..self
         }
     }
 }
@@ -58,6 +99,8 @@ impl TopologyConfig {
 pub struct Topology {
     validators: Vec<Validator>,
     executors: Vec<Executor>,
+    light_nodes: Vec<LightNode>,
+    faults: Option<NetworkFaults>,
 }
 
 impl Topology {
@@ -75,41 +118,112 @@ impl Topology {
         }
 
         let consensus_configs = create_consensus_configs(&ids, config.consensus_params);
-        let da_configs = create_da_configs(&ids, config.da_params);
+        let mut da_configs = create_da_configs(&ids, config.da_params);
+        declare_stake(&mut da_configs, &consensus_configs);
         let network_configs = create_network_configs(&ids, config.network_params);
         let mix_configs = create_mix_configs(&ids);
         let api_configs = create_api_configs(&ids);
         let tracing_configs = create_tracing_configs(&ids);
 
-        let mut validators = Vec::new();
-        for i in 0..config.n_validators {
-            let config = create_validator_config(GeneralConfig {
+        let mut general_configs: Vec<_> = (0..n_participants)
+            .map(|i| GeneralConfig {
                 consensus_config: consensus_configs[i].to_owned(),
                 da_config: da_configs[i].to_owned(),
                 network_config: network_configs[i].to_owned(),
                 mix_config: mix_configs[i].to_owned(),
                 api_config: api_configs[i].to_owned(),
                 tracing_config: tracing_configs[i].to_owned(),
-            });
-            validators.push(Validator::spawn(config).await)
+            })
+            .collect();
+
+        // light nodes follow the participants without being part of consensus or of the DA
+        // membership
+        let mut light_ids = vec![[0; 32]; config.n_light_nodes];
+        for id in &mut light_ids {
+            thread_rng().fill(id);
+        }
+        let mut light_network_configs =
+            create_light_network_configs(&light_ids, &network_configs[0]);
+        let mut light_da_configs = create_light_da_configs(&light_ids, &da_configs[0]);
+        let light_api_configs = create_api_configs(&light_ids);
+        let light_tracing_configs = create_tracing_configs(&light_ids);
+
+        let faults = config.fault_injection.then(|| {
+            let mut owners: HashMap<u16, NodeId> = HashMap::new();
+            for (i, config) in general_configs.iter().enumerate() {
+                owners.insert(config.network_config.swarm_config.port, i);
+                owners.extend(udp_port(&config.da_config.listening_address).map(|port| (port, i)));
+                owners.extend(
+                    udp_port(&config.mix_config.backend.listening_address).map(|port| (port, i)),
+                );
+            }
+            for (i, (network, da)) in light_network_configs
+                .iter()
+                .zip(&light_da_configs)
+                .enumerate()
+            {
+                owners.insert(network.swarm_config.port, n_participants + i);
+                owners
+                    .extend(udp_port(&da.listening_address).map(|port| (port, n_participants + i)));
+            }
+
+            let mut faults = NetworkFaults::new();
+            for (i, config) in general_configs.iter_mut().enumerate() {
+                route_peers(
+                    &mut faults,
+                    i,
+                    &owners,
+                    &mut config.network_config.initial_peers,
+                    &mut config.da_config.addresses,
+                );
+                for node in &mut config.mix_config.membership {
+                    node.address = faults.route(i, &owners, &node.address);
+                }
+            }
+            for (i, (network, da)) in light_network_configs
+                .iter_mut()
+                .zip(&mut light_da_configs)
+                .enumerate()
+            {
+                route_peers(
+                    &mut faults,
+                    n_participants + i,
+                    &owners,
+                    &mut network.initial_peers,
+                    &mut da.addresses,
+                );
+            }
+            faults
+        });
+
+        let mut general_configs = general_configs.into_iter();
+        let mut validators = Vec::new();
+        for general_config in general_configs.by_ref().take(config.n_validators) {
+            validators.push(Validator::spawn(create_validator_config(general_config)).await)
         }
 
         let mut executors = Vec::new();
-        for i in config.n_validators..n_participants {
-            let config = create_executor_config(GeneralConfig {
-                consensus_config: consensus_configs[i].to_owned(),
-                da_config: da_configs[i].to_owned(),
-                network_config: network_configs[i].to_owned(),
-                mix_config: mix_configs[i].to_owned(),
-                api_config: api_configs[i].to_owned(),
-                tracing_config: tracing_configs[i].to_owned(),
-            });
-            executors.push(Executor::spawn(config).await)
+        for general_config in general_configs {
+            executors.push(Executor::spawn(create_executor_config(general_config)).await)
+        }
+
+        let mut light_nodes = Vec::new();
+        for i in 0..config.n_light_nodes {
+            let config = create_light_node_config(
+                consensus_configs[0].to_owned(),
+                light_da_configs[i].to_owned(),
+                light_network_configs[i].to_owned(),
+                light_api_configs[i].to_owned(),
+                light_tracing_configs[i].to_owned(),
+            );
+            light_nodes.push(LightNode::spawn(config).await)
         }
 
         Self {
             validators,
             executors,
+            light_nodes,
+            faults,
         }
     }
 
@@ -120,4 +234,42 @@ impl Topology {
     pub fn executors(&self) -> &[Executor] {
         &self.executors
     }
+
+    pub fn light_nodes(&self) -> &[LightNode] {
+        &self.light_nodes
+    }
+
+    pub fn validator_id(&self, index: usize) -> NodeId {
+        index
+    }
+
+    pub fn executor_id(&self, index: usize) -> NodeId {
+        self.validators.len() + index
+    }
+
+    pub fn light_node_id(&self, index: usize) -> NodeId {
+        self.validators.len() + self.executors.len() + index
+    }
+
+    pub fn faults(&self) -> &NetworkFaults {
+        self.faults
+            .as_ref()
+            .expect("fault injection is not enabled in the topology config")
+    }
+}
+
+/// Make a node reach its initial peers and the DA network through the fault proxies
+fn route_peers(
+    faults: &mut NetworkFaults,
+    id: NodeId,
+    owners: &HashMap<u16, NodeId>,
+    initial_peers: &mut [Multiaddr],
+    da_addresses: &mut HashMap<PeerId, Multiaddr>,
+) {
+    for addr in initial_peers {
+        *addr = faults.route(id, owners, addr);
+    }
+    for addr in da_addresses.values_mut() {
+        *addr = faults.route(id, owners, addr);
+    }
 }